@@ -1,4 +1,4 @@
-use save_guardian::steam::SteamScanner;
+use save_guardian_core::steam::SteamScanner;
 use std::path::PathBuf;
 
 /// Example demonstrating the Steam API game name fetching functionality
@@ -0,0 +1,63 @@
+use crate::types::*;
+use crate::backup::BackupManager;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::BTreeMap;
+use walkdir::WalkDir;
+
+/// Number of save changes per local calendar day, used for the play activity heatmap
+#[derive(Debug, Clone, Default)]
+pub struct ActivityCalendar {
+    pub days: BTreeMap<NaiveDate, u32>,
+}
+
+impl ActivityCalendar {
+    pub fn count(&self, day: NaiveDate) -> u32 {
+        self.days.get(&day).copied().unwrap_or(0)
+    }
+
+    pub fn max_count(&self) -> u32 {
+        self.days.values().copied().max().unwrap_or(0)
+    }
+
+    /// Days with any activity between `from` and `to`, inclusive
+    pub fn active_days(&self, from: NaiveDate, to: NaiveDate) -> usize {
+        self.days.range(from..=to).filter(|(_, count)| **count > 0).count()
+    }
+
+    fn record(&mut self, at: DateTime<Local>) {
+        *self.days.entry(at.date_naive()).or_insert(0) += 1;
+    }
+}
+
+/// When a game's save changed, i.e. roughly when it was played
+///
+/// Combines the modification times of the files in the save folder (only the latest
+/// change per file survives on disk) with the game's backups whose contents differ
+/// from the backup before them.
+pub fn save_activity(game_save: &GameSave, backups: &[BackupInfo]) -> ActivityCalendar {
+    let mut calendar = ActivityCalendar::default();
+
+    for entry in WalkDir::new(&game_save.save_path).follow_links(false).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Some(modified) = entry.metadata().ok().and_then(|m| m.modified().ok()) {
+            calendar.record(DateTime::<Local>::from(modified));
+        }
+    }
+
+    let mut game_backups = BackupManager::backups_for_save(backups, game_save);
+    game_backups.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let mut previous_content: Option<&str> = None;
+    for backup in game_backups {
+        let content = backup.content_sha256.as_deref();
+        // Unknown hashes (older backups) count as a change
+        if content.is_none() || content != previous_content {
+            calendar.record(backup.created_at.with_timezone(&Local));
+        }
+        previous_content = content;
+    }
+
+    calendar
+}
@@ -0,0 +1,120 @@
+use crate::types::*;
+use crate::steam::UserdataFolder;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Steam Cloud quotas are usually a few hundred MB, a remote folder above this is suspicious
+pub const HUGE_REMOTE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Saves untouched for longer than this are flagged
+pub const STALE_DAYS: i64 = 365;
+
+/// userdata folders Steam keeps for itself: client settings, screenshots,
+/// controller configs and the redistributables
+const STEAM_INTERNAL_APPS: [u32; 4] = [7, 760, 241100, 228980];
+
+/// What the report suggests doing about an anomaly
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuggestedAction {
+    Archive, // Final pinned backup, see the uninstalled games prompt
+    BackUp,
+}
+
+/// One oddity in a userdata app folder
+#[derive(Debug, Clone)]
+pub struct UserdataAnomaly {
+    pub kind: AnomalyKind,
+    pub app_id: u32,
+    pub game_name: String,
+    pub user_id: String,
+    pub path: PathBuf, // The app folder, userdata/<user>/<app id>
+    pub remote_size: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl UserdataAnomaly {
+    pub fn describe(&self) -> String {
+        match self.kind {
+            AnomalyKind::HugeRemote => format!("{} keeps {} in its remote folder", self.game_name, format_size(self.remote_size)),
+            AnomalyKind::NotInstalled => format!("{} ({}) has userdata but isn't installed", self.game_name, self.app_id),
+            AnomalyKind::Stale => match self.last_modified {
+                Some(time) => format!("{} hasn't changed since {}", self.game_name, time.format("%Y-%m-%d")),
+                None => format!("{} has no files with a modification time", self.game_name),
+            },
+        }
+    }
+
+    pub fn suggested_action(&self) -> SuggestedAction {
+        match self.kind {
+            // Back it up before looking into what grew, a runaway log or cache is usually the cause
+            AnomalyKind::HugeRemote => SuggestedAction::BackUp,
+            AnomalyKind::NotInstalled | AnomalyKind::Stale => SuggestedAction::Archive,
+        }
+    }
+
+    /// The folder holding the saves, as in `GameSave::save_path`
+    pub fn remote_path(&self) -> PathBuf {
+        self.path.join("remote")
+    }
+}
+
+/// Check every userdata app folder for oversized remote folders, missing installs and old saves
+///
+/// `installed` is None when no Steam library could be read; the "not installed" check is
+/// skipped then rather than flagging everything.
+pub fn find_anomalies(
+    folders: &[UserdataFolder],
+    installed: Option<&HashSet<u32>>,
+    game_name: impl Fn(u32) -> String,
+    ignored: &[IgnoredAnomaly],
+) -> Vec<UserdataAnomaly> {
+    let stale_before = Utc::now() - Duration::days(STALE_DAYS);
+    let mut anomalies = Vec::new();
+
+    for folder in folders.iter().filter(|f| !STEAM_INTERNAL_APPS.contains(&f.app_id)) {
+        let remote = folder.path.join("remote");
+        let remote_size = if remote.is_dir() { calculate_path_size(&remote) } else { 0 };
+        let last_modified = newest_modification(&folder.path);
+
+        let mut kinds = Vec::new();
+        if remote_size > HUGE_REMOTE_BYTES {
+            kinds.push(AnomalyKind::HugeRemote);
+        }
+        if installed.map_or(false, |installed| !installed.contains(&folder.app_id)) {
+            kinds.push(AnomalyKind::NotInstalled);
+        }
+        if last_modified.map_or(true, |time| time < stale_before) {
+            kinds.push(AnomalyKind::Stale);
+        }
+
+        for kind in kinds {
+            if ignored.iter().any(|i| i.kind == kind && i.path == folder.path) {
+                continue;
+            }
+            anomalies.push(UserdataAnomaly {
+                kind,
+                app_id: folder.app_id,
+                game_name: game_name(folder.app_id),
+                user_id: folder.user_id.clone(),
+                path: folder.path.clone(),
+                remote_size,
+                last_modified,
+            });
+        }
+    }
+
+    anomalies.sort_by(|a, b| a.game_name.to_lowercase().cmp(&b.game_name.to_lowercase()));
+    anomalies
+}
+
+fn newest_modification(path: &Path) -> Option<DateTime<Utc>> {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+        .map(DateTime::<Utc>::from)
+}
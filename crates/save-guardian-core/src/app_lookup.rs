@@ -0,0 +1,278 @@
+//! Steam app IDs for saves found outside Steam
+//!
+//! A Steam game installed by copying its folder, or a cracked copy being put right, is
+//! scanned as a non-Steam save: it keeps its folder name and can't be paired with its
+//! Steam save by app ID. The lookup tries a `steam_appid.txt` in the game's folders first
+//! (left there by the developer or an emulator), then the install folder, executable and
+//! save names against the Steam app list. Only whole names match; the list has far too
+//! many near misses for anything looser. Suggestions are applied only once confirmed,
+//! as an `AppIdAssignment`.
+
+use crate::types::*;
+use crate::normalize;
+use crate::progress::ArchiveProgress;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use log::{debug, info, warn};
+
+const APP_LIST_URL: &str = "https://api.steampowered.com/ISteamApps/GetAppList/v2/";
+
+/// How many folders up from a save to look for the game's own files
+const SEARCH_DEPTH: usize = 5;
+
+/// Executables in a game folder that aren't the game
+const NOT_THE_EXE: &[&str] = &["unins000", "uninstall", "crashreporter", "crashhandler", "launcher", "setup", "vcredist", "dxsetup", "ueprereqsetup", "dotnetfx"];
+
+/// Words of apps that share a name with their game but aren't it
+const NOT_THE_GAME: &[&str] = &["soundtrack", "ost", "demo", "playtest", "dedicated", "server", "sdk", "editor", "benchmark", "trailer", "artbook", "dlc"];
+
+/// A Steam app a non-Steam save probably belongs to, and why
+#[derive(Debug, Clone)]
+pub struct AppIdSuggestion {
+    pub save_path: PathBuf,
+    pub save_name: String,
+    pub app_id: u32,
+    pub app_name: Option<String>,
+    pub source: AppIdSource,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppIdSource {
+    AppIdFile(PathBuf), // steam_appid.txt, naming the app outright
+    InstallFolder(String),
+    Executable(String),
+    SaveName,
+}
+
+impl AppIdSource {
+    pub fn describe(&self) -> String {
+        match self {
+            AppIdSource::AppIdFile(path) => format!("from {}", path.display()),
+            AppIdSource::InstallFolder(name) => format!("install folder \"{}\" matches", name),
+            AppIdSource::Executable(name) => format!("{} matches", name),
+            AppIdSource::SaveName => "save name matches".to_string(),
+        }
+    }
+}
+
+/// The app list on disk, so the multi-megabyte download isn't repeated every time
+#[derive(Serialize, Deserialize)]
+struct CachedAppList {
+    fetched_at: DateTime<Utc>,
+    apps: Vec<(u32, String)>,
+}
+
+#[derive(Deserialize)]
+struct AppListResponse {
+    applist: AppListApps,
+}
+
+#[derive(Deserialize)]
+struct AppListApps {
+    apps: Vec<AppListEntry>,
+}
+
+#[derive(Deserialize)]
+struct AppListEntry {
+    appid: u32,
+    name: String,
+}
+
+/// Steam app names by compact name, for looking games up by folder and file names
+pub struct AppList {
+    by_key: HashMap<String, u32>, // Lowest app ID of the name, the game rather than a re-release
+    names: HashMap<u32, String>,
+}
+
+impl AppList {
+    /// The Steam app list, plus `known` names (e.g. from the name cache and installed games)
+    ///
+    /// Read from the cache file while it is fresh; downloaded when it isn't and the Steam Store
+    /// may be asked. Offline, an outdated cache or only the known names answer.
+    pub fn load(online: &OnlineConfig, known: Vec<(u32, String)>, progress: &ArchiveProgress) -> Result<Self> {
+        let cache_path = cache_path();
+        let cached = read_cache(&cache_path);
+        let fresh = cached.as_ref().map_or(false, |cached| {
+            Utc::now() - cached.fetched_at < chrono::Duration::days(online.api_cache_days.max(1) as i64)
+        });
+
+        let apps = if fresh || !online.allows(ApiSource::SteamStore) {
+            cached.map(|cached| cached.apps).unwrap_or_default()
+        } else {
+            progress.start_file("Downloading the Steam app list…")?;
+            match download() {
+                Ok(apps) => {
+                    if online.api_cache_days > 0 {
+                        write_cache(&cache_path, &apps);
+                    }
+                    apps
+                }
+                Err(e) => {
+                    warn!("Steam app list not downloaded: {}", e);
+                    cached.map(|cached| cached.apps).unwrap_or_default()
+                }
+            }
+        };
+        progress.check()?;
+
+        let list = Self::from_apps(apps.into_iter().chain(known));
+        if list.names.is_empty() {
+            return Err(SaveGuardianError::BackupOperationFailed(
+                "No Steam app names to compare with; allow Steam Store lookups in Settings → Online Access".to_string()
+            ));
+        }
+        info!("Steam app list has {} names", list.names.len());
+        Ok(list)
+    }
+
+    fn from_apps(apps: impl Iterator<Item = (u32, String)>) -> Self {
+        let mut by_key: HashMap<String, u32> = HashMap::new();
+        let mut names = HashMap::new();
+        for (app_id, name) in apps {
+            let words = normalize::match_key(&name);
+            if words.split(' ').any(|word| NOT_THE_GAME.contains(&word)) {
+                continue;
+            }
+            let key = normalize::compact_key(&name);
+            if key.chars().count() < 3 {
+                continue;
+            }
+            by_key.entry(key).and_modify(|known| *known = (*known).min(app_id)).or_insert(app_id);
+            names.insert(app_id, name);
+        }
+        Self { by_key, names }
+    }
+
+    /// The app called `name`, ignoring case, spaces and punctuation
+    pub fn find(&self, name: &str) -> Option<u32> {
+        self.by_key.get(&normalize::compact_key(name)).copied()
+    }
+
+    pub fn name(&self, app_id: u32) -> Option<&str> {
+        self.names.get(&app_id).map(String::as_str)
+    }
+
+    /// The app a non-Steam save probably belongs to; `exe_name` is the program seen writing
+    /// it, when known (see game_detection.rs)
+    pub fn suggest(&self, save: &GameSave, exe_name: Option<&str>) -> Option<AppIdSuggestion> {
+        let (app_id, source) = match app_id_file(&save.save_path) {
+            Some((app_id, path)) => (app_id, AppIdSource::AppIdFile(path)),
+            None => self.suggest_by_name(save, exe_name)?,
+        };
+        debug!("{} is probably Steam app {} ({:?})", save.name, app_id, source);
+        Some(AppIdSuggestion {
+            save_path: save.save_path.clone(),
+            save_name: save.name.clone(),
+            app_id,
+            app_name: self.name(app_id).map(str::to_string),
+            source,
+        })
+    }
+
+    fn suggest_by_name(&self, save: &GameSave, exe_name: Option<&str>) -> Option<(u32, AppIdSource)> {
+        let install = install_folder(&save.save_path);
+        let mut candidates = Vec::new();
+        if let Some(name) = install.as_deref().and_then(Path::file_name) {
+            let name = name.to_string_lossy().to_string();
+            candidates.push((name.clone(), AppIdSource::InstallFolder(name)));
+        }
+        let exes = exe_name.map(str::to_string).into_iter()
+            .chain(install.as_deref().map(executables).unwrap_or_default());
+        for exe in exes {
+            let stem = exe.rsplit_once('.').map_or(exe.as_str(), |(stem, _)| stem).to_string();
+            candidates.push((stem, AppIdSource::Executable(exe)));
+        }
+        candidates.push((save.name.clone(), AppIdSource::SaveName));
+
+        candidates.into_iter().find_map(|(name, source)| self.find(&name).map(|app_id| (app_id, source)))
+    }
+}
+
+/// The app ID in a `steam_appid.txt` next to the save or in a folder above it
+///
+/// Developers ship the file for testing, and Steam emulators keep one in `steam_settings`.
+pub fn app_id_file(save_path: &Path) -> Option<(u32, PathBuf)> {
+    save_path.ancestors()
+        .take(SEARCH_DEPTH)
+        .flat_map(|folder| [folder.join("steam_appid.txt"), folder.join("steam_settings").join("steam_appid.txt")])
+        .find_map(|path| {
+            let app_id = fs::read_to_string(&path).ok()?.trim().parse::<u32>().ok()?;
+            Some((app_id, path))
+        })
+}
+
+/// The folder the game is installed in, when the save lives inside it: the nearest one
+/// holding an executable
+fn install_folder(save_path: &Path) -> Option<PathBuf> {
+    save_path.ancestors()
+        .take(SEARCH_DEPTH)
+        .find(|folder| !executables(folder).is_empty())
+        .map(Path::to_path_buf)
+}
+
+/// File names of the game executables in `folder`, installers and crash reporters left out
+fn executables(folder: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            let lower = name.to_lowercase();
+            lower.ends_with(".exe") && !NOT_THE_EXE.iter().any(|skip| lower.starts_with(skip))
+        })
+        .collect()
+}
+
+fn cache_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("SaveGuardian")
+        .join("steam_app_list.json")
+}
+
+fn read_cache(path: &Path) -> Option<CachedAppList> {
+    let contents = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(cached) => Some(cached),
+        Err(e) => {
+            warn!("Ignoring unreadable Steam app list cache {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn write_cache(path: &Path, apps: &[(u32, String)]) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let cached = CachedAppList { fetched_at: Utc::now(), apps: apps.to_vec() };
+    match serde_json::to_string(&cached) {
+        Ok(json) => {
+            if let Err(e) = fs::write(path, json) {
+                warn!("Failed to save the Steam app list: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize the Steam app list: {}", e),
+    }
+}
+
+fn download() -> std::result::Result<Vec<(u32, String)>, Box<dyn std::error::Error>> {
+    info!("Downloading the Steam app list");
+    let response: AppListResponse = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()?
+        .get(APP_LIST_URL)
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.applist.apps.into_iter()
+        .filter(|app| !app.name.trim().is_empty())
+        .map(|app| (app.appid, app.name))
+        .collect())
+}
@@ -0,0 +1,269 @@
+use crate::types::*;
+use crate::backup::ZIP64_SIZE_THRESHOLD;
+use crate::encryption;
+use crate::volumes;
+use sevenz_rust::{Password, SevenZArchiveEntry, SevenZReader, SevenZWriter};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Zstandard level of tar.zst archives; zstd's own default, quick with a good ratio
+const ZSTD_LEVEL: i32 = 3;
+
+/// Writes a new backup archive in any of the formats, entry by entry
+///
+/// ZIP and 7z seek back to fill in headers, tar.zst is written front to back.
+pub enum ArchiveWriter<W: Write + Seek> {
+    Zip(ZipWriter<W>),
+    TarZst(tar::Builder<zstd::Encoder<'static, W>>),
+    SevenZ(SevenZWriter<W>),
+}
+
+impl<W: Write + Seek> ArchiveWriter<W> {
+    pub fn new(format: ArchiveFormat, output: W) -> Result<Self> {
+        Ok(match format {
+            ArchiveFormat::Zip => ArchiveWriter::Zip(ZipWriter::new(output)),
+            ArchiveFormat::TarZst => ArchiveWriter::TarZst(tar::Builder::new(zstd::Encoder::new(output, ZSTD_LEVEL)?)),
+            ArchiveFormat::SevenZ => ArchiveWriter::SevenZ(SevenZWriter::new(output).map_err(seven_z_error)?),
+        })
+    }
+
+    /// Let tar.zst compress on `threads` threads
+    ///
+    /// ZIP entries are compressed one by one and get their threads from `PackedEntry`
+    /// instead; 7z compresses the whole archive as one stream, on one thread.
+    pub fn with_threads(mut self, threads: usize) -> Result<Self> {
+        if let ArchiveWriter::TarZst(ref mut builder) = self {
+            if threads > 1 {
+                builder.get_mut().multithread(threads as u32)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Add a folder entry; `name` ends with '/'
+    pub fn add_directory(&mut self, name: &str) -> Result<()> {
+        match self {
+            ArchiveWriter::Zip(zip) => zip.add_directory(name, zip_options())
+                .map_err(SaveGuardianError::Zip),
+            ArchiveWriter::TarZst(builder) => builder.append_data(&mut tar_header(tar::EntryType::Directory, 0), name, io::empty())
+                .map_err(SaveGuardianError::Io),
+            ArchiveWriter::SevenZ(writer) => {
+                let mut entry = SevenZArchiveEntry::new();
+                entry.name = name.trim_end_matches('/').to_string();
+                entry.is_directory = true;
+                entry.has_stream = false;
+                writer.push_archive_entry::<io::Empty>(entry, None)
+                    .map(|_| ())
+                    .map_err(seven_z_error)
+            }
+        }
+    }
+
+    /// Add a file of `len` bytes read from `data`
+    ///
+    /// tar records the size before the contents, so data ending early fails the entry and
+    /// anything past `len` is left out, in every format alike.
+    pub fn add_file(&mut self, name: &str, len: u64, data: &mut dyn Read) -> Result<()> {
+        let mut data = ExactReader { inner: data, remaining: len };
+        match self {
+            ArchiveWriter::Zip(zip) => {
+                zip.start_file(name, zip_options().large_file(len >= ZIP64_SIZE_THRESHOLD))
+                    .map_err(SaveGuardianError::Zip)?;
+                io::copy(&mut data, zip)?;
+                Ok(())
+            }
+            ArchiveWriter::TarZst(builder) => builder.append_data(&mut tar_header(tar::EntryType::Regular, len), name, &mut data)
+                .map_err(SaveGuardianError::Io),
+            ArchiveWriter::SevenZ(writer) => {
+                // The writer sets the size and stream flag from what it reads
+                let mut entry = SevenZArchiveEntry::new();
+                entry.name = name.to_string();
+                writer.push_archive_entry(entry, Some(&mut data))
+                    .map(|_| ())
+                    .map_err(seven_z_error)
+            }
+        }
+    }
+
+    /// Add a file compressed ahead with `PackedEntry::pack`; only ZIP archives take them
+    pub fn add_packed(&mut self, packed: &PackedEntry) -> Result<()> {
+        match self {
+            ArchiveWriter::Zip(zip) => {
+                let mut source = ZipArchive::new(Cursor::new(packed.zip.as_slice())).map_err(SaveGuardianError::Zip)?;
+                let entry = source.by_index(0).map_err(SaveGuardianError::Zip)?;
+                zip.raw_copy_file(entry).map_err(SaveGuardianError::Zip)
+            }
+            ArchiveWriter::TarZst(_) | ArchiveWriter::SevenZ(_) => Err(SaveGuardianError::BackupOperationFailed(
+                "Only ZIP archives take files compressed ahead".to_string()
+            )),
+        }
+    }
+
+    /// Write the archive's index (or the end of the stream) and hand back the output
+    pub fn finish(self) -> Result<W> {
+        match self {
+            ArchiveWriter::Zip(mut zip) => zip.finish().map_err(SaveGuardianError::Zip),
+            ArchiveWriter::TarZst(builder) => Ok(builder.into_inner()?.finish()?),
+            ArchiveWriter::SevenZ(writer) => Ok(writer.finish()?),
+        }
+    }
+}
+
+/// A ZIP entry compressed ahead of time, so several can be deflated at once on worker
+/// threads and then copied into the archive in order without compressing them again
+pub struct PackedEntry {
+    zip: Vec<u8>, // A ZIP holding just this entry
+}
+
+impl PackedEntry {
+    /// Deflate a file into an entry named `name`, the same as `ArchiveWriter::add_file` would
+    pub fn pack(name: &str, data: &[u8]) -> Result<Self> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(name, zip_options().large_file(data.len() as u64 >= ZIP64_SIZE_THRESHOLD))
+            .map_err(SaveGuardianError::Zip)?;
+        zip.write_all(data)?;
+        let zip = zip.finish().map_err(SaveGuardianError::Zip)?.into_inner();
+        Ok(Self { zip })
+    }
+}
+
+/// Call `each` with every entry of an archive in order: its name (ending with '/' for
+/// folders), its size and its contents
+pub fn for_each_entry<R: Read + Seek>(format: ArchiveFormat, mut reader: R, mut each: impl FnMut(&str, u64, &mut dyn Read) -> Result<()>) -> Result<()> {
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = ZipArchive::new(reader).map_err(SaveGuardianError::Zip)?;
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i).map_err(SaveGuardianError::Zip)?;
+                let name = file.name().to_string();
+                let size = file.size();
+                each(&name, size, &mut file)?;
+            }
+            Ok(())
+        }
+        ArchiveFormat::TarZst => {
+            let mut archive = tar::Archive::new(zstd::Decoder::new(reader)?);
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let kind = entry.header().entry_type();
+                // Links and devices aren't written by backups, nothing to restore them as
+                if !kind.is_file() && !kind.is_dir() {
+                    continue;
+                }
+                let mut name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+                if kind.is_dir() && !name.ends_with('/') {
+                    name.push('/');
+                }
+                let size = entry.size();
+                each(&name, size, &mut entry)?;
+            }
+            Ok(())
+        }
+        ArchiveFormat::SevenZ => {
+            let len = reader.seek(SeekFrom::End(0))?;
+            reader.seek(SeekFrom::Start(0))?;
+            let mut archive = SevenZReader::new(reader, len, Password::empty()).map_err(seven_z_error)?;
+            // The callback can't return our errors, they wait here until the walk has stopped
+            let mut failure = None;
+            archive.for_each_entries(|entry, data| {
+                let name = if entry.is_directory() { format!("{}/", entry.name()) } else { entry.name().to_string() };
+                if let Err(e) = each(&name, entry.size(), data) {
+                    failure = Some(e);
+                    return Ok(false);
+                }
+                // Entries share compressed streams, what the callback skipped still has to be read past
+                if let Err(e) = io::copy(data, &mut io::sink()) {
+                    failure = Some(e.into());
+                    return Ok(false);
+                }
+                Ok(true)
+            }).map_err(seven_z_error)?;
+            match failure {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+/// Names of an archive's entries, folders ending with '/'
+///
+/// Only ZIP has an index; the other formats are read through to the end.
+pub fn entry_names<R: Read + Seek>(format: ArchiveFormat, reader: R) -> Result<Vec<String>> {
+    if format == ArchiveFormat::Zip {
+        let archive = ZipArchive::new(reader).map_err(SaveGuardianError::Zip)?;
+        return Ok(archive.file_names().map(String::from).collect());
+    }
+    let mut names = Vec::new();
+    for_each_entry(format, reader, |name, _, _| {
+        names.push(name.to_string());
+        Ok(())
+    })?;
+    Ok(names)
+}
+
+/// `for_each_entry` over a backup's archive, split or not
+///
+/// Encrypted archives are decrypted into memory first, which needs the passphrase.
+pub fn read_backup(backup_info: &BackupInfo, each: impl FnMut(&str, u64, &mut dyn Read) -> Result<()>) -> Result<()> {
+    let format = ArchiveFormat::of_path(&backup_info.backup_path);
+    if backup_info.encrypted {
+        return for_each_entry(format, Cursor::new(encryption::decrypt_backup(backup_info)?), each);
+    }
+    let reader = volumes::open(backup_info)
+        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to open backup file: {}", e)))?;
+    for_each_entry(format, reader, each)
+}
+
+/// `entry_names` of a backup's archive, split or not
+pub fn backup_entry_names(backup_info: &BackupInfo) -> Result<Vec<String>> {
+    if backup_info.encrypted {
+        let format = ArchiveFormat::of_path(&backup_info.backup_path);
+        return entry_names(format, Cursor::new(encryption::decrypt_backup(backup_info)?));
+    }
+    let reader = volumes::open(backup_info)
+        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to open backup file: {}", e)))?;
+    entry_names(ArchiveFormat::of_path(&backup_info.backup_path), reader)
+}
+
+fn zip_options() -> FileOptions {
+    FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .unix_permissions(0o755)
+}
+
+fn tar_header(kind: tar::EntryType, len: u64) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(kind);
+    header.set_size(len);
+    header.set_mode(0o755);
+    header.set_mtime(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs()));
+    header
+}
+
+fn seven_z_error(e: sevenz_rust::Error) -> SaveGuardianError {
+    SaveGuardianError::BackupOperationFailed(format!("7z error: {}", e))
+}
+
+/// Reads exactly `remaining` bytes, failing if the file got shorter since it was measured
+struct ExactReader<'a> {
+    inner: &'a mut dyn Read,
+    remaining: u64,
+}
+
+impl Read for ExactReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let room = self.remaining.min(buf.len() as u64) as usize;
+        let read = self.inner.read(&mut buf[..room])?;
+        if read == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "File got shorter while it was archived"));
+        }
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::types::*;
+use crate::archive;
+use crate::backup::BackupManager;
+use crate::encryption;
+use crate::integrity::{BackupSigner, VerificationStatus};
+use crate::volumes;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::info;
+
+/// Something wrong with the backup store, as found by `save-guardian verify`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditIssue {
+    UnreadableMetadata, // A .backup.json that doesn't parse
+    MissingArchive,     // Metadata whose archive is gone
+    ChecksumMismatch,   // Archive changed since it was recorded
+    BadSignature,
+    CorruptArchive, // Not a readable ZIP
+    SizeMismatch,   // Archive size differs from the metadata
+    OrphanArchive,  // An archive (or part of a split one) no metadata points to
+    MissingBase,    // An incremental backup whose base backup is gone
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditFinding {
+    pub issue: AuditIssue,
+    pub path: PathBuf,
+    pub backup_id: Option<String>,
+    pub detail: String,
+}
+
+/// Result of walking one backup folder
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub backup_root: PathBuf,
+    pub checked_at: DateTime<Utc>,
+    pub backups: usize,
+    pub verified: usize,  // Checksum matched, or signature valid where signed
+    pub unchecked: usize, // No checksum recorded, only readability was checked
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// One line per finding, for people rather than scripts
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "{}: {} backups, {} verified, {} without checksum, {} problems\n",
+            self.backup_root.display(), self.backups, self.verified, self.unchecked, self.findings.len()
+        );
+        for finding in &self.findings {
+            text.push_str(&format!("  {:?}: {} ({})\n", finding.issue, finding.path.display(), finding.detail));
+        }
+        text
+    }
+}
+
+/// Check every backup in a folder: metadata against archives, checksums, signatures and orphans
+pub fn audit_backup_root(backup_root: &Path, signer: Option<BackupSigner>, worker_threads: usize) -> Result<AuditReport> {
+    if !backup_root.is_dir() {
+        return Err(SaveGuardianError::PathNotFound(backup_root.to_path_buf()));
+    }
+    let manager = BackupManager::new(backup_root.to_path_buf(), 0)?
+        .with_signer(signer)
+        .with_worker_threads(worker_threads);
+
+    let mut report = AuditReport {
+        backup_root: backup_root.to_path_buf(),
+        checked_at: Utc::now(),
+        backups: 0,
+        verified: 0,
+        unchecked: 0,
+        findings: Vec::new(),
+    };
+    let mut referenced: HashSet<String> = HashSet::new();
+    let mut archives: Vec<PathBuf> = Vec::new();
+    let mut backups: Vec<BackupInfo> = Vec::new();
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(backup_root)?.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries.into_iter().filter(|p| p.is_file()) {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if volumes::is_archive_name(&name) {
+            archives.push(path);
+            continue;
+        }
+        if !name.ends_with(".backup.json") {
+            continue;
+        }
+
+        let backup_info: BackupInfo = match fs::read_to_string(&path).map_err(SaveGuardianError::Io)
+            .and_then(|json| serde_json::from_str(&json).map_err(SaveGuardianError::Serde)) {
+            Ok(backup_info) => backup_info,
+            Err(e) => {
+                report.findings.push(AuditFinding { issue: AuditIssue::UnreadableMetadata, path, backup_id: None, detail: e.to_string() });
+                continue;
+            }
+        };
+        report.backups += 1;
+        for archive in volumes::archive_files(&backup_info) {
+            if let Some(file_name) = archive.file_name() {
+                referenced.insert(file_name.to_string_lossy().to_string());
+            }
+        }
+        backups.push(backup_info);
+    }
+
+    // Hashing dominates, so every archive is checked up front on the worker threads
+    let statuses = manager.verify_backups(&backups);
+    for (backup_info, status) in backups.iter().zip(statuses) {
+        check_backup(backup_info, status, &mut report);
+    }
+
+    for backup_info in &backups {
+        if let Some(ref base) = backup_info.base_backup {
+            if !backups.iter().any(|b| &b.id == base) {
+                report.findings.push(AuditFinding {
+                    issue: AuditIssue::MissingBase,
+                    path: backup_info.backup_path.clone(),
+                    backup_id: Some(backup_info.id.clone()),
+                    detail: format!("Only holds the changes since backup {}, which is missing", base),
+                });
+            }
+        }
+    }
+
+    for archive in archives {
+        let name = archive.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if !referenced.contains(&name) {
+            report.findings.push(AuditFinding {
+                issue: AuditIssue::OrphanArchive,
+                path: archive,
+                backup_id: None,
+                detail: "No metadata file refers to this archive".to_string(),
+            });
+        }
+    }
+
+    info!("Audited {:?}: {} backups, {} problems", backup_root, report.backups, report.findings.len());
+    Ok(report)
+}
+
+fn check_backup(backup_info: &BackupInfo, status: VerificationStatus, report: &mut AuditReport) {
+    let finding = |issue: AuditIssue, detail: String| AuditFinding {
+        issue,
+        path: backup_info.backup_path.clone(),
+        backup_id: Some(backup_info.id.clone()),
+        detail,
+    };
+
+    match status {
+        VerificationStatus::MissingArchive => {
+            report.findings.push(finding(AuditIssue::MissingArchive, status.describe().to_string()));
+            return;
+        }
+        VerificationStatus::ArchiveModified => {
+            report.findings.push(finding(AuditIssue::ChecksumMismatch, status.describe().to_string()));
+            return;
+        }
+        VerificationStatus::BadSignature => report.findings.push(finding(AuditIssue::BadSignature, status.describe().to_string())),
+        VerificationStatus::Verified => report.verified += 1,
+        VerificationStatus::Unsigned | VerificationStatus::NoKey => {
+            if backup_info.archive_sha256.is_some() {
+                report.verified += 1;
+            } else {
+                report.unchecked += 1;
+            }
+        }
+    }
+
+    let size = volumes::archive_size(backup_info);
+    if size != backup_info.size {
+        report.findings.push(finding(AuditIssue::SizeMismatch, format!("{} bytes on disk, {} recorded", size, backup_info.size)));
+    }
+
+    // Without the passphrase only the checksum of an encrypted archive can be checked
+    if backup_info.encrypted && !encryption::is_unlocked() {
+        return;
+    }
+    if let Err(e) = archive::backup_entry_names(backup_info) {
+        report.findings.push(finding(AuditIssue::CorruptArchive, e.to_string()));
+    }
+}
+
+/// The backup folder and every household profile's folder under it
+pub fn backup_roots(backup_path: &Path) -> Vec<PathBuf> {
+    let mut roots = vec![backup_path.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(backup_path.join("profiles")) {
+        let mut profiles: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        profiles.sort();
+        roots.extend(profiles);
+    }
+    roots
+}
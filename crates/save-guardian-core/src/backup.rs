@@ -0,0 +1,2408 @@
+use crate::types::*;
+use crate::archive::{self, ArchiveWriter, PackedEntry};
+use crate::integrity::{self, BackupSigner, ContentCheck, VerificationStatus};
+use crate::exclusions::ExclusionRules;
+use crate::game_settings;
+use crate::incremental::{self, FileIndex, IndexChanges};
+use crate::dedup_store::{DedupStore, StoreReport};
+use crate::encryption;
+use crate::network;
+use crate::normalize;
+use crate::progress::ArchiveProgress;
+use crate::save_formats;
+use crate::import::DownloadedArchive;
+use crate::protection::Unprotected;
+use crate::volumes::{self, VolumeWriter};
+use crate::workers;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use walkdir::WalkDir;
+use zip::{ZipArchive, ZipWriter};
+use chrono::Utc;
+use log::{debug, info, warn};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+
+/// Entries at or above this size need Zip64 sizes in their headers
+pub const ZIP64_SIZE_THRESHOLD: u64 = u32::MAX as u64;
+
+/// Archives with more entries than this need a Zip64 end of central directory
+pub const ZIP64_ENTRY_THRESHOLD: usize = u16::MAX as usize;
+
+/// Longest entry name a ZIP header can hold, in bytes
+pub const MAX_ENTRY_NAME_BYTES: usize = u16::MAX as usize;
+
+/// Files larger than this are streamed into the archive instead of read into memory
+const STREAM_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Small files read and compressed together on the worker threads before going into a ZIP
+const PACK_BATCH_BYTES: u64 = 64 * 1024 * 1024;
+const PACK_BATCH_FILES: usize = 256;
+
+/// Suffix of the folder a restore is extracted into before it replaces the target
+const STAGING_SUFFIX: &str = ".sgrestore";
+
+pub struct BackupManager {
+    backup_root: PathBuf,
+    retention_days: u32,
+    signer: Option<BackupSigner>,
+    exclusions: ExclusionConfig,
+    network: NetworkConfig,
+    keep_latest: bool,
+    retention_exemptions: Vec<RetentionExemption>,
+    duplicates: DuplicatePolicy,
+    protected_saves: Vec<PathBuf>,
+    machine: Option<MachineInfo>,
+    settings_games: Vec<String>,
+    volume_size: Option<u64>,
+    timestamp: FilenameTimestampConfig,
+    progress: Option<Arc<ArchiveProgress>>,
+    worker_threads: usize,
+    incremental: IncrementalConfig,
+    dedup_min_size: Option<u64>,
+    archive_format: ArchiveFormat,
+    encrypt: bool,
+}
+
+/// What ended up in (and was left out of) a freshly written archive
+struct ArchiveSummary {
+    size: u64,
+    excluded_files: usize,
+    excluded_bytes: u64,
+    content_sha256: String,
+    settings_files: Vec<SettingsFile>,
+    volumes: Vec<PathBuf>,
+    stored_files: Vec<StoredFile>,
+    file_checksums: Vec<FileChecksum>,
+}
+
+/// Result of asking for a new backup
+#[derive(Debug, Clone)]
+pub enum BackupOutcome {
+    Created(BackupInfo),
+    Unchanged(BackupInfo), // Nothing changed since this existing backup, so none was made
+}
+
+impl BackupOutcome {
+    /// The backup that now holds the save's current state
+    pub fn into_backup(self) -> BackupInfo {
+        match self {
+            BackupOutcome::Created(backup) | BackupOutcome::Unchanged(backup) => backup,
+        }
+    }
+}
+
+impl BackupManager {
+    pub fn new(backup_root: PathBuf, retention_days: u32) -> Result<Self> {
+        // Create backup directory if it doesn't exist
+        if !backup_root.exists() {
+            fs::create_dir_all(&backup_root)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create backup directory: {}", e)))?;
+            info!("Created backup directory: {:?}", backup_root);
+        }
+
+        Ok(Self {
+            backup_root,
+            retention_days,
+            signer: None,
+            exclusions: ExclusionConfig::default(),
+            network: NetworkConfig::default(),
+            keep_latest: false,
+            retention_exemptions: Vec::new(),
+            duplicates: DuplicatePolicy::Keep,
+            protected_saves: Vec::new(),
+            machine: None,
+            settings_games: Vec::new(),
+            volume_size: None,
+            timestamp: FilenameTimestampConfig::default(),
+            progress: None,
+            worker_threads: 1,
+            incremental: IncrementalConfig::default(),
+            dedup_min_size: None,
+            archive_format: ArchiveFormat::Zip,
+            encrypt: false,
+        })
+    }
+
+    /// Record this install in every new backup
+    pub fn with_machine(mut self, machine: MachineInfo) -> Self {
+        self.machine = Some(machine);
+        self
+    }
+
+    /// How many archives `verify_backups` hashes at once
+    pub fn with_worker_threads(mut self, threads: usize) -> Self {
+        self.worker_threads = threads.max(1);
+        self
+    }
+
+    /// Only archive the files changed since a game's previous backup, with a full one every so often
+    pub fn with_incremental(mut self, incremental: IncrementalConfig) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    /// Keep files of at least this many bytes in the dedup store instead of each archive
+    pub fn with_dedup_store(mut self, min_size: Option<u64>) -> Self {
+        self.dedup_min_size = min_size;
+        self
+    }
+
+    /// Format of new archives; existing backups are read in whatever format they have
+    pub fn with_archive_format(mut self, format: ArchiveFormat) -> Self {
+        self.archive_format = format;
+        self
+    }
+
+    /// Seal new archives with the session's backup passphrase, see encryption.rs
+    ///
+    /// Encrypted archives aren't split and keep large files out of the dedup store, whose
+    /// objects are plain.
+    pub fn with_encryption(mut self, encrypt: bool) -> Self {
+        self.encrypt = encrypt;
+        self
+    }
+
+    /// Games, by backup key, whose settings files go into a separate section of their archives
+    pub fn with_settings_games(mut self, settings_games: Vec<String>) -> Self {
+        self.settings_games = settings_games;
+        self
+    }
+
+    /// Split archives larger than this many bytes into numbered parts, e.g. for FAT32 drives
+    pub fn with_volume_size(mut self, volume_size: Option<u64>) -> Self {
+        self.volume_size = volume_size.filter(|&size| size > 0);
+        self
+    }
+
+    /// How the creation time is written into new archive names
+    pub fn with_filename_timestamp(mut self, timestamp: FilenameTimestampConfig) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Keep `latest/<game>.zip` pointing at each game's newest backup
+    pub fn with_latest_links(mut self, keep_latest: bool) -> Self {
+        self.keep_latest = keep_latest;
+        self
+    }
+
+    /// Rules that keep matching backups out of retention cleanup
+    pub fn with_retention_exemptions(mut self, exemptions: Vec<RetentionExemption>) -> Self {
+        self.retention_exemptions = exemptions;
+        self
+    }
+
+    /// How to handle a backup whose files are identical to the game's previous backup
+    pub fn with_duplicate_policy(mut self, duplicates: DuplicatePolicy) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Retry reads that fail transiently, for saves on network drives
+    pub fn with_network_config(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Skip junk files matching these exclusion rules when archiving
+    pub fn with_exclusions(mut self, exclusions: ExclusionConfig) -> Self {
+        self.exclusions = exclusions;
+        self
+    }
+
+    /// Sign new backups and check signatures with the given signer
+    pub fn with_signer(mut self, signer: Option<BackupSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Write-protected save folders, unlocked while a backup is restored into them
+    pub fn with_protected_saves(mut self, protected_saves: Vec<PathBuf>) -> Self {
+        self.protected_saves = protected_saves;
+        self
+    }
+
+    /// Report each file archived or extracted to `progress`, and stop when it is cancelled
+    pub fn with_progress(mut self, progress: Arc<ArchiveProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Folder holding this manager's archives and metadata
+    pub fn backup_root(&self) -> &PathBuf {
+        &self.backup_root
+    }
+
+    /// Create a backup of a game save
+    ///
+    /// If nothing changed since the game's last backup and duplicates are skipped,
+    /// that backup is returned instead, so it still serves as a restore point.
+    pub fn create_backup(&self, game_save: &GameSave, description: Option<String>) -> Result<BackupInfo> {
+        self.create_backup_checked(game_save, description).map(BackupOutcome::into_backup)
+    }
+
+    /// Back up a save unless its files look the same as at the game's last backup
+    ///
+    /// Meant for automatic backups: only names, sizes and modification times are compared,
+    /// so nothing is read or compressed when the save is unchanged. The skip is recorded
+    /// on the existing backup as `verified_unchanged_at`.
+    pub fn create_backup_if_changed(&self, game_save: &GameSave, description: Option<String>) -> Result<BackupOutcome> {
+        let fingerprint = self.source_fingerprint(game_save);
+
+        let backups = self.list_backups(None, None)?;
+        let previous = Self::backups_for_save(&backups, game_save)
+            .into_iter()
+            .max_by(|a, b| a.created_at.cmp(&b.created_at))
+            .cloned();
+
+        if let Some(mut previous) = previous {
+            if previous.source_fingerprint.as_deref() == Some(fingerprint.as_str()) && volumes::archive_exists(&previous) {
+                info!("{} unchanged since backup {}, skipping", game_save.name, previous.id);
+                previous.verified_unchanged_at = Some(Utc::now());
+                if let Err(e) = self.save_backup_metadata(&previous) {
+                    warn!("Failed to record unchanged check for {}: {}", previous.id, e);
+                }
+                return Ok(BackupOutcome::Unchanged(previous));
+            }
+        }
+
+        self.create_backup_checked(game_save, description)
+    }
+
+    /// Quick fingerprint of a save: relative names, sizes and modification times, no contents
+    pub fn source_fingerprint(&self, game_save: &GameSave) -> String {
+        let rules = self.exclusion_rules_for(game_save);
+        let mut hasher = Sha256::new();
+
+        for entry in WalkDir::new(&game_save.save_path).follow_links(false).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+            let relative_path = match entry.path().strip_prefix(&game_save.save_path) {
+                Ok(relative_path) => relative_path,
+                Err(_) => continue,
+            };
+            if !entry.file_type().is_file() || rules.is_excluded(relative_path) {
+                continue;
+            }
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            let modified = metadata.modified().ok()
+                .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_nanos());
+
+            hasher.update(relative_path.to_string_lossy().replace('\\', "/").as_bytes());
+            hasher.update([0u8]);
+            hasher.update(metadata.len().to_le_bytes());
+            hasher.update(modified.to_le_bytes());
+        }
+
+        // A changed keybind counts as a change too, for games that include their settings
+        for path in self.settings_files_for(game_save) {
+            if let Ok(metadata) = fs::metadata(&path) {
+                let modified = metadata.modified().ok()
+                    .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map_or(0, |d| d.as_nanos());
+                hasher.update(path.to_string_lossy().as_bytes());
+                hasher.update([0u8]);
+                hasher.update(metadata.len().to_le_bytes());
+                hasher.update(modified.to_le_bytes());
+            }
+        }
+
+        integrity::hex_encode(&hasher.finalize())
+    }
+
+    /// Create a backup, telling apart a new archive from "no changes since last backup"
+    pub fn create_backup_checked(&self, game_save: &GameSave, description: Option<String>) -> Result<BackupOutcome> {
+        self.write_backup(game_save, description, false)
+    }
+
+    /// Archive a save
+    ///
+    /// With `consolidating`, `game_save` is a chain rebuilt in a staging folder: the archive is
+    /// always a new full one, neither an increment nor skipped or linked as a duplicate of the
+    /// game's last backup, and no settings files are looked for next to the staging folder.
+    fn write_backup(&self, game_save: &GameSave, description: Option<String>, consolidating: bool) -> Result<BackupOutcome> {
+        // One ID per archive, so each backup keeps its own metadata file
+        let started = std::time::Instant::now();
+        if self.encrypt {
+            // Locked: fail before spending time on an archive that can't be sealed
+            encryption::cipher()?;
+        }
+        let created_at = Utc::now();
+        let backup_id = self.unused_backup_id(&format!(
+            "{}_{}",
+            self.generate_backup_id(game_save),
+            filename_timestamp(created_at, &self.timestamp)
+        ));
+        let backup_filename = format!("{}.{}", backup_id, self.archive_format.extension());
+        let backup_path = self.backup_root.join(&backup_filename);
+
+        info!("Creating backup for {} at {:?}", game_save.name, backup_path);
+
+        // Taken before archiving, so changes made while zipping show up next time
+        let source_fingerprint = self.source_fingerprint(game_save);
+
+        let rules = self.exclusion_rules_for(game_save);
+        // Next to a staging folder is the backup folder, not the game's settings
+        let settings_files = if consolidating { Vec::new() } else { self.settings_files_for(game_save) };
+
+        // Indexed for the next incremental backup, and compared with the previous one's index
+        let base = if consolidating { None } else { self.incremental_base(game_save) };
+        let index = if self.incremental.enabled && game_save.save_path.is_dir() {
+            Some(FileIndex::scan(&game_save.save_path, &rules, base.as_ref().map(|(_, index)| index), self.worker_threads)?)
+        } else {
+            None
+        };
+        let increment: Option<(BackupInfo, IndexChanges)> = match (base, &index) {
+            (Some((previous, previous_index)), Some(index)) => Some((previous, index.changes_since(&previous_index))),
+            _ => None,
+        };
+        if let Some((ref previous, ref changes)) = increment {
+            // Hard links keep a restore point with its description, an empty increment does too
+            if changes.is_empty() && self.duplicates == DuplicatePolicy::Skip {
+                info!("No changes since last backup of {} ({}), skipped", game_save.name, previous.id);
+                return Ok(BackupOutcome::Unchanged(previous.clone()));
+            }
+            info!("Incremental backup of {} on {}: {} changed, {} removed",
+                game_save.name, previous.id, changes.changed.len(), changes.removed.len());
+        }
+
+        // Create the archive, leaving out logs, crash dumps and caches
+        let only = increment.as_ref().map(|(_, changes)| &changes.changed);
+        let summary = self.create_archive(&game_save.save_path, &backup_path, &rules, &settings_files, only)?;
+        if summary.excluded_files > 0 {
+            info!("Skipped {} junk files ({} bytes) for {}", summary.excluded_files, summary.excluded_bytes, game_save.name);
+        }
+
+        // Identical files to the previous backup of this game? An increment is never whole, so never identical
+        let mut deduplicated = false;
+        if self.duplicates != DuplicatePolicy::Keep && !consolidating && increment.is_none() {
+            if let Some(previous) = self.find_duplicate(game_save, &summary.content_sha256) {
+                match self.duplicates {
+                    DuplicatePolicy::Skip => {
+                        let _ = volumes::remove_files(&backup_path, &summary.volumes);
+                        info!("No changes since last backup of {} ({}), skipped", game_save.name, previous.id);
+                        return Ok(BackupOutcome::Unchanged(previous));
+                    }
+                    // Parts can't be linked one to one, split archives keep their copy; neither can
+                    // a plain archive stand in for an encrypted one
+                    DuplicatePolicy::HardLink if previous.volumes.is_empty() && summary.volumes.is_empty() && previous.encrypted == self.encrypt => {
+                        deduplicated = Self::link_duplicate(&previous.backup_path, &backup_path);
+                    }
+                    DuplicatePolicy::HardLink | DuplicatePolicy::Keep => {}
+                }
+            }
+        }
+
+        // A linked duplicate shares the earlier archive, sealed already
+        if self.encrypt && !deduplicated {
+            if let Err(e) = encryption::encrypt_file(&backup_path) {
+                let _ = fs::remove_file(&backup_path);
+                return Err(e);
+            }
+        }
+
+        let archive_sha256 = if summary.volumes.is_empty() {
+            integrity::sha256_file(&backup_path)?
+        } else {
+            volumes::sha256_files(&summary.volumes)?
+        };
+        let size = if deduplicated || self.encrypt {
+            fs::metadata(&backup_path).map(|m| m.len()).unwrap_or(summary.size)
+        } else {
+            summary.size
+        };
+
+        let mut backup_info = BackupInfo {
+            id: backup_id,
+            game_name: game_save.name.clone(),
+            app_id: game_save.app_id,
+            save_type: game_save.save_type.clone(),
+            original_path: game_save.save_path.clone(),
+            backup_path,
+            created_at,
+            size,
+            description,
+            archive_sha256: Some(archive_sha256),
+            signature: None,
+            steam_user_id: game_save.steam_user_id.clone(),
+            sandbox: game_save.sandbox.clone(),
+            source_size: calculate_path_size(&game_save.save_path),
+            excluded_files: summary.excluded_files,
+            excluded_bytes: summary.excluded_bytes,
+            pinned: false,
+            tags: Vec::new(),
+            content_sha256: Some(summary.content_sha256),
+            deduplicated,
+            source_fingerprint: Some(source_fingerprint),
+            verified_unchanged_at: None,
+            machine: self.machine.clone(),
+            settings_files: summary.settings_files,
+            stored_files: summary.stored_files,
+            file_checksums: summary.file_checksums,
+            save_metadata: save_formats::scan_folder(&game_save.save_path),
+            volumes: summary.volumes,
+            duration_ms: Some(started.elapsed().as_millis() as u64),
+            base_backup: increment.as_ref().map(|(previous, _)| previous.id.clone()),
+            removed_files: increment.map(|(_, changes)| changes.removed).unwrap_or_default(),
+            encrypted: self.encrypt,
+        };
+
+        if let Some(ref signer) = self.signer {
+            backup_info.signature = signer.sign(&backup_info);
+        }
+
+        // Save backup metadata
+        self.save_backup_metadata(&backup_info)?;
+        if let Some(index) = index {
+            if let Err(e) = index.save(&backup_info) {
+                warn!("Failed to save the file index of {}, the next backup will be a full one: {}", backup_info.id, e);
+            }
+        }
+        self.update_latest(&backup_info);
+
+        info!("Backup created successfully: {}", backup_info.id);
+        Ok(BackupOutcome::Created(backup_info))
+    }
+
+    fn exclusion_rules_for(&self, game_save: &GameSave) -> ExclusionRules {
+        if game_save.is_steam_profile() {
+            ExclusionRules::for_steam_profile(&self.exclusions)
+        } else {
+            ExclusionRules::for_save(&self.exclusions, &game_save.name, &game_save.save_path)
+        }
+    }
+
+    /// Settings files to archive with a save, empty unless the game includes its settings
+    fn settings_files_for(&self, game_save: &GameSave) -> Vec<PathBuf> {
+        if self.settings_games.contains(&Self::key_for_save(game_save)) {
+            game_settings::find_settings_files(&game_save.save_path)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The backup a new incremental one of this save would build on, with its file index
+    ///
+    /// None when incremental backups are off, the game has no indexed backup yet, or its
+    /// chain reached `full_every` increments, so the next backup is a full one.
+    fn incremental_base(&self, game_save: &GameSave) -> Option<(BackupInfo, FileIndex)> {
+        if !self.incremental.enabled || !game_save.save_path.is_dir() {
+            return None;
+        }
+        let backups = self.list_backups(None, None).ok()?;
+        let previous = Self::backups_for_save(&backups, game_save)
+            .into_iter()
+            .max_by(|a, b| a.created_at.cmp(&b.created_at))
+            .filter(|b| volumes::archive_exists(b))
+            .cloned()?;
+        let index = FileIndex::load(&previous)?;
+
+        match self.backup_chain(&previous) {
+            Ok(chain) if chain.len() > self.incremental.full_every as usize => {
+                info!("{} incremental backups of {} in a row, making a full one", chain.len() - 1, game_save.name);
+                None
+            }
+            Ok(_) => Some((previous, index)),
+            Err(e) => {
+                warn!("Making a full backup of {}: {}", game_save.name, e);
+                None
+            }
+        }
+    }
+
+    /// A backup and the ones it builds on, oldest first: just the backup itself unless it's incremental
+    pub fn backup_chain(&self, backup_info: &BackupInfo) -> Result<Vec<BackupInfo>> {
+        let mut chain = vec![backup_info.clone()];
+        while let Some(base_id) = chain.last().and_then(|b| b.base_backup.clone()) {
+            let dependent = chain.last().map(|b| b.id.clone()).unwrap_or_default();
+            if chain.iter().any(|b| b.id == base_id) {
+                return Err(SaveGuardianError::BackupOperationFailed(format!("{} builds on itself through {}", dependent, base_id)));
+            }
+            let base = self.load_backup_metadata(&self.get_metadata_path(&base_id))
+                .ok()
+                .filter(|base| volumes::archive_exists(base))
+                .ok_or_else(|| SaveGuardianError::BackupOperationFailed(format!(
+                    "{} only holds the changes since backup {}, which is missing", dependent, base_id
+                )))?;
+            chain.push(base);
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Write the save as it was at `tip`, an incremental backup, into a new full backup
+    ///
+    /// The chain is extracted next to the backups like a restore and archived again, so
+    /// the backups it was made of can be deleted afterwards. Settings files stay with the
+    /// backups they were made with.
+    pub fn consolidate_chain(&self, tip: &BackupInfo) -> Result<BackupInfo> {
+        let chain = self.backup_chain(tip)?;
+        if chain.len() < 2 {
+            return Err(SaveGuardianError::BackupOperationFailed(format!("{} is a full backup already", tip.id)));
+        }
+        for link in &chain {
+            let status = self.verify_backup(link);
+            if status.is_tampered() {
+                return Err(SaveGuardianError::BackupOperationFailed(format!(
+                    "Refusing to consolidate {}: {}", link.id, status.describe()
+                )));
+            }
+        }
+        info!("Consolidating {} backups of {} up to {}", chain.len(), tip.game_name, tip.id);
+
+        let staging = staging_path(&self.backup_root.join(&tip.id));
+        if staging.exists() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        let result = self.extract_backup_chain(&chain, &staging).and_then(|()| {
+            let game_save = GameSave {
+                name: tip.game_name.clone(),
+                app_id: tip.app_id,
+                save_type: tip.save_type.clone(),
+                save_path: staging.clone(),
+                last_modified: Some(tip.created_at),
+                size: 0,
+                backup_count: 0,
+                is_synced: false,
+                steam_user_id: tip.steam_user_id.clone(),
+                is_network: false,
+                sandbox: tip.sandbox.clone(),
+                origin: None,
+            };
+            let description = format!("Consolidated from {} backups, {} to {}",
+                chain.len(), chain[0].created_at.format("%Y-%m-%d %H:%M"), tip.created_at.format("%Y-%m-%d %H:%M"));
+            self.write_backup(&game_save, Some(description), true)
+        });
+        let _ = fs::remove_dir_all(&staging);
+
+        // Written from the staging folder; it stands for the save the chain was made of
+        let mut backup_info = result?.into_backup();
+        backup_info.original_path = tip.original_path.clone();
+        backup_info.source_fingerprint = tip.source_fingerprint.clone();
+        backup_info.pinned = tip.pinned;
+        backup_info.tags = tip.tags.clone();
+        if let Some(ref signer) = self.signer {
+            backup_info.signature = signer.sign(&backup_info);
+        }
+        self.save_backup_metadata(&backup_info)?;
+
+        info!("Consolidated {} into {}", tip.id, backup_info.id);
+        Ok(backup_info)
+    }
+
+    /// The game's newest backup, if its files hash the same as `content_sha256`
+    fn find_duplicate(&self, game_save: &GameSave, content_sha256: &str) -> Option<BackupInfo> {
+        let backups = self.list_backups(None, None).ok()?;
+        Self::backups_for_save(&backups, game_save)
+            .into_iter()
+            .max_by(|a, b| a.created_at.cmp(&b.created_at))
+            .filter(|b| b.content_sha256.as_deref() == Some(content_sha256) && volumes::archive_exists(b))
+            .cloned()
+    }
+
+    /// Replace a freshly written archive with a hard link to an identical earlier one
+    ///
+    /// Returns false (keeping the new archive) where hard links aren't supported.
+    fn link_duplicate(previous: &PathBuf, backup_path: &PathBuf) -> bool {
+        let mut link_path = backup_path.as_os_str().to_os_string();
+        link_path.push(".link");
+        let link_path = PathBuf::from(link_path);
+        if let Err(e) = fs::hard_link(previous, &link_path) {
+            debug!("Hard link to {:?} not possible, keeping the copy: {}", previous, e);
+            return false;
+        }
+        match fs::rename(&link_path, backup_path) {
+            Ok(()) => {
+                info!("Deduplicated {:?} against {:?}", backup_path, previous);
+                true
+            }
+            Err(e) => {
+                warn!("Failed to replace duplicate archive {:?}: {}", backup_path, e);
+                let _ = fs::remove_file(&link_path);
+                false
+            }
+        }
+    }
+
+    /// Create an archive of a directory or file, in the configured format
+    ///
+    /// Settings files go under `game_settings::SETTINGS_FOLDER`, apart from the save files.
+    /// With a volume size set, archives larger than it are written as `<name>.zip.001`, `.002`, ...
+    /// With `only` set, an incremental backup, just those files (and every folder) are written.
+    fn create_archive(&self, source_path: &PathBuf, backup_path: &PathBuf, rules: &ExclusionRules, settings: &[PathBuf], only: Option<&HashSet<String>>) -> Result<ArchiveSummary> {
+        let volume_size = if self.encrypt { None } else { self.volume_size };
+        let backup_file = VolumeWriter::create(backup_path, volume_size)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create backup file: {}", e)))?;
+
+        let mut writer = match ArchiveWriter::new(self.archive_format, backup_file).and_then(|writer| writer.with_threads(self.worker_threads)) {
+            Ok(writer) => writer,
+            Err(e) => {
+                let _ = fs::remove_file(volumes::volume_path(backup_path, 1));
+                return Err(e);
+            }
+        };
+        match self.write_entries(&mut writer, source_path, rules, settings, only) {
+            Ok((entries, summary)) => {
+                let backup_file = writer.finish()?;
+                let parts = backup_file.finish()
+                    .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to write backup file: {}", e)))?;
+                self.check_archive(backup_path, parts, entries, summary)
+            }
+            Err(e) => {
+                // Whatever was written so far is of no use
+                if let Ok(backup_file) = writer.finish() {
+                    backup_file.discard();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Add the save (and settings) files to an archive
+    ///
+    /// Returns the number of entries, for check_archive, and a summary still missing the size and parts.
+    fn write_entries<W: Write + Seek>(&self, writer: &mut ArchiveWriter<W>, source_path: &PathBuf, rules: &ExclusionRules, settings: &[PathBuf], only: Option<&HashSet<String>>) -> Result<(usize, ArchiveSummary)> {
+        let mut excluded_files = 0;
+        let mut excluded_bytes = 0;
+        // Entry names and contents only, so unchanged saves hash the same every time
+        let mut content_hasher = Sha256::new();
+
+        let mut entries = 0;
+        let mut stored_files = Vec::new();
+        let mut file_checksums = Vec::new();
+        // Small files waiting to be compressed together, see add_packed_files
+        let mut pending: Vec<(String, PathBuf)> = Vec::new();
+        let mut pending_bytes = 0;
+
+        if source_path.is_file() {
+            // Backup single file
+            let filename = source_path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown");
+            self.begin_progress(1 + settings.len());
+            match self.store_large_file(filename, source_path, &mut content_hasher)? {
+                Some(stored) => stored_files.push(stored),
+                None => {
+                    file_checksums.push(self.add_file_entry(writer, filename, source_path, &mut content_hasher)?);
+                    entries += 1;
+                }
+            }
+        } else if source_path.is_dir() {
+            // Backup directory, listed up front so the progress knows the number of files
+            let walker: Vec<walkdir::DirEntry> = WalkDir::new(source_path)
+                .follow_links(false)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .collect();
+            self.begin_progress(walker.iter().filter(|entry| entry.path().is_file()).count() + settings.len());
+
+            for entry in walker {
+                let path = entry.path();
+                let relative_path = path.strip_prefix(source_path)
+                    .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Path error: {}", e)))?;
+
+                if path.is_file() {
+                    if rules.is_excluded(relative_path) {
+                        excluded_files += 1;
+                        excluded_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                        debug!("Excluded junk file: {:?}", relative_path);
+                        self.finish_progress_file();
+                        continue;
+                    }
+
+                    let file_path_str = relative_path.to_string_lossy().replace('\\', "/");
+                    if only.map_or(false, |only| !only.contains(&file_path_str)) {
+                        // Unchanged since the backup this increment builds on
+                        self.finish_progress_file();
+                        continue;
+                    }
+                    let len = entry.metadata().map(|m| m.len()).ok();
+                    let packable = self.packs_ahead() && len.map_or(false, |len| len < STREAM_THRESHOLD)
+                        && self.store_min_size().map_or(true, |min_size| len.map_or(false, |len| len < min_size));
+                    if packable {
+                        check_entry_name(&file_path_str)?;
+                        pending.push((file_path_str, path.to_path_buf()));
+                        pending_bytes += len.unwrap_or(0);
+                        if pending_bytes >= PACK_BATCH_BYTES || pending.len() >= PACK_BATCH_FILES {
+                            entries += self.add_packed_files(writer, &mut pending, &mut content_hasher, &mut file_checksums)?;
+                            pending_bytes = 0;
+                        }
+                        continue;
+                    }
+                    // Whatever waits goes in first, so entries keep their order
+                    entries += self.add_packed_files(writer, &mut pending, &mut content_hasher, &mut file_checksums)?;
+                    pending_bytes = 0;
+                    if let Some(stored) = self.store_large_file(&file_path_str, path, &mut content_hasher)? {
+                        stored_files.push(stored);
+                        continue;
+                    }
+                    file_checksums.push(self.add_file_entry(writer, &file_path_str, path, &mut content_hasher)?);
+                    entries += 1;
+
+                    debug!("Added file to backup: {}", file_path_str);
+                } else if path.is_dir() && relative_path.as_os_str() != "" {
+                    if rules.is_excluded_dir(relative_path) {
+                        continue;
+                    }
+
+                    entries += self.add_packed_files(writer, &mut pending, &mut content_hasher, &mut file_checksums)?;
+                    pending_bytes = 0;
+
+                    // Add directory entry
+                    let dir_path_str = format!("{}/", relative_path.to_string_lossy().replace('\\', "/"));
+                    check_entry_name(&dir_path_str)?;
+                    writer.add_directory(&dir_path_str)?;
+                    hash_entry(&mut content_hasher, &dir_path_str, &[]);
+                    entries += 1;
+
+                    debug!("Added directory to backup: {}", dir_path_str);
+                }
+            }
+            entries += self.add_packed_files(writer, &mut pending, &mut content_hasher, &mut file_checksums)?;
+        } else {
+            return Err(SaveGuardianError::BackupOperationFailed(
+                "Source path is neither file nor directory".to_string()
+            ));
+        }
+
+        let mut settings_files = Vec::new();
+        for (index, path) in settings.iter().enumerate() {
+            let archive_name = game_settings::archive_name(index, path);
+            self.start_progress_file(&archive_name)?;
+            // Settings are a bonus, an unreadable one shouldn't fail the save's backup
+            let buffer = match network::with_retry(&self.network, || fs::read(path)) {
+                Ok(buffer) => buffer,
+                Err(e) => {
+                    warn!("Skipping settings file {:?}: {}", path, e);
+                    self.finish_progress_file();
+                    continue;
+                }
+            };
+            hash_entry(&mut content_hasher, &archive_name, &buffer);
+            file_checksums.push(checksum_of(&archive_name, &buffer));
+            writer.add_file(&archive_name, buffer.len() as u64, &mut buffer.as_slice())?;
+            debug!("Added settings file to backup: {:?}", path);
+            settings_files.push(SettingsFile { archive_name, original_path: path.clone() });
+            entries += 1;
+            self.finish_progress_file();
+        }
+
+        if entries > ZIP64_ENTRY_THRESHOLD && self.archive_format == ArchiveFormat::Zip {
+            info!("{} entries, writing a Zip64 archive", entries);
+        }
+
+        Ok((entries, ArchiveSummary {
+            size: 0,
+            excluded_files,
+            excluded_bytes,
+            content_sha256: integrity::hex_encode(&content_hasher.finalize()),
+            settings_files,
+            volumes: Vec::new(),
+            stored_files,
+            file_checksums,
+        }))
+    }
+
+    /// Read the entry list of a finished archive back, so one the format couldn't hold
+    /// (or a part that didn't reassemble) fails here rather than on restore
+    ///
+    /// A ZIP's central directory is enough; tar.zst and 7z archives are read through.
+    fn check_archive(&self, backup_path: &PathBuf, parts: Vec<PathBuf>, entries: usize, summary: ArchiveSummary) -> Result<ArchiveSummary> {
+        let files = if parts.is_empty() { vec![backup_path.clone()] } else { parts.clone() };
+        let readable = volumes::VolumeReader::open(&files)
+            .map_err(SaveGuardianError::Io)
+            .and_then(|reader| archive::entry_names(self.archive_format, reader))
+            .map(|names| names.len());
+        match readable {
+            Ok(count) if count == entries => {}
+            Ok(count) => {
+                let _ = volumes::remove_files(backup_path, &parts);
+                return Err(SaveGuardianError::BackupOperationFailed(format!(
+                    "The archive lists {} of {} entries; this save can't be stored as {}", count, entries, self.archive_format.label()
+                )));
+            }
+            Err(e) => {
+                let _ = volumes::remove_files(backup_path, &parts);
+                return Err(SaveGuardianError::BackupOperationFailed(format!("The new archive can't be read back: {}", e)));
+            }
+        }
+
+        let size = files.iter().map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0)).sum();
+        Ok(ArchiveSummary { size, volumes: parts, ..summary })
+    }
+
+    /// Smallest file that goes into the dedup store, None while the store is off or unusable
+    fn store_min_size(&self) -> Option<u64> {
+        self.dedup_min_size.filter(|_| !self.encrypt)
+    }
+
+    /// Put a file into the dedup store instead of the archive, if the store is on and it's large enough
+    ///
+    /// The content hash gets the object's hash in place of the contents, the same for
+    /// every backup of an unchanged file.
+    fn store_large_file(&self, name: &str, path: &Path, hasher: &mut Sha256) -> Result<Option<StoredFile>> {
+        let min_size = match self.store_min_size() {
+            Some(min_size) => min_size,
+            None => return Ok(None),
+        };
+        let len = network::with_retry(&self.network, || fs::metadata(path))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", path, e)))?
+            .len();
+        if len < min_size {
+            return Ok(None);
+        }
+
+        self.start_progress_file(name)?;
+        let mut file = network::with_retry(&self.network, || fs::File::open(path))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", path, e)))?;
+        let object = DedupStore::new(&self.backup_root).put(&mut file, &|| self.check_cancelled())?;
+        hash_stored_entry(hasher, name, object.size, &object.sha256);
+        self.finish_progress_file();
+
+        debug!("{} file {} kept in the store as {}", if object.added { "New" } else { "Known" }, name, object.sha256);
+        Ok(Some(StoredFile { name: name.to_string(), sha256: object.sha256, size: object.size }))
+    }
+
+    /// Add one file to an archive, with Zip64 sizes when a ZIP needs them
+    ///
+    /// Small files are read in one go, with retries for flaky network drives; large ones
+    /// are streamed so a multi-GB save doesn't have to fit in memory. Returns the file's
+    /// own checksum.
+    fn add_file_entry<W: Write + Seek>(&self, writer: &mut ArchiveWriter<W>, name: &str, path: &Path, hasher: &mut Sha256) -> Result<FileChecksum> {
+        check_entry_name(name)?;
+        self.start_progress_file(name)?;
+        let len = network::with_retry(&self.network, || fs::metadata(path))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", path, e)))?
+            .len();
+
+        if len < STREAM_THRESHOLD {
+            let buffer = network::with_retry(&self.network, || fs::read(path))
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", path, e)))?;
+            hash_entry(hasher, name, &buffer);
+            writer.add_file(name, buffer.len() as u64, &mut buffer.as_slice())?;
+            self.finish_progress_file();
+            return Ok(checksum_of(name, &buffer));
+        }
+
+        if len >= ZIP64_SIZE_THRESHOLD && self.archive_format == ArchiveFormat::Zip {
+            info!("{} is {}, storing it with Zip64 sizes", name, format_size(len));
+        }
+        let mut file = network::with_retry(&self.network, || fs::File::open(path))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", path, e)))?;
+
+        // Same hash as hash_entry, fed a chunk at a time as the archive reads the file
+        hasher.update(name.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(len.to_le_bytes());
+        let mut reader = HashingReader { inner: &mut file, hasher, file_hasher: Sha256::new(), check: &|| self.check_cancelled(), failure: None };
+        let added = writer.add_file(name, len, &mut reader);
+        if let Some(e) = reader.failure {
+            return Err(e);
+        }
+        added.map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to archive file {:?}: {}", path, e)))?;
+        self.finish_progress_file();
+        Ok(FileChecksum { name: name.to_string(), sha256: integrity::hex_encode(&reader.file_hasher.finalize()), size: len })
+    }
+
+    /// Whether small files are compressed ahead on the worker threads
+    ///
+    /// Only ZIP compresses file by file; tar.zst runs zstd on the threads instead, see
+    /// `ArchiveWriter::with_threads`.
+    fn packs_ahead(&self) -> bool {
+        self.archive_format == ArchiveFormat::Zip && self.worker_threads > 1
+    }
+
+    /// Read and compress the waiting files on the worker threads, then add them in order
+    ///
+    /// The archive, content hash and progress still see one file after the other, exactly
+    /// as `add_file_entry` would add them. Returns the number of entries added.
+    fn add_packed_files<W: Write + Seek>(&self, writer: &mut ArchiveWriter<W>, pending: &mut Vec<(String, PathBuf)>, hasher: &mut Sha256, checksums: &mut Vec<FileChecksum>) -> Result<usize> {
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        self.check_cancelled()?;
+        let batch = std::mem::take(pending);
+        let packed = workers::map(&batch, self.worker_threads, |(name, path)| -> Result<(Vec<u8>, PackedEntry)> {
+            let data = network::with_retry(&self.network, || fs::read(path))
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", path, e)))?;
+            let packed = PackedEntry::pack(name, &data)?;
+            Ok((data, packed))
+        });
+
+        for ((name, _), result) in batch.iter().zip(packed) {
+            self.start_progress_file(name)?;
+            let (data, packed) = result?;
+            hash_entry(hasher, name, &data);
+            checksums.push(checksum_of(name, &data));
+            writer.add_packed(&packed)?;
+            self.finish_progress_file();
+            debug!("Added file to backup: {}", name);
+        }
+        Ok(batch.len())
+    }
+
+    fn begin_progress(&self, total: usize) {
+        if let Some(ref progress) = self.progress {
+            progress.begin(total);
+        }
+    }
+
+    /// Announce the next file to the progress; fails with `Cancelled` once it was cancelled
+    fn start_progress_file(&self, name: &str) -> Result<()> {
+        match self.progress {
+            Some(ref progress) => progress.start_file(name),
+            None => Ok(()),
+        }
+    }
+
+    fn finish_progress_file(&self) {
+        if let Some(ref progress) = self.progress {
+            progress.finish_file();
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        match self.progress {
+            Some(ref progress) => progress.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Restore a backup to a specified location
+    pub fn restore_backup(&self, backup_info: &BackupInfo, restore_path: &PathBuf, overwrite: bool) -> Result<()> {
+        info!("Restoring backup {} to {:?}", backup_info.id, restore_path);
+
+        if restore_path.exists() && !overwrite {
+            return Err(SaveGuardianError::BackupOperationFailed(
+                "Restore path already exists and overwrite is disabled".to_string()
+            ));
+        }
+
+        // An incremental backup needs every archive it builds on, each intact
+        let chain = self.backup_chain(backup_info)?;
+        for link in &chain {
+            let status = self.verify_backup(link);
+            if status.is_tampered() {
+                return Err(SaveGuardianError::BackupOperationFailed(format!(
+                    "Refusing to restore {}: {}", link.id, status.describe()
+                )));
+            }
+        }
+
+        // Create parent directories if they don't exist
+        if let Some(parent) = restore_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create restore directory: {}", e)))?;
+        }
+
+        // Protected again once the guard goes out of scope
+        let _unprotected = Unprotected::lift(&self.protected_saves, &[restore_path.as_path()])?;
+
+        // Extracted next to the target first: a failed or cancelled extraction leaves it untouched
+        let staging = staging_path(restore_path);
+        if staging.exists() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        if let Err(e) = self.extract_backup_chain(&chain, &staging) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+
+        // Renames within one folder, quick enough not to need cancelling
+        let moved = move_into_place(&staging, restore_path);
+        let _ = fs::remove_dir_all(&staging);
+        moved?;
+
+        info!("Backup restored successfully to {:?}", restore_path);
+        Ok(())
+    }
+
+    /// Extract a backup to a directory, from the full backup through each increment on it
+    ///
+    /// Files an increment records as removed are deleted before its own files go in, and
+    /// each backup's files in the dedup store are copied out after its archive.
+    fn extract_backup_chain(&self, chain: &[BackupInfo], extract_path: &PathBuf) -> Result<()> {
+        let mut total = 0;
+        for backup_info in chain {
+            total += archive::backup_entry_names(backup_info)?.iter()
+                .filter(|name| !name.ends_with('/') && !game_settings::is_settings_entry(name))
+                .count() + backup_info.stored_files.len();
+        }
+        self.begin_progress(total);
+        fs::create_dir_all(extract_path)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create directory: {}", e)))?;
+
+        for backup_info in chain {
+            for name in &backup_info.removed_files {
+                if !incremental::is_safe_entry(name) {
+                    warn!("Ignoring removed entry {:?} of {}", name, backup_info.id);
+                    continue;
+                }
+                let path = extract_path.join(name);
+                let removed = if name.ends_with('/') { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+                if removed.is_ok() {
+                    debug!("Removed {:?}, deleted before {}", path, backup_info.id);
+                }
+            }
+            self.extract_entries(backup_info, extract_path)?;
+
+            let store = DedupStore::of_backup(backup_info);
+            for stored in &backup_info.stored_files {
+                if !incremental::is_safe_entry(&stored.name) {
+                    warn!("Ignoring stored file {:?} of {}", stored.name, backup_info.id);
+                    continue;
+                }
+                self.start_progress_file(&stored.name)?;
+                store.copy_out(stored, &extract_path.join(&stored.name), &|| self.check_cancelled())?;
+                self.finish_progress_file();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extract one archive's save files, reading split archives' parts in order
+    fn extract_entries(&self, backup_info: &BackupInfo, extract_path: &Path) -> Result<()> {
+        archive::read_backup(backup_info, |name, size, file| {
+            // Restored on their own, see restore_settings
+            if game_settings::is_settings_entry(name) {
+                return Ok(());
+            }
+            if !incremental::is_safe_entry(name.trim_end_matches('/')) {
+                warn!("Ignoring entry {:?} of {}, it points outside the save folder", name, backup_info.id);
+                return Ok(());
+            }
+
+            let file_path = extract_path.join(name);
+
+            if name.ends_with('/') {
+                // Directory
+                fs::create_dir_all(&file_path)
+                    .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create directory: {}", e)))?;
+            } else {
+                // File
+                if let Some(parent) = file_path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create parent directory: {}", e)))?;
+                }
+
+                self.start_progress_file(name)?;
+                let mut output_file = fs::File::create(&file_path)
+                    .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create output file: {}", e)))?;
+
+                if size < STREAM_THRESHOLD {
+                    std::io::copy(file, &mut output_file)
+                        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to extract file: {}", e)))?;
+                } else {
+                    // A chunk at a time, so cancelling doesn't wait for a multi-GB file
+                    let mut chunk = vec![0u8; 1024 * 1024];
+                    loop {
+                        self.check_cancelled()?;
+                        let read = file.read(&mut chunk)
+                            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to extract file: {}", e)))?;
+                        if read == 0 {
+                            break;
+                        }
+                        output_file.write_all(&chunk[..read])
+                            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to extract file: {}", e)))?;
+                    }
+                }
+                self.finish_progress_file();
+
+                debug!("Extracted file: {:?}", file_path);
+            }
+            Ok(())
+        })
+    }
+
+    /// Put a backup's settings files back where they came from, leaving the save alone
+    ///
+    /// Returns how many files were restored.
+    pub fn restore_settings(&self, backup_info: &BackupInfo) -> Result<usize> {
+        if backup_info.settings_files.is_empty() {
+            return Err(SaveGuardianError::BackupOperationFailed(format!("{} has no settings files", backup_info.id)));
+        }
+
+        let status = self.verify_backup(backup_info);
+        if status.is_tampered() {
+            return Err(SaveGuardianError::BackupOperationFailed(format!(
+                "Refusing to restore {}: {}", backup_info.id, status.describe()
+            )));
+        }
+
+        let mut restored = 0;
+        archive::read_backup(backup_info, |name, _, file| {
+            let settings_file = match backup_info.settings_files.iter().find(|s| s.archive_name == name) {
+                Some(settings_file) => settings_file,
+                None => return Ok(()),
+            };
+            if let Some(parent) = settings_file.original_path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create settings directory: {}", e)))?;
+            }
+            let mut output_file = fs::File::create(&settings_file.original_path)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create settings file: {}", e)))?;
+            std::io::copy(file, &mut output_file)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to extract settings file: {}", e)))?;
+            debug!("Restored settings file: {:?}", settings_file.original_path);
+            restored += 1;
+            Ok(())
+        })?;
+        if restored < backup_info.settings_files.len() {
+            return Err(SaveGuardianError::BackupOperationFailed(format!(
+                "Only {} of {} settings files are in the archive", restored, backup_info.settings_files.len()
+            )));
+        }
+
+        info!("Restored {} settings files of {}", backup_info.settings_files.len(), backup_info.game_name);
+        Ok(backup_info.settings_files.len())
+    }
+
+    /// Check a backup archive against its recorded hash and signature
+    pub fn verify_backup(&self, backup_info: &BackupInfo) -> VerificationStatus {
+        if !volumes::archive_exists(backup_info) {
+            return VerificationStatus::MissingArchive;
+        }
+
+        if let Some(ref expected) = backup_info.archive_sha256 {
+            match volumes::sha256(backup_info) {
+                Ok(actual) if &actual == expected => {}
+                Ok(_) => return VerificationStatus::ArchiveModified,
+                Err(e) => {
+                    warn!("Failed to hash backup {}: {}", backup_info.id, e);
+                    return VerificationStatus::MissingArchive;
+                }
+            }
+        }
+
+        // Files in the dedup store are as much a part of the backup as the archive
+        let store = DedupStore::of_backup(backup_info);
+        for stored in &backup_info.stored_files {
+            match store.check(stored) {
+                Ok(true) => {}
+                Ok(false) => return VerificationStatus::ArchiveModified,
+                Err(e) => {
+                    warn!("Stored file {} of backup {} unavailable: {}", stored.name, backup_info.id, e);
+                    return VerificationStatus::MissingArchive;
+                }
+            }
+        }
+
+        match (&backup_info.signature, &self.signer) {
+            (None, _) => VerificationStatus::Unsigned,
+            (Some(_), None) => VerificationStatus::NoKey,
+            (Some(signature), Some(signer)) => {
+                if signer.verify(backup_info, signature) {
+                    VerificationStatus::Verified
+                } else {
+                    VerificationStatus::BadSignature
+                }
+            }
+        }
+    }
+
+    /// `verify_backup` for many backups, spread over the worker threads, in the given order
+    pub fn verify_backups(&self, backups: &[BackupInfo]) -> Vec<VerificationStatus> {
+        workers::map(backups, self.worker_threads, |backup_info| self.verify_backup(backup_info))
+    }
+
+    /// Read every file of a backup's archive back and compare it with the checksum taken
+    /// when it was archived, so damage shows up before a restore needs the backup
+    ///
+    /// Backups made before checksums were recorded are still read through, which finds
+    /// archives too damaged to extract. Fails only when cancelled, or for an encrypted backup
+    /// while locked.
+    pub fn verify_contents(&self, backup_info: &BackupInfo) -> Result<ContentCheck> {
+        self.begin_progress(backup_info.file_checksums.len());
+        self.check_contents(backup_info, true)
+    }
+
+    /// `verify_backup` and `verify_contents` for many backups on the worker threads, in order
+    ///
+    /// The progress counts backups rather than files. A missing archive isn't read.
+    pub fn verify_backups_fully(&self, backups: &[BackupInfo]) -> Result<Vec<(VerificationStatus, Option<ContentCheck>)>> {
+        self.begin_progress(backups.len());
+        workers::map(backups, self.worker_threads, |backup_info| -> Result<(VerificationStatus, Option<ContentCheck>)> {
+            self.start_progress_file(&backup_info.game_name)?;
+            let status = self.verify_backup(backup_info);
+            // Without the passphrase an encrypted archive only gets its checksum checked
+            let locked = backup_info.encrypted && !encryption::is_unlocked();
+            let contents = if status == VerificationStatus::MissingArchive || locked {
+                None
+            } else {
+                Some(self.check_contents(backup_info, false)?)
+            };
+            self.finish_progress_file();
+            Ok((status, contents))
+        }).into_iter().collect()
+    }
+
+    fn check_contents(&self, backup_info: &BackupInfo, file_progress: bool) -> Result<ContentCheck> {
+        let recorded: HashMap<&str, &FileChecksum> = backup_info.file_checksums.iter()
+            .map(|checksum| (checksum.name.as_str(), checksum))
+            .collect();
+        let mut check = ContentCheck::default();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        let read = archive::read_backup(backup_info, |name, _, data| {
+            if name.ends_with('/') {
+                return Ok(());
+            }
+            if file_progress {
+                self.start_progress_file(name)?;
+            } else {
+                self.check_cancelled()?;
+            }
+            let mut hasher = Sha256::new();
+            let size = io::copy(data, &mut hasher)?;
+            match recorded.get(name) {
+                Some(expected) => {
+                    seen.insert(name.to_string());
+                    if expected.size == size && integrity::hex_encode(&hasher.finalize()) == expected.sha256 {
+                        check.checked += 1;
+                    } else {
+                        warn!("{} of backup {} doesn't match its checksum", name, backup_info.id);
+                        check.corrupt.push(name.to_string());
+                    }
+                }
+                None => check.unrecorded += 1,
+            }
+            if file_progress {
+                self.finish_progress_file();
+            }
+            Ok(())
+        });
+        match read {
+            Ok(()) => {
+                check.missing = backup_info.file_checksums.iter()
+                    .filter(|checksum| !seen.contains(&checksum.name))
+                    .map(|checksum| checksum.name.clone())
+                    .collect();
+            }
+            Err(e @ (SaveGuardianError::Cancelled | SaveGuardianError::Locked)) => return Err(e),
+            Err(e) => {
+                warn!("Backup {} can't be read back: {}", backup_info.id, e);
+                check.unreadable = Some(e.to_string());
+            }
+        }
+        Ok(check)
+    }
+
+    /// Move the large files of existing archives into the dedup store, rewriting each archive without them
+    ///
+    /// Split and encrypted archives and those in other formats than ZIP are left as they are, and so are
+    /// backups failing verification or signed with a key that isn't loaded.
+    pub fn repack_into_store(&self) -> Result<StoreReport> {
+        let min_size = self.dedup_min_size
+            .ok_or_else(|| SaveGuardianError::BackupOperationFailed("The dedup store is off".to_string()))?;
+        let store = DedupStore::new(&self.backup_root);
+        let mut report = StoreReport::default();
+
+        let backups = self.list_backups(None, None)?;
+        self.begin_progress(backups.len());
+        for mut backup_info in backups {
+            self.start_progress_file(&backup_info.id)?;
+            match self.repack_backup(&store, &mut backup_info, min_size) {
+                Ok(Some((moved, freed))) => {
+                    report.backups_repacked += 1;
+                    report.files_moved += moved;
+                    report.bytes_freed += freed;
+                }
+                Ok(None) => {}
+                Err(SaveGuardianError::Cancelled) => return Err(SaveGuardianError::Cancelled),
+                Err(e) => {
+                    warn!("Failed to repack {}: {}", backup_info.id, e);
+                    report.failed.push((backup_info.id.clone(), e.to_string()));
+                }
+            }
+            self.finish_progress_file();
+        }
+
+        info!("Repacked {} backups into the store: {}", report.backups_repacked, report.describe());
+        Ok(report)
+    }
+
+    /// Rewrite one archive without its large files, which go into the store
+    ///
+    /// Returns the number of files moved and the bytes freed, None if nothing was large enough.
+    fn repack_backup(&self, store: &DedupStore, backup_info: &mut BackupInfo, min_size: u64) -> Result<Option<(usize, u64)>> {
+        if !backup_info.volumes.is_empty() || backup_info.encrypted || ArchiveFormat::of_path(&backup_info.backup_path) != ArchiveFormat::Zip {
+            return Ok(None);
+        }
+        let is_large = |file: &zip::read::ZipFile| {
+            !file.is_dir() && !game_settings::is_settings_entry(file.name()) && file.size() >= min_size
+        };
+
+        let mut archive = ZipArchive::new(fs::File::open(&backup_info.backup_path)?)
+            .map_err(SaveGuardianError::Zip)?;
+        let mut any_large = false;
+        for i in 0..archive.len() {
+            if is_large(&archive.by_index(i).map_err(SaveGuardianError::Zip)?) {
+                any_large = true;
+                break;
+            }
+        }
+        if !any_large {
+            return Ok(None);
+        }
+
+        let status = self.verify_backup(backup_info);
+        if status.is_tampered() || status == VerificationStatus::MissingArchive {
+            return Err(SaveGuardianError::BackupOperationFailed(status.describe().to_string()));
+        }
+        if backup_info.signature.is_some() && self.signer.is_none() {
+            return Err(SaveGuardianError::BackupOperationFailed("Signed, but the signing key isn't loaded".to_string()));
+        }
+
+        let old_size = fs::metadata(&backup_info.backup_path)?.len();
+        let temp_path = backup_info.backup_path.with_extension("zip.repack");
+        let rewritten = (|| -> Result<(Vec<StoredFile>, String, u64)> {
+            let mut zip = ZipWriter::new(fs::File::create(&temp_path)?);
+            let mut hasher = Sha256::new();
+            let mut stored_files = Vec::new();
+            let mut added_bytes = 0;
+
+            for i in 0..archive.len() {
+                self.check_cancelled()?;
+                let mut file = archive.by_index(i).map_err(SaveGuardianError::Zip)?;
+                let name = file.name().to_string();
+                if is_large(&file) {
+                    let object = store.put(&mut file, &|| self.check_cancelled())?;
+                    if object.added {
+                        added_bytes += object.size;
+                    }
+                    hash_stored_entry(&mut hasher, &name, object.size, &object.sha256);
+                    stored_files.push(StoredFile { name, sha256: object.sha256, size: object.size });
+                    continue;
+                }
+
+                // Small files are read once for the content hash and copied still compressed
+                let mut data = Vec::new();
+                file.read_to_end(&mut data)?;
+                hash_entry(&mut hasher, &name, &data);
+                drop(file);
+                zip.raw_copy_file(archive.by_index(i).map_err(SaveGuardianError::Zip)?)
+                    .map_err(SaveGuardianError::Zip)?;
+            }
+            zip.finish().map_err(SaveGuardianError::Zip)?;
+            Ok((stored_files, integrity::hex_encode(&hasher.finalize()), added_bytes))
+        })();
+        drop(archive);
+        let (stored_files, content_sha256, added_bytes) = match rewritten {
+            Ok(rewritten) => rewritten,
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                return Err(e);
+            }
+        };
+
+        // Replacing a hard-linked duplicate only changes this backup's copy
+        fs::rename(&temp_path, &backup_info.backup_path)?;
+        let moved = stored_files.len();
+        // Files stored earlier were hashed in their place among the entries, which is lost here
+        backup_info.content_sha256 = if backup_info.stored_files.is_empty() { Some(content_sha256) } else { None };
+        // Moved files are checked by the store from now on
+        backup_info.file_checksums.retain(|checksum| !stored_files.iter().any(|stored| stored.name == checksum.name));
+        backup_info.stored_files.extend(stored_files);
+        backup_info.archive_sha256 = Some(integrity::sha256_file(&backup_info.backup_path)?);
+        backup_info.size = fs::metadata(&backup_info.backup_path)?.len();
+        backup_info.deduplicated = false;
+        if backup_info.signature.is_some() {
+            if let Some(ref signer) = self.signer {
+                backup_info.signature = signer.sign(backup_info);
+            }
+        }
+        self.save_backup_metadata(backup_info)?;
+
+        debug!("Repacked {}: {} files into the store", backup_info.id, moved);
+        Ok(Some((moved, old_size.saturating_sub(backup_info.size).saturating_sub(added_bytes))))
+    }
+
+    /// List all backups for a specific game
+    pub fn list_backups(&self, game_name: Option<&str>, app_id: Option<u32>) -> Result<Vec<BackupInfo>> {
+        let mut backups = Vec::new();
+
+        // Read backup metadata files
+        let metadata_pattern = "*.backup.json";
+        let entries = fs::read_dir(&self.backup_root)
+            .map_err(|e| SaveGuardianError::Io(e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| SaveGuardianError::Io(e))?;
+            let path = entry.path();
+
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+                if let Some(filename) = path.file_stem().and_then(|s| s.to_str()) {
+                    if filename.ends_with(".backup") {
+                        if let Ok(backup_info) = self.load_backup_metadata(&path) {
+                            // Filter by game name or app ID if specified
+                            let matches = match (game_name, app_id) {
+                                (Some(name), Some(id)) => backup_info.game_name.contains(name) && backup_info.app_id == Some(id),
+                                (Some(name), None) => backup_info.game_name.contains(name),
+                                (None, Some(id)) => backup_info.app_id == Some(id),
+                                (None, None) => true,
+                            };
+
+                            if matches {
+                                backups.push(backup_info);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Sort by creation date (newest first)
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(backups)
+    }
+
+    /// Delete a backup
+    pub fn delete_backup(&self, backup_info: &BackupInfo) -> Result<()> {
+        info!("Deleting backup: {}", backup_info.id);
+
+        // Its increments couldn't be restored any more
+        if let Some(dependent) = self.list_backups(None, None)?
+            .into_iter()
+            .find(|b| b.base_backup.as_deref() == Some(backup_info.id.as_str())) {
+            return Err(SaveGuardianError::BackupOperationFailed(format!(
+                "Incremental backup {} builds on this one, delete it first", dependent.id
+            )));
+        }
+
+        // Delete the backup file, or all its parts
+        volumes::remove(backup_info)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to delete backup file: {}", e)))?;
+
+        // Delete the metadata file
+        let metadata_path = self.get_metadata_path(&backup_info.id);
+        if metadata_path.exists() {
+            fs::remove_file(&metadata_path)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to delete metadata file: {}", e)))?;
+        }
+        incremental::remove_index(backup_info);
+
+        // Point latest/ at whatever backup of this game is now the newest
+        if self.keep_latest {
+            let key = Self::latest_key(backup_info);
+            let newest = self.list_backups(None, None)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|b| Self::latest_key(b) == key && volumes::archive_exists(b));
+            match newest {
+                Some(ref newest) => self.update_latest(newest),
+                None => self.remove_latest(&key),
+            }
+        }
+
+        info!("Backup deleted successfully: {}", backup_info.id);
+        Ok(())
+    }
+
+    /// Why retention cleanup leaves this backup alone, if it does
+    pub fn exemption_reason(&self, backup_info: &BackupInfo) -> Option<String> {
+        if backup_info.pinned {
+            return Some("pinned".to_string());
+        }
+        self.retention_exemptions.iter()
+            .find(|rule| rule.matches(backup_info))
+            .map(|rule| rule.describe())
+    }
+
+    /// Backups the retention policy would remove, oldest first, without touching anything
+    pub fn plan_cleanup(&self) -> Result<CleanupPlan> {
+        let now = Utc::now();
+        let cutoff_date = now - chrono::Duration::days(self.retention_days as i64);
+        let mut plan = CleanupPlan::default();
+        let backups = self.list_backups(None, None)?;
+
+        for backup in &backups {
+            if backup.created_at >= cutoff_date {
+                continue;
+            }
+            let candidate = CleanupCandidate {
+                age_days: (now - backup.created_at).num_days(),
+                backup: backup.clone(),
+            };
+            match self.exemption_reason(&candidate.backup) {
+                Some(reason) => {
+                    debug!("Keeping {} past retention: {}", candidate.backup.id, reason);
+                    plan.exempt.push((candidate, reason));
+                }
+                None => plan.candidates.push(candidate),
+            }
+        }
+
+        // Backups that kept incremental ones build on stay as long as those do
+        let by_id: HashMap<&str, &BackupInfo> = backups.iter().map(|b| (b.id.as_str(), b)).collect();
+        let mut needed: HashSet<String> = HashSet::new();
+        for backup in backups.iter().filter(|b| !plan.candidates.iter().any(|c| c.backup.id == b.id)) {
+            let mut base = backup.base_backup.clone();
+            while let Some(id) = base {
+                if !needed.insert(id.clone()) {
+                    break;
+                }
+                base = by_id.get(id.as_str()).and_then(|b| b.base_backup.clone());
+            }
+        }
+        let (kept, candidates): (Vec<CleanupCandidate>, Vec<CleanupCandidate>) = plan.candidates
+            .into_iter()
+            .partition(|c| needed.contains(&c.backup.id));
+        plan.candidates = candidates;
+        for candidate in kept {
+            debug!("Keeping {} past retention: a newer incremental backup builds on it", candidate.backup.id);
+            plan.exempt.push((candidate, "base of a newer incremental backup".to_string()));
+        }
+        plan.candidates.sort_by(|a, b| a.backup.created_at.cmp(&b.backup.created_at));
+
+        Ok(plan)
+    }
+
+    /// Delete the given backups, logging each one with its age and size
+    pub fn run_cleanup(&self, candidates: &[CleanupCandidate]) -> CleanupReport {
+        let mut report = CleanupReport::default();
+
+        // Newest first, so increments go before the backups they build on
+        let mut candidates: Vec<&CleanupCandidate> = candidates.iter().collect();
+        candidates.sort_by(|a, b| b.backup.created_at.cmp(&a.backup.created_at));
+
+        for candidate in candidates {
+            let backup = &candidate.backup;
+            match self.delete_backup(backup) {
+                Ok(_) => {
+                    info!("Deleted old backup: {} ({}, {} days old, {})",
+                        backup.id, backup.backup_path.display(), candidate.age_days, format_size(backup.size));
+                    report.freed_bytes += backup.size;
+                    report.deleted.push(candidate.clone());
+                }
+                Err(e) => {
+                    warn!("Failed to delete old backup {}: {}", backup.id, e);
+                    report.failed.push((candidate.clone(), e.to_string()));
+                }
+            }
+        }
+
+        if !report.deleted.is_empty() {
+            info!("Cleaned up {} old backups, freed {}", report.deleted.len(), format_size(report.freed_bytes));
+        }
+
+        report
+    }
+
+    /// Clean up old backups based on retention policy
+    pub fn cleanup_old_backups(&self) -> Result<CleanupReport> {
+        let plan = self.plan_cleanup()?;
+        Ok(self.run_cleanup(&plan.candidates))
+    }
+
+    /// `id`, or `id_2`, `id_3`... if a backup already has it, e.g. with a format without
+    /// seconds, or a local time repeated when the clocks go back
+    fn unused_backup_id(&self, id: &str) -> String {
+        let taken = |candidate: &str| {
+            let archive_taken = ArchiveFormat::ALL.iter().any(|format| {
+                let archive = self.backup_root.join(format!("{}.{}", candidate, format.extension()));
+                archive.exists() || volumes::volume_path(&archive, 1).exists()
+            });
+            archive_taken || self.get_metadata_path(candidate).exists()
+        };
+        let mut candidate = id.to_string();
+        let mut number = 2;
+        while taken(&candidate) {
+            candidate = format!("{}_{}", id, number);
+            number += 1;
+        }
+        candidate
+    }
+
+    fn generate_backup_id(&self, game_save: &GameSave) -> String {
+        Self::key_for_save(game_save)
+    }
+
+    /// Key of the game (and owning account) a save belongs to, matching `latest_key`
+    pub fn key_for_save(game_save: &GameSave) -> String {
+        Self::game_key(&game_save.name, game_save.steam_user_id.as_deref(), game_save.sandbox.as_ref(), game_save.app_id, &game_save.save_type)
+    }
+
+    /// Key a save had before backups were kept apart by Steam account, None when it's the same
+    fn legacy_key_for_save(game_save: &GameSave) -> Option<String> {
+        game_save.steam_user_id.as_ref()?;
+        Some(Self::game_key(&game_save.name, None, game_save.sandbox.as_ref(), game_save.app_id, &game_save.save_type))
+    }
+
+    /// The backups of a save among `backups`
+    ///
+    /// Backups made before saves were told apart by Steam account have no account in their
+    /// key. While a save has no backups under its own key, those stand in for it, so the
+    /// first backup after upgrading still builds on, or is compared with, the earlier ones.
+    pub fn backups_for_save<'a>(backups: &'a [BackupInfo], game_save: &GameSave) -> Vec<&'a BackupInfo> {
+        let key = Self::key_for_save(game_save);
+        let own: Vec<&BackupInfo> = backups.iter().filter(|b| Self::latest_key(b) == key).collect();
+        match Self::legacy_key_for_save(game_save) {
+            Some(legacy) if own.is_empty() => backups.iter()
+                .filter(|b| b.steam_user_id.is_none() && Self::latest_key(b) == legacy)
+                .collect(),
+            _ => own,
+        }
+    }
+
+    /// Filesystem-safe key identifying one game (and owning account)
+    fn game_key(name: &str, steam_user_id: Option<&str>, sandbox: Option<&Sandbox>, app_id: Option<u32>, save_type: &SaveType) -> String {
+        let game_name_clean = normalize::file_safe(name);
+        // Family Sharing: keep each account's backups of the same game apart
+        let user_part = match steam_user_id {
+            Some(user_id) => format!("_{}", user_id),
+            None => String::new(),
+        };
+        // The same game in two bottles is two sets of saves
+        let sandbox_part = match sandbox {
+            Some(sandbox) => format!("_{}", sandbox.key()),
+            None => String::new(),
+        };
+        let app_id_part = match app_id {
+            Some(id) => format!("_{}", id),
+            None => String::new(),
+        };
+        let save_type = match save_type {
+            SaveType::Steam => "steam",
+            SaveType::NonSteam => "nonsteam",
+        };
+
+        format!("{}{}{}{}_{}", game_name_clean, user_part, sandbox_part, app_id_part, save_type)
+    }
+
+    /// Key of the game (and owning account) a backup belongs to
+    pub fn latest_key(backup_info: &BackupInfo) -> String {
+        Self::game_key(&backup_info.game_name, backup_info.steam_user_id.as_deref(), backup_info.sandbox.as_ref(), backup_info.app_id, &backup_info.save_type)
+    }
+
+    /// Path of the rolling `latest/<game>.zip` entry for a game key, with the archive's extension
+    pub fn latest_path(&self, key: &str, format: ArchiveFormat) -> PathBuf {
+        self.backup_root.join("latest").join(format!("{}.{}", key, format.extension()))
+    }
+
+    /// Drop a game's `latest/` entry, in whichever format it was
+    fn remove_latest(&self, key: &str) {
+        for format in ArchiveFormat::ALL {
+            let latest_path = self.latest_path(key, format);
+            // remove_file works on the link itself, never on the archive it points to
+            if fs::symlink_metadata(&latest_path).is_ok() {
+                if let Err(e) = fs::remove_file(&latest_path) {
+                    warn!("Failed to remove {:?}: {}", latest_path, e);
+                }
+            }
+        }
+    }
+
+    /// Point a game's `latest/` entry at this backup, symlinking where possible and copying otherwise
+    fn update_latest(&self, backup_info: &BackupInfo) {
+        if !self.keep_latest {
+            return;
+        }
+        // latest/ holds whole archives; a split one has no single file to point at
+        if !backup_info.volumes.is_empty() {
+            debug!("Not linking split backup {} into latest/", backup_info.id);
+            return;
+        }
+        // Nor does an increment, which only holds the changed files
+        if backup_info.base_backup.is_some() {
+            debug!("Not linking incremental backup {} into latest/", backup_info.id);
+            return;
+        }
+
+        let key = Self::latest_key(backup_info);
+        let latest_path = self.latest_path(&key, ArchiveFormat::of_path(&backup_info.backup_path));
+        if let Some(parent) = latest_path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                warn!("Failed to create latest directory: {}", e);
+                return;
+            }
+        }
+
+        // Also drops the entry of an older backup in another format
+        self.remove_latest(&key);
+        // And the one from before backups were kept apart by Steam account, which this replaces
+        if backup_info.steam_user_id.is_some() {
+            let legacy = Self::game_key(&backup_info.game_name, None, backup_info.sandbox.as_ref(), backup_info.app_id, &backup_info.save_type);
+            self.remove_latest(&legacy);
+        }
+        if fs::symlink_metadata(&latest_path).is_ok() {
+            warn!("Failed to replace {:?}", latest_path);
+            return;
+        }
+
+        #[cfg(unix)]
+        let linked = std::os::unix::fs::symlink(&backup_info.backup_path, &latest_path);
+        // Windows only allows symlinks with Developer Mode or admin rights
+        #[cfg(windows)]
+        let linked = std::os::windows::fs::symlink_file(&backup_info.backup_path, &latest_path);
+
+        match linked {
+            Ok(()) => debug!("Linked {:?} -> {:?}", latest_path, backup_info.backup_path),
+            Err(_) => match fs::copy(&backup_info.backup_path, &latest_path) {
+                Ok(_) => debug!("Copied newest backup to {:?}", latest_path),
+                Err(e) => warn!("Failed to update {:?}: {}", latest_path, e),
+            },
+        }
+    }
+
+    /// Point a game's `latest/` entry at its newest backup again, or drop it once the game has none
+    fn refresh_latest(&self, key: &str) {
+        if !self.keep_latest {
+            return;
+        }
+
+        let newest = match self.list_backups(None, None) {
+            Ok(backups) => backups.into_iter()
+                .filter(|b| Self::latest_key(b) == key)
+                .max_by_key(|b| b.created_at),
+            Err(e) => {
+                warn!("Failed to list backups for {}: {}", key, e);
+                return;
+            }
+        };
+
+        match newest {
+            Some(backup_info) => self.update_latest(&backup_info),
+            None => self.remove_latest(key),
+        }
+    }
+
+    /// Register an archive downloaded from a link as a backup of `game`
+    ///
+    /// The staging file is renamed like the game's own backups. Nothing is known about the
+    /// machine or the files' checksums; the archive hash is that of the download.
+    pub fn import_archive(&self, downloaded: &DownloadedArchive, game: &GameIdentity, original_path: PathBuf, description: Option<String>) -> Result<BackupInfo> {
+        let created_at = Utc::now();
+        let key = Self::game_key(&game.name, game.steam_user_id.as_deref(), None, game.app_id, &game.save_type);
+        let backup_id = self.unused_backup_id(&format!("{}_{}", key, filename_timestamp(created_at, &self.timestamp)));
+        let backup_path = self.backup_root.join(format!("{}.{}", backup_id, downloaded.format.extension()));
+
+        fs::rename(&downloaded.path, &backup_path)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to move the downloaded archive into place: {}", e)))?;
+
+        let mut backup_info = BackupInfo {
+            id: backup_id,
+            game_name: game.name.clone(),
+            app_id: game.app_id,
+            save_type: game.save_type.clone(),
+            original_path,
+            backup_path,
+            created_at,
+            size: downloaded.size,
+            description: description.or_else(|| Some(format!("📥 Imported from {}", downloaded.url))),
+            archive_sha256: Some(downloaded.sha256.clone()),
+            signature: None,
+            steam_user_id: game.steam_user_id.clone(),
+            sandbox: None,
+            source_size: 0,
+            excluded_files: 0,
+            excluded_bytes: 0,
+            pinned: false,
+            tags: vec!["imported".to_string()],
+            content_sha256: None,
+            deduplicated: false,
+            source_fingerprint: None,
+            verified_unchanged_at: None,
+            machine: None,
+            settings_files: Vec::new(),
+            stored_files: Vec::new(),
+            file_checksums: Vec::new(),
+            save_metadata: downloaded.save_metadata.clone(),
+            volumes: Vec::new(),
+            duration_ms: None,
+            base_backup: None,
+            removed_files: Vec::new(),
+            encrypted: false,
+        };
+
+        if let Some(ref signer) = self.signer {
+            backup_info.signature = signer.sign(&backup_info);
+        }
+        self.save_backup_metadata(&backup_info)?;
+        self.update_latest(&backup_info);
+
+        info!("Imported {} as backup {} of {}", downloaded.file_name, backup_info.id, backup_info.game_name);
+        Ok(backup_info)
+    }
+
+    /// Write changed metadata (pin, tags, description) back next to the archive
+    pub fn update_backup_metadata(&self, backup_info: &BackupInfo) -> Result<()> {
+        self.save_backup_metadata(backup_info)
+    }
+
+    /// Apply one edit to many backups, e.g. to tidy up an imported catalog
+    pub fn edit_backups(&self, backups: &[BackupInfo], edit: &BackupEdit) -> BulkEditReport {
+        let mut report = BulkEditReport::default();
+        let mut moved_keys: Vec<String> = Vec::new();
+
+        for backup in backups {
+            let mut edited = backup.clone();
+            if !edit.apply(&mut edited) {
+                report.unchanged += 1;
+                continue;
+            }
+
+            // The game name is part of the signed manifest; re-sign only what was genuine before
+            if edited.game_name != backup.game_name {
+                if let (Some(signature), Some(signer)) = (&backup.signature, &self.signer) {
+                    if signer.verify(backup, signature) {
+                        edited.signature = signer.sign(&edited);
+                    } else {
+                        warn!("Backup {} has an invalid signature, not re-signing it for the new game", backup.id);
+                    }
+                }
+            }
+
+            match self.save_backup_metadata(&edited) {
+                Ok(()) => {
+                    report.updated += 1;
+                    let (old_key, new_key) = (Self::latest_key(backup), Self::latest_key(&edited));
+                    if old_key != new_key {
+                        for key in [old_key, new_key] {
+                            if !moved_keys.contains(&key) {
+                                moved_keys.push(key);
+                            }
+                        }
+                    }
+                }
+                Err(e) => report.failed.push((backup.game_name.clone(), e.to_string())),
+            }
+        }
+
+        for key in &moved_keys {
+            self.refresh_latest(key);
+        }
+
+        info!("Bulk edit: {} updated, {} unchanged, {} failed", report.updated, report.unchanged, report.failed.len());
+        report
+    }
+
+    /// Save backup metadata to a JSON file
+    fn save_backup_metadata(&self, backup_info: &BackupInfo) -> Result<()> {
+        let metadata_path = self.get_metadata_path(&backup_info.id);
+        let metadata_json = serde_json::to_string_pretty(backup_info)
+            .map_err(|e| SaveGuardianError::Serde(e))?;
+
+        fs::write(&metadata_path, metadata_json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save metadata: {}", e)))?;
+
+        debug!("Saved backup metadata: {:?}", metadata_path);
+        Ok(())
+    }
+
+    /// Load backup metadata from a JSON file
+    fn load_backup_metadata(&self, metadata_path: &PathBuf) -> Result<BackupInfo> {
+        let metadata_json = fs::read_to_string(metadata_path)
+            .map_err(|e| SaveGuardianError::Io(e))?;
+
+        let backup_info: BackupInfo = serde_json::from_str(&metadata_json)
+            .map_err(|e| SaveGuardianError::Serde(e))?;
+
+        Ok(backup_info)
+    }
+
+    /// Get the metadata file path for a backup ID
+    fn get_metadata_path(&self, backup_id: &str) -> PathBuf {
+        self.backup_root.join(format!("{}.backup.json", backup_id))
+    }
+
+    /// Get backup statistics over backups already listed, without reading their metadata again
+    pub fn stats_for(all_backups: &[BackupInfo]) -> BackupStats {
+        let total_count = all_backups.len();
+        let total_size = all_backups.iter().map(|b| b.size).sum();
+        let deduplicated_count = all_backups.iter().filter(|b| b.deduplicated).count();
+        let deduplicated_bytes = all_backups.iter().filter(|b| b.deduplicated).map(|b| b.size).sum();
+
+        let mut steam_count = 0;
+        let mut non_steam_count = 0;
+        let mut oldest_backup = None;
+        let mut newest_backup = None;
+
+        for backup in all_backups {
+            match backup.save_type {
+                SaveType::Steam => steam_count += 1,
+                SaveType::NonSteam => non_steam_count += 1,
+            }
+
+            if oldest_backup.is_none() || backup.created_at < oldest_backup.unwrap() {
+                oldest_backup = Some(backup.created_at);
+            }
+
+            if newest_backup.is_none() || backup.created_at > newest_backup.unwrap() {
+                newest_backup = Some(backup.created_at);
+            }
+        }
+
+        BackupStats {
+            total_count,
+            total_size,
+            deduplicated_count,
+            deduplicated_bytes,
+            steam_count,
+            non_steam_count,
+            oldest_backup,
+            newest_backup,
+        }
+    }
+    
+    /// Open the backup folder in the system file explorer
+    pub fn open_backup_folder(&self, backup_info: &BackupInfo) -> Result<()> {
+        let folder_path = if backup_info.backup_path.is_file() || !backup_info.volumes.is_empty() {
+            backup_info.backup_path.parent().unwrap_or(&self.backup_root)
+        } else {
+            &backup_info.backup_path
+        };
+        
+        // Selecting the archive; the first part stands in for a split one
+        #[cfg(windows)]
+        {
+            std::process::Command::new("explorer")
+                .arg("/select,")
+                .arg(&volumes::archive_files(backup_info)[0])
+                .spawn()
+                .map_err(|e| SaveGuardianError::Io(e))?;
+        }
+        
+        #[cfg(target_os = "macos")]
+        {
+            std::process::Command::new("open")
+                .arg("-R")
+                .arg(&volumes::archive_files(backup_info)[0])
+                .spawn()
+                .map_err(|e| SaveGuardianError::Io(e))?;
+        }
+        
+        #[cfg(target_os = "linux")]
+        {
+            // Try to open the folder with the default file manager
+            std::process::Command::new("xdg-open")
+                .arg(folder_path)
+                .spawn()
+                .map_err(|e| SaveGuardianError::Io(e))?;
+        }
+        
+        info!("Opened backup folder: {:?}", folder_path);
+        Ok(())
+    }
+}
+
+/// A backup that is past the retention period
+#[derive(Debug, Clone)]
+pub struct CleanupCandidate {
+    pub backup: BackupInfo,
+    pub age_days: i64,
+}
+
+/// Outcome of a cleanup dry run
+#[derive(Debug, Clone, Default)]
+pub struct CleanupPlan {
+    pub candidates: Vec<CleanupCandidate>,
+    pub exempt: Vec<(CleanupCandidate, String)>, // Past retention but kept, with the reason
+}
+
+/// What a cleanup run removed
+#[derive(Debug, Clone, Default)]
+pub struct CleanupReport {
+    pub deleted: Vec<CleanupCandidate>,
+    pub failed: Vec<(CleanupCandidate, String)>,
+    pub freed_bytes: u64,
+}
+
+impl CleanupReport {
+    pub fn describe(&self) -> String {
+        if self.failed.is_empty() {
+            format!("Cleaned up {} old backups ({} freed)", self.deleted.len(), format_size(self.freed_bytes))
+        } else {
+            format!("Cleaned up {} old backups ({} freed), {} could not be deleted",
+                self.deleted.len(), format_size(self.freed_bytes), self.failed.len())
+        }
+    }
+
+    /// One line per backup, for the cleanup log
+    pub fn log_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.deleted.iter()
+            .map(|c| format!("Deleted {} from {} ({} days old, {})",
+                c.backup.game_name,
+                c.backup.created_at.format("%Y-%m-%d %H:%M"),
+                c.age_days,
+                format_size(c.backup.size)))
+            .collect();
+        lines.extend(self.failed.iter().map(|(c, error)| format!("Failed to delete {} from {}: {}",
+            c.backup.game_name, c.backup.created_at.format("%Y-%m-%d %H:%M"), error)));
+        lines
+    }
+}
+
+/// The game a backup belongs to, as recorded in its metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameIdentity {
+    pub name: String,
+    pub app_id: Option<u32>,
+    pub save_type: SaveType,
+    pub steam_user_id: Option<String>,
+}
+
+impl GameIdentity {
+    pub fn of_save(game_save: &GameSave) -> Self {
+        Self {
+            name: game_save.name.clone(),
+            app_id: game_save.app_id,
+            save_type: game_save.save_type.clone(),
+            steam_user_id: game_save.steam_user_id.clone(),
+        }
+    }
+
+    pub fn of_backup(backup_info: &BackupInfo) -> Self {
+        Self {
+            name: backup_info.game_name.clone(),
+            app_id: backup_info.app_id,
+            save_type: backup_info.save_type.clone(),
+            steam_user_id: backup_info.steam_user_id.clone(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut label = match self.app_id {
+            Some(app_id) => format!("{} ({})", self.name, app_id),
+            None => self.name.clone(),
+        };
+        if let Some(ref user_id) = self.steam_user_id {
+            label.push_str(&format!(" · 👤 {}", user_id));
+        }
+        label
+    }
+}
+
+/// Backups filed under a game the scan no longer finds, e.g. after it was renamed or re-detected
+#[derive(Debug, Clone)]
+pub struct ReassignSuggestion {
+    pub from: GameIdentity,
+    pub backups: Vec<BackupInfo>,
+    pub to: Option<GameIdentity>, // Best guess among the scanned games
+    pub reason: Option<String>,   // Why that guess was made
+}
+
+/// Group backups whose game matches no scanned save and guess where they belong now
+pub fn suggest_reassignments(backups: &[BackupInfo], saves: &[GameSave]) -> Vec<ReassignSuggestion> {
+    let current: Vec<GameIdentity> = saves.iter().map(GameIdentity::of_save).collect();
+    let mut suggestions: Vec<ReassignSuggestion> = Vec::new();
+
+    for backup in backups {
+        let identity = GameIdentity::of_backup(backup);
+        if current.contains(&identity) {
+            continue;
+        }
+        match suggestions.iter_mut().find(|s| s.from == identity) {
+            Some(suggestion) => suggestion.backups.push(backup.clone()),
+            None => suggestions.push(ReassignSuggestion {
+                from: identity,
+                backups: vec![backup.clone()],
+                to: None,
+                reason: None,
+            }),
+        }
+    }
+
+    for suggestion in &mut suggestions {
+        // The save folder is the strongest hint, the app ID only helps when the account matches too
+        let same_folder = saves.iter().find(|save| suggestion.backups.iter().any(|b| same_path(&b.original_path, &save.save_path)));
+        let same_app = saves.iter().find(|save| {
+            suggestion.from.app_id.is_some()
+                && save.app_id == suggestion.from.app_id
+                && save.steam_user_id == suggestion.from.steam_user_id
+        });
+
+        if let Some(save) = same_folder {
+            suggestion.to = Some(GameIdentity::of_save(save));
+            suggestion.reason = Some("same save folder".to_string());
+        } else if let Some(save) = same_app {
+            suggestion.to = Some(GameIdentity::of_save(save));
+            suggestion.reason = Some(format!("same app ID {}", save.app_id.unwrap_or_default()));
+        }
+    }
+
+    suggestions.sort_by(|a, b| b.to.is_some().cmp(&a.to.is_some()).then_with(|| a.from.name.cmp(&b.from.name)));
+    suggestions
+}
+
+/// Games whose newest backup was made on another machine than any backup made here
+///
+/// Keyed by `BackupManager::latest_key`; the value is that newest backup. Games never
+/// backed up on this machine are left out, there is nothing here for them to be newer than.
+pub fn newer_on_other_machines<'a>(backups: impl IntoIterator<Item = &'a BackupInfo>, machine_id: &str) -> HashMap<String, BackupInfo> {
+    let mut newest: HashMap<String, &BackupInfo> = HashMap::new();
+    let mut newest_here: HashMap<String, chrono::DateTime<Utc>> = HashMap::new();
+
+    for backup in backups {
+        let key = BackupManager::latest_key(backup);
+        if backup.machine.as_ref().map_or(false, |m| m.id == machine_id) {
+            let time = newest_here.entry(key.clone()).or_insert(backup.created_at);
+            *time = (*time).max(backup.created_at);
+        }
+        match newest.get(&key) {
+            Some(current) if current.created_at >= backup.created_at => {}
+            _ => {
+                newest.insert(key, backup);
+            }
+        }
+    }
+
+    newest.into_iter()
+        .filter(|(key, backup)| {
+            let elsewhere = backup.machine.as_ref().map_or(false, |m| m.id != machine_id);
+            elsewhere && newest_here.get(key).map_or(false, |here| backup.created_at > *here)
+        })
+        .map(|(key, backup)| (key, backup.clone()))
+        .collect()
+}
+
+/// The creation time as written into an archive name, e.g. `20240101_120000`
+///
+/// Characters no file system accepts are replaced; an invalid format falls back to the default.
+pub fn filename_timestamp(created_at: chrono::DateTime<Utc>, config: &FilenameTimestampConfig) -> String {
+    let format = match timestamp_format_error(&config.format) {
+        None => config.format.as_str(),
+        Some(error) => {
+            warn!("Backup file name format {:?}: {}, using the default", config.format, error);
+            FilenameTimestampConfig::DEFAULT_FORMAT
+        }
+    };
+    let formatted = if config.local_time {
+        created_at.with_timezone(&chrono::Local).format(format).to_string()
+    } else {
+        created_at.format(format).to_string()
+    };
+    formatted
+        .chars()
+        .map(|c| if c.is_control() || c.is_whitespace() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '-' } else { c })
+        .collect()
+}
+
+/// Why a file name timestamp format can't be used, if it can't
+pub fn timestamp_format_error(format: &str) -> Option<&'static str> {
+    use chrono::format::{Item, StrftimeItems};
+    if format.trim().is_empty() {
+        return Some("the format is empty");
+    }
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Some("not a valid strftime format");
+    }
+    if !items.iter().any(|item| matches!(item, Item::Numeric(..) | Item::Fixed(..))) {
+        return Some("the format contains no date or time");
+    }
+    None
+}
+
+/// Compare two save folders the way Windows does: case-insensitive, either slash, no trailing one
+fn same_path(a: &PathBuf, b: &PathBuf) -> bool {
+    let normalize = |p: &PathBuf| p.to_string_lossy().replace('\\', "/").trim_end_matches('/').to_lowercase();
+    !a.as_os_str().is_empty() && normalize(a) == normalize(b)
+}
+
+/// Changes made to every selected backup at once; unset fields are left alone
+#[derive(Debug, Clone, Default)]
+pub struct BackupEdit {
+    pub add_tag: Option<String>,
+    pub description_prefix: Option<(String, String)>, // Replace this prefix with that one; an empty old prefix prepends
+    pub reassign_to: Option<GameIdentity>,
+    pub pinned: Option<bool>,
+}
+
+impl BackupEdit {
+    pub fn is_empty(&self) -> bool {
+        self.add_tag.is_none() && self.description_prefix.is_none() && self.reassign_to.is_none() && self.pinned.is_none()
+    }
+
+    /// Apply the edit to one backup, returning whether anything changed
+    pub fn apply(&self, backup_info: &mut BackupInfo) -> bool {
+        let mut changed = false;
+
+        if let Some(ref tag) = self.add_tag {
+            let tag = tag.trim();
+            if !tag.is_empty() && !backup_info.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                backup_info.tags.push(tag.to_string());
+                changed = true;
+            }
+        }
+
+        if let Some((ref old_prefix, ref new_prefix)) = self.description_prefix {
+            let description = backup_info.description.clone().unwrap_or_default();
+            let updated = if old_prefix.is_empty() {
+                Some(format!("{}{}", new_prefix, description))
+            } else {
+                description.strip_prefix(old_prefix.as_str()).map(|rest| format!("{}{}", new_prefix, rest))
+            };
+            if let Some(updated) = updated {
+                if updated != description {
+                    backup_info.description = if updated.is_empty() { None } else { Some(updated) };
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some(ref identity) = self.reassign_to {
+            if &GameIdentity::of_backup(backup_info) != identity {
+                backup_info.game_name = identity.name.clone();
+                backup_info.app_id = identity.app_id;
+                backup_info.save_type = identity.save_type.clone();
+                backup_info.steam_user_id = identity.steam_user_id.clone();
+                changed = true;
+            }
+        }
+
+        if let Some(pinned) = self.pinned {
+            if backup_info.pinned != pinned {
+                backup_info.pinned = pinned;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+/// What a bulk edit changed
+#[derive(Debug, Clone, Default)]
+pub struct BulkEditReport {
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: Vec<(String, String)>, // Game name, error
+}
+
+impl BulkEditReport {
+    pub fn describe(&self) -> String {
+        if self.failed.is_empty() {
+            format!("Updated {} backups ({} already matched)", self.updated, self.unchanged)
+        } else {
+            format!("Updated {} backups, {} could not be saved: {}", self.updated, self.failed.len(), self.failed[0].1)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupStats {
+    pub total_count: usize,
+    pub total_size: u64, // Counts hard-linked duplicates at full size
+    #[serde(default)]
+    pub deduplicated_count: usize,
+    #[serde(default)]
+    pub deduplicated_bytes: u64, // Space saved by hard-linking duplicate archives
+    pub steam_count: usize,
+    pub non_steam_count: usize,
+    pub oldest_backup: Option<chrono::DateTime<Utc>>,
+    pub newest_backup: Option<chrono::DateTime<Utc>>,
+}
+
+impl BackupStats {
+    pub fn format_total_size(&self) -> String {
+        if self.total_size < 1024 {
+            format!("{} B", self.total_size)
+        } else if self.total_size < 1024 * 1024 {
+            format!("{:.1} KB", self.total_size as f64 / 1024.0)
+        } else if self.total_size < 1024 * 1024 * 1024 {
+            format!("{:.1} MB", self.total_size as f64 / (1024.0 * 1024.0))
+        } else {
+            format!("{:.1} GB", self.total_size as f64 / (1024.0 * 1024.0 * 1024.0))
+        }
+    }
+}
+
+/// Sibling folder a restore into `restore_path` is extracted to first
+fn staging_path(restore_path: &Path) -> PathBuf {
+    let name = restore_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "restore".to_string());
+    restore_path.with_file_name(format!("{}{}", name, STAGING_SUFFIX))
+}
+
+/// Move everything extracted into `staging` over the same paths under `target`
+///
+/// Files at the target that the backup doesn't have are left alone, as when extracting in place.
+fn move_into_place(staging: &Path, target: &Path) -> Result<()> {
+    fs::create_dir_all(target)
+        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create restore directory: {}", e)))?;
+    for entry in WalkDir::new(staging).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read the extracted files: {}", e)))?;
+        let relative = entry.path().strip_prefix(staging)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Path error: {}", e)))?;
+        let destination = target.join(relative);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create directory: {}", e)))?;
+        } else if fs::rename(entry.path(), &destination).is_err() {
+            // e.g. the staging folder ended up on another drive through a mount point
+            fs::copy(entry.path(), &destination)
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to restore {:?}: {}", destination, e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fail clearly on entry names a ZIP header can't hold, instead of writing a broken archive
+fn check_entry_name(name: &str) -> Result<()> {
+    if name.len() > MAX_ENTRY_NAME_BYTES {
+        return Err(SaveGuardianError::BackupOperationFailed(format!(
+            "Path too long for a ZIP archive ({} bytes, at most {}): {}...",
+            name.len(), MAX_ENTRY_NAME_BYTES, name.chars().take(80).collect::<String>()
+        )));
+    }
+    Ok(())
+}
+
+/// Content hash input for a file kept in the dedup store: its object hash stands in for the data
+fn hash_stored_entry(hasher: &mut Sha256, name: &str, len: u64, sha256: &str) {
+    hasher.update(name.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(len.to_le_bytes());
+    hasher.update(sha256.as_bytes());
+}
+
+/// Feeds what an archive reads of a file into the content hash and the file's own
+/// checksum, checking for cancellation
+///
+/// A cancellation can only leave the archive writer as an I/O error, so it's kept in
+/// `failure` to be returned as itself.
+struct HashingReader<'a> {
+    inner: &'a mut dyn Read,
+    hasher: &'a mut Sha256,
+    file_hasher: Sha256,
+    check: &'a dyn Fn() -> Result<()>,
+    failure: Option<SaveGuardianError>,
+}
+
+impl Read for HashingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Err(e) = (self.check)() {
+            let message = e.to_string();
+            self.failure = Some(e);
+            return Err(io::Error::new(io::ErrorKind::Other, message));
+        }
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.file_hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// The checksum recorded for a file read in one go
+fn checksum_of(name: &str, data: &[u8]) -> FileChecksum {
+    FileChecksum { name: name.to_string(), sha256: integrity::sha256_bytes(data), size: data.len() as u64 }
+}
+
+/// Feed one archive entry into the content hash
+fn hash_entry(hasher: &mut Sha256, name: &str, data: &[u8]) {
+    hasher.update(name.as_bytes());
+    hasher.update([0u8]);
+    hasher.update((data.len() as u64).to_le_bytes());
+    hasher.update(data);
+}
@@ -0,0 +1,156 @@
+use crate::types::*;
+use super::{CloudEntry, CloudProvider, TransferCounter};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use rand::RngCore;
+use std::sync::Arc;
+
+/// Start of every archive we encrypted, including the format version
+const MAGIC: &[u8; 8] = b"SGCRYPT1";
+
+/// Start of local backup archives sealed with the backup passphrase, see encryption.rs;
+/// a cloud download starting like this is passed through as it is
+pub const BACKUP_MAGIC: &[u8; 8] = b"SGBACKP1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// How much larger an encrypted archive is than the plain one
+pub const OVERHEAD: u64 = (MAGIC.len() + SALT_LEN + NONCE_LEN + TAG_LEN) as u64;
+
+/// AES-256-GCM with a key derived from the user's passphrase
+///
+/// Every file gets its own salt and nonce: `MAGIC | salt | nonce | ciphertext and tag`.
+/// The key is derived with Argon2id, so guessing passphrases is slow.
+#[derive(Clone)]
+pub struct CloudCipher {
+    passphrase: String,
+    magic: &'static [u8; 8],
+}
+
+impl CloudCipher {
+    pub fn new(passphrase: &str) -> Self {
+        Self { passphrase: passphrase.to_string(), magic: MAGIC }
+    }
+
+    /// The same encryption for local backup archives, marked with `BACKUP_MAGIC`
+    pub fn for_backups(passphrase: &str) -> Self {
+        Self { passphrase: passphrase.to_string(), magic: BACKUP_MAGIC }
+    }
+
+    /// The cipher for the encryption settings, if encryption is on and has a passphrase
+    pub fn from_config(config: &CloudEncryptionConfig) -> Option<Self> {
+        (config.enabled && !config.passphrase.is_empty()).then(|| Self::new(&config.passphrase))
+    }
+
+    pub fn encrypt(&self, plain: &[u8]) -> Result<Vec<u8>> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let sealed = self.cipher(&salt)?
+            .encrypt(Nonce::from_slice(&nonce), plain)
+            .map_err(|_| SaveGuardianError::BackupOperationFailed("Failed to encrypt archive".to_string()))?;
+
+        let mut data = Vec::with_capacity(sealed.len() + MAGIC.len() + SALT_LEN + NONCE_LEN);
+        data.extend_from_slice(self.magic);
+        data.extend_from_slice(&salt);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&sealed);
+        Ok(data)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !data.starts_with(self.magic) || data.len() < OVERHEAD as usize {
+            return Err(SaveGuardianError::BackupOperationFailed("Not an encrypted archive".to_string()));
+        }
+        let (salt, rest) = data[MAGIC.len()..].split_at(SALT_LEN);
+        let (nonce, sealed) = rest.split_at(NONCE_LEN);
+
+        self.cipher(salt)?
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| SaveGuardianError::BackupOperationFailed(
+                "Failed to decrypt archive: wrong passphrase or damaged file".to_string()
+            ))
+    }
+
+    fn cipher(&self, salt: &[u8]) -> Result<Aes256Gcm> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to derive encryption key: {}", e)))?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+}
+
+/// Whether data was encrypted by `CloudCipher`
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypts uploads when a cipher is set and decrypts encrypted downloads
+///
+/// Plain files already in the cloud still download as they are, so encryption can be
+/// switched on for an existing folder. Names stay the same; only contents are hidden.
+pub struct EncryptedProvider {
+    inner: Box<dyn CloudProvider>,
+    cipher: Option<CloudCipher>,
+}
+
+impl EncryptedProvider {
+    pub fn new(inner: Box<dyn CloudProvider>, cipher: Option<CloudCipher>) -> Self {
+        Self { inner, cipher }
+    }
+}
+
+impl CloudProvider for EncryptedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn test_connection(&self) -> Result<()> {
+        self.inner.test_connection()
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<CloudEntry>> {
+        self.inner.list(path)
+    }
+
+    fn stat(&self, path: &str) -> Result<Option<CloudEntry>> {
+        self.inner.stat(path)
+    }
+
+    fn upload_tracked(&self, path: &str, data: &[u8], counter: &Arc<TransferCounter>) -> Result<()> {
+        match self.cipher {
+            Some(ref cipher) => self.inner.upload_tracked(path, &cipher.encrypt(data)?, counter),
+            None => self.inner.upload_tracked(path, data, counter),
+        }
+    }
+
+    fn download_tracked(&self, path: &str, counter: &Arc<TransferCounter>) -> Result<Vec<u8>> {
+        let data = self.inner.download_tracked(path, counter)?;
+        if !is_encrypted(&data) {
+            return Ok(data);
+        }
+        match self.cipher {
+            Some(ref cipher) => cipher.decrypt(&data),
+            None => Err(SaveGuardianError::BackupOperationFailed(format!(
+                "{} is encrypted; enter the passphrase under Cloud Sync in Settings", path
+            ))),
+        }
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        self.inner.delete(path)
+    }
+
+    fn mkdir(&self, path: &str) -> Result<()> {
+        self.inner.mkdir(path)
+    }
+
+    fn share_link(&self, path: &str, expires: Option<chrono::NaiveDate>) -> Result<String> {
+        self.inner.share_link(path, expires)
+    }
+}
@@ -0,0 +1,224 @@
+use crate::types::*;
+use std::net::ToSocketAddrs;
+use std::time::Instant;
+use log::info;
+
+/// Contents of the probe file written to check write access
+const PROBE_CONTENT: &[u8] = b"Save Guardian connection test, safe to delete";
+
+/// How one step of the connection diagnostics went
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Passed,
+    Failed,
+    Skipped, // An earlier step failed, so this one couldn't run
+}
+
+/// One check of the connection diagnostics, with what to do about a failure
+#[derive(Debug, Clone)]
+pub struct DiagnosticStep {
+    pub name: &'static str,
+    pub outcome: StepOutcome,
+    pub detail: String,
+    pub hint: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of checking a cloud destination step by step, see `diagnose`
+#[derive(Debug, Clone)]
+pub struct ConnectionReport {
+    pub destination: String, // e.g. "Koofr https://app.koofr.net/dav/Koofr (me@example.com)"
+    pub steps: Vec<DiagnosticStep>,
+}
+
+impl ConnectionReport {
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|step| step.outcome == StepOutcome::Passed)
+    }
+
+    /// The first step that failed, the one to fix first
+    pub fn first_failure(&self) -> Option<&DiagnosticStep> {
+        self.steps.iter().find(|step| step.outcome == StepOutcome::Failed)
+    }
+
+    fn run(&mut self, name: &'static str, check: impl FnOnce() -> std::result::Result<String, (String, Option<String>)>) -> bool {
+        if self.first_failure().is_some() {
+            self.steps.push(DiagnosticStep { name, outcome: StepOutcome::Skipped, detail: String::new(), hint: None, duration_ms: 0 });
+            return false;
+        }
+        let started = Instant::now();
+        let result = check();
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let step = match result {
+            Ok(detail) => DiagnosticStep { name, outcome: StepOutcome::Passed, detail, hint: None, duration_ms },
+            Err((detail, hint)) => DiagnosticStep { name, outcome: StepOutcome::Failed, detail, hint, duration_ms },
+        };
+        info!("Connection check \"{}\": {:?} {}", name, step.outcome, step.detail);
+        let passed = step.outcome == StepOutcome::Passed;
+        self.steps.push(step);
+        passed
+    }
+}
+
+/// Check a cloud destination one step at a time: the server's address, signing in, the sync
+/// folder, and writing, reading back and deleting a probe file
+///
+/// Every step after the first failure is skipped, so the report points at what to fix.
+pub fn diagnose(config: &Config) -> ConnectionReport {
+    let mut report = ConnectionReport {
+        destination: format!("{} {}", config.cloud_name(), config.cloud_endpoint()),
+        steps: Vec::new(),
+    };
+
+    match config.cloud_backend {
+        CloudBackend::WebDav => {
+            let url = config.koofr_config.server_url.trim().to_string();
+            report.run("Server address", || resolve_url(&url));
+        }
+        CloudBackend::Sftp => {
+            let address = format!("{}:{}", config.sftp_config.host.trim(), config.sftp_config.port);
+            report.run("Server address", || resolve(&address));
+        }
+        CloudBackend::Folder => {
+            let path = config.folder_target.path.clone();
+            report.run("Folder reachable", || {
+                if path.is_dir() {
+                    Ok(format!("{} is there", path.display()))
+                } else {
+                    Err((
+                        format!("{} not found", path.display()),
+                        Some("Connect the drive or mount the network share, or pick another folder".to_string()),
+                    ))
+                }
+            });
+        }
+    }
+
+    let provider = super::provider_from_config(config);
+    let provider = provider.as_ref();
+    report.run("Sign in", || {
+        provider.test_connection()
+            .map(|()| format!("Connected to {}", provider.name()))
+            .map_err(|e| (e.to_string(), sign_in_hint(config, &e)))
+    });
+
+    let folder = config.effective_sync_folder();
+    report.run("Sync folder", || match provider.list("") {
+        Ok(entries) => Ok(format!("/{} holds {} entries", folder.trim_start_matches('/'), entries.len())),
+        Err(SaveGuardianError::PathNotFound(_)) => provider.mkdir("")
+            .map(|()| format!("/{} didn't exist and was created", folder.trim_start_matches('/')))
+            .map_err(|e| (
+                format!("/{} doesn't exist and couldn't be created: {}", folder.trim_start_matches('/'), e),
+                Some("Create the folder in the provider's web interface, or change the sync folder in Settings".to_string()),
+            )),
+        Err(e) => Err((e.to_string(), Some("The account can sign in but not list the sync folder; check its permissions".to_string()))),
+    });
+
+    let probe = format!(".save-guardian-probe-{:08x}", rand::random::<u32>());
+    report.run("Write access", || {
+        provider.upload(&probe, PROBE_CONTENT)
+            .map(|()| format!("Wrote {}", probe))
+            .map_err(|e| (e.to_string(), Some("The account is read-only here; check the folder's sharing permissions or pick another folder".to_string())))
+    });
+
+    let encrypted = config.cloud_encryption.enabled;
+    report.run("Read back", || match provider.download(&probe) {
+        Ok(data) if data == PROBE_CONTENT => Ok(format!("Read {} bytes back unchanged", data.len())),
+        Ok(data) => Err((
+            format!("Read {} bytes back, but they differ from what was written", data.len()),
+            Some("Something between here and the server changes files, e.g. a proxy or antivirus".to_string()),
+        )),
+        Err(e) => Err((
+            e.to_string(),
+            Some(if encrypted {
+                "Check the encryption passphrase".to_string()
+            } else {
+                "Files can be written but not read back; check the server's permissions".to_string()
+            }),
+        )),
+    });
+
+    let written = report.steps.iter().any(|s| s.name == "Write access" && s.outcome == StepOutcome::Passed);
+    if written {
+        // Runs even after a failed read, the probe shouldn't stay behind
+        let started = Instant::now();
+        let cleanup = provider.delete(&probe);
+        let duration_ms = started.elapsed().as_millis() as u64;
+        report.steps.push(match cleanup {
+            Ok(()) => DiagnosticStep { name: "Delete access", outcome: StepOutcome::Passed, detail: format!("Deleted {}", probe), hint: None, duration_ms },
+            Err(e) => DiagnosticStep {
+                name: "Delete access",
+                outcome: StepOutcome::Failed,
+                detail: e.to_string(),
+                hint: Some(format!("Backups can be uploaded, but cleaning up fails; delete {} by hand", probe)),
+                duration_ms,
+            },
+        });
+    } else {
+        report.steps.push(DiagnosticStep { name: "Delete access", outcome: StepOutcome::Skipped, detail: String::new(), hint: None, duration_ms: 0 });
+    }
+
+    report
+}
+
+fn resolve_url(url: &str) -> std::result::Result<String, (String, Option<String>)> {
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+        None => return Err((
+            format!("{:?} is not a URL", url),
+            Some("The server URL starts with https://, e.g. https://app.koofr.net/dav/Koofr".to_string()),
+        )),
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let has_port = host.rsplit_once(':').map_or(false, |(_, port)| port.chars().all(|c| c.is_ascii_digit()));
+    let address = if has_port {
+        host.to_string()
+    } else {
+        format!("{}:{}", host, if scheme == "http" { 80 } else { 443 })
+    };
+    resolve(&address)
+}
+
+fn resolve(address: &str) -> std::result::Result<String, (String, Option<String>)> {
+    match address.to_socket_addrs() {
+        Ok(mut addresses) => match addresses.next() {
+            Some(resolved) => Ok(format!("{} is {}", address, resolved.ip())),
+            None => Err((format!("{} has no address", address), Some("Check the server name for typos".to_string()))),
+        },
+        Err(e) => Err((
+            format!("Could not look up {}: {}", address, e),
+            Some("Check the server name for typos and that this PC is online (DNS, VPN, proxy)".to_string()),
+        )),
+    }
+}
+
+/// What to try when signing in failed, from the error the provider gave
+fn sign_in_hint(config: &Config, error: &SaveGuardianError) -> Option<String> {
+    let server = config.koofr_config.server;
+    let message = error.to_string();
+    let status = error.http_status();
+    let hint = if status == Some(401) {
+        match server {
+            WebDavServer::Koofr => "Koofr refuses the login: use your Koofr email and an app password (Account Settings > Passwords), not your account password".to_string(),
+            WebDavServer::Nextcloud | WebDavServer::OwnCloud => format!("{} refuses the login: with two-factor login on, create an app password at Settings > Security", server.label()),
+            WebDavServer::Generic => "The server refuses the login: check the user name and password".to_string(),
+        }
+    } else if status == Some(403) {
+        "The login works but this account may not use the URL; check the WebDAV path".to_string()
+    } else if matches!(status, Some(404) | Some(405)) {
+        format!("The server has no WebDAV folder at this URL. {}", server.url_hint())
+    } else if message.contains("authenticate") {
+        match config.sftp_config.auth {
+            SftpAuth::Password => "The SSH server refuses the login: check the user name and password, and that password logins are allowed".to_string(),
+            SftpAuth::KeyFile => "The SSH server refuses the key: check the user name, the key file and its passphrase, and that the public key is in authorized_keys".to_string(),
+        }
+    } else if message.to_lowercase().contains("host key") {
+        "The server's host key changed or is unknown; make sure it really is your server before trusting it".to_string()
+    } else if message.to_lowercase().contains("timed out") || message.contains("connect") {
+        "The server doesn't answer: check the address and port, and that no firewall or VPN blocks it".to_string()
+    } else {
+        return None;
+    };
+    Some(hint)
+}
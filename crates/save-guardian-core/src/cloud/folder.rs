@@ -0,0 +1,144 @@
+use crate::types::*;
+use crate::network;
+use super::{CloudEntry, CloudProvider, TrackedReader, TransferCounter};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use log::info;
+
+/// Suffix for archives still being copied; renamed into place once complete
+const TEMP_SUFFIX: &str = ".sgtmp";
+
+/// A mounted folder used as the sync target: NAS share, USB drive, Syncthing folder, ...
+pub struct FolderProvider {
+    root: PathBuf,
+    network: NetworkConfig,
+}
+
+impl FolderProvider {
+    pub fn new(root: PathBuf, network: NetworkConfig) -> Self {
+        Self { root, network }
+    }
+
+    fn local_path(&self, path: &str) -> PathBuf {
+        path.split('/')
+            .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+            .fold(self.root.clone(), |acc, segment| acc.join(segment))
+    }
+
+    /// Fail fast when a share or drive is offline instead of hanging on it
+    fn ensure_reachable(&self) -> Result<()> {
+        let timeout = Duration::from_secs(self.network.offline_timeout_secs);
+        let probe = if self.root.exists() { self.root.as_path() } else { self.root.parent().unwrap_or(Path::new("")) };
+        if network::is_reachable(probe, timeout) {
+            Ok(())
+        } else {
+            Err(SaveGuardianError::BackupOperationFailed(format!("Folder {} is not reachable", self.root.display())))
+        }
+    }
+}
+
+impl CloudProvider for FolderProvider {
+    fn name(&self) -> &str {
+        "Folder"
+    }
+
+    fn test_connection(&self) -> Result<()> {
+        self.ensure_reachable()?;
+        self.mkdir("")?;
+
+        // Make sure we can actually write there, e.g. not a read-only mount
+        let probe = self.root.join(format!(".save-guardian-probe{}", TEMP_SUFFIX));
+        fs::write(&probe, b"ok")
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Cannot write to {}: {}", self.root.display(), e)))?;
+        let _ = fs::remove_file(&probe);
+        Ok(())
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<CloudEntry>> {
+        self.ensure_reachable()?;
+        let folder = self.local_path(path);
+        if !folder.exists() {
+            return Err(SaveGuardianError::PathNotFound(folder));
+        }
+
+        let prefix = path.trim_matches('/');
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&folder).map_err(SaveGuardianError::Io)? {
+            let entry = entry.map_err(SaveGuardianError::Io)?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.ends_with(TEMP_SUFFIX) {
+                continue;
+            }
+            let metadata = entry.metadata().map_err(SaveGuardianError::Io)?;
+            entries.push(CloudEntry {
+                name: if prefix.is_empty() { file_name } else { format!("{}/{}", prefix, file_name) },
+                size: if metadata.is_file() { Some(metadata.len()) } else { None },
+                is_dir: metadata.is_dir(),
+                etag: metadata.modified().ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| format!("{}-{}", d.as_secs(), metadata.len())),
+                modified: metadata.modified().ok().map(chrono::DateTime::<chrono::Utc>::from),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn upload_tracked(&self, path: &str, data: &[u8], counter: &Arc<TransferCounter>) -> Result<()> {
+        self.ensure_reachable()?;
+        let target = self.local_path(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(SaveGuardianError::Io)?;
+        }
+        info!("Copying {} bytes to {}", data.len(), target.display());
+
+        // Write under a temporary name so a dropped share never leaves half an archive
+        let mut temp_name = target.as_os_str().to_os_string();
+        temp_name.push(TEMP_SUFFIX);
+        let temp_path = PathBuf::from(temp_name);
+
+        let written = network::with_retry(&self.network, || {
+            counter.restart();
+            let mut file = fs::File::create(&temp_path)?;
+            io::copy(&mut TrackedReader::new(data, Arc::clone(counter)), &mut file)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp_path, &target));
+
+        written.map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            SaveGuardianError::BackupOperationFailed(format!("Failed to copy {} to {}: {}", path, self.root.display(), e))
+        })
+    }
+
+    fn download_tracked(&self, path: &str, counter: &Arc<TransferCounter>) -> Result<Vec<u8>> {
+        self.ensure_reachable()?;
+        let source = self.local_path(path);
+        network::with_retry(&self.network, || {
+            counter.restart();
+            let mut data = Vec::new();
+            TrackedReader::new(fs::File::open(&source)?, Arc::clone(counter)).read_to_end(&mut data)?;
+            Ok(data)
+        })
+        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read {}: {}", source.display(), e)))
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        let target = self.local_path(path);
+        if target.is_dir() {
+            fs::remove_dir_all(&target).map_err(SaveGuardianError::Io)
+        } else if target.exists() {
+            fs::remove_file(&target).map_err(SaveGuardianError::Io)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn mkdir(&self, path: &str) -> Result<()> {
+        fs::create_dir_all(self.local_path(path))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to create folder in {}: {}", self.root.display(), e)))
+    }
+}
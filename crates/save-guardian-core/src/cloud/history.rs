@@ -0,0 +1,99 @@
+use crate::types::*;
+use super::transfer::{TransferDirection, TransferState, TransferStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::warn;
+
+const HISTORY_FILE: &str = "cloud_history.json";
+
+/// Oldest operations are dropped beyond this, a few months of daily syncs
+const MAX_OPERATIONS: usize = 5000;
+
+/// How a cloud operation ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CloudOutcome {
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+/// One file moved to or from the cloud, as remembered in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudOperation {
+    pub finished_at: DateTime<Utc>,
+    pub run: String,      // What started it: "Full Sync", "Upload", an automatic sync...
+    pub provider: String, // e.g. "Koofr" or "SFTP"
+    pub direction: TransferDirection,
+    pub remote_name: String,
+    pub local_path: PathBuf,
+    pub size: u64,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub retries: u32,
+    pub outcome: CloudOutcome,
+}
+
+impl CloudOperation {
+    pub fn succeeded(&self) -> bool {
+        self.outcome == CloudOutcome::Done
+    }
+}
+
+/// Every cloud upload and download, newest last, so a past sync can be looked up later
+///
+/// Kept next to the sync state in the backup folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CloudHistory {
+    pub operations: Vec<CloudOperation>,
+}
+
+impl CloudHistory {
+    pub fn load(backup_root: &Path) -> Self {
+        let path = backup_root.join(HISTORY_FILE);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable cloud history {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(backup_root.join(HISTORY_FILE), json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save cloud history: {}", e)))
+    }
+
+    /// Add the files of a finished transfer queue; files it never got to are left out
+    pub fn record_transfer(&mut self, status: &TransferStatus, run: &str, provider: &str) {
+        for item in &status.items {
+            let outcome = match item.state {
+                TransferState::Done => CloudOutcome::Done,
+                TransferState::Failed(ref error) => CloudOutcome::Failed(error.clone()),
+                TransferState::Cancelled if item.elapsed.is_some() => CloudOutcome::Cancelled,
+                // Nothing went over the wire
+                TransferState::AlreadyThere => continue,
+                TransferState::Cancelled | TransferState::Queued | TransferState::Running => continue,
+            };
+            self.operations.push(CloudOperation {
+                finished_at: item.finished_at.unwrap_or_else(Utc::now),
+                run: run.to_string(),
+                provider: provider.to_string(),
+                direction: item.job.direction,
+                remote_name: item.job.remote_name.clone(),
+                local_path: item.job.local_path.clone(),
+                size: item.bytes_done,
+                duration_ms: item.elapsed.map_or(0, |d| d.as_millis() as u64),
+                retries: item.retries,
+                outcome,
+            });
+        }
+
+        let excess = self.operations.len().saturating_sub(MAX_OPERATIONS);
+        self.operations.drain(..excess);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::types::*;
+use super::{remote_file_name, CloudEntry, CloudProvider};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use log::info;
+
+/// Name of the manifest at the top of the sync folder
+pub const MANIFEST_FILE: &str = "save_guardian_manifest.json";
+
+/// One archive in the cloud, with the metadata of the machine that uploaded it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub remote_name: String, // Relative to the sync folder, including the game folder
+    pub machine: String,     // Name of the computer that uploaded it
+    #[serde(default)]
+    pub machine_id: String,
+    #[serde(default)]
+    pub sha256: Option<String>, // Of the archive as uploaded, before encryption; checked on download
+    pub backup: BackupInfo,
+}
+
+impl ManifestEntry {
+    /// The hash a download of this archive must have
+    ///
+    /// Entries from before upload hashes were recorded fall back to the archive hash the
+    /// backup was created with, the same bytes unless the archive changed since. That hash
+    /// covers the whole archive, so parts of a split one have nothing to fall back to.
+    pub fn expected_sha256(&self) -> Option<String> {
+        self.sha256.clone().or_else(|| self.backup.archive_sha256.clone().filter(|_| self.backup.volumes.is_empty()))
+    }
+}
+
+/// Metadata of every backup in the cloud, so another machine can rebuild it on download
+/// instead of guessing game, paths and hashes from the file name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CloudManifest {
+    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_by: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl CloudManifest {
+    /// Read the manifest from the sync folder; empty if there is none yet
+    pub fn fetch(provider: &dyn CloudProvider) -> Result<Self> {
+        if !provider.list("")?.iter().any(|e| !e.is_dir && e.name == MANIFEST_FILE) {
+            return Ok(Self::default());
+        }
+        let data = provider.download(MANIFEST_FILE)?;
+        serde_json::from_slice(&data).map_err(SaveGuardianError::Serde)
+    }
+
+    pub fn upload(&self, provider: &dyn CloudProvider) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(SaveGuardianError::Serde)?;
+        provider.upload(MANIFEST_FILE, &json)?;
+        info!("Uploaded cloud manifest with {} backups", self.entries.len());
+        Ok(())
+    }
+
+    /// The entry for an archive, by file name, wherever it is in the sync folder
+    pub fn entry_for(&self, file_name: &str) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|e| crate::normalize::same_text(remote_file_name(&e.remote_name), file_name))
+    }
+
+    /// Note an uploaded archive, replacing any earlier entry for it
+    pub fn record(&mut self, remote_name: &str, backup: &BackupInfo, machine: &MachineInfo, sha256: Option<String>) {
+        self.entries.retain(|e| remote_file_name(&e.remote_name) != remote_file_name(remote_name));
+        self.entries.push(ManifestEntry {
+            remote_name: remote_name.to_string(),
+            machine: machine.name.clone(),
+            machine_id: machine.id.clone(),
+            sha256,
+            backup: backup.clone(),
+        });
+        self.updated_at = Some(Utc::now());
+        self.updated_by = machine.name.clone();
+    }
+
+    /// Forget archives that are no longer in the cloud
+    pub fn prune(&mut self, remote: &[CloudEntry], uploaded: &[String]) {
+        self.entries.retain(|e| remote.iter().any(|r| r.name == e.remote_name) || uploaded.contains(&e.remote_name));
+    }
+}
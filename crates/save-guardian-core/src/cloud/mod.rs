@@ -0,0 +1,170 @@
+pub mod webdav;
+pub mod sftp;
+pub mod folder;
+pub mod transfer;
+pub mod state;
+pub mod retry;
+pub mod crypto;
+pub mod manifest;
+pub mod history;
+pub mod replicas;
+pub mod diagnose;
+pub mod recompress;
+
+use crate::types::*;
+use std::sync::Arc;
+
+pub use webdav::WebDavProvider;
+pub use sftp::SftpProvider;
+pub use folder::FolderProvider;
+pub use transfer::{PausedTransfer, TrackedReader, TransferCounter, TransferQueue};
+pub use state::SyncState;
+pub use retry::{RetryError, RetryPolicy};
+pub use crypto::{CloudCipher, EncryptedProvider};
+pub use manifest::{CloudManifest, ManifestEntry};
+pub use history::{CloudHistory, CloudOperation, CloudOutcome};
+pub use replicas::ReplicaReport;
+pub use diagnose::{ConnectionReport, StepOutcome};
+
+/// A file or folder stored with a cloud provider
+#[derive(Debug, Clone)]
+pub struct CloudEntry {
+    pub name: String, // Path relative to the provider's sync folder, decoded
+    pub size: Option<u64>,
+    pub is_dir: bool,
+    pub etag: Option<String>, // Changes whenever the file does: HTTP ETag, or modification time and size
+    pub modified: Option<chrono::DateTime<chrono::Utc>>, // Last change on the remote side, if the provider reports it
+}
+
+impl CloudEntry {
+    /// The last part of the name, i.e. the archive's file name without its game folder
+    pub fn file_name(&self) -> &str {
+        remote_file_name(&self.name)
+    }
+}
+
+/// The last part of a remote path
+pub fn remote_file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Where a backup archive goes in the cloud: `<game name>/<file name>`
+///
+/// One folder per game keeps large libraries browsable in the provider's web UI.
+pub fn game_archive_path(game_name: &str, file_name: &str) -> String {
+    let folder: String = crate::normalize::nfc(game_name)
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    // Windows and some WebDAV servers refuse names ending in a dot or space
+    let folder = folder.trim().trim_end_matches('.');
+    if folder.is_empty() {
+        format!("Unknown Game/{}", file_name)
+    } else {
+        format!("{}/{}", folder, file_name)
+    }
+}
+
+/// Backup archives (and parts of split ones) in the sync folder: those in game folders, plus
+/// any left at the top level by versions that stored everything in one flat folder
+pub fn list_archives(provider: &dyn CloudProvider) -> Result<Vec<CloudEntry>> {
+    let is_archive = |entry: &CloudEntry| !entry.is_dir && crate::volumes::is_archive_name(&entry.name);
+    let mut archives = Vec::new();
+    for entry in provider.list("")? {
+        if entry.is_dir {
+            archives.extend(provider.list(&entry.name)?.into_iter().filter(is_archive));
+        } else if is_archive(&entry) {
+            archives.push(entry);
+        }
+    }
+    Ok(archives)
+}
+
+/// A remote store that backups can be synced to
+///
+/// Paths are relative to the provider's sync folder and use `/` as separator;
+/// an empty path means the sync folder itself. Providers are moved to a
+/// background thread for transfers, hence `Send`.
+pub trait CloudProvider: Send {
+    /// Human readable name for status messages, e.g. "Koofr" or "Nextcloud"
+    fn name(&self) -> &str;
+
+    /// Check that the server is reachable and accepts the credentials
+    fn test_connection(&self) -> Result<()>;
+
+    /// List the entries directly inside a folder
+    fn list(&self, path: &str) -> Result<Vec<CloudEntry>>;
+
+    /// The entry at `path`, None if there is nothing there
+    ///
+    /// Looks through the parent folder's listing unless the provider can ask for one file.
+    fn stat(&self, path: &str) -> Result<Option<CloudEntry>> {
+        let path = path.trim_matches('/');
+        let folder = path.rsplit_once('/').map_or("", |(folder, _)| folder);
+        match self.list(folder) {
+            Ok(entries) => Ok(entries.into_iter().find(|entry| entry.name == path)),
+            Err(SaveGuardianError::PathNotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn upload(&self, path: &str, data: &[u8]) -> Result<()> {
+        self.upload_tracked(path, data, &TransferCounter::new())
+    }
+
+    fn download(&self, path: &str) -> Result<Vec<u8>> {
+        self.download_tracked(path, &TransferCounter::new())
+    }
+
+    /// Upload while counting the bytes sent, aborting once the counter is cancelled
+    fn upload_tracked(&self, path: &str, data: &[u8], counter: &Arc<TransferCounter>) -> Result<()>;
+
+    /// Download while counting the bytes received, aborting once the counter is cancelled
+    fn download_tracked(&self, path: &str, counter: &Arc<TransferCounter>) -> Result<Vec<u8>>;
+
+    fn delete(&self, path: &str) -> Result<()>;
+
+    /// Create a folder, succeeding if it already exists
+    fn mkdir(&self, path: &str) -> Result<()>;
+
+    /// A link anyone can download the file at `path` from, valid through `expires` if given
+    ///
+    /// Only some servers have a sharing API; the others refuse.
+    fn share_link(&self, path: &str, expires: Option<chrono::NaiveDate>) -> Result<String> {
+        let _ = expires;
+        Err(SaveGuardianError::cloud_failure(
+            self.name(), &format!("share {}", path), "", "this server can't create sharing links",
+        ))
+    }
+}
+
+/// Build the provider configured for this profile, encrypting if enabled
+pub fn provider_from_config(config: &Config) -> Box<dyn CloudProvider> {
+    let provider: Box<dyn CloudProvider> = match config.cloud_backend {
+        CloudBackend::WebDav => Box::new(WebDavProvider::new(
+            config.koofr_config.server.label(),
+            &config.koofr_config.server_url,
+            &config.koofr_config.username,
+            &config.koofr_config.password,
+            &config.effective_sync_folder(),
+        )
+        .with_server(config.koofr_config.server)
+        .with_retry_policy(RetryPolicy::from_config(&config.cloud_retry))),
+        CloudBackend::Sftp => Box::new(SftpProvider::new(
+            config.sftp_config.clone(),
+            &config.effective_sync_folder(),
+        ).with_retry_policy(RetryPolicy::from_config(&config.cloud_retry))),
+        CloudBackend::Folder => {
+            // Profiles with their own cloud folder get a subfolder of the target
+            let root = match config.active_profile() {
+                Some(profile) if !profile.cloud_folder.trim_matches('/').is_empty() => {
+                    config.folder_target.path.join(profile.cloud_folder.trim_matches('/'))
+                }
+                _ => config.folder_target.path.clone(),
+            };
+            Box::new(FolderProvider::new(root, config.network.clone()))
+        }
+    };
+    // Always wrapped, so encrypted files are recognized even with encryption switched off
+    Box::new(EncryptedProvider::new(provider, CloudCipher::from_config(&config.cloud_encryption)))
+}
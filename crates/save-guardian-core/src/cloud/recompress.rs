@@ -0,0 +1,111 @@
+use crate::types::*;
+use crate::volumes;
+use std::io::{self, Cursor};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+use log::{info, warn};
+
+/// Zstandard level for uploads: slow to write, as fast as any level to read back
+const ZSTD_LEVEL: i32 = 19;
+
+const DEFLATE_MAX_LEVEL: i32 = 9;
+
+/// Entries this large need Zip64 sizes
+const ZIP64_SIZE_THRESHOLD: u64 = u32::MAX as u64;
+
+/// The archive to upload in place of `data`, recompressed as configured
+///
+/// Only whole zip archives are touched; parts of a split archive, other files and
+/// archives that don't get smaller are uploaded as they are.
+pub fn for_upload(remote_name: &str, data: Vec<u8>, recompression: CloudRecompression) -> Vec<u8> {
+    let (method, level) = match recompression {
+        CloudRecompression::Off => return data,
+        CloudRecompression::Zstd => (CompressionMethod::Zstd, ZSTD_LEVEL),
+        CloudRecompression::DeflateMax => (CompressionMethod::Deflated, DEFLATE_MAX_LEVEL),
+    };
+    let name = super::remote_file_name(remote_name);
+    if !name.to_lowercase().ends_with(".zip") || volumes::is_volume_name(name) {
+        return data;
+    }
+
+    match transcode(&data, method, Some(level)) {
+        Ok(smaller) if smaller.len() < data.len() => {
+            info!(
+                "Recompressed {} for upload: {} -> {}",
+                remote_name, format_size(data.len() as u64), format_size(smaller.len() as u64)
+            );
+            smaller
+        }
+        Ok(_) => data,
+        Err(e) => {
+            warn!("Uploading {} as it is, recompressing failed: {}", remote_name, e);
+            data
+        }
+    }
+}
+
+/// Undo a Zstandard recompression on a downloaded archive
+///
+/// The local copy gets Deflate entries again, so it opens in any zip tool like the
+/// archives made here. Anything else is returned as downloaded.
+pub fn after_download(remote_name: &str, data: Vec<u8>) -> Vec<u8> {
+    if !uses_zstd(&data) {
+        return data;
+    }
+    match transcode(&data, CompressionMethod::Deflated, None) {
+        Ok(deflated) => {
+            info!("Unpacked the recompressed {} ({} -> {})", remote_name, format_size(data.len() as u64), format_size(deflated.len() as u64));
+            deflated
+        }
+        Err(e) => {
+            // Still a valid zip, just one fewer tools can open
+            warn!("Keeping {} with Zstandard entries, converting failed: {}", remote_name, e);
+            data
+        }
+    }
+}
+
+fn uses_zstd(data: &[u8]) -> bool {
+    let mut archive = match ZipArchive::new(Cursor::new(data)) {
+        Ok(archive) => archive,
+        Err(_) => return false,
+    };
+    (0..archive.len()).any(|i| archive.by_index_raw(i).map_or(false, |entry| entry.compression() == CompressionMethod::Zstd))
+}
+
+/// Copy every entry of an archive into a new one with another compression
+///
+/// Names, times, permissions and the order of entries stay as they were.
+fn transcode(data: &[u8], method: CompressionMethod, level: Option<i32>) -> Result<Vec<u8>> {
+    let mut archive = ZipArchive::new(Cursor::new(data))
+        .map_err(SaveGuardianError::Zip)?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(data.len())));
+    writer.set_comment(String::from_utf8_lossy(archive.comment()).to_string());
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(SaveGuardianError::Zip)?;
+        let mut options = FileOptions::default()
+            .compression_method(method)
+            .compression_level(level)
+            .last_modified_time(entry.last_modified())
+            .large_file(entry.size() >= ZIP64_SIZE_THRESHOLD);
+        if let Some(mode) = entry.unix_mode() {
+            options = options.unix_permissions(mode);
+        }
+
+        let name = entry.name().to_string();
+        if entry.is_dir() {
+            writer.add_directory(name, options)
+                .map_err(SaveGuardianError::Zip)?;
+        } else {
+            writer.start_file(name, options)
+                .map_err(SaveGuardianError::Zip)?;
+            io::copy(&mut entry, &mut writer)
+                .map_err(SaveGuardianError::Io)?;
+        }
+    }
+
+    let cursor = writer.finish()
+        .map_err(SaveGuardianError::Zip)?;
+    Ok(cursor.into_inner())
+}
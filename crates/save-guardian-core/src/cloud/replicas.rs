@@ -0,0 +1,159 @@
+use crate::types::*;
+use crate::volumes;
+use super::transfer::{TransferDirection, TransferState, TransferStatus};
+use super::CloudEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+
+const REPLICA_REPORT_FILE: &str = "cloud_replicas.json";
+
+/// A local archive file with no copy in the cloud, e.g. because its upload failed or was skipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingReplica {
+    pub backup_id: String,
+    pub game_name: String,
+    pub local_path: PathBuf, // The archive, or one part of a split archive
+}
+
+/// An archive in the cloud that no local backup knows, e.g. made on another PC or deleted here
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownReplica {
+    pub remote_name: String,
+    pub size: Option<u64>,
+}
+
+/// Outcome of the last comparison of the cloud listing with the local backups
+///
+/// Kept per destination like the sync state: switching servers or folders starts from scratch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicaReport {
+    pub endpoint: String,
+    pub checked_at: Option<DateTime<Utc>>,
+    pub error: Option<String>, // Why the last check couldn't list the cloud
+    pub local_files: usize,
+    pub remote_files: usize,
+    pub missing_remote: Vec<MissingReplica>,
+    pub unknown_local: Vec<UnknownReplica>,
+}
+
+impl ReplicaReport {
+    pub fn load(backup_root: &Path, endpoint: &str) -> Self {
+        let path = backup_root.join(REPLICA_REPORT_FILE);
+        let report: Self = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable replica report {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+
+        if report.endpoint == endpoint {
+            report
+        } else {
+            Self { endpoint: endpoint.to_string(), ..Self::default() }
+        }
+    }
+
+    pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(backup_root.join(REPLICA_REPORT_FILE), json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save replica report: {}", e)))
+    }
+
+    /// Whether the schedule says another check is due
+    pub fn is_due(&self, config: &ReplicaCheckConfig) -> bool {
+        if !config.enabled {
+            return false;
+        }
+        match self.checked_at {
+            Some(checked_at) => Utc::now() - checked_at >= chrono::Duration::hours(config.interval_hours as i64),
+            None => true,
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.missing_remote.is_empty() && self.unknown_local.is_empty()
+    }
+
+    /// A failed listing keeps the previous findings, they are likely still true
+    pub fn record_error(&mut self, error: String) {
+        self.checked_at = Some(Utc::now());
+        self.error = Some(error);
+    }
+
+    /// Drop findings a finished transfer has fixed, without waiting for the next check
+    pub fn forget_transferred(&mut self, status: &TransferStatus) {
+        for item in status.items.iter().filter(|item| matches!(item.state, TransferState::Done | TransferState::AlreadyThere)) {
+            match item.job.direction {
+                TransferDirection::Upload => self.missing_remote.retain(|m| m.local_path != item.job.local_path),
+                TransferDirection::Download => self.unknown_local.retain(|u| u.remote_name != item.job.remote_name),
+            }
+        }
+    }
+
+    /// Drop findings about local archives that have since been deleted
+    pub fn forget_deleted(&mut self) {
+        self.missing_remote.retain(|m| m.local_path.exists());
+    }
+}
+
+/// Compare the cloud listing against the local backups by archive file name
+///
+/// `kept_local` and `remote_kept_local` tell which archives are meant to stay out of the
+/// cloud, so games excluded from sync aren't reported. Only the archive's presence is
+/// compared: sizes differ for encrypted uploads, and hashes are checked on download.
+pub fn compare(
+    endpoint: &str,
+    backups: &[BackupInfo],
+    remote: &[CloudEntry],
+    kept_local: impl Fn(&BackupInfo) -> bool,
+    remote_kept_local: impl Fn(&CloudEntry) -> bool,
+) -> ReplicaReport {
+    let remote_names: HashSet<&str> = remote.iter().map(|e| e.file_name()).collect();
+    let mut local_names = HashSet::new();
+    let mut missing_remote = Vec::new();
+    let mut local_files = 0;
+
+    for backup in backups {
+        let files = volumes::archive_files(backup);
+        local_names.extend(files.iter().filter_map(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()));
+        if !volumes::archive_exists(backup) || kept_local(backup) {
+            continue;
+        }
+        for local_path in files {
+            local_files += 1;
+            let name = local_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if !remote_names.contains(name.as_str()) {
+                missing_remote.push(MissingReplica {
+                    backup_id: backup.id.clone(),
+                    game_name: backup.game_name.clone(),
+                    local_path,
+                });
+            }
+        }
+    }
+
+    let unknown_local: Vec<UnknownReplica> = remote.iter()
+        .filter(|e| !local_names.contains(e.file_name()) && !remote_kept_local(e))
+        .map(|e| UnknownReplica { remote_name: e.name.clone(), size: e.size })
+        .collect();
+
+    info!(
+        "Replica check: {} local archive files, {} in the cloud, {} missing remotely, {} unknown locally",
+        local_files, remote.len(), missing_remote.len(), unknown_local.len()
+    );
+    ReplicaReport {
+        endpoint: endpoint.to_string(),
+        checked_at: Some(Utc::now()),
+        error: None,
+        local_files,
+        remote_files: remote.len(),
+        missing_remote,
+        unknown_local,
+    }
+}
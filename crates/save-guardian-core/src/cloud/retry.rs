@@ -0,0 +1,100 @@
+use crate::types::*;
+use super::TransferCounter;
+use std::time::Duration;
+use log::warn;
+
+/// Cancellation is checked this often while waiting for the next attempt
+const SLEEP_SLICE: Duration = Duration::from_millis(100);
+
+/// Why an attempt failed, as far as retrying is concerned
+pub enum RetryError {
+    Transient(SaveGuardianError), // Timeout, dropped connection, 5xx: worth another try
+    Fatal(SaveGuardianError),     // Bad credentials, missing file, cancelled: retrying won't help
+}
+
+impl RetryError {
+    fn into_inner(self) -> SaveGuardianError {
+        match self {
+            RetryError::Transient(e) | RetryError::Fatal(e) => e,
+        }
+    }
+}
+
+/// Exponential backoff for cloud requests: 1 s, 2 s, 4 s... up to a cap, optionally jittered
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &CloudRetryConfig) -> Self {
+        Self {
+            attempts: config.attempts.max(1),
+            initial_delay: Duration::from_millis(config.initial_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms.max(config.initial_delay_ms)),
+            jitter: config.jitter,
+        }
+    }
+
+    /// A single attempt, no retries
+    pub fn none() -> Self {
+        Self::from_config(&CloudRetryConfig { attempts: 1, ..CloudRetryConfig::default() })
+    }
+
+    /// Wait before the given retry (1 = first retry)
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.initial_delay.saturating_mul(factor).min(self.max_delay);
+        if self.jitter {
+            // Somewhere between half and the full delay, so clients that failed together
+            // don't all come back at the same moment
+            delay.mul_f64(rand::random::<f64>() * 0.5 + 0.5)
+        } else {
+            delay
+        }
+    }
+
+    /// Run `op` until it succeeds, fails for good or the attempts are used up
+    ///
+    /// Each retry is noted on the counter, so the transfer queue can report it.
+    pub fn run<T>(
+        &self,
+        what: &str,
+        counter: &TransferCounter,
+        mut op: impl FnMut() -> std::result::Result<T, RetryError>,
+    ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(RetryError::Transient(e)) if attempt < self.attempts && !counter.is_cancelled() => {
+                    let delay = self.delay_for(attempt);
+                    warn!("Failed to {} (attempt {}/{}), retrying in {:.1} s: {}", what, attempt, self.attempts, delay.as_secs_f64(), e);
+                    counter.note_retry();
+                    if !sleep_unless_cancelled(delay, counter) {
+                        return Err(e);
+                    }
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into_inner()),
+            }
+        }
+    }
+}
+
+/// Sleep in slices so a cancel doesn't wait out the whole backoff; false if cancelled
+fn sleep_unless_cancelled(delay: Duration, counter: &TransferCounter) -> bool {
+    let mut remaining = delay;
+    while !remaining.is_zero() {
+        if counter.is_cancelled() {
+            return false;
+        }
+        let slice = remaining.min(SLEEP_SLICE);
+        std::thread::sleep(slice);
+        remaining -= slice;
+    }
+    !counter.is_cancelled()
+}
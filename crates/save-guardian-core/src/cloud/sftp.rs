@@ -0,0 +1,243 @@
+use crate::types::*;
+use super::{CloudEntry, CloudProvider, RetryError, RetryPolicy, TrackedReader, TransferCounter};
+use ssh2::{CheckResult, KnownHostFileKind, Session, Sftp};
+use std::io::{self, Read};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use log::{info, warn};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Cloud provider pushing archives to a server over SSH, e.g. a home server or NAS
+///
+/// One SSH session is opened on first use and reused for later operations.
+pub struct SftpProvider {
+    config: SftpConfig,
+    root_folder: String,
+    connection: Mutex<Option<(Session, Sftp)>>,
+    retry: RetryPolicy,
+}
+
+impl SftpProvider {
+    pub fn new(config: SftpConfig, root_folder: &str) -> Self {
+        let root_folder = root_folder.trim_end_matches('/').to_string();
+        Self {
+            config,
+            root_folder: if root_folder.is_empty() { ".".to_string() } else { root_folder },
+            connection: Mutex::new(None),
+            retry: RetryPolicy::none(),
+        }
+    }
+
+    /// Backoff for (re)connecting; an interrupted transfer is retried on the new connection
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Remote path for a path inside the sync folder
+    fn remote_path(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            self.root_folder.clone()
+        } else {
+            format!("{}/{}", self.root_folder, path)
+        }
+    }
+
+    fn sftp_error(&self, what: &str, error: impl std::fmt::Display) -> SaveGuardianError {
+        let url = format!("sftp://{}@{}:{}", self.config.username, self.config.host.trim(), self.config.port);
+        SaveGuardianError::cloud_failure("SFTP", what, &url, error)
+    }
+
+    /// Open a session; network trouble is transient, a rejected key or login is not
+    fn connect(&self) -> std::result::Result<(Session, Sftp), RetryError> {
+        let address = format!("{}:{}", self.config.host.trim(), self.config.port);
+        let socket = address.to_socket_addrs()
+            .map_err(|e| RetryError::Transient(self.sftp_error(&format!("resolve {}", address), e)))?
+            .next()
+            .ok_or_else(|| RetryError::Fatal(self.sftp_error(&format!("resolve {}", address), "host not found")))?;
+
+        let tcp = TcpStream::connect_timeout(&socket, CONNECT_TIMEOUT)
+            .map_err(|e| RetryError::Transient(self.sftp_error(&format!("connect to {}", address), e)))?;
+
+        let mut session = Session::new().map_err(|e| RetryError::Fatal(self.sftp_error("start SSH session", e)))?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(60_000);
+        session.handshake().map_err(|e| RetryError::Transient(self.sftp_error("complete SSH handshake", e)))?;
+        self.check_host_key(&session).map_err(RetryError::Fatal)?;
+
+        match self.config.auth {
+            SftpAuth::Password => session.userauth_password(&self.config.username, &self.config.password),
+            SftpAuth::KeyFile => {
+                let passphrase = if self.config.key_passphrase.is_empty() { None } else { Some(self.config.key_passphrase.as_str()) };
+                session.userauth_pubkey_file(&self.config.username, None, &self.config.key_path, passphrase)
+            }
+        }
+        .map_err(|e| RetryError::Fatal(self.sftp_error("authenticate", e)))?;
+
+        let sftp = session.sftp().map_err(|e| RetryError::Transient(self.sftp_error("open SFTP channel", e)))?;
+        info!("Connected to SFTP server {} as {}", address, self.config.username);
+        Ok((session, sftp))
+    }
+
+    /// Refuse servers whose key changed since it was recorded in ~/.ssh/known_hosts
+    fn check_host_key(&self, session: &Session) -> Result<()> {
+        let (key, _) = match session.host_key() {
+            Some(key) => key,
+            None => return Err(self.sftp_error("check the host key", "the server sent none")),
+        };
+
+        let mut known_hosts = session.known_hosts().map_err(|e| self.sftp_error("read known hosts", e))?;
+        if let Some(path) = dirs::home_dir().map(|h| h.join(".ssh").join("known_hosts")) {
+            if path.exists() {
+                if let Err(e) = known_hosts.read_file(&path, KnownHostFileKind::OpenSSH) {
+                    warn!("Could not read {:?}: {}", path, e);
+                }
+            }
+        }
+
+        match known_hosts.check_port(self.config.host.trim(), self.config.port, key) {
+            CheckResult::Mismatch => Err(self.sftp_error(
+                "check the host key",
+                format!("the host key of {} does not match known_hosts, refusing to connect", self.config.host),
+            )),
+            CheckResult::NotFound => {
+                warn!("Host {} is not in known_hosts, connecting anyway", self.config.host);
+                Ok(())
+            }
+            CheckResult::Match | CheckResult::Failure => Ok(()),
+        }
+    }
+
+    /// Run an operation on the shared connection, reconnecting once if it dropped
+    fn with_sftp<T>(&self, operation: impl Fn(&Sftp) -> Result<T>) -> Result<T> {
+        let mut connection = self.connection.lock()
+            .map_err(|_| SaveGuardianError::BackupOperationFailed("SFTP connection poisoned".to_string()))?;
+
+        if let Some((_, ref sftp)) = *connection {
+            match operation(sftp) {
+                Ok(value) => return Ok(value),
+                Err(e) => warn!("SFTP operation failed, reconnecting: {}", e),
+            }
+        }
+
+        let (session, sftp) = self.retry.run("connect to the SFTP server", &TransferCounter::default(), || self.connect())?;
+        let result = operation(&sftp);
+        *connection = Some((session, sftp));
+        result
+    }
+}
+
+impl CloudProvider for SftpProvider {
+    fn name(&self) -> &str {
+        "SFTP"
+    }
+
+    fn test_connection(&self) -> Result<()> {
+        self.with_sftp(|sftp| {
+            sftp.stat(Path::new(".")).map(|_| ()).map_err(|e| self.sftp_error("read the home folder", e))
+        })
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<CloudEntry>> {
+        let remote = self.remote_path(path);
+        let prefix = path.trim_matches('/').to_string();
+
+        self.with_sftp(|sftp| {
+            let entries = sftp.readdir(Path::new(&remote)).map_err(|e| {
+                if e.code() == ssh2::ErrorCode::SFTP(2) { // LIBSSH2_FX_NO_SUCH_FILE
+                    SaveGuardianError::PathNotFound(remote.clone().into())
+                } else {
+                    self.sftp_error(&format!("list {}", remote), e)
+                }
+            })?;
+
+            Ok(entries.into_iter()
+                .filter_map(|(entry_path, stat)| {
+                    let file_name = entry_path.file_name()?.to_string_lossy().to_string();
+                    if file_name == "." || file_name == ".." {
+                        return None;
+                    }
+                    let name = if prefix.is_empty() { file_name } else { format!("{}/{}", prefix, file_name) };
+                    let etag = stat.mtime.map(|mtime| format!("{}-{}", mtime, stat.size.unwrap_or(0)));
+                    let modified = stat.mtime.and_then(|mtime| chrono::DateTime::from_timestamp(mtime as i64, 0));
+                    Some(CloudEntry { name, size: stat.size, is_dir: stat.is_dir(), etag, modified })
+                })
+                .collect())
+        })
+    }
+
+    fn upload_tracked(&self, path: &str, data: &[u8], counter: &Arc<TransferCounter>) -> Result<()> {
+        let remote = self.remote_path(path);
+        info!("Uploading {} bytes to sftp://{}/{}", data.len(), self.config.host, remote);
+
+        self.with_sftp(|sftp| {
+            // A cancelled transfer must not be retried on a fresh connection
+            counter.check()?;
+            counter.restart();
+            let mut file = sftp.create(Path::new(&remote)).map_err(|e| self.sftp_error(&format!("create {}", remote), e))?;
+            let copied = io::copy(&mut TrackedReader::new(data, Arc::clone(counter)), &mut file);
+            counter.check()?;
+            copied.map(|_| ()).map_err(|e| self.sftp_error(&format!("write {}", remote), e))
+        })
+    }
+
+    fn download_tracked(&self, path: &str, counter: &Arc<TransferCounter>) -> Result<Vec<u8>> {
+        let remote = self.remote_path(path);
+        info!("Downloading sftp://{}/{}", self.config.host, remote);
+
+        self.with_sftp(|sftp| {
+            counter.check()?;
+            counter.restart();
+            let file = sftp.open(Path::new(&remote)).map_err(|e| self.sftp_error(&format!("open {}", remote), e))?;
+            let mut data = Vec::new();
+            let read = TrackedReader::new(file, Arc::clone(counter)).read_to_end(&mut data);
+            counter.check()?;
+            read.map_err(|e| self.sftp_error(&format!("read {}", remote), e))?;
+            Ok(data)
+        })
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        let remote = self.remote_path(path);
+        self.with_sftp(|sftp| {
+            let remote_path = Path::new(&remote);
+            match sftp.stat(remote_path) {
+                // Already gone is as good as deleted
+                Err(_) => Ok(()),
+                Ok(stat) if stat.is_dir() => sftp.rmdir(remote_path).map_err(|e| self.sftp_error(&format!("delete {}", remote), e)),
+                Ok(_) => sftp.unlink(remote_path).map_err(|e| self.sftp_error(&format!("delete {}", remote), e)),
+            }
+        })
+    }
+
+    fn mkdir(&self, path: &str) -> Result<()> {
+        let remote = self.remote_path(path);
+        self.with_sftp(|sftp| {
+            // Create each missing level, like `mkdir -p`
+            let mut current = String::new();
+            for segment in remote.split('/') {
+                if segment.is_empty() {
+                    if current.is_empty() {
+                        current.push('/');
+                    }
+                    continue;
+                }
+                if !current.is_empty() && !current.ends_with('/') {
+                    current.push('/');
+                }
+                current.push_str(segment);
+
+                let folder = Path::new(&current);
+                if segment != "." && sftp.stat(folder).is_err() {
+                    info!("Creating SFTP folder {}", current);
+                    sftp.mkdir(folder, 0o755).map_err(|e| self.sftp_error(&format!("create folder {}", current), e))?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,204 @@
+use crate::types::*;
+use super::CloudEntry;
+use super::crypto;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::warn;
+
+const SYNC_STATE_FILE: &str = "cloud_sync_state.json"; // Before each destination got its own file
+
+/// A file as it was when it was last uploaded or downloaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedFile {
+    pub size: u64,
+    pub etag: Option<String>, // Remote version tag, learned on the next listing after an upload
+    #[serde(default)]
+    pub remote_size: Option<u64>, // Size in the cloud, when it isn't the local size, e.g. recompressed
+    pub local_modified: Option<DateTime<Utc>>,
+    pub synced_at: DateTime<Utc>,
+}
+
+/// Remembers what was transferred so a sync only moves new or changed archives
+///
+/// Kept per destination, in a file of its own, so mirroring to several remotes or switching
+/// between them doesn't make the others start from scratch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    pub endpoint: String,
+    pub files: HashMap<String, SyncedFile>, // By remote name, including the game folder
+}
+
+impl SyncState {
+    pub fn load(backup_root: &Path, endpoint: &str) -> Self {
+        let path = Some(state_path(backup_root, endpoint))
+            .filter(|path| path.exists())
+            .unwrap_or_else(|| backup_root.join(SYNC_STATE_FILE));
+        let state: Self = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable cloud sync state {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+
+        if state.endpoint == endpoint {
+            state
+        } else {
+            Self { endpoint: endpoint.to_string(), files: HashMap::new() }
+        }
+    }
+
+    pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(state_path(backup_root, &self.endpoint), json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save cloud sync state: {}", e)))
+    }
+
+    /// Whether a local archive has to be uploaded, given the remote entry of the same name
+    pub fn upload_needed(&self, name: &str, local_path: &Path, remote: Option<&CloudEntry>) -> bool {
+        let remote = match remote {
+            Some(remote) => remote,
+            None => return true,
+        };
+        let (local_size, local_modified) = local_version(local_path);
+
+        match self.files.get(name) {
+            Some(synced) => {
+                synced.size != local_size
+                    || synced.local_modified != local_modified
+                    || !synced.matches_remote(remote)
+            }
+            // Synced before the state file existed: same name and size is the same archive
+            None => !same_size(remote.size, local_size),
+        }
+    }
+
+    /// Whether a remote archive has to be downloaded to `local_path`
+    pub fn download_needed(&self, remote: &CloudEntry, local_path: &Path) -> bool {
+        if !local_path.exists() {
+            return true;
+        }
+        let (local_size, local_modified) = local_version(local_path);
+
+        match self.files.get(&remote.name) {
+            Some(synced) => {
+                !synced.matches_remote(remote)
+                    || synced.size != local_size
+                    || synced.local_modified != local_modified
+            }
+            None => !same_size(remote.size, local_size),
+        }
+    }
+
+    /// Whether both copies changed since the last sync, so moving either one over the other loses data
+    pub fn is_conflict(&self, name: &str, local_path: &Path, remote: &CloudEntry) -> bool {
+        if !local_path.exists() {
+            return false;
+        }
+        let (local_size, local_modified) = local_version(local_path);
+
+        match self.files.get(name) {
+            Some(synced) => {
+                let local_changed = synced.size != local_size || synced.local_modified != local_modified;
+                local_changed && !synced.matches_remote(remote)
+            }
+            // Never synced: same name but a different size can't be the same archive
+            None => !same_size(remote.size, local_size),
+        }
+    }
+
+    /// Note that local and remote copies of a file are now the same
+    pub fn record(&mut self, name: &str, local_path: &Path, remote: Option<&CloudEntry>) {
+        let (size, local_modified) = local_version(local_path);
+        let etag = remote.and_then(|r| r.etag.clone());
+        // A recompressed archive is unpacked again on download
+        let remote_size = remote.and_then(|r| r.size).filter(|&remote_size| !same_size(Some(remote_size), size));
+        self.files.insert(name.to_string(), SyncedFile {
+            size,
+            etag,
+            remote_size,
+            local_modified,
+            synced_at: Utc::now(),
+        });
+    }
+
+    /// Like `record`, for an upload of `uploaded` bytes, fewer than the local size if recompressed
+    pub fn record_upload(&mut self, name: &str, local_path: &Path, uploaded: u64) {
+        self.record(name, local_path, None);
+        if let Some(synced) = self.files.get_mut(name) {
+            if !same_size(Some(uploaded), synced.size) {
+                synced.remote_size = Some(uploaded);
+            }
+        }
+    }
+
+    /// Learn version tags of files uploaded last time, whose tag the upload didn't return
+    pub fn learn_etags(&mut self, remote: &[CloudEntry]) {
+        for entry in remote {
+            if let Some(synced) = self.files.get_mut(&entry.name) {
+                if synced.etag.is_none() && synced.has_size_of(entry) {
+                    synced.etag = entry.etag.clone();
+                }
+            }
+        }
+    }
+
+    /// Forget files that are gone on both sides
+    pub fn prune(&mut self, remote: &[CloudEntry], backup_root: &Path) {
+        self.files.retain(|name, _| {
+            remote.iter().any(|entry| &entry.name == name) || backup_root.join(super::remote_file_name(name)).exists()
+        });
+    }
+}
+
+impl SyncedFile {
+    fn matches_remote(&self, remote: &CloudEntry) -> bool {
+        match (&self.etag, &remote.etag) {
+            (Some(ours), Some(theirs)) => ours == theirs,
+            _ => self.has_size_of(remote),
+        }
+    }
+
+    fn has_size_of(&self, remote: &CloudEntry) -> bool {
+        match self.remote_size {
+            Some(size) => remote.size == Some(size),
+            None => same_size(remote.size, self.size),
+        }
+    }
+}
+
+/// Whether a remote file has the size of a local archive, as is or encrypted
+pub fn same_size(remote: Option<u64>, local: u64) -> bool {
+    remote == Some(local) || remote == Some(local + crypto::OVERHEAD)
+}
+
+/// Where to keep the cloud copy of a conflicting archive: `X (cloud).zip`, `X (cloud 2).zip`...
+pub fn conflict_copy_path(local_path: &Path) -> PathBuf {
+    let stem = local_path.file_stem().and_then(|s| s.to_str()).unwrap_or("backup");
+    let extension = local_path.extension().and_then(|e| e.to_str()).unwrap_or("zip");
+    let mut candidate = local_path.with_file_name(format!("{} (cloud).{}", stem, extension));
+    let mut n = 2;
+    while candidate.exists() {
+        candidate = local_path.with_file_name(format!("{} (cloud {}).{}", stem, n, extension));
+        n += 1;
+    }
+    candidate
+}
+
+fn local_version(path: &Path) -> (u64, Option<DateTime<Utc>>) {
+    match fs::metadata(path) {
+        Ok(metadata) => (metadata.len(), metadata.modified().ok().map(DateTime::<Utc>::from)),
+        Err(_) => (0, None),
+    }
+}
+
+/// `cloud_sync_state_<hash of the destination>.json`
+fn state_path(backup_root: &Path, endpoint: &str) -> PathBuf {
+    let hash = Sha256::digest(endpoint.as_bytes());
+    backup_root.join(format!("cloud_sync_state_{}.json", crate::integrity::hex_encode(&hash[..8])))
+}
@@ -0,0 +1,520 @@
+use crate::types::*;
+use super::{recompress, state, CloudProvider};
+use crate::integrity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use log::{info, warn};
+
+/// Bytes are reported to the progress window in pieces of this size
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Longest single sleep of the rate limiter, so a cancel is noticed quickly
+const THROTTLE_SLICE: Duration = Duration::from_millis(100);
+
+const CANCELLED: &str = "Transfer cancelled";
+
+const PAUSED_TRANSFER_FILE: &str = "cloud_paused_transfer.json";
+
+/// Bytes moved by the file in flight, and whether the user asked to stop
+///
+/// Shared between the transfer thread, the provider doing the I/O and the UI.
+#[derive(Debug, Default)]
+pub struct TransferCounter {
+    bytes: AtomicU64,
+    cancelled: AtomicBool,
+    paused: AtomicBool, // Checked between files, the file in flight finishes
+    retries: AtomicU32,
+    rate_limit: AtomicU64, // Bytes per second for the file in flight, 0 = unlimited
+    expected: AtomicU64,   // Size of the file in flight, for request timeouts
+    started: Mutex<Option<Instant>>,
+}
+
+impl TransferCounter {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn add(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Start counting from zero, for the next file or a retry of the current one
+    pub fn restart(&self) {
+        self.bytes.store(0, Ordering::Relaxed);
+        if let Ok(mut started) = self.started.lock() {
+            *started = Some(Instant::now());
+        }
+    }
+
+    pub fn set_rate_limit(&self, bytes_per_second: u64) {
+        self.rate_limit.store(bytes_per_second, Ordering::Relaxed);
+    }
+
+    /// Announce the size of the next file, see `timeout`
+    pub fn expect(&self, bytes: u64) {
+        self.expected.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Request timeout for the file in flight: `base`, plus the time the rate limit needs for it
+    pub fn timeout(&self, base: Duration) -> Duration {
+        match self.rate_limit.load(Ordering::Relaxed) {
+            0 => base,
+            limit => base + Duration::from_secs(self.expected.load(Ordering::Relaxed) / limit),
+        }
+    }
+
+    /// Wait until the bytes counted so far are within the rate limit
+    fn throttle(&self) {
+        let limit = self.rate_limit.load(Ordering::Relaxed);
+        let started = self.started.lock().ok().and_then(|started| *started);
+        let started = match started {
+            Some(started) if limit > 0 => started,
+            _ => return,
+        };
+
+        let due = Duration::from_secs_f64(self.bytes() as f64 / limit as f64);
+        while !self.is_cancelled() {
+            let elapsed = started.elapsed();
+            if elapsed >= due {
+                break;
+            }
+            std::thread::sleep((due - elapsed).min(THROTTLE_SLICE));
+        }
+    }
+
+    /// Count a retried attempt, for the summary at the end
+    pub fn note_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Error out of a transfer loop once cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(SaveGuardianError::BackupOperationFailed(CANCELLED.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Reader that counts what passes through it and fails once the transfer is cancelled,
+/// which aborts the request or copy reading from it
+pub struct TrackedReader<R> {
+    inner: R,
+    counter: Arc<TransferCounter>,
+}
+
+impl<R: Read> TrackedReader<R> {
+    pub fn new(inner: R, counter: Arc<TransferCounter>) -> Self {
+        Self { inner, counter }
+    }
+}
+
+impl<R: Read> Read for TrackedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.counter.is_cancelled() {
+            return Err(io::Error::new(io::ErrorKind::Other, CANCELLED));
+        }
+        let limit = buf.len().min(CHUNK_SIZE);
+        let read = self.inner.read(&mut buf[..limit])?;
+        self.counter.add(read as u64);
+        self.counter.throttle();
+        Ok(read)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// One file to move between the backup folder and the cloud
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferJob {
+    pub direction: TransferDirection,
+    pub remote_name: String, // Relative to the sync folder
+    pub local_path: PathBuf,
+    pub size: u64, // Expected size, 0 if the server didn't say
+    pub sha256: Option<String>, // Downloads: hash the archive must have, from the cloud manifest
+    #[serde(default)]
+    pub replace: bool, // Uploads: the cloud copy is known to be outdated, upload even if its size matches
+}
+
+/// Files a paused transfer still has to move, kept in the backup folder so the pause
+/// survives closing the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PausedTransfer {
+    pub endpoint: String,       // Destination the jobs are for, see `Config::sync_endpoint`
+    pub mirror: Option<String>, // Remote name when mirroring, None for the selected destination
+    pub operation: String,      // What was running, e.g. "Full Sync"
+    pub paused_at: DateTime<Utc>,
+    pub jobs: Vec<TransferJob>,
+    #[serde(default)]
+    pub restore_to: Option<PathBuf>, // A cloud restore extracts the download here once resumed
+}
+
+impl PausedTransfer {
+    pub fn load(backup_root: &Path) -> Option<Self> {
+        let path = backup_root.join(PAUSED_TRANSFER_FILE);
+        let json = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<Self>(&json) {
+            Ok(paused) if !paused.jobs.is_empty() => Some(paused),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Ignoring unreadable paused transfer {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(backup_root.join(PAUSED_TRANSFER_FILE), json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save paused transfer: {}", e)))
+    }
+
+    /// Forget the paused transfer, once resumed to the end or discarded
+    pub fn clear(backup_root: &Path) {
+        let path = backup_root.join(PAUSED_TRANSFER_FILE);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to remove {:?}: {}", path, e);
+            }
+        }
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.jobs.iter().map(|job| job.size).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferState {
+    Queued,
+    Running,
+    Done,
+    AlreadyThere, // Upload skipped, the remote file already had the same name and size
+    Failed(String),
+    Cancelled,
+}
+
+/// How `run_job` finished a file
+enum JobResult {
+    Moved(String), // SHA-256 of what was uploaded or downloaded
+    AlreadyThere,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferItem {
+    pub job: TransferJob,
+    pub state: TransferState,
+    pub bytes_done: u64,
+    pub retries: u32, // Attempts that failed transiently before the final outcome
+    pub sha256: Option<String>, // Hash of the archive that was uploaded or verified on download
+    pub elapsed: Option<Duration>, // Time the file took, retries included; None until it ends
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Snapshot of a transfer queue for the progress window
+#[derive(Debug, Clone)]
+pub struct TransferStatus {
+    pub items: Vec<TransferItem>,
+    pub started_at: Instant,
+    pub finished_at: Option<Instant>,
+}
+
+impl TransferStatus {
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// The file being transferred right now
+    pub fn current(&self) -> Option<&TransferItem> {
+        self.items.iter().find(|item| item.state == TransferState::Running)
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.items.iter().map(|item| item.job.size.max(item.bytes_done)).sum()
+    }
+
+    pub fn done_bytes(&self) -> u64 {
+        self.items.iter().map(|item| item.bytes_done).sum()
+    }
+
+    pub fn count(&self, state: &TransferState) -> usize {
+        self.items.iter().filter(|item| &item.state == state).count()
+    }
+
+    /// Files that needed more than one attempt
+    pub fn retried(&self) -> usize {
+        self.items.iter().filter(|item| item.retries > 0).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.items.iter().filter(|item| matches!(item.state, TransferState::Failed(_))).count()
+    }
+
+    /// Jobs not finished yet: the queued ones and the one in flight
+    pub fn remaining_jobs(&self) -> Vec<TransferJob> {
+        self.items.iter()
+            .filter(|item| matches!(item.state, TransferState::Queued | TransferState::Running))
+            .map(|item| item.job.clone())
+            .collect()
+    }
+
+    /// Average speed since the queue started, in bytes per second
+    pub fn speed(&self) -> f64 {
+        let elapsed = self.finished_at.unwrap_or_else(Instant::now).duration_since(self.started_at).as_secs_f64();
+        if elapsed < 0.5 {
+            0.0
+        } else {
+            self.done_bytes() as f64 / elapsed
+        }
+    }
+
+    /// Time left at the current speed, unknown until some bytes have moved
+    pub fn eta(&self) -> Option<Duration> {
+        let speed = self.speed();
+        if speed <= 0.0 || self.is_finished() {
+            return None;
+        }
+        let remaining = self.total_bytes().saturating_sub(self.done_bytes());
+        Some(Duration::from_secs_f64(remaining as f64 / speed))
+    }
+}
+
+/// Uploads and downloads running on a background thread, one file at a time
+pub struct TransferQueue {
+    status: Arc<Mutex<TransferStatus>>,
+    counter: Arc<TransferCounter>,
+    provider_name: String,
+}
+
+impl TransferQueue {
+    pub fn start(provider: Box<dyn CloudProvider>, jobs: Vec<TransferJob>, bandwidth: BandwidthLimit, recompression: CloudRecompression) -> Self {
+        let status = Arc::new(Mutex::new(TransferStatus {
+            items: jobs.into_iter()
+                .map(|job| TransferItem { job, state: TransferState::Queued, bytes_done: 0, retries: 0, sha256: None, elapsed: None, finished_at: None })
+                .collect(),
+            started_at: Instant::now(),
+            finished_at: None,
+        }));
+        let counter = TransferCounter::new();
+        let provider_name = provider.name().to_string();
+
+        let worker_status = Arc::clone(&status);
+        let worker_counter = Arc::clone(&counter);
+        std::thread::spawn(move || run_queue(provider.as_ref(), &worker_status, &worker_counter, bandwidth, recompression));
+
+        Self { status, counter, provider_name }
+    }
+
+    pub fn provider_name(&self) -> &str {
+        &self.provider_name
+    }
+
+    /// Current state, with the live byte count of the file in flight
+    pub fn status(&self) -> TransferStatus {
+        let mut status = match self.status.lock() {
+            Ok(status) => status.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        let bytes = self.counter.bytes();
+        if let Some(item) = status.items.iter_mut().find(|item| item.state == TransferState::Running) {
+            item.bytes_done = bytes;
+        }
+        status
+    }
+
+    /// Stop after aborting the file in flight; queued files are skipped
+    pub fn cancel(&self) {
+        self.counter.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.counter.is_cancelled()
+    }
+
+    /// Hold the queue once the file in flight is done, until `resume`
+    pub fn pause(&self) {
+        self.counter.set_paused(true);
+    }
+
+    pub fn resume(&self) {
+        self.counter.set_paused(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.counter.is_paused()
+    }
+}
+
+fn run_queue(
+    provider: &dyn CloudProvider,
+    status: &Mutex<TransferStatus>,
+    counter: &Arc<TransferCounter>,
+    bandwidth: BandwidthLimit,
+    recompression: CloudRecompression,
+) {
+    let count = status.lock().map(|s| s.items.len()).unwrap_or(0);
+    let mut created_folders = HashSet::new();
+
+    for index in 0..count {
+        while counter.is_paused() && !counter.is_cancelled() {
+            std::thread::sleep(THROTTLE_SLICE);
+        }
+        let job = match status.lock() {
+            Ok(mut status) => {
+                let item = &mut status.items[index];
+                if counter.is_cancelled() {
+                    item.state = TransferState::Cancelled;
+                    continue;
+                }
+                item.state = TransferState::Running;
+                item.job.clone()
+            }
+            Err(_) => return,
+        };
+
+        let kbps = match job.direction {
+            TransferDirection::Upload => bandwidth.upload_kbps,
+            TransferDirection::Download => bandwidth.download_kbps,
+        };
+        counter.set_rate_limit(kbps as u64 * 1024);
+        counter.expect(job.size);
+        counter.restart();
+        let retries_before = counter.retries();
+        let job_started = Instant::now();
+        let result = run_job(provider, &job, counter, &mut created_folders, recompression);
+
+        if let Ok(mut status) = status.lock() {
+            let item = &mut status.items[index];
+            item.elapsed = Some(job_started.elapsed());
+            item.finished_at = Some(Utc::now());
+            item.bytes_done = counter.bytes();
+            item.retries = counter.retries() - retries_before;
+            item.state = match result {
+                Ok(JobResult::Moved(sha256)) => {
+                    item.sha256 = Some(sha256);
+                    // Recompression can make the upload smaller than the local archive
+                    item.job.size = item.bytes_done;
+                    TransferState::Done
+                }
+                Ok(JobResult::AlreadyThere) => {
+                    item.job.size = 0;
+                    TransferState::AlreadyThere
+                }
+                Err(_) if counter.is_cancelled() => TransferState::Cancelled,
+                Err(e) => {
+                    warn!("Transfer of {} failed: {}", job.remote_name, e);
+                    TransferState::Failed(e.to_string())
+                }
+            };
+        }
+    }
+
+    if let Ok(mut status) = status.lock() {
+        status.finished_at = Some(Instant::now());
+    }
+    info!("Transfer queue with {} files finished{}", count, if counter.is_cancelled() { " (cancelled)" } else { "" });
+}
+
+/// Move one file, returning the SHA-256 of the archive as uploaded (recompressed, before encryption)
+///
+/// Uploads are skipped when the remote file already has the size of what would be sent,
+/// the same name and size being the same archive, unless the job is known to replace an
+/// outdated copy: a changed archive can keep its size. Downloads are checked against the job's
+/// expected hash before anything is written, so a corrupted transfer never lands in the
+/// backup folder.
+fn run_job(
+    provider: &dyn CloudProvider,
+    job: &TransferJob,
+    counter: &Arc<TransferCounter>,
+    created_folders: &mut HashSet<String>,
+    recompression: CloudRecompression,
+) -> Result<JobResult> {
+    match job.direction {
+        TransferDirection::Upload => {
+            let data = fs::read(&job.local_path).map_err(SaveGuardianError::Io)?;
+            let data = recompress::for_upload(&job.remote_name, data, recompression);
+            match provider.stat(&job.remote_name) {
+                Ok(Some(remote)) if !job.replace && !remote.is_dir && state::same_size(remote.size, data.len() as u64) => {
+                    info!("{} is already in the cloud with the same size, not uploading it again", job.remote_name);
+                    return Ok(JobResult::AlreadyThere);
+                }
+                Ok(_) => {}
+                // Some servers refuse single-file lookups; uploading is still safe
+                Err(e) => warn!("Could not check for {} before uploading: {}", job.remote_name, e),
+            }
+            counter.expect(data.len() as u64);
+            let sha256 = integrity::sha256_bytes(&data);
+            // Game folder first, once per queue
+            if let Some((folder, _)) = job.remote_name.rsplit_once('/') {
+                if !created_folders.contains(folder) {
+                    provider.mkdir(folder)?;
+                    created_folders.insert(folder.to_string());
+                }
+            }
+            provider.upload_tracked(&job.remote_name, &data, counter)?;
+            Ok(JobResult::Moved(sha256))
+        }
+        TransferDirection::Download => {
+            let data = provider.download_tracked(&job.remote_name, counter)?;
+            let sha256 = integrity::sha256_bytes(&data);
+            if let Some(ref expected) = job.sha256 {
+                if !expected.eq_ignore_ascii_case(&sha256) {
+                    return Err(SaveGuardianError::BackupOperationFailed(format!(
+                        "Checksum mismatch for {}: expected {}, got {}; the download was discarded",
+                        job.remote_name, expected, sha256
+                    )));
+                }
+            }
+            let data = recompress::after_download(&job.remote_name, data);
+
+            // Never leave half a file behind if the app closes or the user cancels mid-write
+            let mut temp_name = job.local_path.as_os_str().to_os_string();
+            temp_name.push(".part");
+            let temp_path = PathBuf::from(temp_name);
+            fs::write(&temp_path, &data)
+                .and_then(|_| fs::rename(&temp_path, &job.local_path))
+                .map_err(|e| {
+                    let _ = fs::remove_file(&temp_path);
+                    SaveGuardianError::Io(e)
+                })?;
+            Ok(JobResult::Moved(sha256))
+        }
+    }
+}
@@ -0,0 +1,555 @@
+use crate::types::*;
+use super::{CloudEntry, CloudProvider, RetryError, RetryPolicy, TrackedReader, TransferCounter};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::blocking::{Body, Client, RequestBuilder, Response};
+use reqwest::{Method, StatusCode};
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use std::time::Duration;
+use log::{debug, info};
+
+/// Everything but unreserved characters (RFC 3986) is escaped in a path segment
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propfind xmlns:D="DAV:">
+    <D:prop>
+        <D:displayname/>
+        <D:getcontentlength/>
+        <D:resourcetype/>
+        <D:getetag/>
+        <D:getlastmodified/>
+    </D:prop>
+</D:propfind>"#;
+
+/// Cloud provider speaking plain WebDAV: Koofr, Nextcloud, ownCloud or any other server
+///
+/// `server_url` is the WebDAV root (e.g. `.../remote.php/dav/files/<user>` on Nextcloud)
+/// and `root_folder` the folder below it that holds the backups, possibly nested.
+pub struct WebDavProvider {
+    name: String,
+    server_url: String,
+    username: String,
+    password: String,
+    root_folder: String,
+    server: WebDavServer, // Which sharing API to use, if any
+    client: Client,
+    retry: RetryPolicy,
+}
+
+impl WebDavProvider {
+    pub fn new(name: &str, server_url: &str, username: &str, password: &str, root_folder: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            server_url: server_url.trim_end_matches('/').to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            root_folder: root_folder.trim_matches('/').to_string(),
+            server: WebDavServer::Generic,
+            client: Client::new(),
+            retry: RetryPolicy::none(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_server(mut self, server: WebDavServer) -> Self {
+        self.server = server;
+        self
+    }
+
+    /// Full URL for a path inside the sync folder, with every segment percent-encoded
+    fn url_for(&self, path: &str) -> String {
+        let root = encode_path(&self.root_folder);
+        let path = encode_path(path);
+        match (root.is_empty(), path.is_empty()) {
+            (true, true) => format!("{}/", self.server_url),
+            (true, false) => format!("{}/{}", self.server_url, path),
+            (false, true) => format!("{}/{}", self.server_url, root),
+            (false, false) => format!("{}/{}/{}", self.server_url, root, path),
+        }
+    }
+
+    fn request(&self, method: Method, url: &str, timeout_secs: u64) -> RequestBuilder {
+        self.client
+            .request(method, url)
+            .basic_auth(&self.username, Some(&self.password))
+            .timeout(Duration::from_secs(timeout_secs))
+    }
+
+    fn method(name: &[u8]) -> Method {
+        Method::from_bytes(name).expect("valid WebDAV method name")
+    }
+
+    /// Send a request, retrying timeouts, dropped connections and server errors
+    ///
+    /// `make` builds the request anew for every attempt; other error statuses are left
+    /// to the caller.
+    fn send(&self, what: &str, counter: &TransferCounter, make: impl Fn() -> RequestBuilder) -> Result<Response> {
+        self.retry.run(what, counter, || self.attempt(what, counter, make()))
+    }
+
+    fn attempt(&self, what: &str, counter: &TransferCounter, builder: RequestBuilder) -> std::result::Result<Response, RetryError> {
+        match builder.send() {
+            Ok(response) if is_transient_status(response.status()) => {
+                Err(RetryError::Transient(self.http_error(what, response)))
+            }
+            Ok(response) => Ok(response),
+            Err(e) => {
+                let transient = !counter.is_cancelled() && (e.is_timeout() || e.is_connect() || e.is_request() || e.is_body());
+                let url = e.url().map(|url| url.to_string()).unwrap_or_default();
+                let error = SaveGuardianError::cloud_failure(&self.name, what, &url, connection_error(&e));
+                Err(if transient { RetryError::Transient(error) } else { RetryError::Fatal(error) })
+            }
+        }
+    }
+
+    /// The error for an answer with an error status, with the server's explanation if it gave one
+    fn http_error(&self, what: &str, response: Response) -> SaveGuardianError {
+        let status = response.status().as_u16();
+        let url = response.url().to_string();
+        let detail = response.text().ok().map(|text| error_summary(&text));
+        SaveGuardianError::cloud_http(&self.name, what, status, &url, detail)
+    }
+}
+
+impl CloudProvider for WebDavProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn test_connection(&self) -> Result<()> {
+        let url = format!("{}/", self.server_url);
+        let response = self.send("connect", &TransferCounter::default(), || {
+            self.request(Self::method(b"PROPFIND"), &url, 10).header("Depth", "0")
+        })?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(self.http_error("connect", response))
+        }
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<CloudEntry>> {
+        let url = format!("{}/", self.url_for(path).trim_end_matches('/'));
+        info!("Listing cloud folder: {}", url);
+
+        let response = self.send("list files", &TransferCounter::default(), || {
+            self.request(Self::method(b"PROPFIND"), &url, 30)
+                .header("Depth", "1")
+                .header("Content-Type", "text/xml")
+                .body(PROPFIND_BODY)
+        })?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(SaveGuardianError::PathNotFound(std::path::PathBuf::from(url)));
+        }
+        if !response.status().is_success() {
+            return Err(self.http_error("list files", response));
+        }
+
+        let body = response.text()
+            .map_err(|e| SaveGuardianError::cloud_failure(&self.name, "read the file listing", &url, e))?;
+        debug!("PROPFIND response (first 1000 chars): {}", body.chars().take(1000).collect::<String>());
+
+        let folder_path = url_decode(&href_path(&url));
+        let prefix = path.trim_matches('/');
+
+        // The listing includes the folder itself; hrefs may be absolute URLs or paths
+        let entries = parse_propfind(&body)
+            .into_iter()
+            .filter(|entry| url_decode(&href_path(&entry.href)).trim_end_matches('/') != folder_path.trim_end_matches('/'))
+            .filter_map(|entry| {
+                let decoded = url_decode(&href_path(&entry.href));
+                let file_name = decoded.trim_end_matches('/').rsplit('/').next()?.to_string();
+                if file_name.is_empty() {
+                    return None;
+                }
+                let name = if prefix.is_empty() { file_name } else { format!("{}/{}", prefix, file_name) };
+                Some(CloudEntry { name, size: entry.size, is_dir: entry.is_dir, etag: entry.etag, modified: entry.modified })
+            })
+            .collect::<Vec<_>>();
+
+        info!("Found {} entries in cloud folder", entries.len());
+        Ok(entries)
+    }
+
+    fn stat(&self, path: &str) -> Result<Option<CloudEntry>> {
+        let url = self.url_for(path);
+        let what = format!("check {}", path);
+        let response = self.send(&what, &TransferCounter::default(), || {
+            self.request(Self::method(b"PROPFIND"), &url, 30)
+                .header("Depth", "0")
+                .header("Content-Type", "text/xml")
+                .body(PROPFIND_BODY)
+        })?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(self.http_error(&what, response));
+        }
+
+        let body = response.text()
+            .map_err(|e| SaveGuardianError::cloud_failure(&self.name, &what, &url, e))?;
+        Ok(parse_propfind(&body).into_iter().next().map(|entry| CloudEntry {
+            name: path.trim_matches('/').to_string(),
+            size: entry.size,
+            is_dir: entry.is_dir,
+            etag: entry.etag,
+            modified: entry.modified,
+        }))
+    }
+
+    fn upload_tracked(&self, path: &str, data: &[u8], counter: &Arc<TransferCounter>) -> Result<()> {
+        let url = self.url_for(path);
+        info!("Uploading {} bytes to {}", data.len(), url);
+
+        let response = self.send(&format!("upload {}", path), counter, || {
+            // Streamed through the counter so a cancel aborts the request mid-body
+            counter.restart();
+            let body = Body::sized(TrackedReader::new(Cursor::new(data.to_vec()), Arc::clone(counter)), data.len() as u64);
+            self.request(Method::PUT, &url, counter.timeout(Duration::from_secs(60)).as_secs())
+                .header("Content-Type", "application/octet-stream")
+                .body(body)
+        });
+        counter.check()?;
+        let response = response?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(self.http_error(&format!("upload {}", path), response))
+        }
+    }
+
+    fn download_tracked(&self, path: &str, counter: &Arc<TransferCounter>) -> Result<Vec<u8>> {
+        let url = self.url_for(path);
+        info!("Downloading {}", url);
+
+        let what = format!("download {}", path);
+        // A connection dropped halfway through the body is retried as well
+        self.retry.run(&what, counter, || {
+            counter.restart();
+            let response = self.attempt(&what, counter, self.request(Method::GET, &url, counter.timeout(Duration::from_secs(60)).as_secs()))?;
+            if !response.status().is_success() {
+                return Err(RetryError::Fatal(self.http_error(&what, response)));
+            }
+
+            let mut data = Vec::new();
+            let read = TrackedReader::new(response, Arc::clone(counter)).read_to_end(&mut data);
+            counter.check().map_err(RetryError::Fatal)?;
+            read.map_err(|e| RetryError::Transient(SaveGuardianError::cloud_failure(&self.name, &what, &url, e)))?;
+            Ok(data)
+        })
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        let url = self.url_for(path);
+        let response = self.send("delete", &TransferCounter::default(), || self.request(Method::DELETE, &url, 30))?;
+
+        match response.status() {
+            // Already gone is as good as deleted
+            StatusCode::NOT_FOUND => Ok(()),
+            status if status.is_success() => Ok(()),
+            _ => Err(self.http_error(&format!("delete {}", path), response)),
+        }
+    }
+
+    fn mkdir(&self, path: &str) -> Result<()> {
+        if path.trim_matches('/').is_empty() {
+            return self.create_root();
+        }
+        self.create_collection(&self.url_for(path))
+    }
+
+    fn share_link(&self, path: &str, expires: Option<chrono::NaiveDate>) -> Result<String> {
+        let what = format!("share {}", path);
+        match self.server {
+            WebDavServer::Koofr => self.share_koofr(&what, path, expires),
+            WebDavServer::Nextcloud | WebDavServer::OwnCloud => self.share_ocs(&what, path, expires),
+            WebDavServer::Generic => Err(SaveGuardianError::cloud_failure(
+                &self.name, &what, "", "plain WebDAV has no sharing links; pick your server type in Settings",
+            )),
+        }
+    }
+}
+
+impl WebDavProvider {
+    /// Create the sync folder one level at a time, since MKCOL needs the parent to exist
+    fn create_root(&self) -> Result<()> {
+        let mut url = self.server_url.clone();
+        for segment in self.root_folder.split('/').filter(|s| !s.is_empty()) {
+            url = format!("{}/{}", url, utf8_percent_encode(segment, PATH_SEGMENT));
+            self.create_collection(&url)?;
+        }
+        Ok(())
+    }
+
+    fn create_collection(&self, url: &str) -> Result<()> {
+        info!("Creating cloud folder at: {}", url);
+
+        let response = self.send("create a folder", &TransferCounter::default(), || self.request(Self::method(b"MKCOL"), url, 30))?;
+        match response.status() {
+            // 405 means the folder is already there
+            StatusCode::CREATED | StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            status @ (StatusCode::NOT_FOUND | StatusCode::CONFLICT) => Err(SaveGuardianError::cloud_http(
+                &self.name, "create a folder", status.as_u16(), url, Some("the parent folder doesn't exist".to_string()),
+            )),
+            status if status.is_success() => Ok(()),
+            _ => Err(self.http_error("create a folder", response)),
+        }
+    }
+
+    /// The path of a file as the server's own APIs see it, below the user's home folder
+    ///
+    /// `prefix` is whatever part of the WebDAV root lies past the DAV endpoint itself.
+    fn home_path(&self, prefix: &str, path: &str) -> String {
+        let parts: Vec<&str> = [prefix, self.root_folder.as_str(), path]
+            .into_iter()
+            .flat_map(|part| part.split('/'))
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        format!("/{}", parts.join("/"))
+    }
+
+    /// Public link through the OCS sharing API of Nextcloud and ownCloud
+    ///
+    /// The API lives next to `remote.php`; share type 3 is a public link, permission 1 read only.
+    fn share_ocs(&self, what: &str, path: &str, expires: Option<chrono::NaiveDate>) -> Result<String> {
+        let (base, dav) = self.server_url.split_once("/remote.php/").ok_or_else(|| {
+            SaveGuardianError::cloud_failure(&self.name, what, &self.server_url, "the server URL doesn't contain /remote.php/")
+        })?;
+        // files/<user>/... on the new endpoint, nothing after webdav on the old one
+        let prefix = match dav.strip_prefix("dav/files/") {
+            Some(rest) => rest.split_once('/').map_or("", |(_, prefix)| prefix),
+            None => dav.strip_prefix("webdav").unwrap_or(""),
+        };
+
+        let url = format!("{}/ocs/v2.php/apps/files_sharing/api/v1/shares?format=json", base);
+        let mut form = vec![
+            ("path", self.home_path(&url_decode(prefix), path)),
+            ("shareType", "3".to_string()),
+            ("permissions", "1".to_string()),
+        ];
+        if let Some(date) = expires {
+            form.push(("expireDate", date.format("%Y-%m-%d").to_string()));
+        }
+
+        info!("Creating share link for {} at: {}", path, url);
+        let response = self.send(what, &TransferCounter::default(), || {
+            self.request(Method::POST, &url, 30)
+                .header("OCS-APIRequest", "true")
+                .form(&form)
+        })?;
+
+        let status = response.status();
+        let answer: serde_json::Value = response.json().unwrap_or_default();
+        match answer.pointer("/ocs/data/url").and_then(|link| link.as_str()) {
+            Some(link) if status.is_success() => Ok(link.to_string()),
+            _ => Err(SaveGuardianError::cloud_http(
+                &self.name, what, status.as_u16(), &url,
+                answer.pointer("/ocs/meta/message").and_then(|m| m.as_str()).map(str::to_string),
+            )),
+        }
+    }
+
+    /// Public link through Koofr's REST API; the WebDAV root is `.../dav/<mount name>/...`
+    fn share_koofr(&self, what: &str, path: &str, expires: Option<chrono::NaiveDate>) -> Result<String> {
+        if expires.is_some() {
+            return Err(SaveGuardianError::cloud_failure(
+                &self.name, what, "", "Koofr links don't expire; create one without an expiry date and delete it on koofr.net when done",
+            ));
+        }
+        let (base, dav) = self.server_url.split_once("/dav/").ok_or_else(|| {
+            SaveGuardianError::cloud_failure(&self.name, what, &self.server_url, "the server URL doesn't contain /dav/")
+        })?;
+        let (mount_name, prefix) = dav.split_once('/').unwrap_or((dav, ""));
+        let mount_name = url_decode(mount_name);
+
+        let mounts_url = format!("{}/api/v2/mounts", base);
+        let response = self.send(what, &TransferCounter::default(), || self.request(Method::GET, &mounts_url, 30))?;
+        if !response.status().is_success() {
+            return Err(self.http_error(what, response));
+        }
+        let mounts: serde_json::Value = response.json().unwrap_or_default();
+        let mounts = mounts["mounts"].as_array().cloned().unwrap_or_default();
+        let mount_id = mounts.iter()
+            .find(|mount| mount["name"].as_str() == Some(mount_name.as_str()))
+            .or_else(|| mounts.iter().find(|mount| mount["isPrimary"].as_bool() == Some(true)))
+            .and_then(|mount| mount["id"].as_str())
+            .ok_or_else(|| SaveGuardianError::cloud_failure(
+                &self.name, what, &mounts_url, format!("no storage named {} on this account", mount_name),
+            ))?
+            .to_string();
+
+        let url = format!("{}/api/v2/mounts/{}/links", base, mount_id);
+        let body = serde_json::json!({ "path": self.home_path(&url_decode(prefix), path) });
+        info!("Creating share link for {} at: {}", path, url);
+        let response = self.send(what, &TransferCounter::default(), || self.request(Method::POST, &url, 30).json(&body))?;
+        if !response.status().is_success() {
+            return Err(self.http_error(what, response));
+        }
+        let link: serde_json::Value = response.json().unwrap_or_default();
+        link["shortUrl"].as_str()
+            .or_else(|| link["url"].as_str())
+            .map(str::to_string)
+            .ok_or_else(|| SaveGuardianError::cloud_failure(&self.name, what, &url, "the answer had no link in it"))
+    }
+}
+
+/// Longest server error text kept in an error message
+const MAX_ERROR_TEXT: usize = 200;
+
+/// The readable part of an error page: tags dropped, whitespace collapsed, cut short
+///
+/// WebDAV servers answer with anything from an XML `<s:message>` to a full HTML page.
+fn error_summary(body: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in body.chars() {
+        match c {
+            '<' => { in_tag = true; text.push(' '); }
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = xml_unescape(&text);
+    if text.chars().count() > MAX_ERROR_TEXT {
+        format!("{}…", text.chars().take(MAX_ERROR_TEXT).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// What went wrong with a request that got no answer, without reqwest's own wrapping
+fn connection_error(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        "timed out".to_string()
+    } else if error.is_connect() {
+        format!("could not connect: {}", std::error::Error::source(error).map_or_else(|| error.to_string(), |source| source.to_string()))
+    } else {
+        error.to_string()
+    }
+}
+
+/// Statuses that say "try again later" rather than "this won't work"
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// One `<response>` of a multistatus body
+struct PropfindEntry {
+    href: String,
+    size: Option<u64>,
+    etag: Option<String>,
+    modified: Option<chrono::DateTime<chrono::Utc>>,
+    is_dir: bool,
+}
+
+/// Pull href, content length, ETag, modification date and collection flag out of each `<response>` in a multistatus body
+fn parse_propfind(body: &str) -> Vec<PropfindEntry> {
+    let mut entries = Vec::new();
+
+    for block in split_elements(body, "response") {
+        let href = match element_text(block, "href") {
+            Some(href) => xml_unescape(href.trim()),
+            None => continue,
+        };
+        let size = element_text(block, "getcontentlength").and_then(|s| s.trim().parse().ok());
+        let etag = element_text(block, "getetag")
+            .map(|s| xml_unescape(s.trim()).trim_matches('"').to_string())
+            .filter(|s| !s.is_empty());
+        // HTTP date, e.g. "Wed, 21 Oct 2015 07:28:00 GMT"
+        let modified = element_text(block, "getlastmodified")
+            .and_then(|s| chrono::DateTime::parse_from_rfc2822(s.trim()).ok())
+            .map(|date| date.with_timezone(&chrono::Utc));
+        let is_dir = block.contains("collection") || href.ends_with('/');
+        entries.push(PropfindEntry { href, size, etag, modified, is_dir });
+    }
+
+    entries
+}
+
+/// Inner text of every `<X:name>` element, whatever namespace prefix the server uses
+fn split_elements<'a>(body: &'a str, name: &str) -> Vec<&'a str> {
+    let mut blocks = Vec::new();
+    let mut rest = body;
+
+    while let Some((open_end, prefix)) = find_open_tag(rest, name) {
+        let close = format!("</{}{}>", prefix, name);
+        match rest[open_end..].find(&close) {
+            Some(end) => {
+                blocks.push(&rest[open_end..open_end + end]);
+                rest = &rest[open_end + end + close.len()..];
+            }
+            None => break,
+        }
+    }
+
+    blocks
+}
+
+fn element_text<'a>(block: &'a str, name: &str) -> Option<&'a str> {
+    split_elements(block, name).into_iter().next()
+}
+
+/// Find `<prefix:name>` or `<name>`, returning the end of the tag and the prefix including `:`
+fn find_open_tag<'a>(text: &'a str, name: &str) -> Option<(usize, &'a str)> {
+    let mut search_pos = 0;
+    while let Some(start) = text[search_pos..].find('<') {
+        let tag_start = search_pos + start + 1;
+        let tag_end = tag_start + text[tag_start..].find('>')?;
+        let tag = &text[tag_start..tag_end];
+        let tag_name = tag.split_whitespace().next().unwrap_or("");
+        let (prefix, local) = match tag_name.rfind(':') {
+            Some(i) => (&tag_name[..=i], &tag_name[i + 1..]),
+            None => ("", tag_name),
+        };
+        if local.eq_ignore_ascii_case(name) && !tag.ends_with('/') {
+            return Some((tag_end + 1, prefix));
+        }
+        search_pos = tag_end + 1;
+    }
+    None
+}
+
+/// Path part of an href, which servers may send as a full URL or as an absolute path
+fn href_path(href: &str) -> String {
+    if href.starts_with("http://") || href.starts_with("https://") {
+        reqwest::Url::parse(href)
+            .map(|u| u.path().to_string())
+            .unwrap_or_else(|_| href.to_string())
+    } else {
+        href.to_string()
+    }
+}
+
+/// Percent-encode each segment of a relative path, keeping the slashes between them
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn url_decode(encoded: &str) -> String {
+    percent_decode_str(encoded).decode_utf8_lossy().to_string()
+}
+
+/// Undo the XML escaping of text content; `&amp;` goes last so `&amp;lt;` stays `&lt;`
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,349 @@
+use crate::types::{
+    machine_name, CloudBackend, CloudRemote, Config, FolderTargetConfig, GameDetails, GameDetailsEntry, GameTags,
+    KoofrConfig, MachineInfo, Result, SaveGuardianError, SftpConfig, UserProfile,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+impl Config {
+    /// Load configuration from file
+    pub fn load_from_file(path: &PathBuf) -> Result<Config> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| SaveGuardianError::Io(e))?;
+        
+        let config: Config = toml::from_str(&contents)
+            .map_err(|e| SaveGuardianError::Toml(e))?;
+        
+        Ok(config)
+    }
+
+    /// Save configuration to file
+    pub fn save_to_file(&self, path: &PathBuf) -> Result<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|_| SaveGuardianError::SaveOperationFailed("Failed to serialize config".to_string()))?;
+        
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SaveGuardianError::Io(e))?;
+        }
+        
+        fs::write(path, contents)
+            .map_err(|e| SaveGuardianError::Io(e))?;
+        
+        Ok(())
+    }
+
+    /// Get the default config file path
+    pub fn get_config_path() -> PathBuf {
+        if let Some(config_dir) = dirs::config_dir() {
+            config_dir.join("save-guardian").join("config.toml")
+        } else {
+            PathBuf::from("config.toml")
+        }
+    }
+
+    /// This install, as recorded in new backups
+    pub fn machine(&self) -> MachineInfo {
+        MachineInfo { id: self.machine_id.clone(), name: machine_name() }
+    }
+
+    /// Get the currently active household profile, if any
+    pub fn active_profile(&self) -> Option<&UserProfile> {
+        let name = self.active_profile.as_ref()?;
+        self.profiles.iter().find(|p| &p.name == name)
+    }
+
+    /// Get the active profile mutably
+    pub fn active_profile_mut(&mut self) -> Option<&mut UserProfile> {
+        let name = self.active_profile.clone()?;
+        self.profiles.iter_mut().find(|p| p.name == name)
+    }
+
+    /// Why `name` can't be used for a new profile, if it can't
+    ///
+    /// Profiles are told apart on disk by their sanitized names, so "a b" and "a_b" would
+    /// share one backup folder, and so would "Alice" and "alice" on Windows and macOS.
+    pub fn profile_name_problem(&self, name: &str) -> Option<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Some("Enter a name".to_string());
+        }
+        if name.chars().all(|c| c == '.') {
+            return Some("A profile name needs more than dots".to_string());
+        }
+        let folder = UserProfile::sanitize_name(name).to_lowercase();
+        self.profiles.iter()
+            .find(|p| UserProfile::sanitize_name(&p.name).to_lowercase() == folder)
+            .map(|p| format!("\"{}\" would share a backup folder with \"{}\"", name, p.name))
+    }
+
+    /// Get the backup directory for the active profile
+    pub fn effective_backup_path(&self) -> PathBuf {
+        match self.active_profile() {
+            Some(profile) => self.backup_path
+                .join("profiles")
+                .join(UserProfile::sanitize_name(&profile.name)),
+            None => self.backup_path.clone(),
+        }
+    }
+
+    /// Get the cloud sync folder for the active profile
+    pub fn effective_sync_folder(&self) -> String {
+        match self.active_profile() {
+            Some(profile) if !profile.cloud_folder.is_empty() => profile.cloud_folder.clone(),
+            _ => match self.cloud_backend {
+                CloudBackend::WebDav => self.koofr_config.sync_folder.clone(),
+                CloudBackend::Sftp => self.sftp_config.remote_path.clone(),
+                CloudBackend::Folder => String::new(), // The folder itself is the root
+            },
+        }
+    }
+
+    /// Whether the selected cloud backend is switched on
+    pub fn cloud_enabled(&self) -> bool {
+        match self.cloud_backend {
+            CloudBackend::WebDav => self.koofr_config.enabled,
+            CloudBackend::Sftp => self.sftp_config.enabled,
+            CloudBackend::Folder => self.folder_target.enabled,
+        }
+    }
+
+    /// Display name of the selected cloud backend
+    pub fn cloud_name(&self) -> &'static str {
+        match self.cloud_backend {
+            CloudBackend::WebDav => self.koofr_config.server.label(),
+            CloudBackend::Sftp => "SFTP",
+            CloudBackend::Folder => "Folder",
+        }
+    }
+
+    /// Whether the selected cloud backend goes over the network, which offline mode forbids
+    pub fn cloud_uses_network(&self) -> bool {
+        !matches!(self.cloud_backend, CloudBackend::Folder)
+    }
+
+    /// Where the selected cloud backend connects to, for status displays
+    pub fn cloud_endpoint(&self) -> String {
+        match self.cloud_backend {
+            CloudBackend::WebDav => format!("{} ({})", self.koofr_config.server_url, self.koofr_config.username),
+            CloudBackend::Sftp => format!("{}@{}:{}", self.sftp_config.username, self.sftp_config.host, self.sftp_config.port),
+            CloudBackend::Folder => self.folder_target.path.display().to_string(),
+        }
+    }
+
+    /// Name of the selected cloud destination, for the remote selector
+    pub fn remote_name(&self) -> String {
+        if self.cloud_remote_name.trim().is_empty() {
+            self.cloud_name().to_string()
+        } else {
+            self.cloud_remote_name.clone()
+        }
+    }
+
+    /// Every destination's name, the selected one included, sorted
+    pub fn remote_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.cloud_remotes.iter().map(|r| r.name.clone()).collect();
+        names.push(self.remote_name());
+        names.sort_by_key(|name| name.to_lowercase());
+        names
+    }
+
+    /// The selected destination's connection settings, as they'd be kept while another is selected
+    pub fn current_remote(&self) -> CloudRemote {
+        CloudRemote {
+            name: self.remote_name(),
+            backend: self.cloud_backend,
+            webdav: self.koofr_config.clone(),
+            sftp: self.sftp_config.clone(),
+            folder: self.folder_target.clone(),
+        }
+    }
+
+    /// A copy of this config with another destination selected, e.g. to mirror uploads to it
+    pub fn with_remote(&self, remote: &CloudRemote) -> Config {
+        let mut config = self.clone();
+        config.apply_remote(remote.clone());
+        config
+    }
+
+    /// Select another destination by name, keeping the current one in the list
+    ///
+    /// Returns false if there is no destination of that name.
+    pub fn select_remote(&mut self, name: &str) -> bool {
+        let index = match self.cloud_remotes.iter().position(|r| r.name == name) {
+            Some(index) => index,
+            None => return false,
+        };
+        let remote = self.cloud_remotes.remove(index);
+        let current = self.current_remote();
+        self.cloud_remotes.push(current);
+        self.apply_remote(remote);
+        true
+    }
+
+    /// Add an empty destination named "Remote 2" or so, and select it to be filled in
+    pub fn add_remote(&mut self) -> String {
+        let names = self.remote_names();
+        let name = (2..)
+            .map(|n| format!("Remote {}", n))
+            .find(|name| !names.contains(name))
+            .expect("some name is free");
+        self.cloud_remotes.push(CloudRemote {
+            name: name.clone(),
+            backend: CloudBackend::WebDav,
+            webdav: KoofrConfig::default(),
+            sftp: SftpConfig::default(),
+            folder: FolderTargetConfig::default(),
+        });
+        self.select_remote(&name);
+        name
+    }
+
+    /// Drop the selected destination and select the first of the others, if there are any
+    pub fn remove_selected_remote(&mut self) -> bool {
+        if self.cloud_remotes.is_empty() {
+            return false;
+        }
+        let next = self.cloud_remotes.remove(0);
+        self.apply_remote(next);
+        true
+    }
+
+    fn apply_remote(&mut self, remote: CloudRemote) {
+        // The automatic sync schedule is shared, it only happens to live in the WebDAV settings
+        let (auto_sync, sync_interval_minutes) = (self.koofr_config.auto_sync, self.koofr_config.sync_interval_minutes);
+        self.cloud_remote_name = remote.name;
+        self.cloud_backend = remote.backend;
+        self.koofr_config = KoofrConfig { auto_sync, sync_interval_minutes, ..remote.webdav };
+        self.sftp_config = remote.sftp;
+        self.folder_target = remote.folder;
+    }
+
+    /// Identifies the cloud destination sync state and replica reports belong to
+    pub fn sync_endpoint(&self) -> String {
+        format!("{} {} /{}", self.cloud_name(), self.cloud_endpoint(), self.effective_sync_folder())
+    }
+
+    /// Check whether a save is hidden for the active profile
+    pub fn is_save_hidden(&self, save_path: &PathBuf) -> bool {
+        self.active_profile()
+            .map_or(false, |p| p.hidden_saves.contains(save_path))
+    }
+
+    /// Check whether a save folder is write-protected by us
+    pub fn is_save_protected(&self, save_path: &Path) -> bool {
+        self.protected_saves.iter().any(|p| p == save_path)
+    }
+
+    /// Tags the user gave a game, by backup key
+    pub fn game_tags(&self, game_key: &str) -> &[String] {
+        self.game_tags.iter()
+            .find(|entry| entry.game_key == game_key)
+            .map_or(&[], |entry| entry.tags.as_slice())
+    }
+
+    /// Check whether a game carries a tag, ignoring case
+    pub fn game_has_tag(&self, game_key: &str, tag: &str) -> bool {
+        self.game_tags(game_key).iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Tag a game, ignoring empty and duplicate tags
+    pub fn add_game_tag(&mut self, game_key: &str, tag: &str) {
+        let tag = tag.trim();
+        if tag.is_empty() || self.game_has_tag(game_key, tag) {
+            return;
+        }
+
+        match self.game_tags.iter_mut().find(|entry| entry.game_key == game_key) {
+            Some(entry) => entry.tags.push(tag.to_string()),
+            None => self.game_tags.push(GameTags {
+                game_key: game_key.to_string(),
+                tags: vec![tag.to_string()],
+            }),
+        }
+    }
+
+    /// Remove a tag from a game, dropping the entry once it has no tags left
+    pub fn remove_game_tag(&mut self, game_key: &str, tag: &str) {
+        for entry in self.game_tags.iter_mut().filter(|entry| entry.game_key == game_key) {
+            entry.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+        }
+        self.game_tags.retain(|entry| !entry.tags.is_empty());
+    }
+
+    /// Genres, year and platforms entered for a game, by backup key
+    pub fn game_details(&self, game_key: &str) -> Option<&GameDetails> {
+        self.game_details.iter()
+            .find(|entry| entry.game_key == game_key)
+            .map(|entry| &entry.details)
+    }
+
+    /// Store the details of a game, dropping the entry when everything was cleared
+    pub fn set_game_details(&mut self, game_key: &str, details: GameDetails) {
+        self.game_details.retain(|entry| entry.game_key != game_key);
+        if !details.is_empty() {
+            self.game_details.push(GameDetailsEntry {
+                game_key: game_key.to_string(),
+                details,
+            });
+        }
+    }
+
+    /// Whether a game's settings files are backed up with its saves
+    pub fn includes_settings(&self, game_key: &str) -> bool {
+        self.settings_games.iter().any(|key| key == game_key)
+    }
+
+    pub fn set_include_settings(&mut self, game_key: &str, include: bool) {
+        self.settings_games.retain(|key| key != game_key);
+        if include {
+            self.settings_games.push(game_key.to_string());
+        }
+    }
+
+    /// Largest archive part in bytes, None when archives aren't split
+    pub fn volume_size(&self) -> Option<u64> {
+        (self.volume_size_mb > 0).then(|| self.volume_size_mb * 1024 * 1024)
+    }
+
+    /// Size from which files go into the dedup store, None while it's off
+    pub fn dedup_min_size(&self) -> Option<u64> {
+        self.dedup_store.enabled.then(|| self.dedup_store.min_file_mb * 1024 * 1024)
+    }
+
+    /// Threads to hash archives with, resolving the automatic setting
+    pub fn worker_threads(&self) -> usize {
+        crate::workers::thread_count(self.worker_threads)
+    }
+
+    /// Every tag in use, sorted, for filters and pickers
+    pub fn all_game_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.game_tags.iter().flat_map(|entry| entry.tags.iter()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    /// Check whether a game is kept out of cloud sync, by itself or by its tags
+    pub fn is_cloud_excluded(&self, game_key: &str) -> bool {
+        self.cloud_excluded_games.iter().any(|key| key == game_key)
+            || self.cloud_excluded_tags.iter().any(|tag| self.game_has_tag(game_key, tag))
+    }
+
+    /// Take a game out of cloud sync or put it back; its tags may still exclude it
+    pub fn set_cloud_excluded(&mut self, game_key: &str, excluded: bool) {
+        self.cloud_excluded_games.retain(|key| key != game_key);
+        if excluded {
+            self.cloud_excluded_games.push(game_key.to_string());
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::types::*;
+use crate::integrity;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use log::{debug, info, warn};
+
+/// Folder under a backup root holding the files shared between its backups
+pub const STORE_FOLDER: &str = "store";
+
+/// Objects younger than this survive garbage collection: a backup still being written
+/// may have stored them without having saved its metadata yet
+const GC_GRACE: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Large save files kept once per content and shared by every backup that has them
+///
+/// Objects live uncompressed in `store/objects/<first two hex digits>/<sha256>`. Backups
+/// list theirs in `BackupInfo::stored_files` instead of holding them in the archive.
+pub struct DedupStore {
+    root: PathBuf,
+}
+
+/// A file copied into the store
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub sha256: String,
+    pub size: u64,
+    pub added: bool, // False when an earlier backup already stored the same contents
+}
+
+/// What repacking backups into the store, or collecting its garbage, did
+#[derive(Debug, Clone, Default)]
+pub struct StoreReport {
+    pub backups_repacked: usize,
+    pub files_moved: usize,
+    pub objects_removed: usize,
+    pub bytes_freed: u64,
+    pub failed: Vec<(String, String)>, // Backup ID or object, and why
+}
+
+impl StoreReport {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.backups_repacked > 0 {
+            parts.push(format!("{} files of {} backups moved into the store", self.files_moved, self.backups_repacked));
+        }
+        if self.objects_removed > 0 {
+            parts.push(format!("{} unused files removed", self.objects_removed));
+        }
+        if parts.is_empty() {
+            parts.push("Nothing to do".to_string());
+        }
+        let mut text = format!("{}, {} freed", parts.join(", "), format_size(self.bytes_freed));
+        if !self.failed.is_empty() {
+            text.push_str(&format!(", {} failed", self.failed.len()));
+        }
+        text
+    }
+}
+
+impl DedupStore {
+    pub fn new(backup_root: &Path) -> Self {
+        Self { root: backup_root.join(STORE_FOLDER) }
+    }
+
+    /// The store holding a backup's stored files, next to its archive
+    pub fn of_backup(backup_info: &BackupInfo) -> Self {
+        Self::new(backup_info.backup_path.parent().unwrap_or(Path::new(".")))
+    }
+
+    pub fn object_path(&self, sha256: &str) -> PathBuf {
+        self.root.join("objects").join(sha256.get(..2).unwrap_or("00")).join(sha256)
+    }
+
+    /// Copy `reader` into the store; `check` runs between chunks, so the copy can be cancelled
+    pub fn put(&self, reader: &mut dyn Read, check: &dyn Fn() -> Result<()>) -> Result<StoredObject> {
+        let temp_dir = self.root.join("tmp");
+        fs::create_dir_all(&temp_dir)?;
+        let temp_path = temp_dir.join(format!("{:016x}.tmp", rand::random::<u64>()));
+
+        let written = Self::write_hashed(reader, &temp_path, check);
+        let (sha256, size) = match written {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                return Err(e);
+            }
+        };
+
+        let object_path = self.object_path(&sha256);
+        if object_path.exists() {
+            let _ = fs::remove_file(&temp_path);
+            return Ok(StoredObject { sha256, size, added: false });
+        }
+        if let Some(parent) = object_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&temp_path, &object_path)?;
+        debug!("Stored {} ({})", sha256, format_size(size));
+        Ok(StoredObject { sha256, size, added: true })
+    }
+
+    fn write_hashed(reader: &mut dyn Read, path: &Path, check: &dyn Fn() -> Result<()>) -> Result<(String, u64)> {
+        let mut output = fs::File::create(path)?;
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        let mut chunk = vec![0u8; 1024 * 1024];
+        loop {
+            check()?;
+            let read = reader.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&chunk[..read]);
+            output.write_all(&chunk[..read])?;
+            size += read as u64;
+        }
+        output.sync_all()?;
+        Ok((integrity::hex_encode(&hasher.finalize()), size))
+    }
+
+    /// Whether a stored file is there with the recorded contents; Err when it's missing
+    pub fn check(&self, stored: &StoredFile) -> Result<bool> {
+        let path = self.object_path(&stored.sha256);
+        if !path.is_file() {
+            return Err(SaveGuardianError::PathNotFound(path));
+        }
+        Ok(integrity::sha256_file(&path)? == stored.sha256)
+    }
+
+    /// Copy a stored file out to where a restore puts it
+    pub fn copy_out(&self, stored: &StoredFile, target: &Path, check: &dyn Fn() -> Result<()>) -> Result<()> {
+        let mut input = fs::File::open(self.object_path(&stored.sha256))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Stored file {} is missing: {}", stored.name, e)))?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut output = fs::File::create(target)?;
+        let mut chunk = vec![0u8; 1024 * 1024];
+        loop {
+            check()?;
+            let read = input.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            output.write_all(&chunk[..read])?;
+        }
+        Ok(())
+    }
+
+    /// Number of objects and their combined size
+    pub fn usage(&self) -> (usize, u64) {
+        WalkDir::new(self.root.join("objects")).into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .fold((0, 0), |(count, bytes), e| (count + 1, bytes + e.metadata().map(|m| m.len()).unwrap_or(0)))
+    }
+
+    /// Delete the objects no backup in `backup_root` refers to any more
+    ///
+    /// Reads the metadata files itself and stops if one can't be read, since the
+    /// objects it refers to can't be told apart from unused ones.
+    pub fn collect_garbage(&self, backup_root: &Path) -> Result<StoreReport> {
+        let mut referenced: HashSet<String> = HashSet::new();
+        for entry in fs::read_dir(backup_root)?.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let is_metadata = path.file_name().and_then(|n| n.to_str()).map_or(false, |n| n.ends_with(".backup.json"));
+            if !is_metadata || !path.is_file() {
+                continue;
+            }
+            let backup_info: BackupInfo = fs::read_to_string(&path).map_err(SaveGuardianError::Io)
+                .and_then(|json| serde_json::from_str(&json).map_err(SaveGuardianError::Serde))
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!(
+                    "{} can't be read ({}), so the store can't tell which files it needs", path.display(), e
+                )))?;
+            referenced.extend(backup_info.stored_files.into_iter().map(|stored| stored.sha256));
+        }
+
+        let mut report = StoreReport::default();
+        for folder in ["objects", "tmp"] {
+            for entry in WalkDir::new(self.root.join(folder)).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if referenced.contains(&name) {
+                    continue;
+                }
+                let metadata = match entry.metadata() {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
+                let recent = metadata.modified().ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .map_or(true, |age| age < GC_GRACE);
+                if recent {
+                    continue;
+                }
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => {
+                        report.objects_removed += 1;
+                        report.bytes_freed += metadata.len();
+                    }
+                    Err(e) => {
+                        warn!("Failed to remove unused {:?}: {}", entry.path(), e);
+                        report.failed.push((name, e.to_string()));
+                    }
+                }
+            }
+        }
+
+        info!("Store garbage collection in {:?}: {} objects removed, {} freed",
+            self.root, report.objects_removed, format_size(report.bytes_freed));
+        Ok(report)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::types::*;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+use log::info;
+
+/// Log lines kept in memory for the diagnostics report
+const LOG_CAPACITY: usize = 200;
+
+pub const ISSUES_URL: &str = "https://github.com/Arionyxx/save-guardian/issues/new";
+
+const REDACTED: &str = "<redacted>";
+
+fn log_buffer() -> &'static Mutex<VecDeque<String>> {
+    static BUFFER: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+    BUFFER.get_or_init(|| Mutex::new(VecDeque::with_capacity(LOG_CAPACITY)))
+}
+
+/// Log target writing to stderr as before and remembering the last lines
+///
+/// Release builds on Windows have no console, so this is the only way to see the log.
+struct LogTee;
+
+impl Write for LogTee {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Ok(mut lines) = log_buffer().lock() {
+            for line in String::from_utf8_lossy(buf).lines().filter(|l| !l.trim().is_empty()) {
+                if lines.len() == LOG_CAPACITY {
+                    lines.pop_front();
+                }
+                lines.push_back(line.to_string());
+            }
+        }
+        io::stderr().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// Set up logging; `RUST_LOG` still works, the default is info so reports have some context
+pub fn init_logging() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .target(env_logger::Target::Pipe(Box::new(LogTee)))
+        .init();
+}
+
+/// Run `work` and log how long it took, to find what slows down startup
+pub fn timed<T>(what: &str, work: impl FnOnce() -> T) -> T {
+    let started = std::time::Instant::now();
+    let result = work();
+    info!("{} took {} ms", what, started.elapsed().as_millis());
+    result
+}
+
+/// The most recent log lines, oldest first
+pub fn recent_log_lines() -> Vec<String> {
+    log_buffer().lock()
+        .map(|lines| lines.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// What the app currently sees, for the report
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticCounts {
+    pub steam_saves: usize,
+    pub non_steam_saves: usize,
+    pub backups: usize,
+    pub backup_bytes: u64,
+    pub steam_accounts: usize,
+}
+
+/// Assemble a report for a bug ticket: versions, OS, counts, config without secrets, recent log
+///
+/// `app_version` is the version of the program asking, the core's own is added to it.
+pub fn build_report(config: &Config, counts: &DiagnosticCounts, app_version: &str) -> String {
+    let mut report = String::new();
+    report.push_str("## Save Guardian diagnostics\n\n");
+    report.push_str(&format!("- Version: {} (core {})\n", app_version, env!("CARGO_PKG_VERSION")));
+    report.push_str(&format!("- OS: {} ({}, {})\n", std::env::consts::OS, std::env::consts::FAMILY, std::env::consts::ARCH));
+    report.push_str(&format!("- Generated: {}\n", chrono::Utc::now().format("%Y-%m-%d %H:%M UTC")));
+    report.push_str(&format!("- Steam saves: {}\n", counts.steam_saves));
+    report.push_str(&format!("- Non-Steam saves: {}\n", counts.non_steam_saves));
+    report.push_str(&format!("- Backups: {} ({})\n", counts.backups, format_size(counts.backup_bytes)));
+    report.push_str(&format!("- Steam accounts: {}\n", counts.steam_accounts));
+    report.push_str(&format!("- Cloud: {}\n", if config.cloud_enabled() { config.cloud_name() } else { "off" }));
+
+    report.push_str("\n### Config\n\n```toml\n");
+    match toml::to_string_pretty(&strip_secrets(config)) {
+        Ok(toml) => report.push_str(&toml),
+        Err(e) => report.push_str(&format!("# Failed to serialize config: {}\n", e)),
+    }
+    report.push_str("```\n");
+
+    report.push_str(&format!("\n### Last {} log lines\n\n```\n", LOG_CAPACITY));
+    for line in recent_log_lines() {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    report.push_str("```\n");
+
+    anonymize(&report)
+}
+
+/// Copy of the config with passwords, account names and servers blanked out
+fn strip_secrets(config: &Config) -> Config {
+    let mut config = config.clone();
+    let redact = |value: &mut String| {
+        if !value.is_empty() {
+            *value = REDACTED.to_string();
+        }
+    };
+
+    redact(&mut config.koofr_config.username);
+    redact(&mut config.koofr_config.password);
+    redact(&mut config.koofr_config.server_url);
+    redact(&mut config.sftp_config.host);
+    redact(&mut config.sftp_config.username);
+    redact(&mut config.sftp_config.password);
+    redact(&mut config.sftp_config.key_passphrase);
+    for remote in &mut config.cloud_remotes {
+        redact(&mut remote.webdav.username);
+        redact(&mut remote.webdav.password);
+        redact(&mut remote.webdav.server_url);
+        redact(&mut remote.sftp.host);
+        redact(&mut remote.sftp.username);
+        redact(&mut remote.sftp.password);
+        redact(&mut remote.sftp.key_passphrase);
+    }
+    redact(&mut config.cloud_encryption.passphrase);
+    redact(&mut config.lan_server.password);
+    for profile in &mut config.profiles {
+        redact(&mut profile.name);
+    }
+    if config.active_profile.is_some() {
+        config.active_profile = Some(REDACTED.to_string());
+    }
+    config
+}
+
+/// Replace the home folder and login name, which show up in nearly every path
+fn anonymize(text: &str) -> String {
+    let mut text = text.to_string();
+
+    if let Some(home) = dirs::home_dir() {
+        let home = home.to_string_lossy().to_string();
+        if home.len() > 1 {
+            text = text.replace(&home, "~");
+            // Paths in TOML strings have their backslashes escaped
+            text = text.replace(&home.replace('\\', "\\\\"), "~");
+        }
+    }
+
+    for var in ["USERNAME", "USER"] {
+        if let Ok(user) = std::env::var(var) {
+            if user.len() > 2 {
+                text = text.replace(&user, "<user>");
+            }
+        }
+    }
+    text
+}
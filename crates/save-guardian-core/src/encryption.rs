@@ -0,0 +1,148 @@
+//! Passphrase-protected backup archives, for saves with account data on shared machines
+//!
+//! An encrypted backup is its whole archive sealed with `CloudCipher` (AES-256-GCM, key
+//! from Argon2id) like an encrypted cloud upload, but with its own magic. The passphrase is never
+//! written to the settings file: it is typed in once per session, or kept in the system
+//! keyring. Only a verifier is stored, to tell a typo from the right passphrase.
+
+use crate::types::*;
+use crate::cloud::CloudCipher;
+use crate::cloud::crypto::BACKUP_MAGIC;
+use crate::volumes;
+use base64::Engine;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use log::{info, warn};
+
+const KEYRING_SERVICE: &str = "save-guardian";
+const KEYRING_USER: &str = "backup-passphrase";
+
+/// Sealed with the passphrase as the verifier
+const VERIFIER_TEXT: &[u8] = b"Save Guardian backup passphrase";
+
+/// The cipher of the passphrase entered this session, shared by every reader of archives
+fn session() -> &'static Mutex<Option<CloudCipher>> {
+    static SESSION: OnceLock<Mutex<Option<CloudCipher>>> = OnceLock::new();
+    SESSION.get_or_init(|| Mutex::new(None))
+}
+
+/// The verifier to keep in the settings for a new passphrase
+pub fn new_verifier(passphrase: &str) -> Result<String> {
+    let sealed = CloudCipher::for_backups(passphrase).encrypt(VERIFIER_TEXT)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+}
+
+/// Whether `passphrase` is the one the verifier was made with
+fn matches(config: &BackupEncryptionConfig, passphrase: &str) -> bool {
+    let sealed = match config.verifier.as_deref().map(|v| base64::engine::general_purpose::STANDARD.decode(v)) {
+        Some(Ok(sealed)) => sealed,
+        Some(Err(_)) | None => return false,
+    };
+    CloudCipher::for_backups(passphrase).decrypt(&sealed).map_or(false, |plain| plain == VERIFIER_TEXT)
+}
+
+/// Unlock encrypted backups for this session, saving the passphrase to the keyring if enabled
+pub fn unlock(config: &BackupEncryptionConfig, passphrase: &str) -> Result<()> {
+    if config.verifier.is_none() {
+        return Err(SaveGuardianError::BackupOperationFailed("Set a backup passphrase in Settings first".to_string()));
+    }
+    if !matches(config, passphrase) {
+        return Err(SaveGuardianError::BackupOperationFailed("Wrong backup passphrase".to_string()));
+    }
+    if config.use_keyring {
+        if let Err(e) = keyring_entry().and_then(|entry| entry.set_password(passphrase)) {
+            warn!("Failed to save the backup passphrase to the system keyring: {}", e);
+        }
+    }
+    set_session(Some(CloudCipher::for_backups(passphrase)));
+    info!("Encrypted backups unlocked");
+    Ok(())
+}
+
+/// Unlock with the passphrase from the system keyring, if it is kept there and still right
+pub fn unlock_from_keyring(config: &BackupEncryptionConfig) -> bool {
+    if !config.use_keyring || is_unlocked() {
+        return is_unlocked();
+    }
+    match keyring_entry().and_then(|entry| entry.get_password()) {
+        Ok(passphrase) if matches(config, &passphrase) => {
+            set_session(Some(CloudCipher::for_backups(&passphrase)));
+            info!("Encrypted backups unlocked from the system keyring");
+            true
+        }
+        Ok(_) => {
+            warn!("The backup passphrase in the system keyring is outdated");
+            false
+        }
+        Err(keyring::Error::NoEntry) => false,
+        Err(e) => {
+            warn!("Failed to read the backup passphrase from the system keyring: {}", e);
+            false
+        }
+    }
+}
+
+/// Forget the passphrase for this session
+pub fn lock() {
+    set_session(None);
+}
+
+pub fn is_unlocked() -> bool {
+    session().lock().map_or(false, |cipher| cipher.is_some())
+}
+
+/// Remove the passphrase from the system keyring, when keeping it there is switched off
+pub fn forget_keyring() {
+    match keyring_entry().and_then(|entry| entry.delete_password()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => warn!("Failed to remove the backup passphrase from the system keyring: {}", e),
+    }
+}
+
+/// The session's cipher, `Locked` until the passphrase was entered
+pub fn cipher() -> Result<CloudCipher> {
+    session().lock().ok()
+        .and_then(|cipher| cipher.clone())
+        .ok_or(SaveGuardianError::Locked)
+}
+
+/// Encrypt a finished archive in place
+pub fn encrypt_file(path: &Path) -> Result<()> {
+    let sealed = cipher()?.encrypt(&fs::read(path)?)?;
+    let partial = path.with_extension("sgcrypt.tmp");
+    fs::write(&partial, sealed)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Whether the file at `path` starts like an encrypted archive, e.g. one downloaded from the cloud
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut magic = [0u8; 8];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_or(false, |_| magic == *BACKUP_MAGIC)
+}
+
+/// The plain archive of an encrypted backup, all parts joined, in memory
+pub fn decrypt_backup(backup_info: &BackupInfo) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    volumes::open(backup_info)
+        .and_then(|mut reader| reader.read_to_end(&mut data))
+        .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to open backup file: {}", e)))?;
+    if !data.starts_with(BACKUP_MAGIC) {
+        return Err(SaveGuardianError::BackupOperationFailed(format!("Backup {} is marked encrypted but isn't", backup_info.id)));
+    }
+    cipher()?.decrypt(&data)
+}
+
+fn set_session(cipher: Option<CloudCipher>) {
+    if let Ok(mut session) = session().lock() {
+        *session = cipher;
+    }
+}
+
+fn keyring_entry() -> std::result::Result<keyring::Entry, keyring::Error> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+}
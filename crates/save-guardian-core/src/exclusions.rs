@@ -0,0 +1,173 @@
+use crate::types::*;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Caches Steam keeps in a userdata profile and rebuilds on its own: library artwork and
+/// store pages, avatars, screenshot thumbnails
+const STEAM_PROFILE_CACHES: [&str; 6] = ["librarycache/", "htmlcache/", "avatarcache/", "thumbnails/", "ShaderCache/", "GPUCache/"];
+
+/// Game engine guessed from a save folder's contents, used to pick default excludes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SaveEngine {
+    Unreal,
+    Unity,
+    Chromium, // Electron/CEF based games and launchers
+    Generic,
+}
+
+/// Patterns of files that are never worth backing up
+///
+/// `*.log` matches file names, `CrashDumps/` matches a directory anywhere in the path,
+/// anything else matches a file name exactly. Matching is case-insensitive.
+pub struct ExclusionRules {
+    patterns: Vec<String>,
+}
+
+impl ExclusionRules {
+    /// Rules that exclude nothing
+    pub fn none() -> Self {
+        Self { patterns: Vec::new() }
+    }
+
+    /// Build the rules for one game's save folder
+    pub fn for_save(config: &ExclusionConfig, game_name: &str, save_path: &Path) -> Self {
+        if !config.enabled {
+            return Self::none();
+        }
+
+        let mut patterns = Vec::new();
+        let game_override = config.game_overrides.iter().find(|o| o.game_name == game_name);
+
+        if game_override.map_or(true, |o| o.use_defaults) {
+            let engine = detect_engine(save_path);
+            patterns.extend(default_patterns(engine).iter().map(|p| p.to_string()));
+            patterns.extend(parse_patterns(&config.extra_patterns));
+        }
+
+        if let Some(o) = game_override {
+            patterns.extend(parse_patterns(&o.patterns));
+        }
+
+        Self {
+            patterns: patterns.into_iter().map(|p| p.to_lowercase()).collect(),
+        }
+    }
+
+    /// Rules for a whole Steam profile (`userdata/<id>`): its caches are always left out,
+    /// the generic junk patterns and extra patterns as configured
+    pub fn for_steam_profile(config: &ExclusionConfig) -> Self {
+        let mut patterns: Vec<String> = STEAM_PROFILE_CACHES.iter().map(|p| p.to_string()).collect();
+        if config.enabled {
+            patterns.extend(default_patterns(SaveEngine::Generic).iter().map(|p| p.to_string()));
+            patterns.extend(parse_patterns(&config.extra_patterns));
+        }
+
+        Self {
+            patterns: patterns.into_iter().map(|p| p.to_lowercase()).collect(),
+        }
+    }
+
+    /// Check a path relative to the save folder root
+    pub fn is_excluded(&self, relative_path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+
+        let components: Vec<String> = relative_path.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+            .collect();
+        let (file_name, dirs) = match components.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+
+        self.patterns.iter().any(|pattern| {
+            if let Some(dir) = pattern.strip_suffix('/') {
+                dirs.iter().any(|d| wildcard_match(dir, d))
+            } else {
+                wildcard_match(pattern, file_name)
+            }
+        })
+    }
+
+    /// Check whether a directory (relative to the save root) is excluded as a whole
+    pub fn is_excluded_dir(&self, relative_path: &Path) -> bool {
+        let components: Vec<String> = relative_path.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+            .collect();
+
+        self.patterns.iter()
+            .filter_map(|p| p.strip_suffix('/'))
+            .any(|dir| components.iter().any(|c| wildcard_match(dir, c)))
+    }
+}
+
+/// Guess the engine from well-known files and folder names
+pub fn detect_engine(save_path: &Path) -> SaveEngine {
+    let path_lower = save_path.to_string_lossy().to_lowercase();
+    if path_lower.contains("locallow") {
+        return SaveEngine::Unity;
+    }
+
+    for entry in WalkDir::new(save_path).max_depth(2).into_iter().filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        match name.as_str() {
+            "savegames" | "config" if path_lower.contains("saved") => return SaveEngine::Unreal,
+            "player.log" | "player-prev.log" => return SaveEngine::Unity,
+            "gpucache" | "code cache" | "local storage" => return SaveEngine::Chromium,
+            _ => {}
+        }
+    }
+
+    SaveEngine::Generic
+}
+
+/// Default junk patterns for an engine, always including the generic ones
+pub fn default_patterns(engine: SaveEngine) -> Vec<&'static str> {
+    let mut patterns = vec!["*.log", "*.dmp", "*.mdmp", "*.tmp", "CrashDumps/", "Crashes/", "Logs/"];
+
+    match engine {
+        SaveEngine::Unreal => patterns.extend(["Webcache*/", "*.ushaderprecache"]),
+        SaveEngine::Unity => patterns.extend(["Unity/", "*.shadercache"]),
+        SaveEngine::Chromium => patterns.extend(["GPUCache/", "Code Cache/", "Cache/", "ShaderCache/", "blob_storage/"]),
+        SaveEngine::Generic => patterns.extend(["GPUCache/", "ShaderCache/"]),
+    }
+
+    patterns
+}
+
+/// Split a comma-separated pattern list as entered in Settings
+pub fn parse_patterns(patterns: &str) -> Vec<String> {
+    patterns.split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let mut remaining = text;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match remaining.strip_prefix(part) {
+                Some(rest) => remaining = rest,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return remaining.ends_with(part);
+        } else {
+            match remaining.find(part) {
+                Some(pos) => remaining = &remaining[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
@@ -0,0 +1,266 @@
+//! Games the scanners miss, noticed while they run
+//!
+//! The scanners only recognise folders that look like saves. `GameDetector` looks at the
+//! save locations on a thread instead: when a folder there changes that no known game
+//! uses, and a running program is named like it, the folder is offered as a new game.
+//! Names have to match, so a browser writing its profile doesn't become a game.
+
+use crate::types::*;
+use crate::network;
+use crate::normalize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+use sysinfo::{ProcessExt, System, SystemExt};
+use walkdir::WalkDir;
+use log::{debug, info};
+
+/// Entries looked at below each folder, so a huge app cache can't stall a pass
+const MAX_ENTRIES_PER_FOLDER: usize = 300;
+
+/// Folders in the save locations that belong to the system or common apps, never offered
+const SKIPPED_FOLDERS: &[&str] = &[
+    "microsoft", "windows", "packages", "temp", "crashdumps", "d3dscache", "nvidia", "nvidia corporation",
+    "amd", "intel", "google", "mozilla", "adobe", "apple", "programs", "steam", "discord", "spotify",
+    "slack", "code", "jetbrains", "npm", "pip", "cache", "fontconfig", "pulse", "dconf", "gtk 3 0",
+    "my games", "rockstar games", "goldberg steamemu saves",
+];
+
+/// Programs that are running while games are, and keep folders in the same places
+const NOT_GAMES: &[&str] = &[
+    "explorer", "steam", "steamwebhelper", "steamservice", "epicgameslauncher", "galaxyclient", "eadesktop",
+    "ubisoftconnect", "battle net", "battlenet", "heroic", "lutris", "bottles", "discord", "slack", "teams",
+    "spotify", "chrome", "firefox", "msedge", "brave", "opera", "vivaldi", "thunderbird", "outlook",
+    "code", "onedrive", "dropbox", "obs64", "obs", "zoom", "telegram", "save guardian",
+];
+
+/// A running program writing into a folder no known game uses
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedGame {
+    pub exe_name: String, // As shown in the prompt, e.g. "Celeste.exe"
+    pub exe_path: Option<PathBuf>,
+    pub save_path: PathBuf,
+    pub name: String, // Suggested game name, from the folder
+}
+
+impl DetectedGame {
+    /// The custom game to add when the user wants its saves tracked
+    pub fn to_custom_game(&self) -> CustomGame {
+        CustomGame {
+            name: self.name.clone(),
+            save_path: self.save_path.clone(),
+            exe_name: Some(self.exe_name.clone()),
+        }
+    }
+}
+
+/// Watches the save locations on a thread while the app runs; dropping it stops the thread
+pub struct GameDetector {
+    detected: Receiver<DetectedGame>,
+    known: Arc<Mutex<Vec<PathBuf>>>,
+    stop: Option<Sender<()>>,
+    worker: Option<JoinHandle<()>>,
+    locations: Vec<PathBuf>,
+    config: GameDetectionConfig,
+}
+
+impl GameDetector {
+    /// Start watching `locations`; the folders present now are the baseline, only later changes count
+    pub fn start(locations: Vec<PathBuf>, config: &GameDetectionConfig) -> Self {
+        let (detected_sender, detected) = mpsc::channel();
+        let (stop, stop_receiver) = mpsc::channel();
+        let known = Arc::new(Mutex::new(config.ignored_folders.clone()));
+
+        let worker_known = Arc::clone(&known);
+        let worker_locations = locations.clone();
+        let interval = Duration::from_secs(config.interval_secs.max(5));
+        let worker = std::thread::spawn(move || {
+            watch(&worker_locations, interval, &worker_known, &stop_receiver, &detected_sender);
+        });
+
+        info!("Watching {} save locations for new games every {} s", locations.len(), interval.as_secs());
+        Self { detected, known, stop: Some(stop), worker: Some(worker), locations, config: config.clone() }
+    }
+
+    /// Whether this detector already watches `locations` as configured, so it needn't be restarted
+    pub fn watches(&self, locations: &[PathBuf], config: &GameDetectionConfig) -> bool {
+        self.locations == locations && self.config.interval_secs == config.interval_secs
+    }
+
+    /// Save folders of the games already known and folders the user declined; nothing at,
+    /// inside or around them is offered
+    pub fn set_known(&self, folders: Vec<PathBuf>) {
+        if let Ok(mut known) = self.known.lock() {
+            *known = folders;
+        }
+    }
+
+    /// Games detected since the last call
+    pub fn detected(&self) -> Vec<DetectedGame> {
+        self.detected.try_iter().collect()
+    }
+}
+
+impl Drop for GameDetector {
+    fn drop(&mut self) {
+        // Closing the channel wakes the thread from its wait
+        self.stop = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        info!("Stopped watching for new games");
+    }
+}
+
+/// A running program and the names it could save under
+struct Program {
+    exe_name: String,
+    exe_path: Option<PathBuf>,
+    keys: Vec<String>, // Compact names of the executable and its install folder
+}
+
+fn watch(locations: &[PathBuf], interval: Duration, known: &Mutex<Vec<PathBuf>>, stop: &Receiver<()>, detected: &Sender<DetectedGame>) {
+    let mut system = System::new();
+    let mut offered: HashSet<PathBuf> = HashSet::new();
+    let mut seen = snapshot(locations, &[]);
+
+    loop {
+        match stop.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => return,
+        }
+
+        let known = known.lock().map(|known| known.clone()).unwrap_or_default();
+        let current = snapshot(locations, &known);
+        let mut changed: Vec<&PathBuf> = current.iter()
+            .filter(|(folder, modified)| seen.get(*folder).map_or(true, |before| *modified > before))
+            .map(|(folder, _)| folder)
+            .filter(|folder| !offered.iter().any(|done| folder.starts_with(done) || done.starts_with(folder)))
+            .collect();
+        if changed.is_empty() {
+            seen = current;
+            continue;
+        }
+        // A game's own folder before the company folder around it
+        changed.sort_by_key(|folder| std::cmp::Reverse(folder.components().count()));
+        debug!("{} folders in the save locations changed", changed.len());
+
+        system.refresh_processes();
+        let programs = running_programs(&system);
+        for folder in changed {
+            if offered.iter().any(|done| folder.starts_with(done) || done.starts_with(folder)) {
+                continue;
+            }
+            let key = match folder.file_name() {
+                Some(name) => normalize::compact_key(&name.to_string_lossy()),
+                None => continue,
+            };
+            if let Some(program) = programs.iter().find(|program| program.keys.iter().any(|k| names_match(k, &key))) {
+                info!("{} is writing to {}, which no known game uses", program.exe_name, folder.display());
+                offered.insert(folder.clone());
+                let game = DetectedGame {
+                    exe_name: program.exe_name.clone(),
+                    exe_path: program.exe_path.clone(),
+                    save_path: folder.clone(),
+                    name: normalize::nfc(&folder.file_name().unwrap_or_default().to_string_lossy()),
+                };
+                if detected.send(game).is_err() {
+                    return;
+                }
+            }
+        }
+        seen = current;
+    }
+}
+
+/// Newest change time of each folder one and two levels below the locations, leaving out
+/// skipped and known ones
+///
+/// Two levels catch `LocalLow/<Company>/<Game>` and `Documents/My Games/<Game>` alike.
+fn snapshot(locations: &[PathBuf], known: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    let mut folders = HashMap::new();
+    for location in locations {
+        // An offline share would stall every pass
+        if network::is_network_path(location) || !location.is_dir() {
+            continue;
+        }
+        let candidates = WalkDir::new(location)
+            .min_depth(1)
+            .max_depth(2)
+            .into_iter()
+            .filter_entry(|entry| entry.file_type().is_dir() && !is_skipped(entry.path(), known))
+            .filter_map(|entry| entry.ok());
+        for entry in candidates {
+            if let Some(modified) = newest_change(entry.path()) {
+                folders.insert(entry.into_path(), modified);
+            }
+        }
+    }
+    folders
+}
+
+fn is_skipped(folder: &Path, known: &[PathBuf]) -> bool {
+    let name = folder.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    name.starts_with('.')
+        || SKIPPED_FOLDERS.contains(&normalize::match_key(&name).as_str())
+        || known.iter().any(|known| folder.starts_with(known) || known.starts_with(folder))
+}
+
+fn newest_change(folder: &Path) -> Option<SystemTime> {
+    WalkDir::new(folder)
+        .max_depth(3)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .take(MAX_ENTRIES_PER_FOLDER)
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()
+}
+
+fn running_programs(system: &System) -> Vec<Program> {
+    let own = sysinfo::get_current_pid().ok();
+    system.processes().iter()
+        .filter(|(pid, _)| Some(**pid) != own)
+        .filter_map(|(_, process)| {
+            // Under Wine the process is the loader; the game is the .exe on its command line
+            let windows_exe = process.cmd().first()
+                .filter(|arg| arg.to_lowercase().ends_with(".exe"))
+                .and_then(|arg| arg.rsplit(|c| c == '/' || c == '\\').next())
+                .map(str::to_string);
+            let exe_name = windows_exe.unwrap_or_else(|| process.name().to_string());
+            let exe_path = Some(process.exe().to_path_buf()).filter(|path| !path.as_os_str().is_empty());
+
+            let stem = exe_name.rsplit_once('.').map_or(exe_name.as_str(), |(stem, _)| stem);
+            if NOT_GAMES.contains(&normalize::match_key(stem).as_str()) {
+                return None;
+            }
+            let mut keys = vec![normalize::compact_key(stem)];
+            if let Some(folder) = exe_path.as_deref().and_then(install_folder) {
+                keys.push(normalize::compact_key(&folder));
+            }
+            Some(Program { exe_name, exe_path, keys })
+        })
+        .collect()
+}
+
+/// The folder a game is installed in, above `bin`, `Binaries/Win64` and the like
+fn install_folder(exe: &Path) -> Option<String> {
+    exe.ancestors()
+        .skip(1)
+        .take(4)
+        .filter_map(|folder| folder.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .find(|name| !matches!(name.to_lowercase().as_str(), "bin" | "bin64" | "binaries" | "win64" | "win32" | "x64" | "x86" | "game" | "linux" | "macos"))
+}
+
+/// The same name, or one containing the other when both are long enough to mean something
+fn names_match(program: &str, folder: &str) -> bool {
+    if program.is_empty() || folder.is_empty() {
+        return false;
+    }
+    program == folder
+        || (program.chars().count() >= 4 && folder.chars().count() >= 4 && (program.contains(folder) || folder.contains(program)))
+}
@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use log::debug;
+
+/// Archive folder holding a game's settings files, next to the save files
+pub const SETTINGS_FOLDER: &str = "__settings__";
+
+/// Extensions of the text config formats games use for settings
+const SETTINGS_EXTENSIONS: [&str; 6] = ["ini", "cfg", "conf", "config", "xml", "json"];
+
+/// Name parts of settings files in formats that also hold other data (json, xml)
+const SETTINGS_NAMES: [&str; 11] = [
+    "settings", "config", "options", "prefs", "preferences", "keybind", "bindings", "input", "controls", "graphics", "video",
+];
+
+/// Known config files that belong to a save: graphics settings, keybinds and the like
+///
+/// Looks next to the save folder (`My Games/<game>/*.ini` beside `Saves`) and, for Unreal
+/// games, in `Saved/Config`. Files inside the save folder are left out, they are
+/// archived with the save anyway.
+pub fn find_settings_files(save_path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    if let Some(parent) = save_path.parent() {
+        if let Ok(entries) = fs::read_dir(parent) {
+            files.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_file() && is_settings_file(p)));
+        }
+    }
+
+    if let Some(saved) = save_path.ancestors().find(|a| a.file_name().map_or(false, |n| n.eq_ignore_ascii_case("Saved"))) {
+        let config = saved.join("Config");
+        files.extend(
+            WalkDir::new(&config)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path())
+                .filter(|p| p.extension().map_or(false, |e| e.eq_ignore_ascii_case("ini"))),
+        );
+    }
+
+    files.retain(|f| !f.starts_with(save_path) && f != save_path);
+    files.sort();
+    files.dedup();
+    debug!("Settings files for {:?}: {:?}", save_path, files);
+    files
+}
+
+/// Name of a settings file inside the archive; numbered, since two folders may hold the same name
+pub fn archive_name(index: usize, path: &Path) -> String {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "settings".to_string());
+    format!("{}/{}/{}", SETTINGS_FOLDER, index, file_name)
+}
+
+/// Whether an archive entry is part of the settings section rather than the save
+pub fn is_settings_entry(name: &str) -> bool {
+    name.strip_prefix(SETTINGS_FOLDER).map_or(false, |rest| rest.starts_with('/'))
+}
+
+fn is_settings_file(path: &Path) -> bool {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if !SETTINGS_EXTENSIONS.contains(&extension.as_str()) {
+        return false;
+    }
+    // ini and cfg files are settings as a rule, other formats only by name
+    let name = path.file_stem().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    matches!(extension.as_str(), "ini" | "cfg") || SETTINGS_NAMES.iter().any(|part| name.contains(part))
+}
@@ -0,0 +1,109 @@
+use crate::types::*;
+use crate::volumes;
+use crate::watchdog;
+use std::path::{Path, PathBuf};
+use log::warn;
+
+/// A source under this share of what the destination holds (or held) looks wrong
+const MIN_SOURCE_PERCENT: u64 = 20;
+
+/// Destinations smaller than this aren't compared, a few KB of config can shrink legitimately
+const MIN_COMPARED_BYTES: u64 = 64 * 1024;
+
+/// Why copying from a source would probably replace good saves with the wrong ones
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceIssue {
+    Empty,
+    Shrunk { expected_size: u64, expected_from: String }, // e.g. "the backup from 2024-05-01 14:02"
+}
+
+/// A source that failed the check before a sync or restore, see `check_sync_source`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceCheck {
+    pub source: String, // Folder or backup being copied from
+    pub destination: PathBuf,
+    pub source_size: u64,
+    pub issue: SourceIssue,
+}
+
+impl SourceCheck {
+    pub fn describe(&self) -> String {
+        match self.issue {
+            SourceIssue::Empty => format!(
+                "{} is empty, copying it would wipe {}",
+                self.source, self.destination.display()
+            ),
+            SourceIssue::Shrunk { expected_size, ref expected_from } => format!(
+                "{} holds {}, much less than the {} of {}",
+                self.source, format_size(self.source_size), format_size(expected_size), expected_from
+            ),
+        }
+    }
+
+    /// What usually causes this, shown next to the override
+    pub fn hint(&self) -> &'static str {
+        match self.issue {
+            SourceIssue::Empty => "Usually the wrong folder was picked, or the game hasn't created its save there yet",
+            SourceIssue::Shrunk { .. } => "Usually the wrong folder was picked, or a fresh install started a new save",
+        }
+    }
+}
+
+impl std::fmt::Display for SourceCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.describe())
+    }
+}
+
+/// Check a sync source against the folder it is about to replace and that folder's backups
+///
+/// Nothing is reported when the destination is empty and never had backups, there is
+/// nothing to lose then.
+pub fn check_sync_source(source: &Path, destination: &Path, backups: &[BackupInfo]) -> Option<SourceCheck> {
+    let source_files = watchdog::count_files(source);
+    let source_size = if source_files == 0 { 0 } else { calculate_path_size(source) };
+    check(source.display().to_string(), source_files, Some(source_size), destination, backups, None)
+}
+
+/// Check a backup about to be restored over `target`
+///
+/// Backups from before source sizes were recorded are only checked for being empty.
+pub fn check_restore(backup: &BackupInfo, target: &Path, backups: &[BackupInfo]) -> Option<SourceCheck> {
+    let source = format!("The backup from {}", backup.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+    // An archive that can't be read fails on restore anyway, with a better error
+    let source_files = watchdog::count_archive_files(backup)?;
+    let source_size = Some(backup.source_size).filter(|&size| size > 0 || source_files == 0);
+    check(source, source_files, source_size, target, backups, Some(&backup.id))
+}
+
+/// `source_size` is None when it isn't known
+fn check(source: String, source_files: usize, source_size: Option<u64>, destination: &Path, backups: &[BackupInfo], skip_backup: Option<&str>) -> Option<SourceCheck> {
+    let destination_size = if destination.exists() { calculate_path_size(destination) } else { 0 };
+    // Backups are sorted newest first; older metadata has no source size
+    let last_backup = backups.iter()
+        .filter(|b| Some(b.id.as_str()) != skip_backup)
+        .find(|b| b.original_path == destination && b.source_size > 0 && volumes::archive_exists(b));
+
+    let (expected_size, expected_from) = match last_backup {
+        Some(backup) if backup.source_size > destination_size => (
+            backup.source_size,
+            format!("the backup from {}", backup.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")),
+        ),
+        _ => (destination_size, format!("{} now", destination.display())),
+    };
+    if expected_size == 0 {
+        return None;
+    }
+
+    let issue = match source_size {
+        _ if source_files == 0 => SourceIssue::Empty,
+        Some(0) => SourceIssue::Empty,
+        Some(size) if expected_size >= MIN_COMPARED_BYTES && size.saturating_mul(100) < expected_size.saturating_mul(MIN_SOURCE_PERCENT) => {
+            SourceIssue::Shrunk { expected_size, expected_from }
+        }
+        _ => return None,
+    };
+    let check = SourceCheck { source, destination: destination.to_path_buf(), source_size: source_size.unwrap_or(0), issue };
+    warn!("Source check failed: {}", check.describe());
+    Some(check)
+}
@@ -0,0 +1,238 @@
+//! Backup archives sent as a link, e.g. a cloud share link from a friend
+//!
+//! `download` fetches the archive into the backup folder under a staging name and reads
+//! it through, so a web page or a truncated file is refused before anything is registered.
+//! `BackupManager::import_archive` then files it under the game the user picked.
+
+use crate::types::*;
+use crate::archive;
+use crate::progress::ArchiveProgress;
+use crate::save_formats::{self, SaveMetadata};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use log::{info, warn};
+
+/// Suffix of an archive downloaded but not imported yet; the backup list ignores it
+const STAGING_SUFFIX: &str = ".sgimport";
+
+/// Bytes read from the connection at a time, between cancellation checks
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// An archive fetched from a link and read through, waiting to be assigned to a game
+#[derive(Debug, Clone)]
+pub struct DownloadedArchive {
+    pub url: String,
+    pub file_name: String, // As the server named it, e.g. "EldenRing_1245620_steam_20240101_120000.zip"
+    pub path: PathBuf,     // Staging file in the backup folder until imported or discarded
+    pub format: ArchiveFormat,
+    pub size: u64,
+    pub files: usize,      // Files in the archive, folders not counted
+    pub sha256: String,
+    pub save_metadata: Vec<SaveMetadata>,
+}
+
+impl DownloadedArchive {
+    /// Game name, app ID and save type as far as the file name tells
+    ///
+    /// Archives made by this app are named `<Game>_<app ID>_<steam|nonsteam>_<time>`;
+    /// anything else keeps its file name as the game name.
+    pub fn guess_game(&self) -> (String, Option<u32>, SaveType) {
+        let id = ArchiveFormat::strip_extension(&self.file_name);
+        let parts: Vec<&str> = id.split('_').collect();
+        match parts.iter().rposition(|part| *part == "steam" || *part == "nonsteam").filter(|&i| i > 0) {
+            Some(index) => {
+                let save_type = if parts[index] == "steam" { SaveType::Steam } else { SaveType::NonSteam };
+                let app_id = parts[..index].iter().find_map(|part| part.parse::<u32>().ok());
+                let name: Vec<&str> = parts[..index].iter().copied().filter(|part| part.parse::<u32>().is_err()).collect();
+                (name.join(" "), app_id, save_type)
+            }
+            None => (id.replace('_', " ").trim().to_string(), None, SaveType::NonSteam),
+        }
+    }
+
+    /// Delete the staging file, when the user cancels the import
+    pub fn discard(&self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("Failed to delete downloaded archive {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// The address the file itself downloads from, for share links that open a web page
+///
+/// Dropbox wants `dl=1`, Google Drive its `uc` endpoint and Nextcloud/ownCloud shares
+/// `/download`; other links are used as they are.
+pub fn direct_url(url: &str) -> String {
+    let url = url.trim();
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(_) => return url.to_string(),
+    };
+    let host = parsed.host_str().unwrap_or_default();
+    let path = parsed.path().trim_end_matches('/');
+
+    if host.ends_with("dropbox.com") {
+        let mut direct = parsed.clone();
+        let query: Vec<(String, String)> = parsed.query_pairs()
+            .filter(|(key, _)| key != "dl")
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        direct.query_pairs_mut().clear().extend_pairs(query).append_pair("dl", "1");
+        return direct.to_string();
+    }
+    if host == "drive.google.com" {
+        if let Some(id) = path.strip_prefix("/file/d/").and_then(|rest| rest.split('/').next()) {
+            return format!("https://drive.google.com/uc?export=download&id={}", id);
+        }
+    }
+    // Nextcloud and ownCloud public shares: /s/<token>, possibly below index.php
+    let segments: Vec<&str> = path.split('/').collect();
+    if segments.len() >= 2 && segments[segments.len() - 2] == "s" {
+        return format!("{}://{}{}{}/download", parsed.scheme(), host,
+            parsed.port().map(|port| format!(":{}", port)).unwrap_or_default(), path);
+    }
+    url.to_string()
+}
+
+/// Download the archive at `url` into `folder` and check that every entry can be read
+///
+/// The file stays under a staging name until imported. `progress` shows the download
+/// and then the check, and can cancel either.
+pub fn download(url: &str, folder: &Path, progress: &ArchiveProgress) -> Result<DownloadedArchive> {
+    let direct = direct_url(url);
+    info!("Downloading backup from {}", direct);
+    progress.begin(0);
+    progress.start_file("Connecting…")?;
+
+    let failure = |detail: String| SaveGuardianError::cloud_failure("Link", "download the backup", &direct, detail);
+    let mut response = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(None) // The default 30 s would cut off any large backup; cancelling stops it instead
+        .build()
+        .and_then(|client| client.get(&direct).send())
+        .map_err(|e| failure(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(SaveGuardianError::cloud_http("Link", "download the backup", response.status().as_u16(), &direct, None));
+    }
+    let is_page = response.headers().get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.starts_with("text/html"));
+    if is_page {
+        return Err(failure("the link opens a web page, not a file; copy the direct download link instead".to_string()));
+    }
+
+    let file_name = file_name_of(&response).unwrap_or_else(|| "Imported backup.zip".to_string());
+    let format = ArchiveFormat::of_name(&file_name).unwrap_or(ArchiveFormat::Zip);
+    let total = response.content_length();
+    let path = staging_path(folder, &file_name);
+
+    let written = write_download(&mut response, &path, progress, total);
+    let size = match written {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = fs::remove_file(&path);
+            return Err(e);
+        }
+    };
+    if total.map_or(false, |total| total != size) {
+        let _ = fs::remove_file(&path);
+        return Err(failure(format!("the connection dropped after {} of {} bytes", size, total.unwrap_or_default())));
+    }
+
+    match check_archive(&path, format, progress) {
+        Ok((files, save_metadata)) => {
+            let sha256 = crate::integrity::sha256_file(&path)?;
+            info!("Downloaded {} ({} bytes, {} files) to {}", file_name, size, files, path.display());
+            Ok(DownloadedArchive { url: url.trim().to_string(), file_name, path, format, size, files, sha256, save_metadata })
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&path);
+            match e {
+                SaveGuardianError::Cancelled => Err(e),
+                e => Err(SaveGuardianError::BackupOperationFailed(format!(
+                    "{} isn't a readable {} archive: {}", file_name, format.extension(), e
+                ))),
+            }
+        }
+    }
+}
+
+/// The archive's name from Content-Disposition, else the last part of the final URL
+fn file_name_of(response: &reqwest::blocking::Response) -> Option<String> {
+    let disposition = response.headers().get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').find_map(|part| part.trim().strip_prefix("filename=")))
+        .map(|name| name.trim_matches('"').to_string());
+    let from_url = response.url().path_segments()
+        .and_then(|segments| segments.filter(|s| !s.is_empty()).last())
+        .map(|segment| percent_encoding::percent_decode_str(segment).decode_utf8_lossy().to_string());
+    disposition.into_iter().chain(from_url)
+        .map(|name| crate::normalize::file_safe(name.rsplit(|c| c == '/' || c == '\\').next().unwrap_or(&name)))
+        .find(|name| ArchiveFormat::of_name(name).is_some())
+}
+
+/// A staging file name in `folder` that nothing uses yet
+fn staging_path(folder: &Path, file_name: &str) -> PathBuf {
+    let mut path = folder.join(format!("{}{}", file_name, STAGING_SUFFIX));
+    let mut number = 2;
+    while path.exists() {
+        path = folder.join(format!("{} ({}){}", file_name, number, STAGING_SUFFIX));
+        number += 1;
+    }
+    path
+}
+
+fn write_download(response: &mut impl Read, path: &Path, progress: &ArchiveProgress, total: Option<u64>) -> Result<u64> {
+    let mut output = BufWriter::new(File::create(path)?);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut size = 0u64;
+    loop {
+        progress.check()?;
+        let read = match response.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        output.write_all(&buffer[..read])?;
+        size += read as u64;
+        let done = match total {
+            Some(total) => format!("Downloading {} of {}", format_size(size), format_size(total)),
+            None => format!("Downloading {}", format_size(size)),
+        };
+        progress.start_file(&done)?;
+    }
+    output.flush()?;
+    Ok(size)
+}
+
+/// Read every entry of the archive to its end; returns the number of files and their save metadata
+fn check_archive(path: &Path, format: ArchiveFormat, progress: &ArchiveProgress) -> Result<(usize, Vec<SaveMetadata>)> {
+    let names = archive::entry_names(format, File::open(path)?)?;
+    let files = names.iter().filter(|name| !name.ends_with('/')).count();
+    if files == 0 {
+        return Err(SaveGuardianError::BackupOperationFailed("it has no files in it".to_string()));
+    }
+    progress.begin(files);
+
+    let mut save_metadata = Vec::new();
+    archive::for_each_entry(format, File::open(path)?, |name, size, data| {
+        if name.ends_with('/') {
+            return Ok(());
+        }
+        progress.start_file(name)?;
+        // Reading to the end is what checks the CRC (ZIP) or the frame checksums (zstd)
+        if save_formats::is_candidate(name) && size <= save_formats::MAX_FILE_SIZE {
+            let mut contents = Vec::with_capacity(size as usize);
+            data.read_to_end(&mut contents)?;
+            save_metadata.extend(save_formats::identify(name, &contents));
+        } else {
+            io::copy(data, &mut io::sink())?;
+        }
+        progress.finish_file();
+        Ok(())
+    })?;
+    Ok((files, save_metadata))
+}
@@ -0,0 +1,223 @@
+use crate::types::*;
+use crate::exclusions::ExclusionRules;
+use crate::integrity;
+use crate::workers;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use log::{debug, warn};
+
+/// One save file as it was at a backup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub size: u64,
+    pub modified: u64, // Nanoseconds since the Unix epoch
+    pub sha256: String,
+}
+
+/// Every file and folder of a save at one backup, kept next to its metadata as `<id>.index.json`
+///
+/// The next incremental backup of the game compares against it. Files whose size and
+/// modification time match keep their recorded hash without being read; the others are
+/// hashed, so a file that was only touched doesn't count as changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileIndex {
+    pub files: BTreeMap<String, IndexedFile>, // By archive entry name, '/' separated
+    #[serde(default)]
+    pub folders: Vec<String>, // Folder entries, with a trailing '/'
+}
+
+/// What an incremental backup has to store compared to the previous backup's index
+#[derive(Debug, Clone, Default)]
+pub struct IndexChanges {
+    pub changed: HashSet<String>, // New or modified files, and new folders
+    pub removed: Vec<String>,     // Files and folders gone since, folders with a trailing '/'
+}
+
+impl IndexChanges {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl FileIndex {
+    /// Index a save folder, reusing `previous`'s hashes for files with the same size and time
+    ///
+    /// Files that need hashing are spread over `threads` worker threads.
+    pub fn scan(source_path: &Path, rules: &ExclusionRules, previous: Option<&FileIndex>, threads: usize) -> Result<Self> {
+        let mut index = FileIndex::default();
+        let mut to_hash: Vec<(String, PathBuf, u64, u64)> = Vec::new();
+
+        for entry in WalkDir::new(source_path).follow_links(false).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+            let relative_path = match entry.path().strip_prefix(source_path) {
+                Ok(relative_path) if relative_path.as_os_str() != "" => relative_path,
+                _ => continue,
+            };
+            let name = relative_path.to_string_lossy().replace('\\', "/");
+
+            if entry.file_type().is_dir() {
+                if !rules.is_excluded_dir(relative_path) {
+                    index.folders.push(format!("{}/", name));
+                }
+                continue;
+            }
+            if !entry.file_type().is_file() || rules.is_excluded(relative_path) {
+                continue;
+            }
+
+            let metadata = entry.metadata()
+                .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to read file {:?}: {}", entry.path(), e)))?;
+            let modified = metadata.modified().ok()
+                .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_nanos() as u64);
+
+            match previous.and_then(|previous| previous.files.get(&name)) {
+                Some(known) if known.size == metadata.len() && known.modified == modified => {
+                    index.files.insert(name, known.clone());
+                }
+                _ => to_hash.push((name, entry.path().to_path_buf(), metadata.len(), modified)),
+            }
+        }
+
+        debug!("Indexing {:?}: {} files unchanged, {} to hash", source_path, index.files.len(), to_hash.len());
+        let hashes = workers::map(&to_hash, threads, |(_, path, _, _)| integrity::sha256_file(path));
+        for ((name, _, size, modified), sha256) in to_hash.into_iter().zip(hashes) {
+            index.files.insert(name, IndexedFile { size, modified, sha256: sha256? });
+        }
+
+        Ok(index)
+    }
+
+    /// Files and folders to archive, and those to delete, to get from `previous` to this index
+    pub fn changes_since(&self, previous: &FileIndex) -> IndexChanges {
+        let mut changes = IndexChanges::default();
+
+        for (name, file) in &self.files {
+            if previous.files.get(name).map_or(true, |known| known.sha256 != file.sha256) {
+                changes.changed.insert(name.clone());
+            }
+        }
+        for folder in self.folders.iter().filter(|folder| !previous.folders.contains(folder)) {
+            changes.changed.insert(folder.clone());
+        }
+
+        changes.removed.extend(previous.files.keys().filter(|name| !self.files.contains_key(*name)).cloned());
+        changes.removed.extend(previous.folders.iter().filter(|folder| !self.folders.contains(folder)).cloned());
+        changes
+    }
+
+    /// The index stored for a backup, None for backups made without incremental backups on
+    pub fn load(backup_info: &BackupInfo) -> Option<Self> {
+        let json = fs::read_to_string(index_path(backup_info)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, backup_info: &BackupInfo) -> Result<()> {
+        let json = serde_json::to_string(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(index_path(backup_info), json)
+            .map_err(SaveGuardianError::Io)
+    }
+}
+
+/// A full backup and the incremental backups built on it, directly or through each other
+#[derive(Debug, Clone)]
+pub struct BackupChain {
+    pub links: Vec<ChainLink>, // Base first, each increment after the backup it builds on
+    pub missing_base: Option<String>, // ID of the backup the first link builds on, when it is gone
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainLink {
+    pub backup: BackupInfo,
+    pub depth: usize, // Backups between it and the base, 0 for the base itself
+}
+
+impl BackupChain {
+    /// Whether the chain's full backup is gone, so none of its increments can be restored
+    pub fn is_orphaned(&self) -> bool {
+        self.missing_base.is_some()
+    }
+
+    pub fn base(&self) -> &BackupInfo {
+        &self.links[0].backup
+    }
+
+    /// The newest backup of the chain, the state consolidating it keeps
+    pub fn tip(&self) -> &BackupInfo {
+        self.links.iter().map(|link| &link.backup).max_by_key(|backup| backup.created_at).unwrap_or(self.base())
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.links.iter().map(|link| link.backup.size).sum()
+    }
+}
+
+/// The chains among `backups`, newest base first; full backups without increments are left out
+pub fn chains(backups: &[BackupInfo]) -> Vec<BackupChain> {
+    let ids: HashSet<&str> = backups.iter().map(|b| b.id.as_str()).collect();
+    let mut chains: Vec<BackupChain> = backups.iter()
+        .filter(|b| b.base_backup.as_deref().map_or(true, |base| !ids.contains(base)))
+        .map(|base| {
+            let mut links = vec![ChainLink { backup: base.clone(), depth: 0 }];
+            add_dependents(backups, base, 1, &mut links);
+            BackupChain { links, missing_base: base.base_backup.clone() }
+        })
+        .filter(|chain| chain.links.len() > 1 || chain.is_orphaned())
+        .collect();
+    chains.sort_by(|a, b| b.base().created_at.cmp(&a.base().created_at));
+    chains
+}
+
+fn add_dependents(backups: &[BackupInfo], base: &BackupInfo, depth: usize, links: &mut Vec<ChainLink>) {
+    let mut dependents: Vec<&BackupInfo> = backups.iter()
+        .filter(|b| b.base_backup.as_deref() == Some(base.id.as_str()))
+        .collect();
+    dependents.sort_by_key(|b| b.created_at);
+    for dependent in dependents {
+        // A damaged metadata file could make a loop
+        if links.iter().any(|link| link.backup.id == dependent.id) {
+            continue;
+        }
+        links.push(ChainLink { backup: dependent.clone(), depth });
+        add_dependents(backups, dependent, depth + 1, links);
+    }
+}
+
+/// The incremental backups that couldn't be restored any more without backup `id`, newest first
+pub fn dependents<'a>(backups: &'a [BackupInfo], id: &str) -> Vec<&'a BackupInfo> {
+    let mut found: Vec<&BackupInfo> = Vec::new();
+    let mut bases = vec![id.to_string()];
+    while let Some(base) = bases.pop() {
+        for backup in backups.iter().filter(|b| b.base_backup.as_deref() == Some(base.as_str())) {
+            if backup.id != id && !found.iter().any(|known| known.id == backup.id) {
+                found.push(backup);
+                bases.push(backup.id.clone());
+            }
+        }
+    }
+    found.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    found
+}
+
+/// Where a backup's file index is kept, next to its archive
+pub fn index_path(backup_info: &BackupInfo) -> PathBuf {
+    backup_info.backup_path.with_file_name(format!("{}.index.json", backup_info.id))
+}
+
+/// Drop a deleted backup's index; there's nothing to do for backups without one
+pub fn remove_index(backup_info: &BackupInfo) {
+    let path = index_path(backup_info);
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            warn!("Failed to remove {:?}: {}", path, e);
+        }
+    }
+}
+
+/// A `removed_files` entry that stays inside the folder being restored into
+pub fn is_safe_entry(name: &str) -> bool {
+    !name.is_empty() && Path::new(name).components().all(|c| matches!(c, std::path::Component::Normal(_)))
+}
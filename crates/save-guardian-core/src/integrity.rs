@@ -0,0 +1,194 @@
+use crate::types::*;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::info;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs backup manifests with a key that never leaves this installation
+#[derive(Clone)]
+pub struct BackupSigner {
+    key: Vec<u8>,
+}
+
+/// Result of checking a backup archive against its recorded hash and signature
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationStatus {
+    Verified,
+    Unsigned,
+    NoKey,
+    ArchiveModified,
+    BadSignature,
+    MissingArchive,
+}
+
+impl VerificationStatus {
+    pub fn is_tampered(&self) -> bool {
+        matches!(self, VerificationStatus::ArchiveModified | VerificationStatus::BadSignature)
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            VerificationStatus::Verified => "Signature valid, archive unmodified",
+            VerificationStatus::Unsigned => "Archive not signed",
+            VerificationStatus::NoKey => "Signed, but signing is disabled on this install",
+            VerificationStatus::ArchiveModified => "Archive contents changed since backup was created",
+            VerificationStatus::BadSignature => "Signature does not match this installation's key",
+            VerificationStatus::MissingArchive => "Backup archive is missing",
+        }
+    }
+}
+
+/// What reading every file of a backup back found, see `BackupManager::verify_contents`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentCheck {
+    pub checked: usize,             // Files matching their recorded checksum
+    pub unrecorded: usize,          // Files of backups made before checksums were recorded, only read through
+    pub corrupt: Vec<String>,       // Contents or size differ from the checksum
+    pub missing: Vec<String>,       // Recorded, but not in the archive
+    pub unreadable: Option<String>, // Why the archive couldn't be read to the end
+}
+
+impl ContentCheck {
+    pub fn is_ok(&self) -> bool {
+        self.corrupt.is_empty() && self.missing.is_empty() && self.unreadable.is_none()
+    }
+
+    pub fn describe(&self) -> String {
+        if let Some(ref error) = self.unreadable {
+            return format!("Archive can't be read back: {}", error);
+        }
+        if !self.is_ok() {
+            let mut problems = Vec::new();
+            if !self.corrupt.is_empty() {
+                problems.push(format!("{} damaged ({})", self.corrupt.len(), name_list(&self.corrupt)));
+            }
+            if !self.missing.is_empty() {
+                problems.push(format!("{} missing ({})", self.missing.len(), name_list(&self.missing)));
+            }
+            return format!("Files {}", problems.join(", "));
+        }
+        if self.checked == 0 {
+            return format!("All {} files readable, no checksums recorded to compare with", self.unrecorded);
+        }
+        format!("All {} files match their checksums", self.checked)
+    }
+}
+
+/// The first few names, for a one-line description
+fn name_list(names: &[String]) -> String {
+    const SHOWN: usize = 3;
+    let mut list = names.iter().take(SHOWN).cloned().collect::<Vec<_>>().join(", ");
+    if names.len() > SHOWN {
+        list.push_str(", ...");
+    }
+    list
+}
+
+impl BackupSigner {
+    /// Load the signing key, generating a new one on first use
+    ///
+    /// A key file that exists but can't be read is an error, not a reason to replace it:
+    /// a new key would make every backup signed so far fail verification.
+    pub fn load_or_create() -> Result<Self> {
+        let key_path = Self::key_path();
+
+        match fs::read_to_string(&key_path) {
+            Ok(contents) => {
+                return hex_decode(contents.trim())
+                    .filter(|key| !key.is_empty())
+                    .map(|key| Self { key })
+                    .ok_or_else(|| SaveGuardianError::SaveOperationFailed(format!(
+                        "Signing key at {:?} is damaged; restore it from a copy or delete it to start a new one", key_path
+                    )));
+            }
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(SaveGuardianError::SaveOperationFailed(format!("Failed to read signing key at {:?}: {}", key_path, e)));
+            }
+            Err(_) => {}
+        }
+
+        let mut key = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        if let Some(parent) = key_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to create key directory: {}", e)))?;
+        }
+        fs::write(&key_path, hex_encode(&key))
+            .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to save signing key: {}", e)))?;
+
+        info!("Generated new backup signing key at {:?}", key_path);
+        Ok(Self { key })
+    }
+
+    /// Get the path of the local signing key
+    pub fn key_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("SaveGuardian")
+            .join("signing.key")
+    }
+
+    /// Sign the manifest of a backup whose archive hash is already recorded
+    pub fn sign(&self, backup_info: &BackupInfo) -> Option<String> {
+        let payload = Self::manifest_payload(backup_info)?;
+        let mut mac = HmacSha256::new_from_slice(&self.key).ok()?;
+        mac.update(payload.as_bytes());
+        Some(hex_encode(&mac.finalize().into_bytes()))
+    }
+
+    /// Check a manifest signature
+    pub fn verify(&self, backup_info: &BackupInfo, signature: &str) -> bool {
+        let (payload, signature) = match (Self::manifest_payload(backup_info), hex_decode(signature)) {
+            (Some(payload), Some(signature)) => (payload, signature),
+            _ => return false,
+        };
+
+        match HmacSha256::new_from_slice(&self.key) {
+            Ok(mut mac) => {
+                mac.update(payload.as_bytes());
+                mac.verify_slice(&signature).is_ok()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// The signed fields: archive hash, size and the game it belongs to
+    fn manifest_payload(backup_info: &BackupInfo) -> Option<String> {
+        let hash = backup_info.archive_sha256.as_ref()?;
+        Some(format!("{}|{}|{}", hash, backup_info.size, backup_info.game_name))
+    }
+}
+
+/// Compute the SHA-256 of a file as a hex string
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| SaveGuardianError::Io(e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| SaveGuardianError::Io(e))?;
+    Ok(hex_encode(&hasher.finalize()))
+}
+
+/// SHA-256 of data in memory as a hex string
+pub fn sha256_bytes(data: &[u8]) -> String {
+    hex_encode(&Sha256::digest(data))
+}
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
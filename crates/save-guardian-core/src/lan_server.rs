@@ -0,0 +1,287 @@
+use crate::types::*;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tiny_http::{Header, Request, Response, Server};
+use log::{debug, info, warn};
+
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+const ALLOWED_METHODS: &str = "OPTIONS, GET, HEAD, PROPFIND";
+
+/// Read-only WebDAV server for the backup folder, so a Steam Deck, laptop or phone on the
+/// same network can browse and fetch archives without a cloud in between
+///
+/// Browsers get an HTML listing; file managers and WebDAV clients can mount it. Anything
+/// that would change the folder is refused.
+pub struct LanServer {
+    server: Arc<Server>,
+    worker: Option<JoinHandle<()>>,
+    port: u16,
+    root: PathBuf,
+    config: LanServerConfig,
+}
+
+impl LanServer {
+    pub fn start(root: PathBuf, config: &LanServerConfig) -> Result<Self> {
+        if !root.is_dir() {
+            return Err(SaveGuardianError::PathNotFound(root));
+        }
+        let server = Server::http(("0.0.0.0", config.port))
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Could not listen on port {}: {}", config.port, e)))?;
+        let server = Arc::new(server);
+
+        // Compared as the encoded header, nothing to decode per request
+        let credentials = (!config.password.is_empty()).then(|| {
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", config.username, config.password)))
+        });
+
+        let worker_server = Arc::clone(&server);
+        let worker_root = root.clone();
+        let worker = std::thread::spawn(move || {
+            for request in worker_server.incoming_requests() {
+                // One thread per request, so a phone downloading a big archive doesn't block the listing
+                let root = worker_root.clone();
+                let credentials = credentials.clone();
+                std::thread::spawn(move || handle(request, &root, credentials.as_deref()));
+            }
+        });
+
+        info!("LAN server listening on port {}{}", config.port, if config.password.is_empty() { " without a password" } else { "" });
+        Ok(Self { server, worker: Some(worker), port: config.port, root, config: config.clone() })
+    }
+
+    /// Whether this server already serves `root` as configured, so it needn't be restarted
+    pub fn serves(&self, root: &Path, config: &LanServerConfig) -> bool {
+        self.root == root && &self.config == config
+    }
+
+    /// Addresses to type into the other device, e.g. `http://192.168.1.20:8421/`
+    pub fn urls(&self) -> Vec<String> {
+        let mut urls = Vec::new();
+        if let Some(ip) = lan_address() {
+            urls.push(format!("http://{}:{}/", ip, self.port));
+        }
+        urls.push(format!("http://localhost:{}/", self.port));
+        urls
+    }
+}
+
+impl Drop for LanServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        info!("LAN server on port {} stopped", self.port);
+    }
+}
+
+/// This machine's address on the local network, as other devices see it
+///
+/// Connecting a UDP socket sends nothing; it only makes the OS pick the outgoing interface.
+pub fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.168.0.1:9").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip()).filter(|ip| !ip.is_unspecified())
+}
+
+fn handle(request: Request, root: &Path, credentials: Option<&str>) {
+    let method = request.method().to_string();
+    let remote = request.remote_addr().map(|addr| addr.to_string()).unwrap_or_default();
+    debug!("LAN server: {} {} from {}", method, request.url(), remote);
+
+    if let Some(expected) = credentials {
+        let authorized = request.headers().iter()
+            .any(|h| h.field.equiv("Authorization") && h.value.as_str() == expected);
+        if !authorized {
+            let response = Response::from_string("Password required")
+                .with_status_code(401)
+                .with_header(header("WWW-Authenticate", "Basic realm=\"Save Guardian\""));
+            respond(request, response);
+            return;
+        }
+    }
+
+    let path = match resolve(root, request.url()) {
+        Some(path) if path.exists() => path,
+        _ => {
+            respond(request, Response::from_string("Not found").with_status_code(404));
+            return;
+        }
+    };
+
+    match method.as_str() {
+        "OPTIONS" => {
+            let response = Response::empty(200)
+                .with_header(header("DAV", "1"))
+                .with_header(header("Allow", ALLOWED_METHODS));
+            respond(request, response);
+        }
+        "GET" | "HEAD" if path.is_dir() => {
+            let html = directory_page(root, &path);
+            respond(request, Response::from_string(html).with_header(header("Content-Type", "text/html; charset=utf-8")));
+        }
+        "GET" | "HEAD" => match fs::File::open(&path) {
+            Ok(file) => {
+                let content_type = match path.extension().and_then(|e| e.to_str()) {
+                    Some("json") => "application/json",
+                    Some("zip") => "application/zip",
+                    Some("zst") => "application/zstd",
+                    Some("7z") => "application/x-7z-compressed",
+                    _ => "application/octet-stream",
+                };
+                respond(request, Response::from_file(file).with_header(header("Content-Type", content_type)));
+            }
+            Err(e) => {
+                warn!("LAN server could not open {:?}: {}", path, e);
+                respond(request, Response::from_string("Could not read the file").with_status_code(500));
+            }
+        },
+        "PROPFIND" => {
+            let depth = request.headers().iter()
+                .find(|h| h.field.equiv("Depth"))
+                .map(|h| h.value.as_str().to_string())
+                .unwrap_or_else(|| "1".to_string());
+            let xml = propfind_body(root, &path, depth != "0");
+            let response = Response::from_string(xml)
+                .with_status_code(207)
+                .with_header(header("Content-Type", "application/xml; charset=utf-8"));
+            respond(request, response);
+        }
+        _ => {
+            // PUT, DELETE, MOVE, MKCOL...: the backup folder is read-only from the network
+            let response = Response::from_string("Read-only")
+                .with_status_code(405)
+                .with_header(header("Allow", ALLOWED_METHODS));
+            respond(request, response);
+        }
+    }
+}
+
+fn respond<R: std::io::Read>(request: Request, response: Response<R>) {
+    if let Err(e) = request.respond(response) {
+        debug!("LAN server response not delivered: {}", e);
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("static header")
+}
+
+/// Map a request path onto the backup folder, refusing anything that would leave it
+fn resolve(root: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let decoded = percent_decode_str(path).decode_utf8().ok()?;
+    let mut resolved = root.to_path_buf();
+    for part in decoded.split('/').filter(|p| !p.is_empty()) {
+        if is_hidden(part) || part == ".." || part.contains(['\\', ':']) {
+            return None;
+        }
+        resolved.push(part);
+    }
+    Some(resolved)
+}
+
+/// Dot files and half-finished downloads stay out of listings and can't be fetched
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('.') || name.ends_with(".part")
+}
+
+/// Visible entries of a folder, folders first, then by name
+fn list_folder(path: &Path) -> Vec<(String, fs::Metadata)> {
+    let mut entries: Vec<(String, fs::Metadata)> = fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok())
+            .filter_map(|e| Some((e.file_name().to_string_lossy().to_string(), fs::metadata(e.path()).ok()?)))
+            .filter(|(name, _)| !is_hidden(name))
+            .collect())
+        .unwrap_or_default();
+    entries.sort_by(|a, b| b.1.is_dir().cmp(&a.1.is_dir()).then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase())));
+    entries
+}
+
+/// URL path of a file or folder inside the backup folder
+fn href(root: &Path, path: &Path, is_dir: bool) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(Path::new(""));
+    let mut href = String::from("/");
+    for part in relative.components() {
+        href.push_str(&utf8_percent_encode(&part.as_os_str().to_string_lossy(), PATH_SEGMENT).to_string());
+        href.push('/');
+    }
+    if !is_dir && href.len() > 1 {
+        href.pop();
+    }
+    href
+}
+
+fn directory_page(root: &Path, path: &Path) -> String {
+    let title = format!("Save Guardian backups{}", href(root, path, true).trim_end_matches('/'));
+    let mut html = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{0}</title></head><body><h1>{0}</h1><table>",
+        escape(&title)
+    );
+    if path != root {
+        html.push_str("<tr><td><a href=\"../\">..</a></td><td></td><td></td></tr>");
+    }
+    for (name, metadata) in list_folder(path) {
+        let link = href(root, &path.join(&name), metadata.is_dir());
+        let size = if metadata.is_dir() { String::new() } else { format_size(metadata.len()) };
+        let modified = metadata.modified().ok()
+            .map(|m| DateTime::<Utc>::from(m).format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        let suffix = if metadata.is_dir() { "/" } else { "" };
+        html.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}{}</a></td><td>{}</td><td>{}</td></tr>",
+            link, escape(&name), suffix, size, modified
+        ));
+    }
+    html.push_str("</table></body></html>");
+    html
+}
+
+fn propfind_body(root: &Path, path: &Path, with_children: bool) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">\n");
+    if let Ok(metadata) = fs::metadata(path) {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        xml.push_str(&propfind_entry(&href(root, path, metadata.is_dir()), &name, &metadata));
+        if with_children && metadata.is_dir() {
+            for (name, metadata) in list_folder(path) {
+                xml.push_str(&propfind_entry(&href(root, &path.join(&name), metadata.is_dir()), &name, &metadata));
+            }
+        }
+    }
+    xml.push_str("</D:multistatus>\n");
+    xml
+}
+
+fn propfind_entry(href: &str, name: &str, metadata: &fs::Metadata) -> String {
+    let modified = metadata.modified().ok().map(DateTime::<Utc>::from);
+    let mut props = format!("<D:displayname>{}</D:displayname>", escape(name));
+    if metadata.is_dir() {
+        props.push_str("<D:resourcetype><D:collection/></D:resourcetype>");
+    } else {
+        props.push_str("<D:resourcetype/>");
+        props.push_str(&format!("<D:getcontentlength>{}</D:getcontentlength>", metadata.len()));
+        props.push_str(&format!(
+            "<D:getetag>\"{}-{}\"</D:getetag>",
+            metadata.len(),
+            modified.map_or(0, |m| m.timestamp())
+        ));
+    }
+    if let Some(modified) = modified {
+        props.push_str(&format!("<D:getlastmodified>{}</D:getlastmodified>", modified.format("%a, %d %b %Y %H:%M:%S GMT")));
+    }
+    format!(
+        "<D:response><D:href>{}</D:href><D:propstat><D:prop>{}</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>\n",
+        escape(href), props
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
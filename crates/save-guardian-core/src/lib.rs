@@ -0,0 +1,64 @@
+//! The engine of Save Guardian: save detection, backups, sync and cloud storage, without a GUI
+//!
+//! The `save-guardian` app is a thin egui front end on this crate; command line tools, a
+//! daemon or third-party programs can use it the same way without pulling in eframe.
+//!
+//! Main entry points:
+//! - [`steam::SteamScanner`] and [`non_steam::NonSteamScanner`] find saves
+//! - [`backup::BackupManager`] creates, restores, verifies and cleans up backups
+//! - [`sync::SyncManager`] keeps the two sides of a save pair in step, paired by app ID, name or a user rule
+//! - [`cloud`] uploads and downloads archives through a [`cloud::CloudProvider`]
+//! - [`maintenance`] and [`audit`] check stored archives, on a schedule or on demand
+//! - [`types::Config`] is the settings file all of these are built from
+//!
+//! # Versioning
+//!
+//! Every `pub` item is public API and follows semver. While the version is 0.x, a release
+//! that breaks the API bumps the minor version and a patch release never does. Adding a
+//! field to a config struct counts as compatible: they all have defaults for reading older
+//! settings files. Persisted formats (backup metadata, `config.toml`, the cloud manifest)
+//! stay readable by newer versions whatever the version number says.
+
+pub mod types;
+pub mod steam;
+pub mod non_steam;
+pub mod backup;
+pub mod sync;
+pub mod config;
+pub mod integrity;
+pub mod watchdog;
+pub mod exclusions;
+pub mod network;
+pub mod restore;
+pub mod maintenance;
+pub mod stats;
+pub mod cloud;
+pub mod activity;
+pub mod diagnostics;
+pub mod protection;
+pub mod names;
+pub mod anomalies;
+pub mod sync_clients;
+pub mod steam_cloud;
+pub mod sync_rules;
+pub mod game_settings;
+pub mod audit;
+pub mod volumes;
+pub mod lan_server;
+pub mod oplog;
+pub mod health;
+pub mod progress;
+pub mod workers;
+pub mod incremental;
+pub mod dedup_store;
+pub mod archive;
+pub mod normalize;
+pub mod save_formats;
+pub mod import;
+pub mod encryption;
+pub mod game_detection;
+pub mod save_watcher;
+pub mod app_lookup;
+
+// Re-export commonly used types
+pub use types::*;
\ No newline at end of file
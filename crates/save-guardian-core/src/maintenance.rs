@@ -0,0 +1,156 @@
+use crate::types::*;
+use crate::backup::BackupManager;
+use crate::integrity::{ContentCheck, VerificationStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{debug, info, warn};
+
+const SWEEP_STATE_FILE: &str = "verification.json";
+
+/// Progress of the rolling verification sweep, stored next to the backups
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SweepState {
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_checked: usize,
+    pub cursor: usize, // Index into the backups sorted oldest first
+    pub problems: Vec<SweepProblem>,
+}
+
+/// A backup that failed its last scheduled check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepProblem {
+    pub backup_id: String,
+    pub game_name: String,
+    pub backup_path: PathBuf,
+    pub found_at: DateTime<Utc>,
+    pub description: String,
+}
+
+impl SweepState {
+    /// Load the sweep state for a backup folder, starting fresh if there is none
+    pub fn load(backup_root: &Path) -> Self {
+        let path = backup_root.join(SWEEP_STATE_FILE);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable sweep state {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(backup_root.join(SWEEP_STATE_FILE), json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save sweep state: {}", e)))
+    }
+
+    /// Whether the schedule says another batch is due
+    pub fn is_due(&self, config: &VerificationScheduleConfig) -> bool {
+        if !config.enabled {
+            return false;
+        }
+        match self.last_run {
+            Some(last_run) => Utc::now() - last_run >= chrono::Duration::hours(config.interval_hours as i64),
+            None => true,
+        }
+    }
+}
+
+/// Verify the next batch of archives against their checksums, wrapping around the store
+pub fn run_sweep(backup_manager: &BackupManager, state: &mut SweepState, batch_size: usize) -> Result<()> {
+    let mut backups = backup_manager.list_backups(None, None)?;
+    backups.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    // Forget problems for backups that have since been deleted
+    state.problems.retain(|p| backups.iter().any(|b| b.backup_path == p.backup_path));
+
+    if backups.is_empty() {
+        state.cursor = 0;
+        state.last_checked = 0;
+        state.last_run = Some(Utc::now());
+        return Ok(());
+    }
+
+    let batch_size = batch_size.clamp(1, backups.len());
+    let start = state.cursor % backups.len();
+
+    let batch: Vec<BackupInfo> = (0..batch_size)
+        .map(|offset| backups[(start + offset) % backups.len()].clone())
+        .collect();
+    let statuses = backup_manager.verify_backups(&batch);
+
+    for (backup, status) in batch.iter().zip(statuses) {
+        debug!("Sweep checked {}: {:?}", backup.id, status);
+
+        state.problems.retain(|p| p.backup_path != backup.backup_path);
+        if status.is_tampered() || status == VerificationStatus::MissingArchive {
+            warn!("Verification sweep: {} - {}", backup.backup_path.display(), status.describe());
+            state.problems.push(SweepProblem {
+                backup_id: backup.id.clone(),
+                game_name: backup.game_name.clone(),
+                backup_path: backup.backup_path.clone(),
+                found_at: Utc::now(),
+                description: status.describe().to_string(),
+            });
+        }
+    }
+
+    state.cursor = (start + batch_size) % backups.len();
+    state.last_checked = batch_size;
+    state.last_run = Some(Utc::now());
+
+    info!("Verification sweep checked {} of {} backups, {} problems outstanding",
+        batch_size, backups.len(), state.problems.len());
+    Ok(())
+}
+
+/// Check every backup in full: archive checksum and signature, then each file read back
+/// against the checksum taken when it was archived
+///
+/// Problems found replace the sweep's earlier findings for the same backups; the sweep's
+/// own schedule and position are left alone. Returns the number of backups checked.
+pub fn run_full_check(backup_manager: &BackupManager, state: &mut SweepState) -> Result<usize> {
+    let mut backups = backup_manager.list_backups(None, None)?;
+    backups.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    state.problems.retain(|p| backups.iter().any(|b| b.backup_path == p.backup_path));
+
+    let results = backup_manager.verify_backups_fully(&backups)?;
+    for (backup, (status, contents)) in backups.iter().zip(results) {
+        debug!("Full check of {}: {:?}, {:?}", backup.id, status, contents);
+
+        state.problems.retain(|p| p.backup_path != backup.backup_path);
+        if let Some(description) = describe_problems(&status, contents.as_ref()) {
+            warn!("Full check: {} - {}", backup.backup_path.display(), description);
+            state.problems.push(SweepProblem {
+                backup_id: backup.id.clone(),
+                game_name: backup.game_name.clone(),
+                backup_path: backup.backup_path.clone(),
+                found_at: Utc::now(),
+                description,
+            });
+        }
+    }
+
+    info!("Full check of {} backups, {} problems outstanding", backups.len(), state.problems.len());
+    Ok(backups.len())
+}
+
+/// What is wrong with a backup after a full check, None if nothing is
+pub fn describe_problems(status: &VerificationStatus, contents: Option<&ContentCheck>) -> Option<String> {
+    let mut problems = Vec::new();
+    if status.is_tampered() || *status == VerificationStatus::MissingArchive {
+        problems.push(status.describe().to_string());
+    }
+    if let Some(contents) = contents.filter(|contents| !contents.is_ok()) {
+        problems.push(contents.describe());
+    }
+    if problems.is_empty() {
+        None
+    } else {
+        Some(problems.join("; "))
+    }
+}
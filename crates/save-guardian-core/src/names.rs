@@ -0,0 +1,569 @@
+use crate::types::{ApiSource, GameDetails, OnlineConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use log::{debug, info, warn};
+
+/// Pause between two online lookups, to be respectful to the APIs
+const FETCH_DELAY: Duration = Duration::from_millis(100);
+
+struct NameCache {
+    names: HashMap<u32, String>, // App ID -> Game Name
+    pending: HashSet<u32>,
+    failed: HashSet<u32>, // Not retried until an explicit refresh
+    responses: ResponseCache,
+    file_path: PathBuf,
+}
+
+/// An API response as it came in, kept for its other fields (genres, header image...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    fetched_at: DateTime<Utc>,
+    details: serde_json::Value,
+}
+
+/// Raw appdetails responses on disk, so clearing or re-checking names doesn't hit the network again
+struct ResponseCache {
+    entries: HashMap<String, CachedResponse>, // "store:<app id>" or "steamspy:<app id>"
+    file_path: PathBuf,
+}
+
+/// Steam game names, fetched online on a background thread
+///
+/// `lookup` and `name` only read the cache and never block, so they are safe to call
+/// while drawing. Names that aren't known yet are queued, and `generation` goes up
+/// whenever one arrives so callers know to refresh what they display.
+///
+/// Every online lookup goes through here and checks the `OnlineConfig` first.
+#[derive(Clone)]
+pub struct NameResolver {
+    cache: Arc<Mutex<NameCache>>,
+    requests: Sender<u32>,
+    generation: Arc<AtomicU64>,
+    online: Arc<RwLock<OnlineConfig>>,
+}
+
+impl NameResolver {
+    pub fn new() -> Self {
+        let file_path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("SaveGuardian")
+            .join("steam_game_cache.json");
+
+        let cache = Arc::new(Mutex::new(NameCache {
+            names: NameCache::load(&file_path),
+            pending: HashSet::new(),
+            failed: HashSet::new(),
+            responses: ResponseCache::load(file_path.with_file_name("steam_api_cache.json")),
+            file_path,
+        }));
+        let generation = Arc::new(AtomicU64::new(0));
+        let online = Arc::new(RwLock::new(OnlineConfig::default()));
+        let (requests, receiver) = mpsc::channel();
+
+        let worker_cache = Arc::clone(&cache);
+        let worker_generation = Arc::clone(&generation);
+        let worker_online = Arc::clone(&online);
+        // Ends once the last resolver handle is dropped
+        std::thread::spawn(move || run_worker(&worker_cache, receiver, &worker_generation, &worker_online));
+
+        Self { cache, requests, generation, online }
+    }
+
+    /// Apply the online settings to every handle, and to lookups already queued
+    pub fn set_online_config(&self, online: OnlineConfig) {
+        if let Ok(mut current) = self.online.write() {
+            *current = online;
+        }
+    }
+
+    fn online_allowed(&self) -> bool {
+        self.online.read().map_or(false, |online| online.allows_any())
+    }
+
+    /// The cached name, if there is a trustworthy one; never touches the network
+    pub fn lookup(&self, app_id: u32) -> Option<String> {
+        let cache = self.cache.lock().ok()?;
+        cache.names.get(&app_id)
+            .filter(|name| !is_likely_incorrect_name(name))
+            .cloned()
+    }
+
+    /// Every trustworthy cached name, e.g. to look games up by name without going online
+    pub fn known_names(&self) -> Vec<(u32, String)> {
+        match self.cache.lock() {
+            Ok(cache) => cache.names.iter()
+                .filter(|(_, name)| !is_likely_incorrect_name(name))
+                .map(|(app_id, name)| (*app_id, name.clone()))
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// The cached name, or a placeholder while the real one is fetched in the background
+    pub fn name(&self, app_id: u32) -> String {
+        match self.lookup(app_id) {
+            Some(name) => name,
+            None => {
+                self.request(app_id, false);
+                format!("Unknown Game {}", app_id)
+            }
+        }
+    }
+
+    /// Genres, release year and platforms from the saved API responses
+    ///
+    /// Like `name`, never blocks: without a saved response a lookup is queued and
+    /// the details show up once it's done.
+    pub fn details(&self, app_id: u32) -> Option<GameDetails> {
+        let saved = {
+            let cache = self.cache.lock().ok()?;
+            cache.responses.saved(ApiSource::SteamStore, app_id).map(details_from_store)
+                .or_else(|| cache.responses.saved(ApiSource::SteamSpy, app_id).map(details_from_steamspy))
+        };
+        if saved.is_none() {
+            self.request(app_id, false);
+        }
+        saved.filter(|details| !details.is_empty())
+    }
+
+    /// Queue an online lookup, unless one is already queued or failed before
+    fn request(&self, app_id: u32, retry_failed: bool) {
+        let mut cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(_) => return,
+        };
+        if cache.pending.contains(&app_id) || (!retry_failed && cache.failed.contains(&app_id)) {
+            return;
+        }
+        // Offline, only a saved response can still answer
+        if !self.online_allowed() && !cache.responses.contains(app_id) {
+            return;
+        }
+        cache.failed.remove(&app_id);
+        cache.pending.insert(app_id);
+        let _ = self.requests.send(app_id);
+    }
+
+    /// Re-fetch cached names that look wrong, e.g. placeholders from an offline start
+    pub fn refresh_incorrect_names(&self) {
+        let incorrect: Vec<u32> = match self.cache.lock() {
+            Ok(cache) => cache.names.iter()
+                .filter(|(_, name)| is_likely_incorrect_name(name))
+                .map(|(app_id, _)| *app_id)
+                .collect(),
+            Err(_) => return,
+        };
+
+        if !incorrect.is_empty() {
+            info!("Found {} incorrect cached names, refreshing in the background", incorrect.len());
+            for app_id in incorrect {
+                self.request(app_id, true);
+            }
+        }
+    }
+
+    /// Re-fetch every cached name
+    pub fn refresh_all(&self) {
+        let app_ids: Vec<u32> = match self.cache.lock() {
+            Ok(cache) => cache.names.keys().copied().collect(),
+            Err(_) => return,
+        };
+        info!("Refreshing {} cached game names in the background", app_ids.len());
+        for app_id in app_ids {
+            self.request(app_id, true);
+        }
+    }
+
+    /// Seed the cache with names known ahead of time
+    pub fn insert_known(&self, names: &[(u32, &str)]) {
+        if let Ok(mut cache) = self.cache.lock() {
+            for (app_id, name) in names {
+                cache.names.insert(*app_id, name.to_string());
+            }
+            info!("Loaded {} game names into cache", cache.names.len());
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Names read from local files (e.g. Steam's appmanifests), used where the cache has none
+    pub fn fill_missing(&self, names: Vec<(u32, String)>) {
+        let mut added = 0;
+        if let Ok(mut cache) = self.cache.lock() {
+            for (app_id, name) in names {
+                let known = cache.names.get(&app_id).map_or(false, |n| !is_likely_incorrect_name(n));
+                if !known && !is_likely_incorrect_name(&name) {
+                    cache.names.insert(app_id, name);
+                    added += 1;
+                }
+            }
+            if added > 0 {
+                debug!("Added {} game names from local files", added);
+                cache.save();
+            }
+        }
+        if added > 0 {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Goes up every time a name is added or changed
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Lookups still waiting or in flight
+    pub fn pending_count(&self) -> usize {
+        self.cache.lock().map(|cache| cache.pending.len()).unwrap_or(0)
+    }
+
+    /// Get cache statistics
+    pub fn cache_stats(&self) -> (usize, String) {
+        match self.cache.lock() {
+            Ok(cache) => (
+                cache.names.len(),
+                format!("Cache file: {} (exists: {})", cache.file_path.to_string_lossy(), cache.file_path.exists()),
+            ),
+            Err(_) => (0, "Cache unavailable".to_string()),
+        }
+    }
+
+    /// Clear the game name cache (useful for troubleshooting)
+    ///
+    /// Saved API responses are kept, so names come back without new requests
+    /// while they are younger than `api_cache_days`.
+    pub fn clear_cache(&self) {
+        let mut cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(_) => return,
+        };
+        info!("Clearing game name cache ({} entries)", cache.names.len());
+        cache.names.clear();
+        cache.failed.clear();
+
+        // Remove the cache file
+        if cache.file_path.exists() {
+            if let Err(e) = fs::remove_file(&cache.file_path) {
+                warn!("Failed to remove cache file: {}", e);
+            } else {
+                info!("Cache file removed successfully");
+            }
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Default for NameResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NameCache {
+    /// Load game name cache from file
+    fn load(file_path: &PathBuf) -> HashMap<u32, String> {
+        let contents = match fs::read_to_string(file_path) {
+            Ok(contents) => contents,
+            Err(_) => return HashMap::new(),
+        };
+        match serde_json::from_str::<HashMap<u32, String>>(&contents) {
+            Ok(names) => {
+                info!("Loaded {} game names from cache", names.len());
+                names
+            }
+            Err(_) => {
+                warn!("Failed to parse game name cache file");
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Save game name cache to file
+    fn save(&self) {
+        // Ensure the directory exists
+        if let Some(parent) = self.file_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+
+        if let Ok(cache_json) = serde_json::to_string_pretty(&self.names) {
+            if let Err(e) = fs::write(&self.file_path, cache_json) {
+                warn!("Failed to save game name cache: {}", e);
+            } else {
+                debug!("Saved {} game names to cache", self.names.len());
+            }
+        }
+    }
+}
+
+impl ResponseCache {
+    fn load(file_path: PathBuf) -> Self {
+        let entries = match fs::read_to_string(&file_path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable API response cache {:?}: {}", file_path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self { entries, file_path }
+    }
+
+    fn save(&self) {
+        if let Some(parent) = self.file_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string(&self.entries) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&self.file_path, json) {
+                    warn!("Failed to save API response cache: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize API response cache: {}", e),
+        }
+    }
+
+    fn key(source: ApiSource, app_id: u32) -> String {
+        match source {
+            ApiSource::SteamStore => format!("store:{}", app_id),
+            ApiSource::SteamSpy => format!("steamspy:{}", app_id),
+        }
+    }
+
+    fn contains(&self, app_id: u32) -> bool {
+        [ApiSource::SteamStore, ApiSource::SteamSpy].iter()
+            .any(|source| self.entries.contains_key(&Self::key(*source, app_id)))
+    }
+
+    /// A saved response that is still fresh; an expired one only if the source can't be asked
+    fn get(&self, source: ApiSource, app_id: u32, online: &OnlineConfig) -> Option<serde_json::Value> {
+        let cached = self.entries.get(&Self::key(source, app_id))?;
+        let age = Utc::now() - cached.fetched_at;
+        let fresh = age < chrono::Duration::days(online.api_cache_days as i64);
+        (fresh || !online.allows(source)).then(|| cached.details.clone())
+    }
+
+    /// A saved response, however old
+    fn saved(&self, source: ApiSource, app_id: u32) -> Option<&serde_json::Value> {
+        self.entries.get(&Self::key(source, app_id)).map(|cached| &cached.details)
+    }
+
+    fn insert(&mut self, source: ApiSource, app_id: u32, details: serde_json::Value) {
+        self.entries.insert(Self::key(source, app_id), CachedResponse { fetched_at: Utc::now(), details });
+        self.save();
+    }
+}
+
+fn run_worker(cache: &Mutex<NameCache>, requests: Receiver<u32>, generation: &AtomicU64, online: &RwLock<OnlineConfig>) {
+    for app_id in requests {
+        // Read per request, so switching to offline mode also stops what is already queued
+        let online = online.read().map(|online| online.clone()).unwrap_or_default();
+        // The lock is not held during the HTTP requests, lookups keep answering meanwhile
+        let fetched = fetch_game_name(app_id, &online, cache);
+
+        let mut cache = match cache.lock() {
+            Ok(cache) => cache,
+            Err(_) => return,
+        };
+        cache.pending.remove(&app_id);
+        match fetched {
+            Some(name) => {
+                if cache.names.get(&app_id) != Some(&name) {
+                    info!("Resolved game name for {}: '{}'", app_id, name);
+                    cache.names.insert(app_id, name);
+                    cache.save();
+                    generation.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            None if !online.allows_any() => {}
+            None => {
+                debug!("No name found for app {}", app_id);
+                cache.failed.insert(app_id);
+                // If the lookup fails, at least remove a clearly wrong name
+                if cache.names.get(&app_id).map_or(false, |name| is_likely_incorrect_name(name)) {
+                    cache.names.remove(&app_id);
+                    cache.save();
+                }
+            }
+        }
+        drop(cache);
+
+        std::thread::sleep(FETCH_DELAY);
+    }
+}
+
+/// Details from a Steam Store appdetails `data` object
+fn details_from_store(data: &serde_json::Value) -> GameDetails {
+    let genres = data.get("genres").and_then(|g| g.as_array())
+        .map(|genres| genres.iter()
+            .filter_map(|genre| genre.get("description").and_then(|d| d.as_str()))
+            .map(|genre| genre.to_string())
+            .collect())
+        .unwrap_or_default();
+
+    // Localized free text such as "21 Aug, 2012" or "Q4 2025"; the year is the only 4-digit number
+    let release_year = data.get("release_date")
+        .and_then(|r| r.get("date"))
+        .and_then(|d| d.as_str())
+        .and_then(|date| date.split(|c: char| !c.is_ascii_digit())
+            .filter(|part| part.len() == 4)
+            .filter_map(|part| part.parse().ok())
+            .last());
+
+    let platforms = data.get("platforms")
+        .map(|platforms| [("windows", "Windows"), ("mac", "macOS"), ("linux", "Linux")].iter()
+            .filter(|(key, _)| platforms.get(key).and_then(|p| p.as_bool()).unwrap_or(false))
+            .map(|(_, label)| label.to_string())
+            .collect())
+        .unwrap_or_default();
+
+    GameDetails { genres, release_year, platforms }
+}
+
+/// SteamSpy only knows genres, as one comma-separated string
+fn details_from_steamspy(data: &serde_json::Value) -> GameDetails {
+    let genres = data.get("genre").and_then(|g| g.as_str())
+        .map(|genres| genres.split(',')
+            .map(|genre| genre.trim().to_string())
+            .filter(|genre| !genre.is_empty())
+            .collect())
+        .unwrap_or_default();
+    GameDetails { genres, ..GameDetails::default() }
+}
+
+/// Check if a cached name is likely incorrect and should be refetched
+fn is_likely_incorrect_name(name: &str) -> bool {
+    // Check for generic patterns that indicate incorrect names
+    name.starts_with("Unknown Game") ||
+    name.contains("(ac)") ||
+    name.contains("(workshop)") ||
+    name.contains("(screenshots)") ||
+    // Check if the name is just a number (app ID) which means it failed to get a real name
+    name.parse::<u32>().is_ok() ||
+    // Some other common incorrect patterns
+    name.is_empty() ||
+    name == "null" ||
+    (name.is_ascii() && name.len() < 3) // Very short names are usually incorrect, but 原神 is two characters
+}
+
+/// Attempt to fetch game name from Steam installation or online sources
+fn fetch_game_name(app_id: u32, online: &OnlineConfig, cache: &Mutex<NameCache>) -> Option<String> {
+    // Try online APIs first (more reliable and up-to-date)
+    debug!("Attempting to fetch game name for app ID {} from online sources", app_id);
+    if let Ok(name) = fetch_game_name_from_api(app_id, online, cache) {
+        return Some(name);
+    }
+
+    // Try to read from Steam's registry (Windows)
+    #[cfg(windows)]
+    {
+        if let Ok(game_name) = get_game_name_from_registry(app_id) {
+            return Some(game_name);
+        }
+    }
+
+    None
+}
+
+/// Fetch game name from Steam API or SteamSpy API, preferring saved responses
+fn fetch_game_name_from_api(app_id: u32, online: &OnlineConfig, cache: &Mutex<NameCache>) -> std::result::Result<String, Box<dyn std::error::Error>> {
+    // Steam Store API first (free, no API key needed), SteamSpy as fallback (also free)
+    for source in [ApiSource::SteamStore, ApiSource::SteamSpy] {
+        let saved = cache.lock().ok().and_then(|cache| cache.responses.get(source, app_id, online));
+        let details = match saved {
+            Some(details) => details,
+            None if online.allows(source) => {
+                let fetched = match source {
+                    ApiSource::SteamStore => fetch_from_steam_store_api(app_id),
+                    ApiSource::SteamSpy => fetch_from_steamspy_api(app_id),
+                };
+                match fetched {
+                    Ok(details) => {
+                        if online.api_cache_days > 0 {
+                            if let Ok(mut cache) = cache.lock() {
+                                cache.responses.insert(source, app_id, details.clone());
+                            }
+                        }
+                        details
+                    }
+                    Err(e) => {
+                        debug!("{:?} lookup of {} failed: {}", source, app_id, e);
+                        continue;
+                    }
+                }
+            }
+            None => continue,
+        };
+
+        if let Some(name) = details.get("name").and_then(|n| n.as_str()) {
+            if !name.is_empty() && name != "null" {
+                info!("Game name from {:?}: {} -> {}", source, app_id, name);
+                return Ok(name.to_string());
+            }
+        }
+    }
+
+    Err("No API sources available".into())
+}
+
+/// Fetch an app's details from the Steam Store API
+fn fetch_from_steam_store_api(app_id: u32) -> std::result::Result<serde_json::Value, Box<dyn std::error::Error>> {
+    // No filter: the full details include genres, release date and header image
+    let url = format!("https://store.steampowered.com/api/appdetails?appids={}", app_id);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()?;
+
+    let response = client.get(&url)
+        .header("User-Agent", "SaveGuardian/1.0")
+        .send()?;
+
+    if response.status().is_success() {
+        let mut json: serde_json::Value = response.json()?;
+
+        if let Some(data) = json.get_mut(app_id.to_string()).and_then(|app_data| app_data.get_mut("data")) {
+            return Ok(data.take());
+        }
+    }
+
+    Err("Failed to get app details from Steam Store API".into())
+}
+
+/// Fetch an app's details from the SteamSpy API
+fn fetch_from_steamspy_api(app_id: u32) -> std::result::Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let url = format!("https://steamspy.com/api.php?request=appdetails&appid={}", app_id);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()?;
+
+    let response = client.get(&url)
+        .header("User-Agent", "SaveGuardian/1.0")
+        .send()?;
+
+    if response.status().is_success() {
+        let json: serde_json::Value = response.json()?;
+        // Unknown apps come back with an empty name rather than an error
+        if json.get("name").and_then(|n| n.as_str()).map_or(false, |name| !name.is_empty()) {
+            return Ok(json);
+        }
+    }
+
+    Err("Failed to get app details from SteamSpy API".into())
+}
+
+#[cfg(windows)]
+fn get_game_name_from_registry(app_id: u32) -> std::result::Result<String, Box<dyn std::error::Error>> {
+    use winreg::{RegKey, enums::*};
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let steam_apps = hklm.open_subkey(r"SOFTWARE\Valve\Steam\Apps")?;
+    let app_key = steam_apps.open_subkey(app_id.to_string())?;
+    let name: String = app_key.get_value("Name")?;
+    Ok(name)
+}
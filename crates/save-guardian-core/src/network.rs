@@ -0,0 +1,114 @@
+use crate::types::*;
+use std::io;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+use log::{debug, warn};
+
+/// Check whether a path lives on a network share (UNC path, mapped drive or network mount)
+pub fn is_network_path(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+    if path_str.starts_with("\\\\") || path_str.starts_with("//") {
+        return true;
+    }
+
+    is_network_mount(path)
+}
+
+#[cfg(windows)]
+fn is_network_mount(path: &Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDriveTypeW;
+    use winapi::um::winbase::DRIVE_REMOTE;
+
+    let path_str = path.to_string_lossy();
+    if path_str.len() < 2 || path_str.as_bytes()[1] != b':' {
+        return false;
+    }
+
+    let root: Vec<u16> = std::ffi::OsStr::new(&format!("{}\\", &path_str[..2]))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(not(windows))]
+fn is_network_mount(path: &Path) -> bool {
+    const NETWORK_FS: &[&str] = &["cifs", "smb3", "smbfs", "nfs", "nfs4", "fuse.sshfs", "afpfs", "9p"];
+
+    let mounts = match std::fs::read_to_string("/proc/mounts") {
+        Ok(mounts) => mounts,
+        Err(_) => return false,
+    };
+
+    // Find the most specific mount point containing the path
+    let mut best: Option<(usize, bool)> = None;
+    for line in mounts.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 3 {
+            continue;
+        }
+        let mount_point = fields[1].replace("\\040", " ");
+        if path.starts_with(&mount_point) && best.map_or(true, |(len, _)| mount_point.len() > len) {
+            best = Some((mount_point.len(), NETWORK_FS.contains(&fields[2])));
+        }
+    }
+
+    best.map_or(false, |(_, is_network)| is_network)
+}
+
+/// Check that a path answers within the timeout; a dead share can block `exists()` for minutes
+pub fn is_reachable(path: &Path, timeout: Duration) -> bool {
+    let (tx, rx) = mpsc::channel();
+    let path = path.to_path_buf();
+
+    std::thread::spawn(move || {
+        let _ = tx.send(path.exists());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(exists) => exists,
+        Err(_) => {
+            warn!("Timed out after {:?} waiting for network path", timeout);
+            false
+        }
+    }
+}
+
+/// Run a filesystem operation, retrying transient network errors
+pub fn with_retry<T>(config: &NetworkConfig, mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < config.retry_attempts && is_transient(&e) => {
+                debug!("Transient error (attempt {}/{}): {}", attempt, config.retry_attempts, e);
+                std::thread::sleep(Duration::from_millis(config.retry_delay_ms * attempt as u64));
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Errors a flaky SMB/NFS share produces that are worth retrying
+fn is_transient(e: &io::Error) -> bool {
+    if matches!(e.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock |
+        io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted |
+        io::ErrorKind::NotConnected | io::ErrorKind::BrokenPipe
+    ) {
+        return true;
+    }
+
+    // ERROR_BAD_NETPATH, ERROR_UNEXP_NET_ERR, ERROR_NETNAME_DELETED, ERROR_SEM_TIMEOUT
+    #[cfg(windows)]
+    let transient_codes: &[i32] = &[53, 59, 64, 121];
+    // EHOSTDOWN, EHOSTUNREACH, ESTALE
+    #[cfg(not(windows))]
+    let transient_codes: &[i32] = &[112, 113, 116];
+
+    e.raw_os_error().map_or(false, |code| transient_codes.contains(&code))
+}
@@ -1,12 +1,28 @@
 use crate::types::*;
+use crate::network;
+use crate::normalize;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use walkdir::WalkDir;
 use log::{debug, info, warn};
 
+/// Folders Steam emulators keep their saves in, one folder per app ID below them
+const STEAM_EMU_SAVE_FOLDERS: &[&str] = &["goldberg steamemu saves", "gse saves", "smartsteamemu"];
+
+/// Scene group folders that hold emulator saves, only when an app ID folder follows
+/// (a game could be called "Rune" too)
+const STEAM_EMU_GROUP_FOLDERS: &[&str] = &["codex", "cpy_saves", "rune", "empress", "onlinefix", "3dmgame", "skidrow", "flt", "tenoke"];
+
+/// How many folders up from a save to look for launcher files
+const ORIGIN_SEARCH_DEPTH: usize = 5;
+
 pub struct NonSteamScanner {
     common_locations: Vec<SaveLocation>,
     custom_locations: Vec<SaveLocation>,
+    custom_games: Vec<CustomGame>,
+    app_ids: Vec<AppIdAssignment>,
+    network: NetworkConfig,
 }
 
 impl NonSteamScanner {
@@ -14,6 +30,9 @@ impl NonSteamScanner {
         Self {
             common_locations: Self::get_default_locations(),
             custom_locations: Vec::new(),
+            custom_games: Vec::new(),
+            app_ids: Vec::new(),
+            network: NetworkConfig::default(),
         }
     }
 
@@ -22,7 +41,23 @@ impl NonSteamScanner {
         self
     }
 
-    /// Get default common save locations for Windows
+    pub fn with_custom_games(mut self, custom_games: Vec<CustomGame>) -> Self {
+        self.custom_games = custom_games;
+        self
+    }
+
+    /// Steam app IDs the user confirmed for some of the saves, see app_lookup.rs
+    pub fn with_app_ids(mut self, app_ids: Vec<AppIdAssignment>) -> Self {
+        self.app_ids = app_ids;
+        self
+    }
+
+    pub fn with_network_config(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Get default common save locations, plus the platform's own on Linux and macOS
     fn get_default_locations() -> Vec<SaveLocation> {
         let mut locations = Vec::new();
         
@@ -36,38 +71,45 @@ impl NonSteamScanner {
                     location_type: LocationType::Documents,
                     description: "Documents\\My Games - Common for many PC games".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 },
                 SaveLocation {
                     path: documents.clone(),
                     location_type: LocationType::Documents,
                     description: "Documents - Direct saves in Documents folder".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 },
                 SaveLocation {
                     path: documents.join("Rockstar Games"),
                     location_type: LocationType::Documents,
                     description: "Documents\\Rockstar Games - Rockstar titles".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 },
             ]);
 
-            // AppData Roaming
+            // AppData Roaming (on macOS this is Application Support, added below)
+            #[cfg(not(target_os = "macos"))]
             if let Some(roaming) = dirs::config_dir() {
                 locations.push(SaveLocation {
                     path: roaming,
                     location_type: LocationType::AppDataRoaming,
                     description: "AppData\\Roaming - Config and saves for many games".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 });
             }
 
-            // AppData Local
+            // AppData Local (on macOS this is ~/Library/Caches, which holds no saves)
+            #[cfg(not(target_os = "macos"))]
             if let Some(local) = dirs::cache_dir() {
                 locations.push(SaveLocation {
                     path: local,
                     location_type: LocationType::AppDataLocal,
                     description: "AppData\\Local - Modern game saves and settings".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 });
             }
 
@@ -79,6 +121,7 @@ impl NonSteamScanner {
                     location_type: LocationType::AppDataLocalLow,
                     description: "AppData\\LocalLow - Unity games persistent data".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 });
             }
 
@@ -90,6 +133,7 @@ impl NonSteamScanner {
                     location_type: LocationType::PublicDocuments,
                     description: "Public Documents - Some cracks and older titles".to_string(),
                     is_custom: false,
+                    sandbox: None,
                 });
             }
 
@@ -101,6 +145,107 @@ impl NonSteamScanner {
                     location_type: LocationType::AppDataRoaming,
                     description: "Goldberg SteamEmu Saves - Emulated Steam saves".to_string(),
                     is_custom: false,
+                    sandbox: None,
+                });
+            }
+
+            #[cfg(target_os = "linux")]
+            locations.extend(Self::get_sandbox_locations(&home));
+
+            #[cfg(target_os = "macos")]
+            locations.extend(Self::get_macos_locations(&home));
+        }
+
+        locations
+    }
+
+    /// Wine bottles managed by Bottles and Flatpak per-app data dirs (`~/.var/app/*/`)
+    ///
+    /// Each location remembers the bottle or app it belongs to, so the saves found there
+    /// are labelled with it and keep their own backups.
+    #[cfg(target_os = "linux")]
+    fn get_sandbox_locations(home: &std::path::Path) -> Vec<SaveLocation> {
+        const BOTTLES_APP_ID: &str = "com.usebottles.bottles";
+        let mut locations = Vec::new();
+
+        // Bottles installed natively or as a Flatpak
+        let bottle_roots = [
+            home.join(".local/share/bottles/bottles"),
+            home.join(".var/app").join(BOTTLES_APP_ID).join("data/bottles/bottles"),
+        ];
+        for bottle in bottle_roots.iter().flat_map(|root| subdirectories(root)) {
+            let name = match bottle.file_name() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => continue,
+            };
+            // Same folders as on Windows, for every Wine user of the bottle
+            for user in subdirectories(&bottle.join("drive_c").join("users")) {
+                for folder in ["Documents", "Saved Games", "AppData/Roaming", "AppData/Local", "AppData/LocalLow"] {
+                    let path = user.join(folder);
+                    if path.is_dir() {
+                        locations.push(SaveLocation {
+                            path,
+                            location_type: LocationType::Bottles,
+                            description: format!("Bottles: {} - {}", name, folder),
+                            is_custom: false,
+                            sandbox: Some(Sandbox::Bottle(name.clone())),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Other Flatpak apps keep their data and config in ~/.var/app/<app id>/
+        for app in subdirectories(&home.join(".var/app")) {
+            let app_id = match app.file_name() {
+                Some(app_id) => app_id.to_string_lossy().to_string(),
+                None => continue,
+            };
+            if app_id == BOTTLES_APP_ID {
+                continue;
+            }
+            locations.push(SaveLocation {
+                description: format!("Flatpak: {} - Per-app data", app_id),
+                path: app,
+                location_type: LocationType::Flatpak,
+                is_custom: false,
+                sandbox: Some(Sandbox::Flatpak(app_id)),
+            });
+        }
+
+        locations
+    }
+
+    /// ~/Library/Application Support, and the same folder inside every sandboxed app's
+    /// container (~/Library/Containers/<bundle id>/Data/Library/Application Support)
+    #[cfg(target_os = "macos")]
+    fn get_macos_locations(home: &std::path::Path) -> Vec<SaveLocation> {
+        let library = home.join("Library");
+        let mut locations = vec![SaveLocation {
+            path: library.join("Application Support"),
+            location_type: LocationType::ApplicationSupport,
+            description: "Application Support - Saves and settings of most Mac games".to_string(),
+            is_custom: false,
+            sandbox: None,
+        }];
+
+        for container in subdirectories(&library.join("Containers")) {
+            let bundle_id = match container.file_name() {
+                Some(bundle_id) => bundle_id.to_string_lossy().to_string(),
+                None => continue,
+            };
+            // Apple's own apps are never games
+            if bundle_id.starts_with("com.apple.") {
+                continue;
+            }
+            let path = container.join("Data/Library/Application Support");
+            if path.is_dir() {
+                locations.push(SaveLocation {
+                    path,
+                    location_type: LocationType::MacContainer,
+                    description: format!("Container: {} - Sandboxed app data", bundle_id),
+                    is_custom: false,
+                    sandbox: Some(Sandbox::MacContainer(bundle_id)),
                 });
             }
         }
@@ -110,12 +255,21 @@ impl NonSteamScanner {
 
     /// Scan for non-Steam game saves
     pub fn scan_non_steam_saves(&self) -> Result<Vec<GameSave>> {
+        self.scan_all(false)
+    }
+
+    /// Scan for non-Steam game saves, skipping network locations if configured
+    pub fn quick_scan_non_steam_saves(&self) -> Result<Vec<GameSave>> {
+        self.scan_all(self.network.skip_in_quick_scan)
+    }
+
+    fn scan_all(&self, skip_network: bool) -> Result<Vec<GameSave>> {
         info!("Starting non-Steam save scan");
         let mut all_saves = Vec::new();
 
         // Scan common locations
         for location in &self.common_locations {
-            if let Ok(mut saves) = self.scan_location(location) {
+            if let Ok(mut saves) = self.scan_location(location, skip_network) {
                 info!("Found {} saves in {}", saves.len(), location.description);
                 all_saves.append(&mut saves);
             }
@@ -123,19 +277,45 @@ impl NonSteamScanner {
 
         // Scan custom locations
         for location in &self.custom_locations {
-            if let Ok(mut saves) = self.scan_location(location) {
+            if let Ok(mut saves) = self.scan_location(location, skip_network) {
                 info!("Found {} saves in custom location: {}", saves.len(), location.description);
                 all_saves.append(&mut saves);
             }
         }
 
+        // Games added by name, whatever their folder looks like; they replace anything found inside it
+        for game in &self.custom_games {
+            all_saves.retain(|save| !save.save_path.starts_with(&game.save_path));
+            if let Some(save) = self.scan_custom_game(game, skip_network) {
+                all_saves.push(save);
+            }
+        }
+
+        for save in &mut all_saves {
+            if let Some(assignment) = self.app_ids.iter().find(|a| a.save_path == save.save_path) {
+                save.app_id = Some(assignment.app_id);
+            }
+        }
+
         info!("Found {} total non-Steam saves", all_saves.len());
         Ok(all_saves)
     }
 
     /// Scan a specific location for game saves
-    fn scan_location(&self, location: &SaveLocation) -> Result<Vec<GameSave>> {
-        if !location.path.exists() {
+    fn scan_location(&self, location: &SaveLocation, skip_network: bool) -> Result<Vec<GameSave>> {
+        let is_network = network::is_network_path(&location.path);
+        if is_network {
+            if skip_network {
+                info!("Skipping network location during quick scan: {:?}", location.path);
+                return Ok(Vec::new());
+            }
+
+            let timeout = Duration::from_secs(self.network.offline_timeout_secs);
+            if !network::is_reachable(&location.path, timeout) {
+                warn!("Network location is offline or unreachable: {:?}", location.path);
+                return Ok(Vec::new());
+            }
+        } else if !location.path.exists() {
             debug!("Location does not exist: {:?}", location.path);
             return Ok(Vec::new());
         }
@@ -163,13 +343,20 @@ impl NonSteamScanner {
 
             // Check if this directory looks like it contains game saves
             if self.is_potential_game_save_directory(path)? {
-                if let Some(game_name) = self.extract_game_name_from_path(path) {
-                    let save = GameSave::new(
+                let game_name = match location.sandbox {
+                    Some(_) => self.extract_sandboxed_game_name(path, location),
+                    None => self.extract_game_name_from_path(path),
+                };
+                if let Some(game_name) = game_name {
+                    let mut save = GameSave::new(
                         game_name,
                         path.to_path_buf(),
                         SaveType::NonSteam,
-                        None, // Non-Steam games don't have app IDs
+                        None, // Unless the user confirmed one, see scan_all
                     );
+                    save.is_network = is_network;
+                    save.sandbox = location.sandbox.clone();
+                    save.origin = Some(detect_origin(path));
                     
                     debug!("Found non-Steam save: {} at {:?}", save.name, save.save_path);
                     saves.push(save);
@@ -180,6 +367,23 @@ impl NonSteamScanner {
         Ok(saves)
     }
 
+    /// The save of a custom game, if its folder is there
+    fn scan_custom_game(&self, game: &CustomGame, skip_network: bool) -> Option<GameSave> {
+        let is_network = network::is_network_path(&game.save_path);
+        if is_network && (skip_network || !network::is_reachable(&game.save_path, Duration::from_secs(self.network.offline_timeout_secs))) {
+            return None;
+        }
+        if !game.save_path.is_dir() {
+            debug!("Custom game folder does not exist: {:?}", game.save_path);
+            return None;
+        }
+
+        let mut save = GameSave::new(game.name.clone(), game.save_path.clone(), SaveType::NonSteam, None);
+        save.is_network = is_network;
+        save.origin = Some(detect_origin(&game.save_path));
+        Some(save)
+    }
+
     /// Check if a directory contains actual game save files
     fn is_potential_game_save_directory(&self, path: &std::path::Path) -> Result<bool> {
         // Check for actual save files
@@ -284,7 +488,30 @@ impl NonSteamScanner {
 
     /// Extract game name from the directory path
     fn extract_game_name_from_path(&self, path: &std::path::Path) -> Option<String> {
-        // Try to get the most specific directory name that represents the game
+        self.find_game_name_component(path).or_else(|| {
+            // Fallback: use the last directory name
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|s| self.clean_game_name(s))
+        })
+    }
+
+    /// Game name for a save inside a bottle, Flatpak or Mac container, looking only below the location
+    ///
+    /// The sandbox's own folders (`.var/app`, `drive_c/users/...`) never name a game. A Flatpak
+    /// or Mac app with nothing better is usually the game itself, so the end of its ID is used.
+    fn extract_sandboxed_game_name(&self, path: &std::path::Path, location: &SaveLocation) -> Option<String> {
+        let relative = path.strip_prefix(&location.path).ok()?;
+        self.find_game_name_component(relative).or_else(|| match location.sandbox {
+            Some(Sandbox::Flatpak(ref id)) | Some(Sandbox::MacContainer(ref id)) => {
+                id.rsplit('.').next().map(|s| self.clean_game_name(s))
+            }
+            _ => None,
+        })
+    }
+
+    /// The most specific directory name of `path` that looks like a game
+    fn find_game_name_component(&self, path: &std::path::Path) -> Option<String> {
         let components: Vec<_> = path.components().collect();
         
         // Look for game-specific patterns in the path
@@ -298,7 +525,8 @@ impl NonSteamScanner {
                     "settings" | "user" | "users" | "documents" | "my games" |
                     "appdata" | "roaming" | "local" | "locallow" | "public" |
                     "remote" | "steam" | "steamemu" | "goldberg" | "minecraft" |
-                    "versions" | "mods" | "libraries" | "bin" | "temp" | "cache"
+                    "versions" | "mods" | "libraries" | "bin" | "temp" | "cache" |
+                    "library" | "application support" | "containers"
                 ) {
                     continue;
                 }
@@ -318,15 +546,13 @@ impl NonSteamScanner {
             }
         }
 
-        // Fallback: use the last directory name
-        path.file_name()
-            .and_then(|n| n.to_str())
-            .map(|s| self.clean_game_name(s))
+        None
     }
 
     /// Clean up the game name by removing common suffixes and formatting
     fn clean_game_name(&self, name: &str) -> String {
-        let mut clean_name = name.to_string();
+        // Composed, so a folder name read on macOS matches the same name from elsewhere
+        let mut clean_name = normalize::nfc(name);
 
         // Remove common version suffixes
         let suffixes_to_remove = vec![
@@ -392,16 +618,68 @@ impl NonSteamScanner {
             let save_path = game_path.join(subdir);
             if save_path.exists() && save_path.is_dir() {
                 if self.is_potential_game_save_directory(&save_path)? {
-                    return Ok(Some(GameSave::new(
+                    let mut save = GameSave::new(
                         format!("{} (Install)", game_name),
                         save_path,
                         SaveType::NonSteam,
                         None,
-                    )));
+                    );
+                    save.origin = Some(detect_origin(game_path));
+                    return Ok(Some(save));
                 }
             }
         }
 
         Ok(None)
     }
-}
\ No newline at end of file
+}
+
+/// Guess which launcher a non-Steam save belongs to from the folders around it
+///
+/// Emulator save folders (Goldberg, CODEX, ...) are recognised by name and give the app ID
+/// of the folder below them. Otherwise the save's folder and a few above it are checked for
+/// an emulator config (`steam_settings`, `steam_emu.ini`), Epic's `.egstore` folder or
+/// GOG's `goggame-*.info`; the nearest one wins.
+pub fn detect_origin(path: &std::path::Path) -> LauncherOrigin {
+    let components: Vec<String> = path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    for (i, component) in components.iter().enumerate() {
+        let app_id = components.get(i + 1).and_then(|next| next.parse::<u32>().ok());
+        if STEAM_EMU_SAVE_FOLDERS.contains(&component.as_str())
+            || (app_id.is_some() && STEAM_EMU_GROUP_FOLDERS.contains(&component.as_str())) {
+            return LauncherOrigin::SteamEmu(app_id);
+        }
+    }
+
+    for dir in path.ancestors().take(ORIGIN_SEARCH_DEPTH) {
+        if dir.join("steam_settings").is_dir() || dir.join("steam_emu.ini").is_file() {
+            return LauncherOrigin::SteamEmu(None);
+        }
+        if dir.join(".egstore").is_dir() {
+            return LauncherOrigin::Epic;
+        }
+        if has_gog_info(dir) {
+            return LauncherOrigin::Gog;
+        }
+    }
+    LauncherOrigin::Standalone
+}
+
+/// Whether `dir` holds the `goggame-<id>.info` file GOG installs put next to the game
+fn has_gog_info(dir: &std::path::Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).any(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            name.starts_with("goggame-") && name.ends_with(".info")
+        }))
+        .unwrap_or(false)
+}
+
+/// Folders directly inside `path`, empty if it can't be read
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn subdirectories(path: &std::path::Path) -> Vec<PathBuf> {
+    fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,75 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Longest game name part of a file name, in bytes; a CJK character takes three, and the
+/// rest of a backup's file name has to fit in the usual 255 as well
+const MAX_FILE_NAME_BYTES: usize = 150;
+
+/// English filler words left out when comparing names; other scripts have no spaces to split on
+const STOP_WORDS: &[&str] = &["the", "a", "an", "and", "or", "of", "in", "on", "at", "to", "for", "with"];
+
+/// The composed (NFC) form of a name
+///
+/// macOS hands out file names decomposed, so "ポケモン" read from a folder there and the
+/// same name from Steam differ byte for byte until both are composed.
+pub fn nfc(text: &str) -> String {
+    if unicode_normalization::is_nfc(text) {
+        return text.to_string();
+    }
+    text.nfc().collect()
+}
+
+/// Whether two names are the same text, however each was composed
+pub fn same_text(a: &str, b: &str) -> bool {
+    a == b || nfc(a) == nfc(b)
+}
+
+/// A game name reduced for matching: composed, lowercased, punctuation of any script
+/// (full-width colons, "・", "—") turned into spaces, English filler words dropped
+///
+/// Apostrophes stay, so "Director's Cut" keeps matching its variations.
+pub fn match_key(name: &str) -> String {
+    let spaced: String = nfc(name)
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '\'' | '\u{2019}' => '\'',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    spaced.split_whitespace()
+        .filter(|word| !STOP_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `match_key` as one word, so "HollowKnight", "hollow_knight" and "Hollow Knight" agree
+///
+/// For names from file and folder names, which drop spaces as often as they keep them.
+pub fn compact_key(name: &str) -> String {
+    match_key(name).chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// A name made safe for file and folder names, keeping letters of every script
+///
+/// Whitespace becomes '_', and so do characters Windows reserves and control characters.
+/// Overlong names are cut at a character boundary.
+pub fn file_safe(name: &str) -> String {
+    let safe: String = nfc(name)
+        .chars()
+        .map(|c| if c.is_whitespace() || c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    truncate_bytes(&safe, MAX_FILE_NAME_BYTES).to_string()
+}
+
+/// The longest prefix of `text` of at most `max` bytes that ends on a character boundary
+pub fn truncate_bytes(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+
+/// Completed operations kept for the Operations window, oldest dropped first
+const MAX_OPERATIONS: usize = 50;
+
+/// What happened to one file, save or backup during an operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryOutcome {
+    Copied,
+    Skipped,
+    Removed,
+    Failed,
+}
+
+impl EntryOutcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            EntryOutcome::Copied => "Copied",
+            EntryOutcome::Skipped => "Skipped",
+            EntryOutcome::Removed => "Removed",
+            EntryOutcome::Failed => "Failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub item: String, // File, game or archive the entry is about
+    pub outcome: EntryOutcome,
+    pub reason: Option<String>, // Why it was skipped or failed, or where it went
+}
+
+impl LogEntry {
+    pub fn new(item: impl Into<String>, outcome: EntryOutcome, reason: Option<String>) -> Self {
+        Self { item: item.into(), outcome, reason }
+    }
+}
+
+/// Per-file record of a backup run, sync, restore or cloud transfer
+///
+/// The status bar only has room for the summary; the entries say which files it covers.
+#[derive(Debug, Clone)]
+pub struct OperationLog {
+    pub name: String, // e.g. "Backup All", "Sync Elden Ring", "Full Sync"
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub summary: String,
+    pub entries: Vec<LogEntry>,
+}
+
+impl OperationLog {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            started_at: Utc::now(),
+            finished_at: None,
+            summary: String::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn copied(&mut self, item: impl Into<String>, reason: Option<String>) {
+        self.entries.push(LogEntry::new(item, EntryOutcome::Copied, reason));
+    }
+
+    pub fn skipped(&mut self, item: impl Into<String>, reason: impl Into<String>) {
+        self.entries.push(LogEntry::new(item, EntryOutcome::Skipped, Some(reason.into())));
+    }
+
+    pub fn failed(&mut self, item: impl Into<String>, reason: impl Into<String>) {
+        self.entries.push(LogEntry::new(item, EntryOutcome::Failed, Some(reason.into())));
+    }
+
+    pub fn finish(&mut self, summary: impl Into<String>) {
+        self.summary = summary.into();
+        self.finished_at = Some(Utc::now());
+    }
+
+    pub fn count(&self, outcome: EntryOutcome) -> usize {
+        self.entries.iter().filter(|entry| entry.outcome == outcome).count()
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.count(EntryOutcome::Failed) == 0
+    }
+}
+
+/// The operations finished in this session, newest last
+#[derive(Debug, Clone, Default)]
+pub struct OperationHistory {
+    operations: VecDeque<OperationLog>,
+}
+
+impl OperationHistory {
+    pub fn push(&mut self, log: OperationLog) {
+        if self.operations.len() >= MAX_OPERATIONS {
+            self.operations.pop_front();
+        }
+        self.operations.push_back(log);
+    }
+
+    pub fn latest(&self) -> Option<&OperationLog> {
+        self.operations.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn newest_first(&self) -> impl Iterator<Item = &OperationLog> {
+        self.operations.iter().rev()
+    }
+}
@@ -0,0 +1,109 @@
+use crate::types::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+/// Files done in the archive being written or extracted, shared with the UI thread
+#[derive(Debug, Default)]
+pub struct ArchiveProgress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    current: Mutex<String>, // Entry being archived or extracted
+    cancelled: AtomicBool,  // Checked before each file and between chunks of large ones
+}
+
+/// What `ArchiveProgress` said at one moment
+#[derive(Debug, Clone, Default)]
+pub struct ProgressSnapshot {
+    pub done: usize,
+    pub total: usize,
+    pub current: String,
+}
+
+impl ArchiveProgress {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Start counting from zero for an archive of `total` files
+    pub fn begin(&self, total: usize) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+        self.set_current(String::new());
+    }
+
+    /// Announce the next file, or fail with `Cancelled` if the user stopped the operation
+    pub fn start_file(&self, name: &str) -> Result<()> {
+        self.check()?;
+        self.set_current(name.to_string());
+        Ok(())
+    }
+
+    pub fn finish_file(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `Cancelled` once `cancel` was called, for loops over the chunks of a large file
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(SaveGuardianError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        ProgressSnapshot {
+            done: self.done.load(Ordering::Relaxed),
+            total: self.total.load(Ordering::Relaxed),
+            current: self.current.lock().map(|current| current.clone()).unwrap_or_default(),
+        }
+    }
+
+    fn set_current(&self, name: String) {
+        if let Ok(mut current) = self.current.lock() {
+            *current = name;
+        }
+    }
+}
+
+/// A backup or restore running on a background thread, so its progress can be drawn
+pub struct ArchiveTask<T> {
+    pub title: String, // e.g. "Backing up Elden Ring"
+    progress: Arc<ArchiveProgress>,
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T: Send + 'static> ArchiveTask<T> {
+    pub fn spawn(title: impl Into<String>, work: impl FnOnce(Arc<ArchiveProgress>) -> T + Send + 'static) -> Self {
+        let progress = ArchiveProgress::new();
+        let (sender, receiver) = mpsc::channel();
+        let worker_progress = Arc::clone(&progress);
+        std::thread::spawn(move || {
+            let _ = sender.send(work(worker_progress));
+        });
+        Self { title: title.into(), progress, receiver }
+    }
+
+    pub fn progress(&self) -> &ArchiveProgress {
+        &self.progress
+    }
+
+    /// The result once the work is done, None while it still runs
+    pub fn try_finish(&self) -> Option<Result<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(Ok(result)),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(SaveGuardianError::BackupOperationFailed(
+                format!("{} stopped unexpectedly", self.title)
+            ))),
+        }
+    }
+}
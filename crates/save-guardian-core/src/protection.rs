@@ -0,0 +1,107 @@
+use crate::types::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use log::{info, warn};
+
+/// Make every file and folder of a save read-only on disk, so games and cloud clients
+/// can't overwrite it. Returns the number of entries changed.
+pub fn protect(save_path: &Path) -> Result<usize> {
+    set_read_only(save_path, true)
+}
+
+/// Make a protected save writable again
+pub fn unprotect(save_path: &Path) -> Result<usize> {
+    set_read_only(save_path, false)
+}
+
+/// Whether nothing in the folder can be written to, i.e. the protection is still in place
+pub fn is_protected(save_path: &Path) -> bool {
+    if !save_path.exists() {
+        return false;
+    }
+    WalkDir::new(save_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .all(|entry| entry.metadata().map_or(true, |m| m.permissions().readonly()))
+}
+
+fn set_read_only(save_path: &Path, read_only: bool) -> Result<usize> {
+    if !save_path.exists() {
+        return Err(SaveGuardianError::PathNotFound(save_path.to_path_buf()));
+    }
+
+    let mut changed = 0;
+    // Folders last when locking and first when unlocking, so we can always reach the files
+    let entries: Vec<PathBuf> = WalkDir::new(save_path)
+        .contents_first(read_only)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .collect();
+
+    for path in entries {
+        let metadata = fs::metadata(&path)
+            .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to read {:?}: {}", path, e)))?;
+        let mut permissions = metadata.permissions();
+        if permissions.readonly() == read_only {
+            continue;
+        }
+
+        set_permissions_read_only(&mut permissions, read_only);
+        fs::set_permissions(&path, permissions)
+            .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to change permissions of {:?}: {}", path, e)))?;
+        changed += 1;
+    }
+
+    info!("{} {:?} ({} entries)", if read_only { "Write-protected" } else { "Unprotected" }, save_path, changed);
+    Ok(changed)
+}
+
+#[cfg(unix)]
+fn set_permissions_read_only(permissions: &mut fs::Permissions, read_only: bool) {
+    use std::os::unix::fs::PermissionsExt;
+    // Only touch the owner's write bit; `set_readonly(false)` would make it world-writable
+    let mode = permissions.mode();
+    permissions.set_mode(if read_only { mode & !0o222 } else { mode | 0o200 });
+}
+
+#[cfg(not(unix))]
+fn set_permissions_read_only(permissions: &mut fs::Permissions, read_only: bool) {
+    permissions.set_readonly(read_only);
+}
+
+/// Protection lifted from some save folders for the duration of a restore or sync
+///
+/// Dropping it puts the protection back, whether the operation succeeded or not.
+pub struct Unprotected {
+    paths: Vec<PathBuf>,
+}
+
+impl Unprotected {
+    /// Lift the protection of those `targets` that are in the protected list
+    pub fn lift(protected: &[PathBuf], targets: &[&Path]) -> Result<Self> {
+        let mut lifted = Self { paths: Vec::new() };
+        for target in targets {
+            if let Some(path) = protected.iter().find(|p| target.starts_with(p)) {
+                if lifted.paths.contains(path) || !path.exists() {
+                    continue;
+                }
+                // On failure, the drop restores whatever was already lifted
+                unprotect(path)?;
+                lifted.paths.push(path.clone());
+            }
+        }
+        Ok(lifted)
+    }
+}
+
+impl Drop for Unprotected {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if let Err(e) = protect(path) {
+                warn!("Failed to write-protect {:?} again: {}", path, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::types::*;
+use crate::backup::BackupManager;
+use crate::health::{self, SourceCheck};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+
+/// One backup waiting in the restore queue
+#[derive(Debug, Clone)]
+pub struct RestoreItem {
+    pub backup: BackupInfo,
+    pub target: PathBuf,
+    pub status: RestoreItemStatus,
+    pub warning: Option<SourceCheck>, // The backup is empty or much smaller than the target, see `check_health`
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreItemStatus {
+    Pending,
+    NeedsTarget, // Original location is gone, the user has to pick one
+    Restored,
+    Failed(String),
+}
+
+/// An ordered batch of restores, e.g. bringing back every game after a reinstall
+#[derive(Debug, Clone, Default)]
+pub struct RestoreQueue {
+    pub items: Vec<RestoreItem>,
+    pub allow_unhealthy: bool, // Restore items with a warning too
+}
+
+/// Outcome of running a restore queue
+#[derive(Debug, Clone)]
+pub struct RestoreSummary {
+    pub restored: usize,
+    pub failed: Vec<(String, String)>, // Game name, error
+    pub cancelled: bool, // Stopped by the user; the item in progress and the rest stay pending
+}
+
+impl RestoreItem {
+    pub fn new(backup: BackupInfo, remaps: &[PathRemap]) -> Self {
+        let target = apply_remaps(&backup.original_path, remaps).unwrap_or_else(|| backup.original_path.clone());
+        let status = if Self::target_is_usable(&target) {
+            RestoreItemStatus::Pending
+        } else {
+            RestoreItemStatus::NeedsTarget
+        };
+
+        Self { backup, target, status, warning: None }
+    }
+
+    /// A target is usable if it or its parent folder still exists on this machine
+    fn target_is_usable(target: &PathBuf) -> bool {
+        if target.as_os_str().is_empty() {
+            return false;
+        }
+        target.exists() || target.parent().map_or(false, |p| p.exists())
+    }
+
+    /// Point this item at a different folder
+    pub fn set_target(&mut self, target: PathBuf) {
+        self.warning = None;
+        self.status = if Self::target_is_usable(&target) {
+            RestoreItemStatus::Pending
+        } else {
+            RestoreItemStatus::NeedsTarget
+        };
+        self.target = target;
+    }
+}
+
+impl RestoreQueue {
+    pub fn new(backups: Vec<BackupInfo>, remaps: &[PathRemap]) -> Self {
+        Self {
+            items: backups.into_iter().map(|b| RestoreItem::new(b, remaps)).collect(),
+            allow_unhealthy: false,
+        }
+    }
+
+    /// Warn about pending items whose backup would replace the target with much less
+    ///
+    /// `history` is every known backup; the target's own backups tell how big it used to be.
+    /// Items whose target changed since the last check are checked again.
+    pub fn check_health(&mut self, history: &[BackupInfo]) {
+        for item in &mut self.items {
+            if item.status == RestoreItemStatus::Pending && item.warning.is_none() {
+                item.warning = health::check_restore(&item.backup, &item.target, history);
+            }
+        }
+    }
+
+    /// Pending items with a warning, held back unless `allow_unhealthy` is set
+    pub fn warning_count(&self) -> usize {
+        self.items.iter().filter(|i| i.status == RestoreItemStatus::Pending && i.warning.is_some()).count()
+    }
+
+    /// Items whose target path still has to be chosen
+    pub fn unresolved_count(&self) -> usize {
+        self.items.iter().filter(|i| i.status == RestoreItemStatus::NeedsTarget).count()
+    }
+
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.items.len() {
+            self.items.swap(index, index - 1);
+        }
+    }
+
+    pub fn move_down(&mut self, index: usize) {
+        if index + 1 < self.items.len() {
+            self.items.swap(index, index + 1);
+        }
+    }
+
+    /// Put failed items back in the queue for another run
+    pub fn retry_failed(&mut self) {
+        for item in &mut self.items {
+            if matches!(item.status, RestoreItemStatus::Failed(_)) {
+                let target = item.target.clone();
+                item.set_target(target);
+            }
+        }
+    }
+
+    /// Restore every pending item in order, carrying on past failures
+    ///
+    /// Stops when the backup manager's progress is cancelled; the item being extracted
+    /// then keeps its target as it was.
+    pub fn run(&mut self, backup_manager: &BackupManager) -> RestoreSummary {
+        let mut summary = RestoreSummary { restored: 0, failed: Vec::new(), cancelled: false };
+
+        for item in &mut self.items {
+            if item.status != RestoreItemStatus::Pending {
+                continue;
+            }
+            if let Some(check) = item.warning.as_ref().filter(|_| !self.allow_unhealthy) {
+                warn!("Restore of {} held back: {}", item.backup.game_name, check);
+                let reason = format!("Not restored: {}", check);
+                item.status = RestoreItemStatus::Failed(reason.clone());
+                summary.failed.push((item.backup.game_name.clone(), reason));
+                continue;
+            }
+
+            info!("Restore queue: {} -> {:?}", item.backup.game_name, item.target);
+            match backup_manager.restore_backup(&item.backup, &item.target, true) {
+                Ok(()) => {
+                    item.status = RestoreItemStatus::Restored;
+                    summary.restored += 1;
+                }
+                Err(SaveGuardianError::Cancelled) => {
+                    info!("Restore queue cancelled at {}", item.backup.game_name);
+                    summary.cancelled = true;
+                    break;
+                }
+                Err(e) => {
+                    warn!("Restore of {} failed: {}", item.backup.game_name, e);
+                    item.status = RestoreItemStatus::Failed(e.to_string());
+                    summary.failed.push((item.backup.game_name.clone(), e.to_string()));
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+impl RestoreSummary {
+    pub fn describe(&self) -> String {
+        let mut text = if self.failed.is_empty() {
+            format!("Restored {} games", self.restored)
+        } else {
+            format!("Restored {} games, {} failed", self.restored, self.failed.len())
+        };
+        if self.cancelled {
+            text.push_str(", cancelled before the rest");
+        }
+        text
+    }
+}
+
+/// The newest backup of each game made at or before `at`, i.e. the state as of that moment
+///
+/// With `game_name` set only that game is considered. Results are sorted by game name.
+pub fn backups_as_of(backups: &[BackupInfo], at: DateTime<Utc>, game_name: Option<&str>) -> Vec<BackupInfo> {
+    let mut newest: HashMap<String, &BackupInfo> = HashMap::new();
+
+    for backup in backups {
+        if backup.created_at > at || game_name.map_or(false, |name| backup.game_name != name) {
+            continue;
+        }
+        let key = BackupManager::latest_key(backup);
+        match newest.get(&key) {
+            Some(current) if current.created_at >= backup.created_at => {}
+            _ => {
+                newest.insert(key, backup);
+            }
+        }
+    }
+
+    let mut resolved: Vec<BackupInfo> = newest.into_values().cloned().collect();
+    resolved.sort_by(|a, b| a.game_name.cmp(&b.game_name).then(a.created_at.cmp(&b.created_at)));
+    resolved
+}
+
+/// Rewrite a path with the longest matching remap rule, if any applies
+///
+/// Prefixes match whole path components, ignoring ASCII case and `/` vs `\`.
+pub fn apply_remaps(path: &Path, remaps: &[PathRemap]) -> Option<PathBuf> {
+    let path_str = path.to_string_lossy();
+    let normalized = normalize(&path_str);
+
+    remaps.iter()
+        .filter(|r| !r.from.trim().is_empty())
+        .filter_map(|r| {
+            let from = normalize(r.from.trim()).trim_end_matches('/').to_string();
+            let rest = normalized.strip_prefix(&from)?;
+            if !rest.is_empty() && !rest.starts_with('/') {
+                return None;
+            }
+            Some((from.len(), r))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(len, r)| {
+            // normalize() keeps byte lengths, so the remainder can be cut from the original
+            let to = r.to.trim().trim_end_matches(['/', '\\']);
+            PathBuf::from(format!("{}{}", to, &path_str[len..]))
+        })
+}
+
+/// Suggest remaps for backups whose original folders don't exist on this machine,
+/// based on the current user's home folder and Steam userdata location
+pub fn suggest_remaps(backups: &[BackupInfo], steam_path: &Path) -> Vec<PathRemap> {
+    let mut suggestions: Vec<PathRemap> = Vec::new();
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string());
+    let steam_is_userdata = steam_path.file_name()
+        .map_or(false, |n| n.to_string_lossy().eq_ignore_ascii_case("userdata"));
+
+    for backup in backups {
+        let original = backup.original_path.to_string_lossy().to_string();
+        if original.is_empty() || backup.original_path.exists() {
+            continue;
+        }
+
+        let parts: Vec<&str> = original.split(['/', '\\']).collect();
+        let lower: Vec<String> = parts.iter().map(|p| p.to_ascii_lowercase()).collect();
+        let sep = if original.contains('\\') { "\\" } else { "/" };
+
+        let userdata = lower.iter().position(|p| p == "userdata").filter(|_| steam_is_userdata);
+        let user_folder = lower.iter().position(|p| p == "users" || p == "home").filter(|i| i + 1 < parts.len());
+
+        let candidate = match (userdata, user_folder, &home) {
+            (Some(i), _, _) => Some((parts[..=i].join(sep), steam_path.to_string_lossy().to_string())),
+            (None, Some(i), Some(home)) => Some((parts[..=i + 1].join(sep), home.clone())),
+            _ => None,
+        };
+
+        if let Some((from, to)) = candidate {
+            if normalize(&from) != normalize(&to)
+                && !Path::new(&from).exists()
+                && !suggestions.iter().any(|r| normalize(&r.from) == normalize(&from)) {
+                info!("Suggesting path remap {} -> {}", from, to);
+                suggestions.push(PathRemap { from, to });
+            }
+        }
+    }
+
+    suggestions
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").to_ascii_lowercase()
+}
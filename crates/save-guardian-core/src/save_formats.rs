@@ -0,0 +1,468 @@
+use crate::archive;
+use crate::types::*;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+/// Larger files aren't read for metadata; GVAS saves of open world games stay well below
+pub(crate) const MAX_FILE_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Save files read per folder or backup, so a huge folder doesn't hold up a backup
+const MAX_FILES: usize = 200;
+
+/// Deepest NBT nesting followed in level.dat, well past anything Minecraft writes
+const MAX_NBT_DEPTH: usize = 32;
+
+const GVAS_MAGIC: &[u8] = b"GVAS";
+
+/// A save format metadata can be read from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SaveFormat {
+    UnrealGvas,     // .sav of Unreal Engine games
+    MinecraftLevel, // level.dat of a Java Edition world
+    RenPy,          // .save of Ren'Py visual novels
+}
+
+impl SaveFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SaveFormat::UnrealGvas => "Unreal Engine save",
+            SaveFormat::MinecraftLevel => "Minecraft world",
+            SaveFormat::RenPy => "Ren'Py save",
+        }
+    }
+}
+
+/// What a save file says about itself, for telling slots apart without loading the game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub file: String, // Path in the save folder, '/' separated like archive entries
+    pub format: SaveFormat,
+    #[serde(default)]
+    pub slot: Option<String>, // e.g. "Slot 3", "Auto save 2"
+    #[serde(default)]
+    pub name: Option<String>, // Save, character or world name
+    #[serde(default)]
+    pub level: Option<u32>,
+    #[serde(default)]
+    pub playtime_secs: Option<u64>,
+    #[serde(skip)]
+    pub screenshot: Option<Vec<u8>>, // PNG of a Ren'Py save, too large for backup metadata
+}
+
+impl SaveMetadata {
+    fn new(file: &str, format: SaveFormat) -> Self {
+        Self {
+            file: file.to_string(),
+            format,
+            slot: None,
+            name: None,
+            level: None,
+            playtime_secs: None,
+            screenshot: None,
+        }
+    }
+
+    /// Whether anything was found besides the format
+    fn has_details(&self) -> bool {
+        self.slot.is_some() || self.name.is_some() || self.level.is_some() || self.playtime_secs.is_some() || self.screenshot.is_some()
+    }
+
+    /// e.g. "Slot 3 – Level 42 – 18h played", the file name when the save tells nothing else
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self.slot.iter().chain(self.name.iter()).cloned().collect();
+        if let Some(level) = self.level {
+            parts.push(format!("Level {}", level));
+        }
+        if let Some(secs) = self.playtime_secs {
+            parts.push(format!("{} played", format_playtime(secs)));
+        }
+        if parts.is_empty() {
+            return self.file.rsplit('/').next().unwrap_or(&self.file).to_string();
+        }
+        parts.join(" – ")
+    }
+}
+
+/// "18h", or minutes below an hour
+fn format_playtime(secs: u64) -> String {
+    if secs < 3600 {
+        format!("{} min", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
+/// Whether a file might be in one of the known formats, judged by its name alone
+pub fn is_candidate(file: &str) -> bool {
+    let file_name = file.rsplit('/').next().unwrap_or(file).to_lowercase();
+    file_name == "level.dat" || file_name.ends_with(".sav") || file_name.ends_with(".save")
+}
+
+/// Metadata of one save file, None if its format isn't known or it holds nothing useful
+pub fn identify(file: &str, data: &[u8]) -> Option<SaveMetadata> {
+    let file_name = file.rsplit('/').next().unwrap_or(file).to_lowercase();
+    let metadata = if data.starts_with(GVAS_MAGIC) {
+        read_gvas(file, data)
+    } else if file_name == "level.dat" {
+        read_level_dat(file, data)
+    } else if file_name.ends_with(".save") && data.starts_with(b"PK") {
+        read_renpy(file, data)
+    } else {
+        None
+    }?;
+    Some(metadata).filter(SaveMetadata::has_details)
+}
+
+/// Metadata of the save files in a folder, or of a single save file
+pub fn scan_folder(path: &Path) -> Vec<SaveMetadata> {
+    let mut found = Vec::new();
+    let mut checked = 0;
+    for entry in WalkDir::new(path).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = match entry.path().strip_prefix(path) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative.to_string_lossy().replace('\\', "/"),
+            _ => entry.file_name().to_string_lossy().into_owned(),
+        };
+        if !is_candidate(&relative) || entry.metadata().map_or(true, |m| m.len() > MAX_FILE_SIZE) {
+            continue;
+        }
+        checked += 1;
+        if checked > MAX_FILES {
+            break;
+        }
+        if let Ok(data) = fs::read(entry.path()) {
+            found.extend(identify(&relative, &data));
+        }
+    }
+    found
+}
+
+/// Metadata of the save files in a backup's archive, for backups made before it was recorded
+///
+/// An incremental backup only holds the files changed since its base, and files in the
+/// dedup store aren't in the archive at all.
+pub fn scan_backup(backup_info: &BackupInfo) -> Result<Vec<SaveMetadata>> {
+    let mut found = Vec::new();
+    let mut checked = 0;
+    archive::read_backup(backup_info, |name, size, data| {
+        if name.ends_with('/') || !is_candidate(name) || size > MAX_FILE_SIZE || checked >= MAX_FILES {
+            return Ok(());
+        }
+        checked += 1;
+        let mut bytes = Vec::with_capacity(size as usize);
+        data.read_to_end(&mut bytes)?;
+        found.extend(identify(name, &bytes));
+        Ok(())
+    })?;
+    Ok(found)
+}
+
+/// Unreal Engine's GVAS: a header, then the save game object's properties
+///
+/// Only top-level numbers and strings are looked at, by name: "SaveName", "PlayerLevel",
+/// "PlayTime" and their usual variations. Each property gives its size, so the others
+/// (structs, arrays, maps) are skipped without being understood.
+fn read_gvas(file: &str, data: &[u8]) -> Option<SaveMetadata> {
+    let mut reader = ByteReader::new(data);
+    reader.skip(GVAS_MAGIC.len())?;
+    let save_game_version = reader.i32_le()?;
+    reader.skip(4)?; // UE4 package version
+    if save_game_version >= 3 {
+        reader.skip(4)?; // UE5 package version
+    }
+    reader.skip(2 + 2 + 2 + 4)?; // Engine major, minor, patch and changelist
+    reader.fstring()?; // Engine branch
+    reader.skip(4)?; // Custom version format
+    let custom_versions = usize::try_from(reader.i32_le()?).ok()?;
+    reader.skip(custom_versions.checked_mul(16 + 4)?)?; // GUID and version each
+    reader.fstring()?; // Save game class
+
+    let mut metadata = SaveMetadata::new(file, SaveFormat::UnrealGvas);
+    metadata.slot = slot_from_file_name(file);
+    // A property it can't follow ends the walk, with what was found up to there
+    let _ = read_gvas_properties(&mut reader, &mut metadata);
+    Some(metadata)
+}
+
+fn read_gvas_properties(reader: &mut ByteReader, metadata: &mut SaveMetadata) -> Option<()> {
+    loop {
+        let name = reader.fstring()?;
+        if name.is_empty() || name == "None" {
+            return Some(());
+        }
+        let kind = reader.fstring()?;
+        let size = usize::try_from(reader.i64_le()?).ok()?;
+        match kind.as_str() {
+            "BoolProperty" => {
+                reader.skip(1)?; // The value is in the header, size is 0
+                reader.skip_property_guid()?;
+            }
+            "StructProperty" => {
+                reader.fstring()?; // Struct type
+                reader.skip(16)?; // Struct GUID
+                reader.skip_property_guid()?;
+                reader.skip(size)?;
+            }
+            "ArrayProperty" | "SetProperty" | "ByteProperty" | "EnumProperty" => {
+                reader.fstring()?; // Inner or enum type
+                reader.skip_property_guid()?;
+                reader.skip(size)?;
+            }
+            "MapProperty" => {
+                reader.fstring()?; // Key type
+                reader.fstring()?; // Value type
+                reader.skip_property_guid()?;
+                reader.skip(size)?;
+            }
+            _ => {
+                reader.skip_property_guid()?;
+                let value = reader.take(size)?;
+                apply_gvas_property(metadata, &name.to_lowercase(), &kind, value);
+            }
+        }
+    }
+}
+
+/// Keep a property's value if its name looks like one of the details shown
+fn apply_gvas_property(metadata: &mut SaveMetadata, name: &str, kind: &str, value: &[u8]) {
+    let number = match (kind, value.len()) {
+        ("IntProperty", 4) => Some(i32::from_le_bytes(value.try_into().unwrap_or_default()) as f64),
+        ("UInt32Property", 4) => Some(u32::from_le_bytes(value.try_into().unwrap_or_default()) as f64),
+        ("Int64Property", 8) => Some(i64::from_le_bytes(value.try_into().unwrap_or_default()) as f64),
+        ("FloatProperty", 4) => Some(f32::from_le_bytes(value.try_into().unwrap_or_default()) as f64),
+        ("DoubleProperty", 8) => Some(f64::from_le_bytes(value.try_into().unwrap_or_default())),
+        _ => None,
+    }.filter(|n| n.is_finite() && *n >= 0.0);
+
+    if let Some(number) = number {
+        if metadata.playtime_secs.is_none() && ["playtime", "timeplayed", "playedtime"].iter().any(|key| name.contains(key)) {
+            metadata.playtime_secs = Some(number as u64);
+        } else if metadata.level.is_none() && name.contains("level") && !name.contains("name") && number <= u32::MAX as f64 {
+            metadata.level = Some(number as u32);
+        }
+    } else if matches!(kind, "StrProperty" | "NameProperty") && metadata.name.is_none()
+        && ["savename", "slotname", "savetitle", "slottitle", "charactername", "playername"].iter().any(|key| name.contains(key))
+    {
+        metadata.name = ByteReader::new(value).fstring().filter(|s| !s.trim().is_empty());
+    }
+}
+
+/// "Slot 3" from "SaveSlot_3.sav" or "Slot3.sav"
+fn slot_from_file_name(file: &str) -> Option<String> {
+    let file_name = file.rsplit('/').next().unwrap_or(file);
+    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 3 {
+        return None;
+    }
+    let number: u32 = stem[stem.len() - digits..].parse().ok()?;
+    Some(format!("Slot {}", number))
+}
+
+/// A Java Edition level.dat: gzipped NBT with the world's details under "Data"
+///
+/// The play time is the world's age in ticks, which only runs while someone is in it.
+fn read_level_dat(file: &str, data: &[u8]) -> Option<SaveMetadata> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).take(MAX_FILE_SIZE).read_to_end(&mut decoded).ok()?;
+    let mut reader = ByteReader::new(&decoded);
+    if reader.u8()? != NBT_COMPOUND {
+        return None;
+    }
+    reader.nbt_string()?; // Root name, empty
+
+    let mut metadata = SaveMetadata::new(file, SaveFormat::MinecraftLevel);
+    let _ = walk_nbt_compound(&mut reader, "", 0, &mut |path, value| match (path, value) {
+        ("/Data/LevelName", NbtValue::Text(name)) => metadata.name = Some(name).filter(|n| !n.trim().is_empty()),
+        ("/Data/Time", NbtValue::Number(ticks)) => metadata.playtime_secs = u64::try_from(ticks / 20).ok(),
+        ("/Data/Player/XpLevel", NbtValue::Number(level)) => metadata.level = u32::try_from(level).ok(),
+        _ => {}
+    });
+    Some(metadata)
+}
+
+const NBT_END: u8 = 0;
+const NBT_COMPOUND: u8 = 10;
+
+enum NbtValue {
+    Number(i64),
+    Text(String),
+}
+
+/// Call `found` with every int, long and string of a compound, by its path ("/Data/LevelName")
+fn walk_nbt_compound(reader: &mut ByteReader, path: &str, depth: usize, found: &mut dyn FnMut(&str, NbtValue)) -> Option<()> {
+    loop {
+        let tag = reader.u8()?;
+        if tag == NBT_END {
+            return Some(());
+        }
+        let name = reader.nbt_string()?;
+        walk_nbt_value(reader, tag, &format!("{}/{}", path, name), depth, found)?;
+    }
+}
+
+fn walk_nbt_value(reader: &mut ByteReader, tag: u8, path: &str, depth: usize, found: &mut dyn FnMut(&str, NbtValue)) -> Option<()> {
+    if depth > MAX_NBT_DEPTH {
+        return None;
+    }
+    match tag {
+        1 => reader.skip(1),
+        2 => reader.skip(2),
+        3 => {
+            found(path, NbtValue::Number(reader.i32_be()? as i64));
+            Some(())
+        }
+        4 => {
+            found(path, NbtValue::Number(reader.i64_be()?));
+            Some(())
+        }
+        5 => reader.skip(4),
+        6 => reader.skip(8),
+        7 => {
+            let len = reader.nbt_len()?;
+            reader.skip(len)
+        }
+        8 => {
+            found(path, NbtValue::Text(reader.nbt_string()?));
+            Some(())
+        }
+        9 => {
+            let inner = reader.u8()?;
+            let len = reader.nbt_len()?;
+            for _ in 0..len {
+                walk_nbt_value(reader, inner, path, depth + 1, found)?;
+            }
+            Some(())
+        }
+        NBT_COMPOUND => walk_nbt_compound(reader, path, depth + 1, found),
+        11 => {
+            let len = reader.nbt_len()?;
+            reader.skip(len.checked_mul(4)?)
+        }
+        12 => {
+            let len = reader.nbt_len()?;
+            reader.skip(len.checked_mul(8)?)
+        }
+        _ => None,
+    }
+}
+
+/// A Ren'Py save: a ZIP with the name typed when saving, a screenshot and the pickled game
+fn read_renpy(file: &str, data: &[u8]) -> Option<SaveMetadata> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).ok()?;
+    let mut metadata = SaveMetadata::new(file, SaveFormat::RenPy);
+    metadata.slot = renpy_slot(file);
+    if let Ok(mut entry) = archive.by_name("extra_info") {
+        let mut text = String::new();
+        if entry.read_to_string(&mut text).is_ok() && !text.trim().is_empty() {
+            metadata.name = Some(text.trim().to_string());
+        }
+    }
+    if let Ok(mut entry) = archive.by_name("screenshot.png") {
+        let mut png = Vec::new();
+        if entry.read_to_end(&mut png).is_ok() && !png.is_empty() {
+            metadata.screenshot = Some(png);
+        }
+    }
+    Some(metadata)
+}
+
+/// "Slot 1-3" from "1-3-LT1.save", "Auto save 2" from "auto-2-LT1.save"
+fn renpy_slot(file: &str) -> Option<String> {
+    let file_name = file.rsplit('/').next().unwrap_or(file);
+    let stem = file_name.strip_suffix(".save")?;
+    let slot = stem.rsplit_once("-LT").map_or(stem, |(slot, _)| slot);
+    if let Some(number) = slot.strip_prefix("auto-") {
+        Some(format!("Auto save {}", number))
+    } else if let Some(number) = slot.strip_prefix("quick-") {
+        Some(format!("Quick save {}", number))
+    } else if !slot.is_empty() {
+        Some(format!("Slot {}", slot))
+    } else {
+        None
+    }
+}
+
+/// Bounds-checked reads over a save file's bytes, None once past the end
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.data.len())?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn i32_le(&mut self) -> Option<i32> {
+        self.take(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i64_le(&mut self) -> Option<i64> {
+        self.take(8).and_then(|b| b.try_into().ok()).map(i64::from_le_bytes)
+    }
+
+    fn i32_be(&mut self) -> Option<i32> {
+        self.take(4).map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i64_be(&mut self) -> Option<i64> {
+        self.take(8).and_then(|b| b.try_into().ok()).map(i64::from_be_bytes)
+    }
+
+    /// Unreal's FString: a length with the terminating zero, negative for UTF-16
+    fn fstring(&mut self) -> Option<String> {
+        let len = self.i32_le()?;
+        let text = if len >= 0 {
+            String::from_utf8_lossy(self.take(len as usize)?).into_owned()
+        } else {
+            let units: Vec<u16> = self.take(len.unsigned_abs() as usize * 2)?
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        };
+        Some(text.trim_end_matches('\0').to_string())
+    }
+
+    /// If the property has a GUID, skip it
+    fn skip_property_guid(&mut self) -> Option<()> {
+        if self.u8()? != 0 {
+            self.skip(16)?;
+        }
+        Some(())
+    }
+
+    /// NBT string: big-endian length, then (modified) UTF-8
+    fn nbt_string(&mut self) -> Option<String> {
+        let len = self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))?;
+        Some(String::from_utf8_lossy(self.take(len as usize)?).into_owned())
+    }
+
+    /// Length of an NBT list or array, never negative
+    fn nbt_len(&mut self) -> Option<usize> {
+        usize::try_from(self.i32_be()?).ok()
+    }
+}
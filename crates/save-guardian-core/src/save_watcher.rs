@@ -0,0 +1,106 @@
+//! Backups after every play session without clicking
+//!
+//! `SaveWatcher` asks the OS to report writes in the save folders (`notify`: inotify,
+//! FSEvents or ReadDirectoryChangesW). A save becomes due once it has been quiet for the
+//! configured minutes after its last write, so a game saving every few seconds still
+//! gets one backup per session. The app backs up what is due; network folders are left
+//! out, their change events are unreliable.
+
+use crate::types::*;
+use crate::network;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::event::ModifyKind;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use log::{debug, info, warn};
+
+/// Watches save folders for writes while the app runs; dropping it stops watching
+pub struct SaveWatcher {
+    _watcher: RecommendedWatcher,
+    last_writes: Arc<Mutex<HashMap<PathBuf, Instant>>>, // Save folder -> last write seen, until it is due
+    save_paths: Vec<PathBuf>, // As asked for, including the folders left out
+    config: SaveWatcherConfig,
+}
+
+impl SaveWatcher {
+    /// Start watching `save_paths`; folders that can't be watched are logged and left out
+    pub fn start(save_paths: Vec<PathBuf>, config: &SaveWatcherConfig) -> Result<Self> {
+        let last_writes: Arc<Mutex<HashMap<PathBuf, Instant>>> = Arc::new(Mutex::new(HashMap::new()));
+        let mut roots: Vec<PathBuf> = save_paths.iter()
+            .filter(|path| path.is_dir() && !network::is_network_path(path))
+            .cloned()
+            .collect();
+        // Longest first, so a write is counted for the innermost save containing it
+        roots.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+
+        let handler_writes = Arc::clone(&last_writes);
+        let handler_roots = roots.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Save folder watch error: {}", e);
+                    return;
+                }
+            };
+            // Reading a save, e.g. our own backups, or write-protecting it is no change
+            if matches!(event.kind, EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_)) | EventKind::Any | EventKind::Other) {
+                return;
+            }
+            if let Ok(mut last_writes) = handler_writes.lock() {
+                for path in &event.paths {
+                    if let Some(root) = save_of(&handler_roots, path) {
+                        last_writes.insert(root.to_path_buf(), Instant::now());
+                    }
+                }
+            }
+        }).map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Could not watch save folders: {}", e)))?;
+
+        let mut watched = 0;
+        for root in &roots {
+            match watcher.watch(root, RecursiveMode::Recursive) {
+                Ok(()) => watched += 1,
+                Err(e) => warn!("Not watching {:?} for changes: {}", root, e),
+            }
+        }
+
+        info!("Watching {} save folders, backing up {} minutes after the last write", watched, config.quiet_minutes);
+        Ok(Self { _watcher: watcher, last_writes, save_paths, config: config.clone() })
+    }
+
+    /// Whether this watcher already watches `save_paths` as configured, so it needn't be restarted
+    pub fn watches(&self, save_paths: &[PathBuf], config: &SaveWatcherConfig) -> bool {
+        self.save_paths == save_paths && &self.config == config
+    }
+
+    /// Save folders written to and quiet since for the configured minutes; each is returned
+    /// once per burst of writes
+    pub fn due(&self) -> Vec<PathBuf> {
+        let quiet = Duration::from_secs(u64::from(self.config.quiet_minutes) * 60);
+        let mut last_writes = match self.last_writes.lock() {
+            Ok(last_writes) => last_writes,
+            Err(_) => return Vec::new(),
+        };
+        let due: Vec<PathBuf> = last_writes.iter()
+            .filter(|(_, written)| written.elapsed() >= quiet)
+            .map(|(folder, _)| folder.clone())
+            .collect();
+        for folder in &due {
+            last_writes.remove(folder);
+            debug!("{:?} quiet for {} minutes, due for a backup", folder, self.config.quiet_minutes);
+        }
+        due
+    }
+
+    /// Save folders written to that are still waiting for quiet
+    pub fn pending(&self) -> usize {
+        self.last_writes.lock().map_or(0, |last_writes| last_writes.len())
+    }
+}
+
+/// The watched save folder `path` lies in, innermost first
+fn save_of<'a>(roots: &'a [PathBuf], path: &Path) -> Option<&'a Path> {
+    roots.iter().find(|root| path.starts_with(root)).map(PathBuf::as_path)
+}
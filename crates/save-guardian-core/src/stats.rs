@@ -0,0 +1,224 @@
+use crate::types::*;
+use crate::backup::BackupManager;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use log::warn;
+
+const HISTORY_FILE: &str = "store_history.json";
+const FORECAST_WINDOW_DAYS: i64 = 30;
+
+/// Size of the backup store at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreSample {
+    pub at: DateTime<Utc>,
+    pub total_size: u64,
+    pub backup_count: usize,
+    pub free_space: Option<u64>,
+}
+
+/// Growth history of a backup folder, stored next to the backups
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreHistory {
+    pub samples: Vec<StoreSample>,
+}
+
+/// How long backups of one game (or of all games) take, from backups that recorded it
+#[derive(Debug, Clone)]
+pub struct BackupSpeed {
+    pub game_name: String,
+    pub timed_backups: usize,
+    pub average_duration: Duration,
+    pub bytes_per_second: f64, // Save data read (uncompressed) per second
+}
+
+/// Where the backup store is heading at its current growth rate
+#[derive(Debug, Clone)]
+pub struct StoreForecast {
+    pub daily_growth: f64, // Bytes per day
+    pub free_space: Option<u64>,
+    pub days_until_full: Option<f64>,
+    pub suggested_retention_days: Option<u32>,
+}
+
+impl StoreHistory {
+    pub fn load(backup_root: &Path) -> Self {
+        let path = backup_root.join(HISTORY_FILE);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable store history {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, backup_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(SaveGuardianError::Serde)?;
+        fs::write(backup_root.join(HISTORY_FILE), json)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to save store history: {}", e)))
+    }
+
+    /// Record the current store size: hourly at most while it changes, daily while it doesn't
+    ///
+    /// Returns true if a sample was added.
+    pub fn record(&mut self, backup_root: &Path, backups: &[BackupInfo]) -> bool {
+        let total_size: u64 = backups.iter().map(|b| b.size).sum();
+        let now = Utc::now();
+
+        if let Some(last) = self.samples.last() {
+            let unchanged = last.total_size == total_size && last.backup_count == backups.len();
+            let age = now - last.at;
+            if age < chrono::Duration::hours(1) || (unchanged && age < chrono::Duration::days(1)) {
+                return false;
+            }
+        }
+
+        self.samples.push(StoreSample {
+            at: now,
+            total_size,
+            backup_count: backups.len(),
+            free_space: available_space(backup_root),
+        });
+        true
+    }
+
+    /// Write the history as CSV for spreadsheets
+    pub fn export_csv(&self, path: &Path) -> Result<()> {
+        let mut csv = String::from("timestamp,total_size_bytes,backup_count,free_space_bytes\n");
+        for sample in &self.samples {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                sample.at.to_rfc3339(),
+                sample.total_size,
+                sample.backup_count,
+                sample.free_space.map(|f| f.to_string()).unwrap_or_default()
+            ));
+        }
+
+        fs::write(path, csv)
+            .map_err(|e| SaveGuardianError::BackupOperationFailed(format!("Failed to export statistics: {}", e)))
+    }
+}
+
+/// Free space on the drive holding `path`
+pub fn available_space(path: &Path) -> Option<u64> {
+    fs2::available_space(path).ok()
+}
+
+/// Project store growth and suggest a retention period that fits the drive
+///
+/// Growth comes from the recorded history when it spans at least a day, otherwise
+/// from the size of backups created in the last 30 days.
+pub fn forecast(history: &StoreHistory, backups: &[BackupInfo], retention_days: u32, free_space: Option<u64>) -> StoreForecast {
+    let window_start = Utc::now() - chrono::Duration::days(FORECAST_WINDOW_DAYS);
+
+    let recent: Vec<&StoreSample> = history.samples.iter().filter(|s| s.at >= window_start).collect();
+    let history_growth = match (recent.first(), recent.last()) {
+        (Some(first), Some(last)) if last.at - first.at >= chrono::Duration::days(1) => {
+            let days = (last.at - first.at).num_seconds() as f64 / 86_400.0;
+            Some((last.total_size as f64 - first.total_size as f64) / days)
+        }
+        _ => None,
+    };
+
+    // Bytes of new backups per day, which is what retention has to absorb
+    let added_per_day = backups.iter()
+        .filter(|b| b.created_at >= window_start)
+        .map(|b| b.size)
+        .sum::<u64>() as f64 / FORECAST_WINDOW_DAYS as f64;
+
+    let daily_growth = history_growth.unwrap_or(added_per_day);
+
+    let days_until_full = match free_space {
+        Some(free) if daily_growth > 0.0 => Some(free as f64 / daily_growth),
+        _ => None,
+    };
+
+    // Steady state is roughly added_per_day * retention; aim to use at most 80% of the space
+    let suggested_retention_days = match free_space {
+        Some(free) if added_per_day > 0.0 => {
+            let capacity = (free + backups.iter().map(|b| b.size).sum::<u64>()) as f64 * 0.8;
+            let fits = (capacity / added_per_day).floor() as u32;
+            if fits < retention_days {
+                Some(fits.max(1))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
+    StoreForecast {
+        daily_growth,
+        free_space,
+        days_until_full,
+        suggested_retention_days,
+    }
+}
+
+/// Average backup time and throughput per game, keyed by `BackupManager::latest_key`
+pub fn backup_speeds(backups: &[BackupInfo]) -> HashMap<String, BackupSpeed> {
+    let mut timed: HashMap<String, Vec<&BackupInfo>> = HashMap::new();
+    for backup in backups.iter().filter(|b| b.duration_ms.is_some()) {
+        timed.entry(BackupManager::latest_key(backup)).or_default().push(backup);
+    }
+    timed.into_iter()
+        .filter_map(|(key, backups)| Some((key, speed_of(&backups)?)))
+        .collect()
+}
+
+/// Average backup time and throughput over all games
+pub fn overall_speed(backups: &[BackupInfo]) -> Option<BackupSpeed> {
+    let timed: Vec<&BackupInfo> = backups.iter().filter(|b| b.duration_ms.is_some()).collect();
+    speed_of(&timed).map(|speed| BackupSpeed { game_name: "All games".to_string(), ..speed })
+}
+
+fn speed_of(backups: &[&BackupInfo]) -> Option<BackupSpeed> {
+    let first = backups.first()?;
+    let total_ms: u64 = backups.iter().filter_map(|b| b.duration_ms).sum();
+    let total_bytes: u64 = backups.iter().map(|b| b.source_size).sum();
+    Some(BackupSpeed {
+        game_name: first.game_name.clone(),
+        timed_backups: backups.len(),
+        average_duration: Duration::from_millis(total_ms / backups.len() as u64),
+        // Millisecond backups of tiny saves would claim absurd speeds otherwise
+        bytes_per_second: total_bytes as f64 / (total_ms.max(1) as f64 / 1000.0),
+    })
+}
+
+/// Guesses how long backups will take from the ones timed so far
+pub struct DurationEstimator {
+    overall: BackupSpeed,
+    speeds: HashMap<String, BackupSpeed>,
+}
+
+impl DurationEstimator {
+    /// None before any backup was timed
+    pub fn new(backups: &[BackupInfo]) -> Option<Self> {
+        Some(Self { overall: overall_speed(backups)?, speeds: backup_speeds(backups) })
+    }
+
+    /// Expected time to back up these saves at their current size
+    ///
+    /// Each game's own speed is used where it has one, the speed over all games otherwise.
+    /// Small archives are dominated by fixed costs, so no save counts for less than its
+    /// average backup time (up to a second).
+    pub fn estimate<'a>(&self, saves: impl IntoIterator<Item = &'a GameSave>) -> Duration {
+        saves.into_iter()
+            .map(|save| {
+                let speed = self.speeds.get(&BackupManager::key_for_save(save)).unwrap_or(&self.overall);
+                let by_size = if speed.bytes_per_second > 0.0 {
+                    Duration::from_secs_f64(save.size as f64 / speed.bytes_per_second)
+                } else {
+                    speed.average_duration
+                };
+                by_size.max(speed.average_duration.min(Duration::from_secs(1)))
+            })
+            .sum()
+    }
+}
@@ -1,32 +1,37 @@
 use crate::types::*;
-use std::collections::HashMap;
+use crate::names::NameResolver;
+use crate::steam_cloud::{self, CloudQuota};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use log::{debug, info, warn};
 
 pub struct SteamScanner {
     steam_userdata_path: PathBuf,
-    app_cache: HashMap<u32, String>, // App ID -> Game Name
-    cache_file_path: PathBuf,
+    names: NameResolver,
+}
+
+/// One `userdata/<user>/<app id>` folder, whether or not it holds saves
+#[derive(Debug, Clone)]
+pub struct UserdataFolder {
+    pub user_id: String,
+    pub app_id: u32,
+    pub path: PathBuf,
 }
 
 impl SteamScanner {
     pub fn new(steam_path: PathBuf) -> Self {
-        let cache_file_path = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("SaveGuardian")
-            .join("steam_game_cache.json");
-            
-        let mut scanner = Self {
+        Self {
             steam_userdata_path: steam_path,
-            app_cache: HashMap::new(),
-            cache_file_path,
-        };
-        
-        // Load existing cache from file
-        scanner.load_cache();
-        scanner
+            names: NameResolver::new(),
+        }
+    }
+
+    /// Share a name resolver, so its cache and background lookups outlive this scanner
+    pub fn with_name_resolver(mut self, names: NameResolver) -> Self {
+        self.names = names;
+        self
     }
 
     /// Scan for all Steam users and their saves
@@ -37,6 +42,8 @@ impl SteamScanner {
             return Err(SaveGuardianError::PathNotFound(self.steam_userdata_path.clone()));
         }
 
+        self.load_manifest_names();
+
         let mut users = Vec::new();
         
         // Read all directories in userdata (each is a Steam user)
@@ -69,6 +76,32 @@ impl SteamScanner {
         Ok(users)
     }
 
+    /// Every app folder of every account in userdata, for the anomaly report
+    pub fn userdata_app_folders(&self) -> Vec<UserdataFolder> {
+        let mut folders = Vec::new();
+        let users = match fs::read_dir(&self.steam_userdata_path) {
+            Ok(users) => users,
+            Err(_) => return folders,
+        };
+
+        for user in users.filter_map(|e| e.ok()) {
+            let user_id = user.file_name().to_string_lossy().to_string();
+            if !user_id.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            let apps = match fs::read_dir(user.path()) {
+                Ok(apps) => apps,
+                Err(_) => continue,
+            };
+            for app in apps.filter_map(|e| e.ok()).filter(|e| e.path().is_dir()) {
+                if let Ok(app_id) = app.file_name().to_string_lossy().parse::<u32>() {
+                    folders.push(UserdataFolder { user_id: user_id.clone(), app_id, path: app.path() });
+                }
+            }
+        }
+        folders
+    }
+
     /// Scan saves for a specific Steam user
     fn scan_user_saves(&mut self, user_id: &str, user_path: &PathBuf) -> Result<SteamUser> {
         let mut games = Vec::new();
@@ -85,7 +118,7 @@ impl SteamScanner {
                 if let Some(app_id_str) = app_path.file_name().and_then(|n| n.to_str()) {
                     // Skip non-numeric directories
                     if let Ok(app_id) = app_id_str.parse::<u32>() {
-                        if let Ok(mut app_games) = self.scan_app_saves(app_id, &app_path) {
+                        if let Ok(mut app_games) = self.scan_app_saves(user_id, app_id, &app_path) {
                             games.append(&mut app_games);
                         }
                     }
@@ -95,14 +128,33 @@ impl SteamScanner {
 
         Ok(SteamUser {
             id: user_id.to_string(),
-            name: None, // We could potentially get this from Steam config files
+            name: Self::read_persona_name(user_path),
             path: user_path.clone(),
             games,
         })
     }
 
+    /// Read the account's display name from its localconfig.vdf
+    fn read_persona_name(user_path: &PathBuf) -> Option<String> {
+        let config_path = user_path.join("config").join("localconfig.vdf");
+        let contents = fs::read_to_string(config_path).ok()?;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with("\"PersonaName\"") {
+                // Format: "PersonaName"		"Some Name"
+                let value = line["\"PersonaName\"".len()..].trim().trim_matches('"');
+                if !value.is_empty() {
+                    return Some(value.to_string());
+                }
+            }
+        }
+
+        None
+    }
+
     /// Scan saves for a specific Steam app
-    fn scan_app_saves(&mut self, app_id: u32, app_path: &PathBuf) -> Result<Vec<GameSave>> {
+    fn scan_app_saves(&mut self, user_id: &str, app_id: u32, app_path: &PathBuf) -> Result<Vec<GameSave>> {
         let mut saves = Vec::new();
         
         // Get proper game name from API/cache
@@ -115,12 +167,13 @@ impl SteamScanner {
         if remote_path.exists() && remote_path.is_dir() {
             // Use more lenient detection for the main save location
             if self.has_save_files_lenient(&remote_path)? {
-                let save = GameSave::new(
+                let mut save = GameSave::new(
                     game_name.clone(),
                     remote_path,
                     SaveType::Steam,
                     Some(app_id),
                 );
+                save.steam_user_id = Some(user_id.to_string());
                 
                 debug!("Found Steam save for app {}: {} at {:?}", app_id, save.name, save.save_path);
                 saves.push(save);
@@ -228,267 +281,121 @@ impl SteamScanner {
         Ok(has_files && file_count > 0)
     }
 
-    /// Get or generate a game name for the given app ID
-    pub fn get_game_name(&mut self, app_id: u32) -> String {
-        // Check if we have a cached name
-        if let Some(cached_name) = self.app_cache.get(&app_id) {
-            // Only use the cached name if it's not a generic fallback
-            // Generic names usually start with "Unknown Game" or are clearly wrong
-            if !cached_name.starts_with("Unknown Game") && 
-               !cached_name.contains("(ac)") &&
-               !self.is_likely_incorrect_name(cached_name, app_id) {
-                return cached_name.clone();
-            }
-            // If the cached name looks wrong, we'll fetch a new one below
-        }
-
-        // Try to get the game name from Steam API or other sources
-        let name = self.fetch_game_name_from_steam(app_id)
-            .unwrap_or_else(|| format!("Unknown Game {}", app_id));
-        
-        // Cache the result and save to file
-        self.app_cache.insert(app_id, name.clone());
-        self.save_cache();
-        
-        name
-    }
-    
-    /// Check if a cached name is likely incorrect and should be refetched
-    fn is_likely_incorrect_name(&self, name: &str, app_id: u32) -> bool {
-        // Check for generic patterns that indicate incorrect names
-        name.starts_with("Unknown Game") ||
-        name.contains("(ac)") ||
-        name.contains("(workshop)") ||
-        name.contains("(screenshots)") ||
-        // Check if the name is just a number (app ID) which means it failed to get a real name
-        name.parse::<u32>().is_ok() ||
-        // Some other common incorrect patterns
-        name.is_empty() ||
-        name == "null" ||
-        name.len() < 3 // Very short names are usually incorrect
-    }
-    
-    /// Refresh incorrect names in the cache by re-fetching from API
-    pub fn refresh_incorrect_names(&mut self) {
-        let incorrect_entries: Vec<(u32, String)> = self.app_cache.iter()
-            .filter(|(app_id, name)| self.is_likely_incorrect_name(name, **app_id))
-            .map(|(app_id, name)| (*app_id, name.clone()))
-            .collect();
-        
-        if !incorrect_entries.is_empty() {
-            info!("Found {} incorrect cached names, refreshing...", incorrect_entries.len());
-            
-            for (app_id, old_name) in incorrect_entries {
-                debug!("Refreshing incorrect name for {}: '{}'", app_id, old_name);
-                if let Ok(new_name) = self.fetch_game_name_from_api(app_id) {
-                    info!("Updated incorrect name for {}: '{}' -> '{}'", app_id, old_name, new_name);
-                    self.app_cache.insert(app_id, new_name);
-                } else {
-                    // If API fails, at least remove the clearly wrong name
-                    self.app_cache.remove(&app_id);
-                }
-                
-                // Small delay to be respectful to APIs
-                std::thread::sleep(std::time::Duration::from_millis(100));
-            }
-            
-            self.save_cache();
-        }
+    /// App IDs with an appmanifest in any Steam library, i.e. the installed games
+    ///
+    /// Returns None if no library could be read, so callers can tell "nothing installed"
+    /// from "Steam not found".
+    pub fn installed_app_ids(&self) -> Option<HashSet<u32>> {
+        let installed: HashSet<u32> = self.app_manifests()?.into_iter().map(|(app_id, _)| app_id).collect();
+        debug!("{} Steam apps installed", installed.len());
+        Some(installed)
     }
 
-    /// Attempt to fetch game name from Steam installation or online sources
-    fn fetch_game_name_from_steam(&self, app_id: u32) -> Option<String> {
-        // Try online APIs first (more reliable and up-to-date)
-        debug!("Attempting to fetch game name for app ID {} from online sources", app_id);
-        if let Ok(name) = self.fetch_game_name_from_api(app_id) {
-            return Some(name);
-        }
-        
-        // Try to read from Steam's registry (Windows)
-        #[cfg(windows)]
-        {
-            if let Ok(game_name) = self.get_game_name_from_registry(app_id) {
-                return Some(game_name);
-            }
-        }
-        
-        // Try to read from Steam's config files
-        if let Ok(name) = self.get_game_name_from_config(app_id) {
-            return Some(name);
-        }
-        
-        None
-    }
-    
-    /// Fetch game name from Steam API or SteamSpy API
-    fn fetch_game_name_from_api(&self, app_id: u32) -> std::result::Result<String, Box<dyn std::error::Error>> {
-        // Try Steam Store API first (free, no API key needed)
-        if let Ok(name) = self.fetch_from_steam_store_api(app_id) {
-            return Ok(name);
-        }
-        
-        // Try SteamSpy API as fallback (also free)
-        if let Ok(name) = self.fetch_from_steamspy_api(app_id) {
-            return Ok(name);
-        }
-        
-        Err("No API sources available".into())
-    }
-    
-    /// Fetch game name from Steam Store API
-    fn fetch_from_steam_store_api(&self, app_id: u32) -> std::result::Result<String, Box<dyn std::error::Error>> {
-        let url = format!("https://store.steampowered.com/api/appdetails?appids={}&filters=basic", app_id);
-        
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
-            .build()?;
-        
-        let response = client.get(&url)
-            .header("User-Agent", "SaveGuardian/1.0")
-            .send()?;
-        
-        if response.status().is_success() {
-            let json: serde_json::Value = response.json()?;
-            
-            if let Some(app_data) = json.get(&app_id.to_string()) {
-                if let Some(data) = app_data.get("data") {
-                    if let Some(name) = data.get("name").and_then(|n| n.as_str()) {
-                        info!("Fetched game name from Steam API: {} -> {}", app_id, name);
-                        return Ok(name.to_string());
-                    }
-                }
-            }
+    /// Steam Cloud quotas by app ID, from Steam's app info cache
+    pub fn cloud_quotas(&self) -> HashMap<u32, CloudQuota> {
+        match self.steam_userdata_path.parent() {
+            Some(steam_root) => steam_cloud::read_quotas(steam_root),
+            None => HashMap::new(),
         }
-        
-        Err("Failed to get game name from Steam Store API".into())
     }
-    
-    /// Fetch game name from SteamSpy API as fallback
-    fn fetch_from_steamspy_api(&self, app_id: u32) -> std::result::Result<String, Box<dyn std::error::Error>> {
-        let url = format!("https://steamspy.com/api.php?request=appdetails&appid={}", app_id);
-        
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
-            .build()?;
-        
-        let response = client.get(&url)
-            .header("User-Agent", "SaveGuardian/1.0")
-            .send()?;
-        
-        if response.status().is_success() {
-            let json: serde_json::Value = response.json()?;
-            
-            if let Some(name) = json.get("name").and_then(|n| n.as_str()) {
-                if !name.is_empty() && name != "null" {
-                    info!("Fetched game name from SteamSpy API: {} -> {}", app_id, name);
-                    return Ok(name.to_string());
+
+    /// Every appmanifest_<id>.acf in the Steam libraries, or None if no library could be read
+    fn app_manifests(&self) -> Option<Vec<(u32, PathBuf)>> {
+        // userdata sits directly in the Steam folder
+        let steam_root = self.steam_userdata_path.parent()?;
+        let mut libraries = vec![steam_root.to_path_buf()];
+        libraries.extend(Self::read_library_folders(steam_root));
+
+        let mut manifests = Vec::new();
+        let mut found_library = false;
+        for library in libraries {
+            let entries = match fs::read_dir(library.join("steamapps")) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            found_library = true;
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let app_id = file_name.strip_prefix("appmanifest_")
+                    .and_then(|rest| rest.strip_suffix(".acf"))
+                    .and_then(|id| id.parse::<u32>().ok());
+                if let Some(app_id) = app_id {
+                    manifests.push((app_id, entry.path()));
                 }
             }
         }
-        
-        Err("Failed to get game name from SteamSpy API".into())
+
+        if found_library { Some(manifests) } else { None }
     }
-    
-    /// Load game name cache from file
-    fn load_cache(&mut self) {
-        if let Ok(cache_content) = fs::read_to_string(&self.cache_file_path) {
-            if let Ok(cache) = serde_json::from_str::<HashMap<u32, String>>(&cache_content) {
-                self.app_cache = cache;
-                info!("Loaded {} game names from cache", self.app_cache.len());
-            } else {
-                warn!("Failed to parse game name cache file");
-            }
-        }
+
+    /// Names of installed games from their appmanifests, so names resolve without going online
+    fn load_manifest_names(&self) {
+        let manifests = match self.app_manifests() {
+            Some(manifests) => manifests,
+            None => return,
+        };
+
+        let names: Vec<(u32, String)> = manifests.into_iter()
+            .filter_map(|(app_id, path)| {
+                let contents = fs::read_to_string(path).ok()?;
+                // Format: "name"		"Some Game"
+                let name = contents.lines()
+                    .find_map(|line| line.trim().strip_prefix("\"name\""))?
+                    .trim()
+                    .trim_matches('"');
+                Some((app_id, name.to_string()))
+            })
+            .collect();
+        self.names.fill_missing(names);
     }
-    
-    /// Save game name cache to file
-    fn save_cache(&self) {
-        // Ensure the directory exists
-        if let Some(parent) = self.cache_file_path.parent() {
-            let _ = fs::create_dir_all(parent);
-        }
-        
-        if let Ok(cache_json) = serde_json::to_string_pretty(&self.app_cache) {
-            if let Err(e) = fs::write(&self.cache_file_path, cache_json) {
-                warn!("Failed to save game name cache: {}", e);
-            } else {
-                debug!("Saved {} game names to cache", self.app_cache.len());
-            }
-        }
+
+    /// Library folders on other drives, from steamapps/libraryfolders.vdf
+    fn read_library_folders(steam_root: &Path) -> Vec<PathBuf> {
+        let contents = match fs::read_to_string(steam_root.join("steamapps").join("libraryfolders.vdf")) {
+            Ok(contents) => contents,
+            Err(_) => return Vec::new(),
+        };
+
+        contents.lines()
+            .filter_map(|line| {
+                // Format: "path"		"D:\\SteamLibrary"
+                let line = line.trim();
+                let value = line.strip_prefix("\"path\"")?.trim().trim_matches('"');
+                Some(PathBuf::from(value.replace("\\\\", "\\")))
+            })
+            .filter(|path| path != steam_root)
+            .collect()
     }
-    
-    /// Refresh all cached game names by fetching them from online APIs
-    pub fn refresh_game_names(&mut self) {
-        info!("Refreshing {} cached game names...", self.app_cache.len());
-        let app_ids: Vec<u32> = self.app_cache.keys().cloned().collect();
-        
-        let mut updated_count = 0;
-        for app_id in app_ids {
-            if let Ok(new_name) = self.fetch_game_name_from_api(app_id) {
-                let old_name = self.app_cache.get(&app_id).cloned().unwrap_or_default();
-                if old_name != new_name {
-                    info!("Updated game name for {}: '{}' -> '{}'", app_id, old_name, new_name);
-                    self.app_cache.insert(app_id, new_name);
-                    updated_count += 1;
-                }
-            }
-            
-            // Small delay to be respectful to APIs
-            std::thread::sleep(std::time::Duration::from_millis(100));
-        }
-        
-        if updated_count > 0 {
-            self.save_cache();
-            info!("Updated {} game names in cache", updated_count);
-        } else {
-            info!("No game names needed updating");
-        }
+
+    /// The game name for an app ID from the cache; unknown names are fetched in the background
+    pub fn get_game_name(&self, app_id: u32) -> String {
+        self.names.name(app_id)
     }
-    
-    /// Get cache statistics
-    pub fn get_cache_stats(&self) -> (usize, String) {
-        let cache_size = self.app_cache.len();
-        let cache_file_exists = self.cache_file_path.exists();
-        let cache_path = self.cache_file_path.to_string_lossy().to_string();
-        
-        (cache_size, format!("Cache file: {} (exists: {})", cache_path, cache_file_exists))
+
+    /// Re-fetch cached names that look wrong, in the background
+    pub fn refresh_incorrect_names(&self) {
+        self.names.refresh_incorrect_names();
     }
-    
-    /// Clear the game name cache (useful for troubleshooting)
-    pub fn clear_cache(&mut self) {
-        info!("Clearing game name cache ({} entries)", self.app_cache.len());
-        self.app_cache.clear();
-        
-        // Remove the cache file
-        if self.cache_file_path.exists() {
-            if let Err(e) = fs::remove_file(&self.cache_file_path) {
-                warn!("Failed to remove cache file: {}", e);
-            } else {
-                info!("Cache file removed successfully");
-            }
-        }
+
+    /// Refresh all cached game names by fetching them from online APIs, in the background
+    pub fn refresh_game_names(&self) {
+        self.names.refresh_all();
     }
 
-    #[cfg(windows)]
-    fn get_game_name_from_registry(&self, app_id: u32) -> std::result::Result<String, Box<dyn std::error::Error>> {
-        use winreg::{RegKey, enums::*};
-        
-        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-        let steam_apps = hklm.open_subkey(r"SOFTWARE\Valve\Steam\Apps")?;
-        let app_key = steam_apps.open_subkey(app_id.to_string())?;
-        let name: String = app_key.get_value("Name")?;
-        Ok(name)
+    /// Get cache statistics
+    pub fn get_cache_stats(&self) -> (usize, String) {
+        self.names.cache_stats()
     }
 
-    fn get_game_name_from_config(&self, _app_id: u32) -> std::result::Result<String, Box<dyn std::error::Error>> {
-        // This could be implemented to read from Steam's localconfig.vdf or other files
-        // For now, we'll just return an error to fall back to the default naming
-        Err("Not implemented".into())
+    /// Clear the game name cache (useful for troubleshooting)
+    pub fn clear_cache(&self) {
+        self.names.clear_cache();
     }
 
     /// Load known game names from a comprehensive database
-    pub fn load_game_database(&mut self) {
-        let common_games = vec![
+    pub fn load_game_database(&self) {
+        let common_games = [
             // Popular multiplayer games
             (570, "Dota 2"),
             (730, "Counter-Strike: Global Offensive"),
@@ -591,11 +498,7 @@ impl SteamScanner {
             (1091500, "Cyberpunk 2077"),
         ];
 
-        for (app_id, name) in common_games {
-            self.app_cache.insert(app_id, name.to_string());
-        }
-        
-        info!("Loaded {} game names into cache", self.app_cache.len());
+        self.names.insert_known(&common_games);
     }
 
     /// Get Steam installation path from registry
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use log::{debug, warn};
+
+/// Usage at or above this share of the quota counts as near the limit
+pub const NEAR_LIMIT_PERCENT: f32 = 80.0;
+
+// appinfo.vdf format versions; 29 moved key names into a string table at the end
+const APPINFO_V27: u32 = 0x0756_4427;
+const APPINFO_V28: u32 = 0x0756_4428;
+const APPINFO_V29: u32 = 0x0756_4429;
+
+// Binary KeyValues value types
+const KV_SECTION: u8 = 0x00;
+const KV_STRING: u8 = 0x01;
+const KV_INT32: u8 = 0x02;
+const KV_FLOAT: u8 = 0x03;
+const KV_POINTER: u8 = 0x04;
+const KV_WIDE_STRING: u8 = 0x05;
+const KV_COLOR: u8 = 0x06;
+const KV_UINT64: u8 = 0x07;
+const KV_END: u8 = 0x08;
+const KV_INT64: u8 = 0x0A;
+
+/// A game's Steam Cloud allowance, from the `ufs` section of its app info
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CloudQuota {
+    pub bytes: Option<u64>,
+    pub max_files: Option<u32>,
+}
+
+/// What a game keeps in Steam Cloud for one account, and how close that is to its quota
+#[derive(Debug, Clone, Default)]
+pub struct SteamCloudUsage {
+    pub files: usize,
+    pub bytes: u64,
+    pub quota: CloudQuota,
+}
+
+impl SteamCloudUsage {
+    /// The larger of bytes and files used, as a percentage of the quota
+    pub fn percent_used(&self) -> Option<f32> {
+        let by_bytes = self.quota.bytes.filter(|q| *q > 0).map(|q| self.bytes as f32 / q as f32 * 100.0);
+        let by_files = self.quota.max_files.filter(|q| *q > 0).map(|q| self.files as f32 / q as f32 * 100.0);
+        match (by_bytes, by_files) {
+            (Some(b), Some(f)) => Some(b.max(f)),
+            (b, f) => b.or(f),
+        }
+    }
+
+    pub fn is_near_limit(&self) -> bool {
+        self.percent_used().map_or(false, |p| p >= NEAR_LIMIT_PERCENT)
+    }
+
+    /// Short form for the saves list, e.g. "45.0 MB / 100.0 MB"
+    pub fn summary(&self) -> String {
+        match self.quota.bytes {
+            Some(quota) if quota > 0 => format!("{} / {}", crate::types::format_size(self.bytes), crate::types::format_size(quota)),
+            _ => crate::types::format_size(self.bytes),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!("Steam Cloud: {} files, {}", self.files, crate::types::format_size(self.bytes));
+        if let Some(quota) = self.quota.bytes.filter(|q| *q > 0) {
+            text.push_str(&format!(" of {}", crate::types::format_size(quota)));
+        }
+        if let Some(max_files) = self.quota.max_files.filter(|q| *q > 0) {
+            text.push_str(&format!(", at most {} files", max_files));
+        }
+        if let Some(percent) = self.percent_used() {
+            text.push_str(&format!(" ({:.0}% used)", percent));
+        }
+        if self.is_near_limit() {
+            text.push_str("\nNear the limit: Steam may stop syncing new saves, keep backups of this game");
+        }
+        text
+    }
+}
+
+/// Steam Cloud usage of a userdata app folder, from the remotecache.vdf Steam keeps there
+///
+/// None if the game doesn't use Steam Cloud for this account.
+pub fn read_usage(app_folder: &Path, quota: Option<CloudQuota>) -> Option<SteamCloudUsage> {
+    let contents = fs::read_to_string(app_folder.join("remotecache.vdf")).ok()?;
+    let mut usage = SteamCloudUsage { quota: quota.unwrap_or_default(), ..SteamCloudUsage::default() };
+
+    // "<app id>" { "ChangeNumber" "..." "<file>" { "size" "1234" ... } ... }
+    let mut depth = 0;
+    for line in contents.lines() {
+        match line.trim() {
+            "{" => {
+                depth += 1;
+                if depth == 2 {
+                    usage.files += 1;
+                }
+            }
+            "}" => depth -= 1,
+            line if depth == 2 => {
+                let mut parts = line.split('"').filter(|s| !s.trim().is_empty());
+                if let (Some("size"), Some(size)) = (parts.next(), parts.next()) {
+                    usage.bytes += size.parse::<u64>().unwrap_or(0);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(usage)
+}
+
+/// Steam Cloud quotas of every app Steam knows about, from appcache/appinfo.vdf
+pub fn read_quotas(steam_root: &Path) -> HashMap<u32, CloudQuota> {
+    let path = steam_root.join("appcache").join("appinfo.vdf");
+    let data = match fs::read(&path) {
+        Ok(data) => data,
+        Err(e) => {
+            debug!("No app info at {:?}: {}", path, e);
+            return HashMap::new();
+        }
+    };
+
+    match parse_appinfo(&data) {
+        Some(quotas) => {
+            debug!("Read Steam Cloud quotas of {} apps", quotas.len());
+            quotas
+        }
+        None => {
+            warn!("Unsupported or damaged {:?}, Steam Cloud quotas are unknown", path);
+            HashMap::new()
+        }
+    }
+}
+
+fn parse_appinfo(data: &[u8]) -> Option<HashMap<u32, CloudQuota>> {
+    let mut reader = Reader::new(data);
+    let version = reader.u32()?;
+    reader.u32()?; // Universe
+
+    let strings = match version {
+        APPINFO_V29 => {
+            let offset = reader.u64()? as usize;
+            Some(read_string_table(data.get(offset..)?)?)
+        }
+        APPINFO_V27 | APPINFO_V28 => None,
+        _ => return None,
+    };
+    // Info state, last update, access token, SHA-1, change number, and since v28 the binary SHA-1
+    let app_header = 4 + 4 + 8 + 20 + 4 + if version == APPINFO_V27 { 0 } else { 20 };
+
+    let mut quotas = HashMap::new();
+    loop {
+        let app_id = reader.u32()?;
+        if app_id == 0 {
+            break;
+        }
+        let size = reader.u32()? as usize;
+        let end = reader.pos.checked_add(size)?;
+        let mut kv = Reader::new(data.get(reader.pos + app_header..end)?);
+        if let Some(quota) = find_cloud_quota(&mut kv, strings.as_deref()) {
+            quotas.insert(app_id, quota);
+        }
+        reader.pos = end;
+    }
+    Some(quotas)
+}
+
+/// Walk an app's binary KeyValues for `quota` and `maxnumfiles` in its `ufs` section
+fn find_cloud_quota(kv: &mut Reader, strings: Option<&[String]>) -> Option<CloudQuota> {
+    let mut sections: Vec<String> = Vec::new();
+    let mut quota = CloudQuota::default();
+
+    while let Some(kind) = kv.u8() {
+        if kind == KV_END {
+            if sections.pop().is_none() {
+                break;
+            }
+            continue;
+        }
+
+        let key = match strings {
+            Some(strings) => strings.get(kv.u32()? as usize)?.clone(),
+            None => kv.cstring()?,
+        };
+        let in_ufs = sections.last().map_or(false, |s| s == "ufs");
+        let number = match kind {
+            KV_SECTION => {
+                sections.push(key);
+                continue;
+            }
+            KV_STRING => kv.cstring()?.parse::<u64>().ok(),
+            KV_INT32 => Some(kv.u32()? as u64),
+            KV_FLOAT | KV_POINTER | KV_COLOR => {
+                kv.skip(4)?;
+                None
+            }
+            KV_UINT64 | KV_INT64 => Some(kv.u64()?),
+            KV_WIDE_STRING => {
+                while kv.u16()? != 0 {}
+                None
+            }
+            _ => return None,
+        };
+
+        if in_ufs {
+            match key.as_str() {
+                "quota" => quota.bytes = number,
+                "maxnumfiles" => quota.max_files = number.map(|n| n as u32),
+                _ => {}
+            }
+        }
+    }
+
+    (quota.bytes.is_some() || quota.max_files.is_some()).then_some(quota)
+}
+
+fn read_string_table(data: &[u8]) -> Option<Vec<String>> {
+    let mut reader = Reader::new(data);
+    let count = reader.u32()? as usize;
+    (0..count).map(|_| reader.cstring()).collect()
+}
+
+/// Little-endian reads from a byte slice, None past the end
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_le_bytes(b.try_into().unwrap_or_default()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|b| u64::from_le_bytes(b.try_into().unwrap_or_default()))
+    }
+
+    fn cstring(&mut self) -> Option<String> {
+        let rest = self.data.get(self.pos..)?;
+        let len = rest.iter().position(|b| *b == 0)?;
+        self.pos += len + 1;
+        Some(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+}
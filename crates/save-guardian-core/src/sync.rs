@@ -0,0 +1,774 @@
+use crate::types::*;
+use crate::backup::BackupOutcome;
+use crate::normalize;
+use crate::oplog::{EntryOutcome, LogEntry};
+use crate::protection::Unprotected;
+use crate::sync_rules::SyncRules;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use chrono::Utc;
+use log::{debug, info, warn};
+
+/// Suffix for files still being written; they are renamed into place once complete
+const TEMP_SUFFIX: &str = ".sgtmp";
+
+pub struct SyncManager {
+    backup_before_sync: bool,
+    skip_unchanged: bool,
+    protected_saves: Vec<PathBuf>,
+    rules: SyncRules,
+    health_history: Option<Vec<BackupInfo>>, // Backups to check sources against, None skips the check
+}
+
+impl SyncManager {
+    pub fn new(backup_before_sync: bool) -> Self {
+        Self {
+            backup_before_sync,
+            skip_unchanged: false,
+            protected_saves: Vec::new(),
+            rules: SyncRules::default(),
+            health_history: None,
+        }
+    }
+
+    /// Don't make a pre-sync backup of a folder that hasn't changed since its last backup
+    pub fn with_skip_unchanged(mut self, skip_unchanged: bool) -> Self {
+        self.skip_unchanged = skip_unchanged;
+        self
+    }
+
+    /// Write-protected save folders, unlocked while a sync writes into them
+    pub fn with_protected_saves(mut self, protected_saves: Vec<PathBuf>) -> Self {
+        self.protected_saves = protected_saves;
+        self
+    }
+
+    /// Known save locations from a community rules file, matched before any name guessing
+    pub fn with_rules(mut self, rules: SyncRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Refuse to replace a folder with an empty source, or one much smaller than the folder
+    /// is or was at its last backup in `backups`
+    pub fn with_health_checks(mut self, backups: Vec<BackupInfo>) -> Self {
+        self.health_history = Some(backups);
+        self
+    }
+
+    /// Find potential sync pairs between Steam and non-Steam saves
+    ///
+    /// Steam saves end up on the left side, non-Steam saves on the right. Non-Steam saves
+    /// of the same game in two places (e.g. an SSD and an external drive install) are
+    /// paired with each other. Games in the rules file are paired by their known locations
+    /// first; name matching only handles what they don't cover.
+    pub fn find_sync_pairs(&self, steam_saves: &[GameSave], non_steam_saves: &[GameSave]) -> Vec<SyncPair> {
+        let mut sync_pairs = self.find_rule_pairs(steam_saves, non_steam_saves);
+
+        // Steam emulator saves sit in a folder named after the app ID, and other non-Steam saves
+        // may have one confirmed by the user; either beats any name match
+        for steam_save in steam_saves {
+            if steam_save.app_id.is_none() || Self::is_paired(&sync_pairs, steam_save) {
+                continue;
+            }
+            let emulated = non_steam_saves.iter().find(|save| {
+                Self::steam_app_id_of(save) == steam_save.app_id
+                    && !Self::is_paired(&sync_pairs, save)
+            });
+            if let Some(emulated) = emulated {
+                debug!("Pairing {} by app ID with the non-Steam save at {:?}", steam_save.name, emulated.save_path);
+                sync_pairs.push(SyncPair::new(Some(steam_save.clone()), Some(emulated.clone()), steam_save.name.clone()));
+            }
+        }
+
+        // Then, try to match by app ID (for games that might have both Steam and non-Steam versions)
+        for steam_save in steam_saves {
+            if let Some(app_id) = steam_save.app_id {
+                if Self::is_paired(&sync_pairs, steam_save) {
+                    continue;
+                }
+                // Look for non-Steam saves with similar names that might match this Steam game
+                for non_steam_save in non_steam_saves {
+                    if !Self::is_paired(&sync_pairs, non_steam_save)
+                        && !Self::emulates_other_app(non_steam_save, app_id)
+                        && self.is_likely_same_game(&steam_save.name, &non_steam_save.name, Some(app_id)) {
+                        sync_pairs.push(SyncPair::new(
+                            Some(steam_save.clone()),
+                            Some(non_steam_save.clone()),
+                            steam_save.name.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Then, try to match by game name similarity for games without clear app ID matches
+        for steam_save in steam_saves {
+            if !Self::is_paired(&sync_pairs, steam_save) {
+                for non_steam_save in non_steam_saves {
+                    if !Self::is_paired(&sync_pairs, non_steam_save)
+                        && !steam_save.app_id.map_or(false, |app_id| Self::emulates_other_app(non_steam_save, app_id))
+                        && self.is_likely_same_game(&steam_save.name, &non_steam_save.name, steam_save.app_id) {
+                        sync_pairs.push(SyncPair::new(
+                            Some(steam_save.clone()),
+                            Some(non_steam_save.clone()),
+                            self.get_common_game_name(&steam_save.name, &non_steam_save.name),
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Pair up copies of the same non-Steam game living in different folders
+        for (i, first) in non_steam_saves.iter().enumerate() {
+            if Self::is_paired(&sync_pairs, first) {
+                continue;
+            }
+            for second in &non_steam_saves[i + 1..] {
+                if second.save_path != first.save_path
+                    && !Self::is_paired(&sync_pairs, second)
+                    && self.normalize_game_name(&first.name) == self.normalize_game_name(&second.name) {
+                    sync_pairs.push(SyncPair::new(Some(first.clone()), Some(second.clone()), first.name.clone()));
+                    break;
+                }
+            }
+        }
+
+        // Add unpaired Steam saves
+        for steam_save in steam_saves {
+            if !Self::is_paired(&sync_pairs, steam_save) {
+                sync_pairs.push(SyncPair::new(Some(steam_save.clone()), None, steam_save.name.clone()));
+            }
+        }
+
+        // Add unpaired non-Steam saves
+        for non_steam_save in non_steam_saves {
+            if !Self::is_paired(&sync_pairs, non_steam_save) {
+                sync_pairs.push(SyncPair::new(None, Some(non_steam_save.clone()), non_steam_save.name.clone()));
+            }
+        }
+
+        info!("Found {} potential sync pairs", sync_pairs.len());
+        sync_pairs
+    }
+
+    /// Pairs for the games in the rules file: the Steam save with the first save found in
+    /// one of the game's locations, or two such saves when the game isn't on Steam here
+    fn find_rule_pairs(&self, steam_saves: &[GameSave], non_steam_saves: &[GameSave]) -> Vec<SyncPair> {
+        let mut sync_pairs: Vec<SyncPair> = Vec::new();
+
+        for rule in &self.rules.games {
+            let mut located = non_steam_saves.iter()
+                .filter(|save| rule.matches_location(save) && !Self::is_paired(&sync_pairs, save));
+            let steam_save = steam_saves.iter()
+                .find(|save| rule.matches_steam(save) && !Self::is_paired(&sync_pairs, save));
+
+            let (left, right) = match steam_save {
+                Some(steam_save) => match located.next() {
+                    Some(non_steam_save) => (steam_save, non_steam_save),
+                    None => continue,
+                },
+                None => match (located.next(), located.next()) {
+                    (Some(first), Some(second)) => (first, second),
+                    _ => continue,
+                },
+            };
+
+            debug!("Rule for {} pairs {:?} with {:?}", rule.name, left.save_path, right.save_path);
+            let mut pair = SyncPair::new(Some(left.clone()), Some(right.clone()), rule.name.clone());
+            pair.app_id = pair.app_id.or(rule.app_id);
+            pair.from_rule = true;
+            sync_pairs.push(pair);
+        }
+
+        if !sync_pairs.is_empty() {
+            info!("Paired {} games from the rules file", sync_pairs.len());
+        }
+        sync_pairs
+    }
+
+    /// Whether `save` is a non-Steam save known to belong to an app other than `app_id`
+    fn emulates_other_app(save: &GameSave, app_id: u32) -> bool {
+        Self::steam_app_id_of(save).map_or(false, |emulated| emulated != app_id)
+    }
+
+    /// The Steam app a non-Steam save belongs to: confirmed by the user, or from its emulator folder
+    fn steam_app_id_of(save: &GameSave) -> Option<u32> {
+        save.app_id.or_else(|| save.origin.and_then(|origin| origin.steam_app_id()))
+    }
+
+    fn is_paired(sync_pairs: &[SyncPair], save: &GameSave) -> bool {
+        sync_pairs.iter().any(|pair| {
+            pair.left.as_ref().map(|s| &s.save_path) == Some(&save.save_path)
+                || pair.right.as_ref().map(|s| &s.save_path) == Some(&save.save_path)
+        })
+    }
+
+    /// Synchronize the two sides of a pair
+    pub fn sync_saves(
+        &self,
+        sync_pair: &mut SyncPair,
+        direction: SyncDirection,
+        backup_manager: Option<&crate::backup::BackupManager>,
+    ) -> Result<SyncResult> {
+        info!("Syncing saves for {} in direction {:?}", sync_pair.game_name, direction);
+        sync_pair.refresh();
+
+        let (left, right) = match (&sync_pair.left, &sync_pair.right) {
+            (Some(left), Some(right)) => (left, right),
+            _ => {
+                return Err(SaveGuardianError::SaveOperationFailed(
+                    "Both save locations are required to sync".to_string()
+                ));
+            }
+        };
+
+        let source_side = match direction {
+            SyncDirection::LeftToRight => PairSide::Left,
+            SyncDirection::RightToLeft => PairSide::Right,
+            // A locked (master) side always wins
+            SyncDirection::Bidirectional => match sync_pair.locked_side {
+                Some(master) => master,
+                None => {
+                    // For bidirectional sync, determine direction based on modification time
+                    let left_time = left.last_modified.unwrap_or(chrono::DateTime::from_timestamp(0, 0).unwrap());
+                    let right_time = right.last_modified.unwrap_or(chrono::DateTime::from_timestamp(0, 0).unwrap());
+
+                    if left_time > right_time { PairSide::Left } else { PairSide::Right }
+                }
+            },
+        };
+
+        if sync_pair.locked_side == Some(source_side.other()) {
+            return Err(SaveGuardianError::SaveOperationFailed(format!(
+                "The {} side of {} is read-only", source_side.other().label(), sync_pair.game_name
+            )));
+        }
+
+        let (source, destination) = match source_side {
+            PairSide::Left => (left, right),
+            PairSide::Right => (right, left),
+        };
+
+        // Merging never deletes, only replacing can wipe the destination
+        if sync_pair.strategy == SyncStrategy::Replace {
+            if let Some(backups) = &self.health_history {
+                if let Some(check) = crate::health::check_sync_source(&source.save_path, &destination.save_path, backups) {
+                    return Err(SaveGuardianError::UnhealthySource(check));
+                }
+            }
+        }
+
+        // A bidirectional merge writes into both folders, unless one of them is locked
+        let merge_both_ways = sync_pair.strategy == SyncStrategy::Merge
+            && direction == SyncDirection::Bidirectional
+            && sync_pair.locked_side.is_none();
+
+        let mut log = Vec::new();
+
+        // Create backup if requested and backup manager is available
+        if self.backup_before_sync {
+            if let Some(bm) = backup_manager {
+                let targets = if merge_both_ways { vec![destination, source] } else { vec![destination] };
+                for target in targets {
+                    let description = Some("Pre-sync backup".to_string());
+                    let outcome = if self.skip_unchanged {
+                        bm.create_backup_if_changed(target, description)
+                    } else {
+                        bm.create_backup_checked(target, description)
+                    };
+                    let item = format!("Pre-sync backup of {}", target.save_path.display());
+                    match outcome {
+                        Ok(BackupOutcome::Created(_)) => {
+                            info!("Created pre-sync backup for {}", target.name);
+                            log.push(LogEntry::new(item, EntryOutcome::Copied, None));
+                        }
+                        Ok(BackupOutcome::Unchanged(_)) => {
+                            info!("{} unchanged since its last backup, no pre-sync backup needed", target.name);
+                            log.push(LogEntry::new(item, EntryOutcome::Skipped, Some("Unchanged since the last backup".to_string())));
+                        }
+                        Err(e) => {
+                            warn!("Failed to create pre-sync backup: {}", e);
+                            log.push(LogEntry::new(item, EntryOutcome::Failed, Some(e.to_string())));
+                        }
+                    }
+                }
+            }
+        }
+
+        let targets = if merge_both_ways {
+            vec![destination.save_path.as_path(), source.save_path.as_path()]
+        } else {
+            vec![destination.save_path.as_path()]
+        };
+        let _unprotected = Unprotected::lift(&self.protected_saves, &targets)?;
+
+        // Perform the actual sync operation
+        let files_copied = match sync_pair.strategy {
+            SyncStrategy::Replace => self.copy_save_files(&source.save_path, &destination.save_path, &mut log)?,
+            SyncStrategy::Merge => {
+                let mut copied = self.merge_save_files(&source.save_path, &destination.save_path, &mut log)?;
+                if merge_both_ways {
+                    copied += self.merge_save_files(&destination.save_path, &source.save_path, &mut log)?;
+                }
+                copied
+            }
+        };
+
+        // Update sync information
+        sync_pair.last_synced = Some(Utc::now());
+        sync_pair.sync_direction = direction;
+
+        Ok(SyncResult {
+            files_copied,
+            bytes_copied: self.calculate_directory_size(&destination.save_path)?,
+            source_path: source.save_path.clone(),
+            destination_path: destination.save_path.clone(),
+            sync_time: Utc::now(),
+            log,
+        })
+    }
+
+    /// Copy save files from source to destination
+    fn copy_save_files(&self, source: &PathBuf, destination: &PathBuf, log: &mut Vec<LogEntry>) -> Result<usize> {
+        info!("Copying save files from {:?} to {:?}", source, destination);
+
+        // Create destination directory if it doesn't exist
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to create destination directory: {}", e)))?;
+        }
+
+        let mut files_copied = 0;
+
+        if source.is_file() {
+            // Copy single file
+            if let Some(filename) = source.file_name() {
+                let dest_file = destination.join(filename);
+                self.copy_file_safely(source, &dest_file)?;
+                files_copied = 1;
+                log.push(LogEntry::new(filename.to_string_lossy(), EntryOutcome::Copied, None));
+                debug!("Copied file: {:?} -> {:?}", source, dest_file);
+            }
+        } else if source.is_dir() {
+            // Copy directory recursively, over the existing files; anything stale is only
+            // removed once every file has been written, so a crash never leaves a half-empty save
+
+            // Create destination directory
+            fs::create_dir_all(destination)
+                .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to create destination directory: {}", e)))?;
+
+            let walker = WalkDir::new(source)
+                .follow_links(false)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| !Self::is_temp_file(e.path()));
+
+            for entry in walker {
+                let path = entry.path();
+                let relative_path = path.strip_prefix(source)
+                    .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Path error: {}", e)))?;
+
+                let dest_path = destination.join(&relative_path);
+
+                if path.is_file() {
+                    // Create parent directories if needed
+                    if let Some(parent) = dest_path.parent() {
+                        fs::create_dir_all(parent)
+                            .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to create parent directory: {}", e)))?;
+                    }
+
+                    // Copy the file
+                    if let Err(e) = self.copy_file_safely(path, &dest_path) {
+                        log.push(LogEntry::new(relative_path.to_string_lossy(), EntryOutcome::Failed, Some(e.to_string())));
+                        return Err(e);
+                    }
+                    
+                    files_copied += 1;
+                    log.push(LogEntry::new(relative_path.to_string_lossy(), EntryOutcome::Copied, None));
+                    debug!("Copied file: {:?} -> {:?}", path, dest_path);
+                } else if path.is_dir() && relative_path.as_os_str() != "" {
+                    // Create directory
+                    fs::create_dir_all(&dest_path)
+                        .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to create directory: {}", e)))?;
+                    
+                    debug!("Created directory: {:?}", dest_path);
+                }
+            }
+
+            self.remove_stale_files(source, destination, log)?;
+        } else {
+            return Err(SaveGuardianError::SaveOperationFailed(
+                "Source path is neither file nor directory".to_string()
+            ));
+        }
+
+        info!("Successfully copied {} files", files_copied);
+        Ok(files_copied)
+    }
+
+    /// Copy files missing from the destination, or older there, without deleting anything
+    fn merge_save_files(&self, source: &PathBuf, destination: &PathBuf, log: &mut Vec<LogEntry>) -> Result<usize> {
+        info!("Merging save files from {:?} into {:?}", source, destination);
+
+        if source.is_file() {
+            let filename = match source.file_name() {
+                Some(filename) => filename,
+                None => return Ok(0),
+            };
+            return self.merge_file(source, &destination.join(filename), &filename.to_string_lossy(), log)
+                .map(|copied| copied as usize);
+        }
+
+        if !source.is_dir() {
+            return Err(SaveGuardianError::SaveOperationFailed(
+                "Source path is neither file nor directory".to_string()
+            ));
+        }
+
+        let mut files_copied = 0;
+        let walker = WalkDir::new(source)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && !Self::is_temp_file(e.path()));
+
+        for entry in walker {
+            let relative_path = entry.path().strip_prefix(source)
+                .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Path error: {}", e)))?;
+
+            if self.merge_file(entry.path(), &destination.join(relative_path), &relative_path.to_string_lossy(), log)? {
+                files_copied += 1;
+            }
+        }
+
+        info!("Merged {} files", files_copied);
+        Ok(files_copied)
+    }
+
+    /// `copy_if_newer`, noting in the log what happened to the file
+    fn merge_file(&self, source: &Path, destination: &Path, item: &str, log: &mut Vec<LogEntry>) -> Result<bool> {
+        let existed = destination.exists();
+        match self.copy_if_newer(source, destination) {
+            Ok(true) => {
+                let reason = if existed { "Newer than the copy it replaced" } else { "Missing on the other side" };
+                log.push(LogEntry::new(item, EntryOutcome::Copied, Some(reason.to_string())));
+                Ok(true)
+            }
+            Ok(false) => {
+                log.push(LogEntry::new(item, EntryOutcome::Skipped, Some("The other side has the same or a newer version".to_string())));
+                Ok(false)
+            }
+            Err(e) => {
+                log.push(LogEntry::new(item, EntryOutcome::Failed, Some(e.to_string())));
+                Err(e)
+            }
+        }
+    }
+
+    /// Copy a single file unless the destination already has the same or a newer version
+    fn copy_if_newer(&self, source: &Path, destination: &Path) -> Result<bool> {
+        let source_modified = source.metadata().and_then(|m| m.modified())
+            .map_err(|e| SaveGuardianError::Io(e))?;
+
+        if let Ok(dest_modified) = destination.metadata().and_then(|m| m.modified()) {
+            // FAT and network shares round timestamps to 2 seconds
+            let newer_by = source_modified.duration_since(dest_modified).unwrap_or_default();
+            if newer_by <= std::time::Duration::from_secs(2) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SaveGuardianError::SaveOperationFailed(format!("Failed to create parent directory: {}", e)))?;
+        }
+
+        self.copy_file_safely(source, destination)?;
+        debug!("Merged file: {:?} -> {:?}", source, destination);
+        Ok(true)
+    }
+
+    /// Copy to a temporary name next to the destination and rename it into place once
+    /// the data is on disk, so the game never sees a truncated save
+    ///
+    /// The copy keeps the source's modification time; with a fresh one, the return pass of
+    /// a two-way merge would take every copied file for a newer one and copy it back.
+    fn copy_file_safely(&self, source: &Path, destination: &Path) -> Result<()> {
+        let file_name = destination.file_name()
+            .ok_or_else(|| SaveGuardianError::SaveOperationFailed(format!("Invalid destination: {:?}", destination)))?;
+        let temp_path = destination.with_file_name(format!("{}{}", file_name.to_string_lossy(), TEMP_SUFFIX));
+
+        let result = fs::copy(source, &temp_path)
+            .and_then(|_| {
+                let copy = fs::OpenOptions::new().write(true).open(&temp_path)?;
+                copy.set_modified(source.metadata()?.modified()?)?;
+                copy.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, destination));
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(SaveGuardianError::SaveOperationFailed(format!("Failed to copy file: {}", e)));
+        }
+
+        Ok(())
+    }
+
+    /// Delete files and folders in the destination that no longer exist in the source,
+    /// including temporary files left behind by an interrupted sync
+    fn remove_stale_files(&self, source: &Path, destination: &Path, log: &mut Vec<LogEntry>) -> Result<()> {
+        let stale: Vec<PathBuf> = WalkDir::new(destination)
+            .follow_links(false)
+            .contents_first(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.depth() > 0)
+            .filter(|e| match e.path().strip_prefix(destination) {
+                Ok(relative) => Self::is_temp_file(e.path()) || !source.join(relative).exists(),
+                Err(_) => false,
+            })
+            .map(|e| e.into_path())
+            .collect();
+
+        for path in stale {
+            let item = path.strip_prefix(destination).unwrap_or(&path).to_string_lossy().to_string();
+            let temp = Self::is_temp_file(&path);
+            let removed = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+            match removed {
+                Ok(()) => {
+                    debug!("Removed stale file: {:?}", path);
+                    let reason = if temp { "Left behind by an interrupted sync" } else { "No longer in the source" };
+                    log.push(LogEntry::new(item, EntryOutcome::Removed, Some(reason.to_string())));
+                }
+                Err(e) => {
+                    warn!("Failed to remove stale file {:?}: {}", path, e);
+                    log.push(LogEntry::new(item, EntryOutcome::Failed, Some(format!("Could not remove: {}", e))));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_temp_file(path: &Path) -> bool {
+        path.to_string_lossy().ends_with(TEMP_SUFFIX)
+    }
+
+    /// Calculate the total size of a directory
+    fn calculate_directory_size(&self, path: &PathBuf) -> Result<u64> {
+        let mut total_size = 0;
+
+        if path.is_file() {
+            total_size = path.metadata()
+                .map_err(|e| SaveGuardianError::Io(e))?
+                .len();
+        } else if path.is_dir() {
+            let walker = WalkDir::new(path)
+                .follow_links(false)
+                .into_iter()
+                .filter_map(|e| e.ok());
+
+            for entry in walker {
+                if entry.file_type().is_file() {
+                    total_size += entry.metadata()
+                        .map_err(|e| SaveGuardianError::Io(std::io::Error::from(e)))?
+                        .len();
+                }
+            }
+        }
+
+        Ok(total_size)
+    }
+
+    /// Check if two game names likely refer to the same game
+    fn is_likely_same_game(&self, name1: &str, name2: &str, app_id: Option<u32>) -> bool {
+        // Normalize names for comparison
+        let norm1 = self.normalize_game_name(name1);
+        let norm2 = self.normalize_game_name(name2);
+
+        // Exact match
+        if norm1 == norm2 {
+            return true;
+        }
+
+        // Check if one name contains the other
+        if norm1.contains(&norm2) || norm2.contains(&norm1) {
+            return true;
+        }
+
+        // Check for common game name variations
+        if self.check_common_variations(&norm1, &norm2) {
+            return true;
+        }
+
+        // If we have an app ID, check against known game mappings
+        if let Some(id) = app_id {
+            if self.check_app_id_name_match(id, &norm2) {
+                return true;
+            }
+        }
+
+        // Calculate similarity score
+        let similarity = self.calculate_string_similarity(&norm1, &norm2);
+        similarity > 0.7 // 70% similarity threshold
+    }
+
+    /// Normalize game name for comparison
+    fn normalize_game_name(&self, name: &str) -> String {
+        normalize::match_key(name)
+    }
+
+    /// Check for common game name variations
+    fn check_common_variations(&self, name1: &str, name2: &str) -> bool {
+        let variations = vec![
+            ("goty", "game of the year"),
+            ("deluxe", "deluxe edition"),
+            ("ultimate", "ultimate edition"),
+            ("remastered", "remaster"),
+            ("enhanced", "enhanced edition"),
+            ("definitive", "definitive edition"),
+            ("directors", "director's cut"),
+            ("complete", "complete edition"),
+        ];
+
+        for (short, long) in variations {
+            if (name1.contains(short) && name2.contains(long)) || 
+               (name1.contains(long) && name2.contains(short)) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Check if an app ID matches a game name
+    fn check_app_id_name_match(&self, app_id: u32, name: &str) -> bool {
+        // This could be expanded with a comprehensive database of Steam app IDs to game names
+        let known_mappings = vec![
+            (239140, "dying light"),
+            (881020, "dying light"),
+            (271590, "grand theft auto"),
+            (271590, "gta"),
+            (730, "counter strike"),
+            (730, "cs go"),
+            (440, "team fortress"),
+            (570, "dota"),
+        ];
+
+        for (id, game_name) in known_mappings {
+            if id == app_id && name.contains(game_name) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Calculate string similarity using a simple algorithm
+    fn calculate_string_similarity(&self, s1: &str, s2: &str) -> f64 {
+        if s1.is_empty() && s2.is_empty() {
+            return 1.0;
+        }
+        
+        if s1.is_empty() || s2.is_empty() {
+            return 0.0;
+        }
+
+        let len1 = s1.chars().count();
+        let len2 = s2.chars().count();
+        let max_len = len1.max(len2);
+
+        // Simple Levenshtein distance calculation
+        let distance = self.levenshtein_distance(s1, s2);
+        1.0 - (distance as f64 / max_len as f64)
+    }
+
+    /// Calculate Levenshtein distance between two strings
+    fn levenshtein_distance(&self, s1: &str, s2: &str) -> usize {
+        // In characters: a Japanese or Cyrillic letter is several bytes
+        let len1 = s1.chars().count();
+        let len2 = s2.chars().count();
+
+        if len1 == 0 { return len2; }
+        if len2 == 0 { return len1; }
+
+        let mut matrix = vec![vec![0; len2 + 1]; len1 + 1];
+
+        for i in 0..=len1 {
+            matrix[i][0] = i;
+        }
+        for j in 0..=len2 {
+            matrix[0][j] = j;
+        }
+
+        let chars1: Vec<char> = s1.chars().collect();
+        let chars2: Vec<char> = s2.chars().collect();
+
+        for i in 1..=len1 {
+            for j in 1..=len2 {
+                let cost = if chars1[i-1] == chars2[j-1] { 0 } else { 1 };
+                matrix[i][j] = (matrix[i-1][j] + 1)
+                    .min(matrix[i][j-1] + 1)
+                    .min(matrix[i-1][j-1] + cost);
+            }
+        }
+
+        matrix[len1][len2]
+    }
+
+    /// Get a common game name from two similar names
+    fn get_common_game_name(&self, name1: &str, name2: &str) -> String {
+        // Use the shorter, cleaner name
+        if name1.len() <= name2.len() {
+            name1.to_string()
+        } else {
+            name2.to_string()
+        }
+    }
+
+    /// Create a sync pair manually from any two saves, whatever their save type
+    pub fn create_manual_sync_pair(
+        &self,
+        left: Option<GameSave>,
+        right: Option<GameSave>,
+        custom_name: Option<String>,
+    ) -> Result<SyncPair> {
+        let game_name = match (&left, &right, custom_name) {
+            (_, _, Some(name)) => name,
+            (Some(left), _, None) => left.name.clone(),
+            (None, Some(right), None) => right.name.clone(),
+            (None, None, None) => return Err(SaveGuardianError::SaveOperationFailed(
+                "At least one save location must be provided".to_string()
+            )),
+        };
+
+        Ok(SyncPair::new(left, right, game_name))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncResult {
+    pub files_copied: usize,
+    pub bytes_copied: u64,
+    pub source_path: PathBuf,
+    pub destination_path: PathBuf,
+    pub sync_time: chrono::DateTime<Utc>,
+    pub log: Vec<LogEntry>, // Per file: copied, skipped as up to date, removed as stale, failed
+}
+
+impl SyncResult {
+    pub fn format_bytes_copied(&self) -> String {
+        if self.bytes_copied < 1024 {
+            format!("{} B", self.bytes_copied)
+        } else if self.bytes_copied < 1024 * 1024 {
+            format!("{:.1} KB", self.bytes_copied as f64 / 1024.0)
+        } else if self.bytes_copied < 1024 * 1024 * 1024 {
+            format!("{:.1} MB", self.bytes_copied as f64 / (1024.0 * 1024.0))
+        } else {
+            format!("{:.1} GB", self.bytes_copied as f64 / (1024.0 * 1024.0 * 1024.0))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+use log::debug;
+
+/// Consumer sync clients that upload whatever lands in their folder on their own
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncClient {
+    OneDrive,
+    Dropbox,
+    GoogleDrive,
+    ICloud,
+}
+
+impl SyncClient {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncClient::OneDrive => "OneDrive",
+            SyncClient::Dropbox => "Dropbox",
+            SyncClient::GoogleDrive => "Google Drive",
+            SyncClient::ICloud => "iCloud Drive",
+        }
+    }
+
+    /// What can go wrong when a save lives in this client's folder
+    pub fn warning(&self) -> String {
+        format!(
+            "This save is inside a {} folder, so it is synced twice. Restoring while {} uploads \
+             can leave a half-written save in its cloud, or {} may put the old files back. \
+             Pause {} before restoring.",
+            self.name(), self.name(), self.name(), self.name()
+        )
+    }
+}
+
+/// The root of a sync client's folder on this machine
+#[derive(Debug, Clone)]
+pub struct SyncClientFolder {
+    pub client: SyncClient,
+    pub path: PathBuf,
+}
+
+/// Find the folders of the sync clients set up on this machine
+///
+/// OneDrive announces itself through environment variables and Dropbox through its
+/// `info.json`; the usual folder names in the home directory cover the rest.
+pub fn detect_folders() -> Vec<SyncClientFolder> {
+    let mut folders = Vec::new();
+
+    for var in ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"] {
+        if let Some(path) = std::env::var_os(var) {
+            folders.push(SyncClientFolder { client: SyncClient::OneDrive, path: PathBuf::from(path) });
+        }
+    }
+
+    folders.extend(dropbox_folders().into_iter().map(|path| SyncClientFolder { client: SyncClient::Dropbox, path }));
+
+    if let Some(home) = dirs::home_dir() {
+        let candidates = [
+            (SyncClient::OneDrive, home.join("OneDrive")),
+            (SyncClient::Dropbox, home.join("Dropbox")),
+            (SyncClient::GoogleDrive, home.join("Google Drive")),
+            (SyncClient::ICloud, home.join("iCloudDrive")),
+            (SyncClient::ICloud, home.join("Library/Mobile Documents")),
+        ];
+        for (client, path) in candidates {
+            if path.is_dir() {
+                folders.push(SyncClientFolder { client, path });
+            }
+        }
+    }
+
+    folders.sort_by(|a, b| a.path.cmp(&b.path));
+    folders.dedup_by(|a, b| a.path == b.path);
+    debug!("Sync client folders: {:?}", folders);
+    folders
+}
+
+/// The sync client whose folder holds `path`, if any
+///
+/// Falls back to folder names, for clients that keep their folder somewhere unusual
+/// (`OneDrive - Contoso`, `Dropbox (Personal)`, `~/Library/CloudStorage/GoogleDrive-...`).
+pub fn client_for(path: &Path, folders: &[SyncClientFolder]) -> Option<SyncClient> {
+    if let Some(folder) = folders.iter().find(|f| path.starts_with(&f.path)) {
+        return Some(folder.client);
+    }
+
+    path.components().find_map(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        if name.starts_with("OneDrive") {
+            Some(SyncClient::OneDrive)
+        } else if name == "Dropbox" || name.starts_with("Dropbox (") {
+            Some(SyncClient::Dropbox)
+        } else if name == "Google Drive" || name == "My Drive" || name.starts_with("GoogleDrive-") {
+            Some(SyncClient::GoogleDrive)
+        } else if name == "iCloudDrive" || name == "Mobile Documents" {
+            Some(SyncClient::ICloud)
+        } else {
+            None
+        }
+    })
+}
+
+/// Personal and business folders listed in Dropbox's `info.json`
+fn dropbox_folders() -> Vec<PathBuf> {
+    let candidates = [
+        dirs::config_dir().map(|d| d.join("Dropbox/info.json")),     // %APPDATA% on Windows
+        dirs::data_local_dir().map(|d| d.join("Dropbox/info.json")), // %LOCALAPPDATA% on Windows
+        dirs::home_dir().map(|d| d.join(".dropbox/info.json")),      // Linux and macOS
+    ];
+
+    let info = match candidates.into_iter().flatten().find_map(|path| std::fs::read_to_string(path).ok()) {
+        Some(info) => info,
+        None => return Vec::new(),
+    };
+    let info: serde_json::Value = match serde_json::from_str(&info) {
+        Ok(info) => info,
+        Err(_) => return Vec::new(),
+    };
+
+    ["personal", "business"]
+        .iter()
+        .filter_map(|account| info.get(account)?.get("path")?.as_str())
+        .map(PathBuf::from)
+        .collect()
+}
@@ -0,0 +1,94 @@
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use log::info;
+
+/// A shared file listing where known games keep their saves
+///
+/// ```toml
+/// [[game]]
+/// name = "Elden Ring"
+/// app_id = 1245620
+/// locations = ["{appdata}/EldenRing", "{appdata}/Goldberg SteamEmu Saves/{app_id}"]
+/// ```
+///
+/// Locations may use `{home}`, `{documents}`, `{appdata}`, `{localappdata}`, `{app_id}`,
+/// and `*` for any single folder name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncRules {
+    #[serde(default, rename = "game")]
+    pub games: Vec<GameRule>,
+}
+
+/// One game of a rules file: its Steam app ID and the non-Steam locations of its saves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRule {
+    pub name: String,
+    #[serde(default)]
+    pub app_id: Option<u32>,
+    #[serde(default)]
+    pub locations: Vec<String>,
+}
+
+impl SyncRules {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(SaveGuardianError::PathNotFound(path.to_path_buf()));
+        }
+        let rules: SyncRules = toml::from_str(&fs::read_to_string(path)?)?;
+        info!("Loaded {} game rules from {:?}", rules.games.len(), path);
+        Ok(rules)
+    }
+
+    /// The rules from the file set in the config, empty if there is none
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match config.sync_rules_file {
+            Some(ref path) => Self::load(path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl GameRule {
+    /// Whether a Steam save belongs to this game
+    pub fn matches_steam(&self, save: &GameSave) -> bool {
+        match self.app_id {
+            Some(app_id) => save.app_id == Some(app_id),
+            None => save.name.eq_ignore_ascii_case(&self.name),
+        }
+    }
+
+    /// Whether a non-Steam save is at, or inside, one of this game's locations
+    pub fn matches_location(&self, save: &GameSave) -> bool {
+        self.locations.iter().any(|location| {
+            let pattern = self.expand(location);
+            let pattern: Vec<Component> = pattern.components().collect();
+            let path: Vec<Component> = save.save_path.components().collect();
+            path.len() >= pattern.len() && pattern.iter().zip(&path).all(|(p, c)| component_matches(p, c))
+        })
+    }
+
+    fn expand(&self, location: &str) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or_default();
+        let documents = dirs::document_dir().unwrap_or_else(|| home.join("Documents"));
+        let appdata = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+        let localappdata = dirs::data_local_dir().unwrap_or_else(|| home.join(".local/share"));
+
+        let mut expanded = location
+            .replace("{home}", &home.to_string_lossy())
+            .replace("{documents}", &documents.to_string_lossy())
+            .replace("{appdata}", &appdata.to_string_lossy())
+            .replace("{localappdata}", &localappdata.to_string_lossy());
+        if let Some(app_id) = self.app_id {
+            expanded = expanded.replace("{app_id}", &app_id.to_string());
+        }
+        PathBuf::from(expanded)
+    }
+}
+
+/// Compare one folder of a location with one of a save path; case-insensitive, as on Windows
+fn component_matches(pattern: &Component, component: &Component) -> bool {
+    let pattern = pattern.as_os_str().to_string_lossy();
+    pattern == "*" || pattern.eq_ignore_ascii_case(&component.as_os_str().to_string_lossy())
+}
@@ -0,0 +1,1626 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SaveType {
+    Steam,
+    NonSteam,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SteamUser {
+    pub id: String,
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub games: Vec<GameSave>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSave {
+    pub name: String,
+    pub app_id: Option<u32>, // Steam app ID, None for non-Steam games
+    pub save_type: SaveType,
+    pub save_path: PathBuf,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub size: u64,
+    pub backup_count: usize,
+    pub is_synced: bool, // Whether this save has a corresponding Steam/non-Steam version
+    #[serde(default)]
+    pub steam_user_id: Option<String>, // Owning account, games can appear under several via Family Sharing
+    #[serde(default)]
+    pub is_network: bool, // Lives on an SMB/NFS share or mapped drive
+    #[serde(default)]
+    pub sandbox: Option<Sandbox>, // Wine bottle or Flatpak app the save was found in
+    #[serde(default)]
+    pub origin: Option<LauncherOrigin>, // Probable launcher of a non-Steam save, see `non_steam::detect_origin`
+}
+
+/// Game name of whole Steam profile backups
+pub const STEAM_PROFILE_NAME: &str = "Steam Profile";
+
+/// A profile is the account's own userdata folder, with no app ID
+fn is_profile_path(path: &std::path::Path, app_id: Option<u32>, steam_user_id: Option<&str>, save_type: &SaveType) -> bool {
+    *save_type == SaveType::Steam
+        && app_id.is_none()
+        && steam_user_id.map_or(false, |id| path.file_name().map_or(false, |name| name.to_string_lossy() == id))
+}
+
+/// Where a non-Steam save probably comes from, guessed from the folders around it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LauncherOrigin {
+    SteamEmu(Option<u32>), // Goldberg, CODEX and the like, with the app ID their save folder is named after
+    Epic,
+    Gog,
+    Standalone,
+}
+
+impl LauncherOrigin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LauncherOrigin::SteamEmu(_) => "Steam emu",
+            LauncherOrigin::Epic => "Epic",
+            LauncherOrigin::Gog => "GOG",
+            LauncherOrigin::Standalone => "Standalone",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            LauncherOrigin::SteamEmu(Some(app_id)) => format!("Saved by a Steam emulator for app {}, paired with the Steam save of that app", app_id),
+            LauncherOrigin::SteamEmu(None) => "Saved by a Steam emulator (steam_settings or steam_emu.ini next to the game)".to_string(),
+            LauncherOrigin::Epic => "Installed by the Epic Games Launcher (.egstore folder)".to_string(),
+            LauncherOrigin::Gog => "Installed from GOG (goggame-*.info file)".to_string(),
+            LauncherOrigin::Standalone => "No launcher found around the save".to_string(),
+        }
+    }
+
+    /// The Steam app ID an emulator save belongs to, when its folder names one
+    pub fn steam_app_id(&self) -> Option<u32> {
+        match self {
+            LauncherOrigin::SteamEmu(app_id) => *app_id,
+            _ => None,
+        }
+    }
+}
+
+/// A sandbox saves can live in (Linux or macOS), restores have to go back into the same one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Sandbox {
+    Bottle(String),       // Bottle name, as shown in Bottles
+    Flatpak(String),      // Flatpak app ID, e.g. com.heroicgameslauncher.hgl
+    MacContainer(String), // Bundle ID of a sandboxed macOS app, ~/Library/Containers/<id>
+}
+
+impl Sandbox {
+    pub fn label(&self) -> String {
+        match self {
+            Sandbox::Bottle(name) => format!("Bottles: {}", name),
+            Sandbox::Flatpak(app_id) => format!("Flatpak: {}", app_id),
+            Sandbox::MacContainer(bundle_id) => format!("Container: {}", bundle_id),
+        }
+    }
+
+    /// Filesystem-safe part of a game key, see `BackupManager::key_for_save`
+    pub fn key(&self) -> String {
+        let key = match self {
+            Sandbox::Bottle(name) => format!("bottle_{}", name),
+            Sandbox::Flatpak(app_id) => format!("flatpak_{}", app_id),
+            Sandbox::MacContainer(bundle_id) => format!("container_{}", bundle_id),
+        };
+        UserProfile::sanitize_name(&key)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveLocation {
+    pub path: PathBuf,
+    pub location_type: LocationType,
+    pub description: String,
+    pub is_custom: bool,
+    #[serde(default)]
+    pub sandbox: Option<Sandbox>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LocationType {
+    Documents,
+    AppDataRoaming,
+    AppDataLocal,
+    AppDataLocalLow,
+    PublicDocuments,
+    GameInstall,
+    Steam,
+    Custom,
+    Bottles,
+    Flatpak,
+    ApplicationSupport,
+    MacContainer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub id: String,
+    pub game_name: String,
+    pub app_id: Option<u32>,
+    pub save_type: SaveType,
+    pub original_path: PathBuf,
+    pub backup_path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub archive_sha256: Option<String>,
+    #[serde(default)]
+    pub signature: Option<String>, // HMAC of the manifest, see integrity.rs
+    #[serde(default)]
+    pub steam_user_id: Option<String>,
+    #[serde(default)]
+    pub sandbox: Option<Sandbox>,
+    #[serde(default)]
+    pub source_size: u64, // Uncompressed size of the save when it was backed up
+    #[serde(default)]
+    pub excluded_files: usize, // Junk files skipped by the exclusion rules
+    #[serde(default)]
+    pub excluded_bytes: u64,
+    #[serde(default)]
+    pub pinned: bool, // Never removed by retention cleanup
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub content_sha256: Option<String>, // Hash of the archived files, independent of ZIP timestamps
+    #[serde(default)]
+    pub deduplicated: bool, // Archive is a hard link to an identical earlier backup
+    #[serde(default)]
+    pub source_fingerprint: Option<String>, // Names, sizes and mtimes of the saved files
+    #[serde(default)]
+    pub verified_unchanged_at: Option<DateTime<Utc>>, // Last automatic backup skipped because nothing changed
+    #[serde(default)]
+    pub machine: Option<MachineInfo>, // Install that made the backup, None before machine IDs existed
+    #[serde(default)]
+    pub settings_files: Vec<SettingsFile>, // Config files archived with the save, see game_settings.rs
+    #[serde(default)]
+    pub volumes: Vec<PathBuf>, // Parts of a split archive in order, empty when backup_path is the whole archive
+    #[serde(default)]
+    pub duration_ms: Option<u64>, // Time taken to write and check the archive, None if not made here
+    #[serde(default)]
+    pub base_backup: Option<String>, // ID of the backup this incremental one only holds the changes since
+    #[serde(default)]
+    pub removed_files: Vec<String>, // Entries deleted from the save since base_backup
+    #[serde(default)]
+    pub stored_files: Vec<StoredFile>, // Large files kept in the dedup store instead of the archive
+    #[serde(default)]
+    pub save_metadata: Vec<crate::save_formats::SaveMetadata>, // Slot names, levels and play times read from the saved files
+    #[serde(default)]
+    pub file_checksums: Vec<FileChecksum>, // Each archived file as it was written, see BackupManager::verify_contents
+    #[serde(default)]
+    pub encrypted: bool, // Archive sealed with the backup passphrase, see encryption.rs
+}
+
+/// A game settings file in a backup and where it came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsFile {
+    pub archive_name: String, // Under game_settings::SETTINGS_FOLDER
+    pub original_path: PathBuf,
+}
+
+/// A save file kept once in the dedup store instead of in the archive, see dedup_store.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredFile {
+    pub name: String, // Entry name it would have had in the archive
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// SHA-256 of one file in a backup's archive, taken while it was archived
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChecksum {
+    pub name: String, // Entry name in the archive
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// One installation of the app, to tell backups from different PCs apart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachineInfo {
+    pub id: String,   // Random, generated once per install
+    pub name: String, // Host name at the time, for display
+}
+
+/// Two save locations of the same game kept in step, of any save type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPair {
+    #[serde(alias = "steam_save")]
+    pub left: Option<GameSave>,
+    #[serde(alias = "non_steam_save")]
+    pub right: Option<GameSave>,
+    pub game_name: String,
+    pub app_id: Option<u32>,
+    pub last_synced: Option<DateTime<Utc>>,
+    pub sync_direction: SyncDirection,
+    #[serde(default)]
+    pub strategy: SyncStrategy,
+    #[serde(default)]
+    pub locked_side: Option<PairSide>, // Read-only master side that sync never writes into
+    #[serde(default)]
+    pub schedule: SyncSchedule,
+    #[serde(default)]
+    pub last_attempt: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_result: Option<String>,
+    #[serde(default)]
+    pub from_rule: bool, // Paired by the community rules file rather than by name
+}
+
+/// When the scheduler syncs a pair on its own
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SyncSchedule {
+    Manual,
+    OnStartup,
+    EveryMinutes(u32),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PairSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SyncDirection {
+    #[serde(alias = "SteamToNonSteam")]
+    LeftToRight,
+    #[serde(alias = "NonSteamToSteam")]
+    RightToLeft,
+    Bidirectional,
+}
+
+/// How a sync pair reconciles its two save folders
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SyncStrategy {
+    Replace, // The newer side replaces the other one completely
+    Merge,   // Union of both sides, newer copy wins per file; for saves made of independent files
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub steam_path: PathBuf,
+    pub backup_path: PathBuf,
+    pub custom_locations: Vec<SaveLocation>,
+    pub auto_backup: bool,
+    pub backup_retention_days: u32,
+    pub theme: Theme,
+    pub window_size: (f32, f32),
+    pub window_position: Option<(f32, f32)>,
+    pub koofr_config: KoofrConfig,
+    #[serde(default)]
+    pub profiles: Vec<UserProfile>,
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    #[serde(default)]
+    pub read_only_mode: bool, // Disable restore, delete, sync and cleanup
+    #[serde(default)]
+    pub sign_backups: bool,
+    #[serde(default)]
+    pub size_watchdog: SizeWatchdogConfig,
+    #[serde(default)]
+    pub exclusions: ExclusionConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub online: OnlineConfig,
+    #[serde(default)]
+    pub cloud_retry: CloudRetryConfig,
+    #[serde(default)]
+    pub cloud_encryption: CloudEncryptionConfig,
+    #[serde(default)]
+    pub cloud_recompression: CloudRecompression,
+    #[serde(default)]
+    pub bandwidth: BandwidthLimit,
+    #[serde(default = "new_machine_id")]
+    pub machine_id: String, // Identifies this install in backups and the cloud manifest
+    #[serde(default = "default_true")]
+    pub keep_latest_links: bool, // Maintain latest/<game>.zip in the backup folder
+    #[serde(default)]
+    pub path_remaps: Vec<PathRemap>,
+    #[serde(default)]
+    pub verification: VerificationScheduleConfig,
+    #[serde(default)]
+    pub sync_pairs: Vec<SyncPair>,
+    #[serde(default)]
+    pub retention_exemptions: Vec<RetentionExemption>,
+    #[serde(default)]
+    pub duplicate_backups: DuplicatePolicy,
+    #[serde(default = "default_true")]
+    pub skip_unchanged_backups: bool, // Automatic backups only, manual ones are always made
+    #[serde(default)]
+    pub cloud_backend: CloudBackend,
+    #[serde(default)]
+    pub cloud_remote_name: String, // Name of the selected destination, empty shows the backend's name
+    #[serde(default)]
+    pub cloud_remotes: Vec<CloudRemote>, // The other destinations, see Config::select_remote
+    #[serde(default)]
+    pub mirror_to_all_remotes: bool, // Uploads to the selected destination are repeated on the others
+    #[serde(default)]
+    pub sftp_config: SftpConfig,
+    #[serde(default)]
+    pub backup_encryption: BackupEncryptionConfig,
+    #[serde(default)]
+    pub folder_target: FolderTargetConfig,
+    #[serde(default)]
+    pub game_tags: Vec<GameTags>,
+    #[serde(default)]
+    pub game_details: Vec<GameDetailsEntry>,
+    #[serde(default)]
+    pub ignored_anomalies: Vec<IgnoredAnomaly>,
+    #[serde(default)]
+    pub cloud_excluded_tags: Vec<String>, // Games carrying any of these tags are never uploaded
+    #[serde(default)]
+    pub cloud_excluded_games: Vec<String>, // Keys of games left out of cloud uploads and downloads
+    #[serde(default)]
+    pub exclude_sync_client_saves: bool, // Don't upload saves OneDrive, Dropbox etc. already sync
+    #[serde(default)]
+    pub sync_rules_file: Option<PathBuf>, // Community rules mapping games to their save locations, see sync_rules
+    #[serde(default)]
+    pub settings_games: Vec<String>, // Keys of games whose config files are backed up with their saves
+    #[serde(default)]
+    pub lan_server: LanServerConfig,
+    #[serde(default)]
+    pub replica_check: ReplicaCheckConfig,
+    #[serde(default)]
+    pub volume_size_mb: u64, // Split archives into parts of this size, 0 keeps them whole
+    #[serde(default)]
+    pub filename_timestamp: FilenameTimestampConfig,
+    #[serde(default)]
+    pub cloud_conflict_policy: ConflictPolicy,
+    #[serde(default = "AppLink::defaults")]
+    pub app_links: Vec<AppLink>,
+    #[serde(default)]
+    pub protected_saves: Vec<PathBuf>, // Save folders made read-only on disk, lifted for restores and syncs
+    #[serde(default)]
+    pub installed_steam_apps: Vec<u32>, // As of the last scan, to notice uninstalls
+    #[serde(default)]
+    pub archived_apps: Vec<u32>, // Uninstalled games whose final backup was taken
+    #[serde(default)]
+    pub worker_threads: usize, // Threads for hashing and compressing archives, 0 picks from the CPU count
+    #[serde(default)]
+    pub incremental: IncrementalConfig,
+    #[serde(default)]
+    pub dedup_store: DedupStoreConfig,
+    #[serde(default)]
+    pub archive_format: ArchiveFormat,
+    #[serde(default)]
+    pub custom_games: Vec<CustomGame>, // Save folders added by hand or from a detected game
+    #[serde(default)]
+    pub game_detection: GameDetectionConfig,
+    #[serde(default)]
+    pub save_watcher: SaveWatcherConfig,
+    #[serde(default)]
+    pub steam_app_ids: Vec<AppIdAssignment>, // Non-Steam saves known to be a Steam game, see app_lookup.rs
+}
+
+/// A web page about a Steam game, offered as a link next to its app ID
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppLink {
+    pub label: String,
+    pub url: String, // {app_id} is replaced with the game's app ID
+    pub enabled: bool,
+}
+
+impl AppLink {
+    fn new(label: &str, url: &str, enabled: bool) -> Self {
+        Self { label: label.to_string(), url: url.to_string(), enabled }
+    }
+
+    pub fn defaults() -> Vec<AppLink> {
+        vec![
+            Self::new("Steam Store", "https://store.steampowered.com/app/{app_id}/", true),
+            Self::new("SteamDB", "https://steamdb.info/app/{app_id}/", true),
+            Self::new("ProtonDB", "https://www.protondb.com/app/{app_id}", true),
+            Self::new("PCGamingWiki", "https://www.pcgamingwiki.com/api/appid.php?appid={app_id}", false),
+        ]
+    }
+
+    pub fn url_for(&self, app_id: u32) -> String {
+        self.url.replace("{app_id}", &app_id.to_string())
+    }
+}
+
+/// User labels for one game, e.g. "playing now", "finished", "kids"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameTags {
+    pub game_key: String, // Same key the backups use, see BackupManager::key_for_save
+    pub tags: Vec<String>,
+}
+
+/// Oddities the userdata report looks for, see anomalies.rs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AnomalyKind {
+    HugeRemote,   // The remote folder is far larger than Steam Cloud saves usually are
+    NotInstalled, // No appmanifest for the app ID in any library
+    Stale,        // Nothing changed in over a year
+}
+
+/// An anomaly the user chose to ignore, for one userdata app folder
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IgnoredAnomaly {
+    pub kind: AnomalyKind,
+    pub path: PathBuf,
+}
+
+/// Genres, release year and platforms of a game, from the Steam Store or entered by hand
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GameDetails {
+    pub genres: Vec<String>,
+    pub release_year: Option<i32>,
+    pub platforms: Vec<String>, // "Windows", "macOS", "Linux"
+}
+
+/// Details entered by hand, for games the Steam Store doesn't know
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameDetailsEntry {
+    pub game_key: String, // Same key as GameTags
+    pub details: GameDetails,
+}
+
+impl GameDetails {
+    pub fn is_empty(&self) -> bool {
+        self.genres.is_empty() && self.release_year.is_none() && self.platforms.is_empty()
+    }
+}
+
+/// Which remote the Cloud tab syncs with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum CloudBackend {
+    WebDav, // Koofr, Nextcloud, ownCloud, ... see koofr_config
+    Sftp,
+    Folder, // Any mounted path: NAS share, USB drive, Syncthing folder
+}
+
+/// What Full Sync does with an archive that changed both locally and in the cloud
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ConflictPolicy {
+    KeepNewest, // By modification time; keeps both if the server doesn't report one
+    KeepBoth,   // The cloud copy is downloaded under a new name, then the local one uploaded
+    Prompt,     // Transfer everything else and ask about the conflicts afterwards
+}
+
+/// Connection settings of a cloud destination that isn't selected right now
+///
+/// The selected one lives in `cloud_backend`, `koofr_config`, `sftp_config` and `folder_target`;
+/// encryption, retries and the automatic sync schedule apply to all destinations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudRemote {
+    pub name: String, // e.g. "Koofr" or "Home NAS"
+    pub backend: CloudBackend,
+    pub webdav: KoofrConfig,
+    pub sftp: SftpConfig,
+    pub folder: FolderTargetConfig,
+}
+
+/// A local or mounted folder used like a cloud destination
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolderTargetConfig {
+    pub enabled: bool,
+    pub path: PathBuf,
+}
+
+/// Read-only access to the backup folder for other devices on the local network, see lan_server.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanServerConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub username: String,
+    pub password: String, // Empty: anyone on the network can fetch backups
+}
+
+impl Default for LanServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8421,
+            username: "guardian".to_string(),
+            password: String::new(),
+        }
+    }
+}
+
+/// A game the scanners don't recognise, tracked through the one folder its saves are in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomGame {
+    pub name: String,
+    pub save_path: PathBuf,
+    #[serde(default)]
+    pub exe_name: Option<String>, // Program seen writing there, e.g. "Celeste.exe"
+}
+
+/// The Steam app a non-Steam save belongs to, confirmed by the user
+///
+/// Gives the save the game's Steam name and pairs it with the Steam save by app ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppIdAssignment {
+    pub save_path: PathBuf,
+    pub app_id: u32,
+}
+
+/// Watching running programs for games the scanners miss, see game_detection.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameDetectionConfig {
+    pub enabled: bool,
+    pub interval_secs: u64, // How often the save locations are looked at
+    #[serde(default)]
+    pub ignored_folders: Vec<PathBuf>, // Offered before and declined, never offered again
+}
+
+impl Default for GameDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 30,
+            ignored_folders: Vec::new(),
+        }
+    }
+}
+
+/// Backups taken by themselves when a save folder changes, see save_watcher.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveWatcherConfig {
+    pub enabled: bool,
+    pub quiet_minutes: u32, // Wait this long after the last write, so a session's saves end up in one backup
+}
+
+impl Default for SaveWatcherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quiet_minutes: 3,
+        }
+    }
+}
+
+/// SSH file transfer to a server of your own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SftpConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub auth: SftpAuth,
+    pub password: String,
+    pub key_path: PathBuf, // Private key, e.g. ~/.ssh/id_ed25519
+    pub key_passphrase: String,
+    pub remote_path: String, // Folder holding the backups, relative to the login folder unless absolute
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SftpAuth {
+    Password,
+    KeyFile,
+}
+
+/// How archives are recompressed before upload to take less cloud storage, see cloud/recompress.rs
+///
+/// The local archive is never changed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum CloudRecompression {
+    Off,
+    Zstd,       // Zstandard at a high level, turned back into Deflate on download
+    DeflateMax, // Deflate at the highest level, opens in any zip tool as it is
+}
+
+/// Container and compression of new backup archives; existing ones keep theirs
+///
+/// Restores read every format, told apart by the archive's extension.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ArchiveFormat {
+    Zip,    // Deflate, opens in any zip tool
+    TarZst, // tar compressed with Zstandard, quicker and smaller for large binary saves
+    SevenZ, // 7z with LZMA2, the smallest and slowest
+}
+
+/// What to do when a new backup has exactly the same files as the game's previous one
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DuplicatePolicy {
+    Keep,     // Store it like any other backup
+    HardLink, // Keep the new backup entry but share the archive with the previous one
+    Skip,     // Don't create a backup, report "no changes since last backup"
+}
+
+/// Keeps backups matching a rule out of retention cleanup, like pinning does for single backups
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetentionExemption {
+    pub kind: ExemptionKind,
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ExemptionKind {
+    TagEquals,           // e.g. "milestone"
+    DescriptionContains, // e.g. "pre-DLC"
+}
+
+/// Periodic re-checking of archives against their checksums to catch bit-rot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationScheduleConfig {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub batch_size: usize, // Archives checked per run, the next run continues where this one stopped
+}
+
+/// Format of the creation time in backup file names, e.g. `Game_123_20240101_120000.zip`
+///
+/// Only the file name changes; `created_at` in the metadata is always UTC.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FilenameTimestampConfig {
+    pub format: String,   // chrono strftime syntax
+    pub local_time: bool, // This PC's clock instead of UTC
+}
+
+/// Periodic comparison of the cloud listing with the local backups, see cloud/replicas.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaCheckConfig {
+    pub enabled: bool,
+    pub interval_hours: u32,
+}
+
+/// Backups that only store the files changed since the game's previous backup, see incremental.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalConfig {
+    pub enabled: bool,
+    pub full_every: u32, // Incremental backups in a row before the next full one
+}
+
+/// Large files shared between backups through a store under the backup folder, see dedup_store.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupStoreConfig {
+    pub enabled: bool,
+    pub min_file_mb: u64, // Smaller files stay in the archive
+}
+
+/// Rewrites the start of a backup's original path when restoring on another machine
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PathRemap {
+    pub from: String, // e.g. C:\Users\Old
+    pub to: String,   // e.g. C:\Users\New
+}
+
+/// Handling of save folders on network drives / NAS shares
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    pub retry_attempts: u32,
+    pub retry_delay_ms: u64, // Multiplied by the attempt number
+    pub offline_timeout_secs: u64,
+    pub skip_in_quick_scan: bool,
+}
+
+/// Retrying cloud requests that failed with a timeout, dropped connection or server error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudRetryConfig {
+    pub attempts: u32, // Including the first one
+    pub initial_delay_ms: u64, // Doubled after every failed attempt
+    pub max_delay_ms: u64,
+    pub jitter: bool, // Randomize each delay between half and all of it
+}
+
+/// Cloud transfer rate limits, so a sync doesn't saturate the connection; 0 = unlimited
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BandwidthLimit {
+    pub upload_kbps: u32, // KB/s
+    pub download_kbps: u32,
+}
+
+/// Encrypting archives before they leave the machine, see cloud/crypto.rs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CloudEncryptionConfig {
+    pub enabled: bool,
+    pub passphrase: String, // Without it the uploaded backups can't be restored
+}
+
+/// Passphrase protection of the local backup archives, see encryption.rs
+///
+/// The passphrase itself is kept in the system keyring or asked for once per session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupEncryptionConfig {
+    pub enabled: bool, // Encrypt new backups; existing ones stay as they are
+    pub use_keyring: bool, // Remember the passphrase in the system keyring instead of asking
+    #[serde(default)]
+    pub verifier: Option<String>, // Sealed known text, to check a typed passphrase
+}
+
+/// Which online services the app may contact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnlineConfig {
+    pub offline_mode: bool, // No requests at all: names come from the cache and Steam's own files
+    pub steam_store_api: bool,
+    pub steamspy: bool,
+    #[serde(default = "default_api_cache_days")]
+    pub api_cache_days: u32, // How long saved API responses are reused before asking again; 0 = don't save
+}
+
+/// An online service looked up for game information
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApiSource {
+    SteamStore,
+    SteamSpy,
+}
+
+impl OnlineConfig {
+    /// Check whether a source may be contacted; offline mode overrides every toggle
+    pub fn allows(&self, source: ApiSource) -> bool {
+        !self.offline_mode && match source {
+            ApiSource::SteamStore => self.steam_store_api,
+            ApiSource::SteamSpy => self.steamspy,
+        }
+    }
+
+    /// Whether any source may be contacted at all
+    pub fn allows_any(&self) -> bool {
+        self.allows(ApiSource::SteamStore) || self.allows(ApiSource::SteamSpy)
+    }
+}
+
+/// Junk exclusion settings, see exclusions.rs for the pattern syntax
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExclusionConfig {
+    pub enabled: bool,
+    pub extra_patterns: String, // Comma-separated, added to the engine defaults
+    pub game_overrides: Vec<GameExclusionOverride>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameExclusionOverride {
+    pub game_name: String,
+    pub use_defaults: bool,
+    pub patterns: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeWatchdogConfig {
+    pub enabled: bool,
+    pub max_size_mb: u64,        // 0 = no global limit
+    pub max_growth_percent: u32, // Growth since last backup, 0 = disabled
+    pub game_limits: Vec<GameSizeLimit>,
+    #[serde(default = "default_max_loss_percent")]
+    pub max_loss_percent: u32, // Shrink since last backup treated as possible save loss, 0 = disabled
+}
+
+/// Per-game override of the global watchdog thresholds
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameSizeLimit {
+    pub game_name: String,
+    pub max_size_mb: u64,
+    pub max_growth_percent: u32,
+}
+
+/// A named person sharing this install, with their own backups and cloud folder
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserProfile {
+    pub name: String,
+    pub cloud_folder: String,
+    pub hidden_saves: Vec<PathBuf>, // Saves this profile doesn't want to see
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KoofrConfig {
+    pub enabled: bool,
+    pub server_url: String,
+    pub username: String,
+    pub password: String, // In a real app, this should be encrypted
+    pub sync_folder: String,
+    pub auto_sync: bool,
+    pub sync_interval_minutes: u32,
+    #[serde(default)]
+    pub server: WebDavServer,
+}
+
+/// Kind of WebDAV server behind the cloud settings; only changes defaults and hints
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WebDavServer {
+    Koofr,
+    Nextcloud,
+    OwnCloud,
+    Generic, // Any standards-compliant WebDAV server
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Theme {
+    Light,
+    Dark,
+    System,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            steam_path: default_steam_path(),
+            backup_path: dirs::document_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("SaveGuardianBackups"),
+            custom_locations: Vec::new(),
+            auto_backup: true,
+            backup_retention_days: 30,
+            theme: Theme::Dark,
+            window_size: (1200.0, 800.0),
+            window_position: None,
+            koofr_config: KoofrConfig::default(),
+            profiles: Vec::new(),
+            active_profile: None,
+            read_only_mode: false,
+            sign_backups: false,
+            size_watchdog: SizeWatchdogConfig::default(),
+            exclusions: ExclusionConfig::default(),
+            network: NetworkConfig::default(),
+            online: OnlineConfig::default(),
+            cloud_retry: CloudRetryConfig::default(),
+            cloud_encryption: CloudEncryptionConfig::default(),
+            cloud_recompression: CloudRecompression::default(),
+            bandwidth: BandwidthLimit::default(),
+            machine_id: new_machine_id(),
+            keep_latest_links: true,
+            path_remaps: Vec::new(),
+            verification: VerificationScheduleConfig::default(),
+            sync_pairs: Vec::new(),
+            retention_exemptions: Vec::new(),
+            duplicate_backups: DuplicatePolicy::default(),
+            skip_unchanged_backups: true,
+            cloud_backend: CloudBackend::default(),
+            cloud_remote_name: String::new(),
+            cloud_remotes: Vec::new(),
+            mirror_to_all_remotes: false,
+            sftp_config: SftpConfig::default(),
+            backup_encryption: BackupEncryptionConfig::default(),
+            folder_target: FolderTargetConfig::default(),
+            game_tags: Vec::new(),
+            game_details: Vec::new(),
+            ignored_anomalies: Vec::new(),
+            cloud_excluded_tags: Vec::new(),
+            cloud_excluded_games: Vec::new(),
+            exclude_sync_client_saves: false,
+            sync_rules_file: None,
+            settings_games: Vec::new(),
+            lan_server: LanServerConfig::default(),
+            replica_check: ReplicaCheckConfig::default(),
+            volume_size_mb: 0,
+            filename_timestamp: FilenameTimestampConfig::default(),
+            cloud_conflict_policy: ConflictPolicy::default(),
+            app_links: AppLink::defaults(),
+            protected_saves: Vec::new(),
+            installed_steam_apps: Vec::new(),
+            archived_apps: Vec::new(),
+            worker_threads: 0,
+            incremental: IncrementalConfig::default(),
+            dedup_store: DedupStoreConfig::default(),
+            archive_format: ArchiveFormat::default(),
+            custom_games: Vec::new(),
+            game_detection: GameDetectionConfig::default(),
+            save_watcher: SaveWatcherConfig::default(),
+            steam_app_ids: Vec::new(),
+        }
+    }
+}
+
+impl Default for VerificationScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            batch_size: 10,
+        }
+    }
+}
+
+impl FilenameTimestampConfig {
+    pub const DEFAULT_FORMAT: &'static str = "%Y%m%d_%H%M%S";
+}
+
+impl Default for FilenameTimestampConfig {
+    fn default() -> Self {
+        Self {
+            format: Self::DEFAULT_FORMAT.to_string(),
+            local_time: false,
+        }
+    }
+}
+
+impl Default for DedupStoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_file_mb: 1,
+        }
+    }
+}
+
+impl Default for IncrementalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            full_every: 10,
+        }
+    }
+}
+
+impl Default for ReplicaCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+        }
+    }
+}
+
+impl PairSide {
+    pub fn other(self) -> Self {
+        match self {
+            PairSide::Left => PairSide::Right,
+            PairSide::Right => PairSide::Left,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PairSide::Left => "left",
+            PairSide::Right => "right",
+        }
+    }
+}
+
+impl RetentionExemption {
+    /// Whether the rule covers this backup, ignoring case
+    pub fn matches(&self, backup: &BackupInfo) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return false;
+        }
+        match self.kind {
+            ExemptionKind::TagEquals => backup.tags.iter().any(|t| t.trim().to_lowercase() == pattern),
+            ExemptionKind::DescriptionContains => backup.description.as_deref()
+                .map_or(false, |d| d.to_lowercase().contains(&pattern)),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            ExemptionKind::TagEquals => format!("tag is \"{}\"", self.pattern.trim()),
+            ExemptionKind::DescriptionContains => format!("description contains \"{}\"", self.pattern.trim()),
+        }
+    }
+}
+
+impl ExemptionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExemptionKind::TagEquals => "Tag is",
+            ExemptionKind::DescriptionContains => "Description contains",
+        }
+    }
+}
+
+impl Default for CloudBackend {
+    fn default() -> Self {
+        CloudBackend::WebDav
+    }
+}
+
+impl Default for ConflictPolicy {
+    fn default() -> Self {
+        ConflictPolicy::KeepBoth
+    }
+}
+
+impl Default for SftpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 22,
+            username: String::new(),
+            auth: SftpAuth::KeyFile,
+            password: String::new(),
+            key_path: dirs::home_dir()
+                .map(|h| h.join(".ssh").join("id_ed25519"))
+                .unwrap_or_default(),
+            key_passphrase: String::new(),
+            remote_path: "SaveGuardian".to_string(),
+        }
+    }
+}
+
+impl Default for DuplicatePolicy {
+    fn default() -> Self {
+        DuplicatePolicy::HardLink
+    }
+}
+
+impl DuplicatePolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DuplicatePolicy::Keep => "Keep as a separate archive",
+            DuplicatePolicy::HardLink => "Hard-link to the previous archive",
+            DuplicatePolicy::Skip => "Skip the backup",
+        }
+    }
+}
+
+impl Default for CloudRecompression {
+    fn default() -> Self {
+        CloudRecompression::Off
+    }
+}
+
+impl CloudRecompression {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CloudRecompression::Off => "Upload archives as they are",
+            CloudRecompression::Zstd => "Recompress with Zstandard",
+            CloudRecompression::DeflateMax => "Recompress with maximum Deflate",
+        }
+    }
+}
+
+impl Default for ArchiveFormat {
+    fn default() -> Self {
+        ArchiveFormat::Zip
+    }
+}
+
+impl ArchiveFormat {
+    pub const ALL: [ArchiveFormat; 3] = [ArchiveFormat::Zip, ArchiveFormat::TarZst, ArchiveFormat::SevenZ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "ZIP (Deflate)",
+            ArchiveFormat::TarZst => "tar.zst (Zstandard)",
+            ArchiveFormat::SevenZ => "7z (LZMA2)",
+        }
+    }
+
+    /// File name extension, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarZst => "tar.zst",
+            ArchiveFormat::SevenZ => "7z",
+        }
+    }
+
+    /// Format of an archive by its file name, None for other files
+    pub fn of_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| {
+            let suffix = format!(".{}", format.extension());
+            name.len() > suffix.len() && name.as_bytes()[name.len() - suffix.len()..].eq_ignore_ascii_case(suffix.as_bytes())
+        })
+    }
+
+    /// Format of a backup's archive; metadata from before other formats always points at a ZIP
+    pub fn of_path(path: &Path) -> Self {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Self::of_name)
+            .unwrap_or(ArchiveFormat::Zip)
+    }
+
+    /// An archive's file name without its extension, the backup ID
+    pub fn strip_extension(name: &str) -> &str {
+        match Self::of_name(name) {
+            Some(format) => &name[..name.len() - format.extension().len() - 1],
+            None => name,
+        }
+    }
+}
+
+impl Default for SyncStrategy {
+    fn default() -> Self {
+        SyncStrategy::Replace
+    }
+}
+
+impl Default for SyncSchedule {
+    fn default() -> Self {
+        SyncSchedule::Manual
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            retry_attempts: 3,
+            retry_delay_ms: 500,
+            offline_timeout_secs: 10,
+            skip_in_quick_scan: true,
+        }
+    }
+}
+
+impl Default for CloudRetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            initial_delay_ms: 1000,
+            max_delay_ms: 30_000,
+            jitter: true,
+        }
+    }
+}
+
+impl Default for OnlineConfig {
+    fn default() -> Self {
+        Self {
+            offline_mode: false,
+            steam_store_api: true,
+            steamspy: true,
+            api_cache_days: default_api_cache_days(),
+        }
+    }
+}
+
+impl Default for ExclusionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra_patterns: String::new(),
+            game_overrides: Vec::new(),
+        }
+    }
+}
+
+impl Default for SizeWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: 2048,
+            max_growth_percent: 50,
+            game_limits: Vec::new(),
+            max_loss_percent: default_max_loss_percent(),
+        }
+    }
+}
+
+impl Default for KoofrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server_url: "https://app.koofr.net/dav/Koofr".to_string(),
+            username: String::new(),
+            password: String::new(),
+            sync_folder: "/SaveGuardian".to_string(),
+            auto_sync: false,
+            sync_interval_minutes: 30,
+            server: WebDavServer::Koofr,
+        }
+    }
+}
+
+impl Default for WebDavServer {
+    fn default() -> Self {
+        WebDavServer::Koofr
+    }
+}
+
+impl WebDavServer {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebDavServer::Koofr => "Koofr",
+            WebDavServer::Nextcloud => "Nextcloud",
+            WebDavServer::OwnCloud => "ownCloud",
+            WebDavServer::Generic => "WebDAV",
+        }
+    }
+
+    /// Example server URL shown under the URL field
+    pub fn url_hint(&self) -> &'static str {
+        match self {
+            WebDavServer::Koofr => "Use: https://app.koofr.net/dav/Koofr",
+            WebDavServer::Nextcloud => "e.g. https://cloud.example.com/remote.php/dav/files/USERNAME",
+            WebDavServer::OwnCloud => "e.g. https://cloud.example.com/remote.php/webdav",
+            WebDavServer::Generic => "The WebDAV root of your server, e.g. https://nas.local/webdav",
+        }
+    }
+
+    pub fn username_hint(&self) -> &'static str {
+        match self {
+            WebDavServer::Koofr => "Your Koofr email address",
+            _ => "Your account name on the server",
+        }
+    }
+
+    pub fn password_hint(&self) -> &'static str {
+        match self {
+            WebDavServer::Koofr => "Generate app password at: Account Settings > Passwords",
+            WebDavServer::Nextcloud | WebDavServer::OwnCloud => "Create an app password at: Settings > Security",
+            WebDavServer::Generic => "Your WebDAV password",
+        }
+    }
+
+    /// Whether the server has an API for public download links, see `CloudProvider::share_link`
+    pub fn can_share(&self) -> bool {
+        !matches!(self, WebDavServer::Generic)
+    }
+}
+
+impl UserProfile {
+    pub fn new(name: String) -> Self {
+        let cloud_folder = format!("/SaveGuardian/{}", Self::sanitize_name(&name));
+        Self {
+            name,
+            cloud_folder,
+            hidden_saves: Vec::new(),
+        }
+    }
+
+    /// Get a filesystem-safe version of the profile name
+    pub fn sanitize_name(name: &str) -> String {
+        let safe = crate::normalize::file_safe(name.trim());
+        // "." or ".." would name the shared backup folder or the one above it
+        if safe.chars().all(|c| c == '.') {
+            return safe.replace('.', "_");
+        }
+        safe
+    }
+}
+
+impl BackupInfo {
+    /// Whether this is an archive of a whole Steam profile, see `GameSave::steam_profile`
+    pub fn is_steam_profile(&self) -> bool {
+        is_profile_path(&self.original_path, self.app_id, self.steam_user_id.as_deref(), &self.save_type)
+    }
+
+    /// Get a display name for the original path
+    pub fn display_original_path(&self) -> String {
+        let path_str = self.original_path.to_string_lossy();
+        
+        // Check if this is a downloaded backup
+        if path_str.contains("Downloaded from cloud") || path_str.contains("cloud") {
+            // Try to extract the original path from description or use a better fallback
+            if let Some(ref desc) = self.description {
+                if desc.contains("Downloaded from cloud") {
+                    return "📥 Downloaded from Cloud Storage".to_string();
+                }
+            }
+            return "📥 Cloud Download".to_string();
+        }
+        
+        // For regular backups, show the full original path
+        path_str.to_string()
+    }
+    
+    /// Get a formatted size string
+    pub fn format_size(&self) -> String {
+        if self.size < 1024 {
+            format!("{} B", self.size)
+        } else if self.size < 1024 * 1024 {
+            format!("{:.1} KB", self.size as f64 / 1024.0)
+        } else if self.size < 1024 * 1024 * 1024 {
+            format!("{:.1} MB", self.size as f64 / (1024.0 * 1024.0))
+        } else {
+            format!("{:.1} GB", self.size as f64 / (1024.0 * 1024.0 * 1024.0))
+        }
+    }
+    
+    /// Check if this backup was downloaded from cloud
+    pub fn is_cloud_download(&self) -> bool {
+        let path_str = self.original_path.to_string_lossy();
+        path_str.contains("Downloaded from cloud") || path_str.contains("cloud") ||
+        self.description.as_ref().map_or(false, |d| d.contains("Downloaded from cloud"))
+    }
+}
+
+impl SyncPair {
+    pub fn new(left: Option<GameSave>, right: Option<GameSave>, game_name: String) -> Self {
+        let app_id = left.as_ref().and_then(|s| s.app_id).or_else(|| right.as_ref().and_then(|s| s.app_id));
+        let sync_direction = match (&left, &right) {
+            (Some(_), None) => SyncDirection::LeftToRight,
+            (None, Some(_)) => SyncDirection::RightToLeft,
+            _ => SyncDirection::Bidirectional,
+        };
+
+        Self {
+            left,
+            right,
+            game_name,
+            app_id,
+            last_synced: None,
+            sync_direction,
+            strategy: SyncStrategy::Replace,
+            locked_side: None,
+            schedule: SyncSchedule::Manual,
+            last_attempt: None,
+            last_result: None,
+            from_rule: false,
+        }
+    }
+
+    /// Next time the scheduler will sync this pair, for interval schedules
+    pub fn next_run(&self) -> Option<DateTime<Utc>> {
+        match self.schedule {
+            SyncSchedule::EveryMinutes(minutes) => Some(match self.last_attempt {
+                Some(last) => last + chrono::Duration::minutes(minutes.max(1) as i64),
+                None => Utc::now(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether an interval schedule says this pair should sync now
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.left.is_some() && self.right.is_some() && self.next_run().map_or(false, |next| next <= now)
+    }
+
+    /// Whether syncing in this direction would write into the locked side
+    pub fn allows_direction(&self, direction: &SyncDirection) -> bool {
+        match (self.locked_side, direction) {
+            (Some(PairSide::Left), SyncDirection::RightToLeft) => false,
+            (Some(PairSide::Right), SyncDirection::LeftToRight) => false,
+            _ => true,
+        }
+    }
+
+    /// Lock one side as the read-only master, pointing the pair away from it
+    pub fn lock_side(&mut self, side: Option<PairSide>) {
+        self.locked_side = side;
+        if !self.allows_direction(&self.sync_direction) {
+            self.sync_direction = match side {
+                Some(PairSide::Left) => SyncDirection::LeftToRight,
+                _ => SyncDirection::RightToLeft,
+            };
+        }
+    }
+
+    /// Re-read both sides from disk, sizes and timestamps go stale between sessions
+    pub fn refresh(&mut self) {
+        for side in [&mut self.left, &mut self.right].into_iter().flatten() {
+            let mut fresh = GameSave::new(side.name.clone(), side.save_path.clone(), side.save_type.clone(), side.app_id);
+            fresh.steam_user_id = side.steam_user_id.take();
+            fresh.is_network = side.is_network;
+            fresh.sandbox = side.sandbox.take();
+            fresh.origin = side.origin;
+            *side = fresh;
+        }
+    }
+}
+
+impl GameSave {
+    pub fn new(name: String, path: PathBuf, save_type: SaveType, app_id: Option<u32>) -> Self {
+        let metadata = std::fs::metadata(&path).ok();
+        let last_modified = metadata.as_ref().and_then(|m| {
+            m.modified()
+                .ok()
+                .map(|t| DateTime::<Utc>::from(t))
+        });
+        let size = calculate_path_size(&path);
+
+        Self {
+            name,
+            app_id,
+            save_type,
+            save_path: path,
+            last_modified,
+            size,
+            backup_count: 0,
+            is_synced: false,
+            steam_user_id: None,
+            is_network: false,
+            sandbox: None,
+            origin: None,
+        }
+    }
+
+    /// A whole `userdata/<user id>` folder, backed up and restored as one unit
+    ///
+    /// The user ID is part of the backup key, so every account keeps its own profile backups.
+    pub fn steam_profile(user_id: &str, userdata_path: PathBuf) -> Self {
+        let mut save = Self::new(STEAM_PROFILE_NAME.to_string(), userdata_path, SaveType::Steam, None);
+        save.steam_user_id = Some(user_id.to_string());
+        save
+    }
+
+    pub fn is_steam_profile(&self) -> bool {
+        is_profile_path(&self.save_path, self.app_id, self.steam_user_id.as_deref(), &self.save_type)
+    }
+
+    pub fn format_size(&self) -> String {
+        format_size(self.size)
+    }
+
+    pub fn display_name(&self) -> String {
+        match &self.app_id {
+            Some(id) => format!("{} ({})", self.name, id),
+            None => self.name.clone(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_loss_percent() -> u32 {
+    80
+}
+
+fn default_api_cache_days() -> u32 {
+    30
+}
+
+fn new_machine_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+/// Name of this computer, as shown next to backups made on it
+pub fn machine_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Unknown machine".to_string())
+}
+
+/// Where Steam keeps userdata by default on this platform
+fn default_steam_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/Steam/userdata")
+    } else if cfg!(target_os = "linux") {
+        home.join(".local/share/Steam/userdata")
+    } else {
+        PathBuf::from(r"C:\Program Files (x86)\Steam\userdata")
+    }
+}
+
+/// Show a folder in Explorer, Finder or the desktop's file manager
+pub fn open_in_file_manager(path: &std::path::Path) -> Result<()> {
+    let program = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program).arg(path).spawn()?;
+    Ok(())
+}
+
+/// Total size of a file, or of every file below a directory
+pub fn calculate_path_size(path: &std::path::Path) -> u64 {
+    if path.is_file() {
+        return std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+
+    walkdir::WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Format a byte count for display
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else if bytes < 1024 * 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
+/// Format a duration for display, e.g. "40 s", "3 min" or "1 h 5 min"
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 1 {
+        "under 1 s".to_string()
+    } else if secs < 90 {
+        format!("{} s", secs)
+    } else if secs < 3600 {
+        format!("{} min", (secs + 30) / 60)
+    } else {
+        format!("{} h {} min", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SaveGuardianError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    
+    #[error("Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
+    
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    
+    #[error("Path not found: {0}")]
+    PathNotFound(PathBuf),
+    
+    #[error("Invalid Steam user ID: {0}")]
+    InvalidSteamUser(String),
+    
+    #[error("Save operation failed: {0}")]
+    SaveOperationFailed(String),
+    
+    #[error("Backup operation failed: {0}")]
+    BackupOperationFailed(String),
+    
+    #[error("{provider} failed to {op}: {}", describe_cloud_failure(.status, .detail, .url))]
+    Cloud {
+        provider: String,       // e.g. "Koofr", "Nextcloud", "SFTP"
+        op: String,             // What was being done, e.g. "upload Elden Ring/backup.zip"
+        status: Option<u16>,    // HTTP status of the answer, None when there was none
+        url: String,            // Where the request went
+        detail: Option<String>, // Error text of the server or the connection
+    },
+    
+    #[error("{0}")]
+    UnhealthySource(crate::health::SourceCheck),
+    
+    #[error("Cancelled")]
+    Cancelled,
+    
+    #[error("Encrypted backups are locked, enter the backup passphrase first")]
+    Locked,
+}
+
+impl SaveGuardianError {
+    /// A cloud request the server answered with an error status
+    pub fn cloud_http(provider: &str, op: &str, status: u16, url: &str, detail: Option<String>) -> Self {
+        SaveGuardianError::Cloud {
+            provider: provider.to_string(),
+            op: op.to_string(),
+            status: Some(status),
+            url: url.to_string(),
+            detail: detail.filter(|d| !d.trim().is_empty()),
+        }
+    }
+
+    /// A cloud request that got no usable answer: no connection, a dropped one, a refused login
+    pub fn cloud_failure(provider: &str, op: &str, url: &str, detail: impl std::fmt::Display) -> Self {
+        SaveGuardianError::Cloud {
+            provider: provider.to_string(),
+            op: op.to_string(),
+            status: None,
+            url: url.to_string(),
+            detail: Some(detail.to_string()),
+        }
+    }
+
+    /// HTTP status of a failed cloud request
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            SaveGuardianError::Cloud { status, .. } => *status,
+            _ => None,
+        }
+    }
+}
+
+/// e.g. "HTTP 401 Unauthorized (https://app.koofr.net/dav/Koofr/)"
+fn describe_cloud_failure(status: &Option<u16>, detail: &Option<String>, url: &str) -> String {
+    let mut text = match status {
+        Some(status) => match reqwest::StatusCode::from_u16(*status).ok().and_then(|s| s.canonical_reason()) {
+            Some(reason) => format!("HTTP {} {}", status, reason),
+            None => format!("HTTP {}", status),
+        },
+        None => String::new(),
+    };
+    if let Some(detail) = detail {
+        if !text.is_empty() {
+            text.push_str(" - ");
+        }
+        text.push_str(detail.trim());
+    }
+    if !url.is_empty() {
+        text.push_str(&format!(" ({})", url));
+    }
+    text
+}
+
+pub type Result<T> = std::result::Result<T, SaveGuardianError>;
\ No newline at end of file
@@ -0,0 +1,237 @@
+use crate::types::*;
+use crate::integrity;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use log::{debug, info};
+
+/// Largest file FAT32 can hold is 4 GiB minus one byte; this leaves room to spare
+pub const FAT32_VOLUME_MB: u64 = 4095;
+
+/// Path of one part of a split archive, numbered from 1: `<id>.zip.001`
+pub fn volume_path(archive: &Path, number: usize) -> PathBuf {
+    let mut name = archive.as_os_str().to_os_string();
+    name.push(format!(".{:03}", number));
+    PathBuf::from(name)
+}
+
+/// Whether a file name is a part of a split archive, like `Game_steam_20240101_120000.zip.002`
+pub fn is_volume_name(name: &str) -> bool {
+    match name.rsplit_once('.') {
+        Some((stem, number)) => ArchiveFormat::of_name(stem).is_some() && number.len() == 3 && number.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+/// Whether a file name is a backup archive (ZIP, tar.zst or 7z) or a part of one
+pub fn is_archive_name(name: &str) -> bool {
+    ArchiveFormat::of_name(name).is_some() || is_volume_name(name)
+}
+
+/// The files on disk holding a backup's archive: its parts, or the single archive
+pub fn archive_files(backup_info: &BackupInfo) -> Vec<PathBuf> {
+    if backup_info.volumes.is_empty() {
+        vec![backup_info.backup_path.clone()]
+    } else {
+        backup_info.volumes.clone()
+    }
+}
+
+/// Whether every file of a backup's archive is still there
+pub fn archive_exists(backup_info: &BackupInfo) -> bool {
+    archive_files(backup_info).iter().all(|path| path.exists())
+}
+
+/// Whether a file on disk is (part of) this backup's archive
+pub fn holds(backup_info: &BackupInfo, path: &Path) -> bool {
+    backup_info.backup_path == path || backup_info.volumes.iter().any(|v| v == path)
+}
+
+/// Combined size of a backup's archive files on disk
+pub fn archive_size(backup_info: &BackupInfo) -> u64 {
+    archive_files(backup_info).iter().map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0)).sum()
+}
+
+/// SHA-256 of a backup's archive, split or not; the same hash either way
+pub fn sha256(backup_info: &BackupInfo) -> Result<String> {
+    sha256_files(&archive_files(backup_info))
+}
+
+/// SHA-256 of the parts of a split archive read back to back
+pub fn sha256_files(paths: &[PathBuf]) -> Result<String> {
+    let mut reader = VolumeReader::open(paths)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(integrity::hex_encode(&hasher.finalize()))
+}
+
+/// Open a backup's archive for reading, with its parts read as one file
+pub fn open(backup_info: &BackupInfo) -> io::Result<VolumeReader> {
+    VolumeReader::open(&archive_files(backup_info))
+}
+
+/// Delete a backup's archive files, skipping ones already gone
+pub fn remove(backup_info: &BackupInfo) -> io::Result<()> {
+    remove_files(&backup_info.backup_path, &backup_info.volumes)
+}
+
+/// Delete an archive given by its path and parts, as recorded in `BackupInfo`
+pub fn remove_files(archive: &Path, volumes: &[PathBuf]) -> io::Result<()> {
+    let single = [archive.to_path_buf()];
+    let files = if volumes.is_empty() { &single[..] } else { volumes };
+    for path in files {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes an archive as numbered parts of at most `volume_size` bytes each
+///
+/// The ZIP writer seeks back to fill in headers, so parts stay open until `finish`.
+/// An archive that fits into one part is renamed to the plain `.zip` name.
+pub struct VolumeWriter {
+    archive: PathBuf,
+    volume_size: u64,
+    files: Vec<fs::File>,
+    position: u64,
+    len: u64,
+}
+
+impl VolumeWriter {
+    /// `None` writes a single archive, however large
+    pub fn create(archive: &Path, volume_size: Option<u64>) -> io::Result<Self> {
+        let mut writer = Self {
+            archive: archive.to_path_buf(),
+            volume_size: volume_size.filter(|&size| size > 0).unwrap_or(u64::MAX),
+            files: Vec::new(),
+            position: 0,
+            len: 0,
+        };
+        writer.open_volume()?;
+        Ok(writer)
+    }
+
+    /// Close the parts; returns them in order, or nothing if the archive wasn't split
+    pub fn finish(mut self) -> io::Result<Vec<PathBuf>> {
+        for file in &mut self.files {
+            file.flush()?;
+        }
+        let count = self.files.len();
+        drop(self.files);
+
+        if count == 1 {
+            fs::rename(volume_path(&self.archive, 1), &self.archive)?;
+            return Ok(Vec::new());
+        }
+        info!("Split {:?} into {} parts", self.archive, count);
+        Ok((1..=count).map(|number| volume_path(&self.archive, number)).collect())
+    }
+
+    /// Remove whatever was written so far, after a failed backup
+    pub fn discard(self) {
+        let count = self.files.len();
+        drop(self.files);
+        for number in 1..=count {
+            let _ = fs::remove_file(volume_path(&self.archive, number));
+        }
+    }
+
+    fn open_volume(&mut self) -> io::Result<()> {
+        let path = volume_path(&self.archive, self.files.len() + 1);
+        debug!("Starting archive part {:?}", path);
+        let file = fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        self.files.push(file);
+        Ok(())
+    }
+}
+
+impl Write for VolumeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let index = (self.position / self.volume_size) as usize;
+        while self.files.len() <= index {
+            self.open_volume()?;
+        }
+        let offset = self.position % self.volume_size;
+        let room = (self.volume_size - offset).min(buf.len() as u64) as usize;
+
+        let file = &mut self.files[index];
+        file.seek(SeekFrom::Start(offset))?;
+        let written = file.write(&buf[..room])?;
+        self.position += written as u64;
+        self.len = self.len.max(self.position);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.files.iter_mut().try_for_each(|file| file.flush())
+    }
+}
+
+impl Seek for VolumeWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = seek_target(pos, self.position, self.len)?;
+        Ok(self.position)
+    }
+}
+
+/// Reads the parts of a split archive back to back, as if they were one file
+pub struct VolumeReader {
+    files: Vec<fs::File>,
+    starts: Vec<u64>, // Offset of each part in the whole archive
+    position: u64,
+    len: u64,
+}
+
+impl VolumeReader {
+    pub fn open(paths: &[PathBuf]) -> io::Result<Self> {
+        let mut files = Vec::new();
+        let mut starts = Vec::new();
+        let mut len = 0;
+        for path in paths {
+            let file = fs::File::open(path)?;
+            starts.push(len);
+            len += file.metadata()?.len();
+            files.push(file);
+        }
+        Ok(Self { files, starts, position: 0, len })
+    }
+}
+
+impl Read for VolumeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+        let index = self.starts.partition_point(|&start| start <= self.position) - 1;
+        let end = self.starts.get(index + 1).copied().unwrap_or(self.len);
+        let room = (end - self.position).min(buf.len() as u64) as usize;
+
+        let file = &mut self.files[index];
+        file.seek(SeekFrom::Start(self.position - self.starts[index]))?;
+        let read = file.read(&mut buf[..room])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for VolumeReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = seek_target(pos, self.position, self.len)?;
+        Ok(self.position)
+    }
+}
+
+fn seek_target(pos: SeekFrom, position: u64, len: u64) -> io::Result<u64> {
+    let target = match pos {
+        SeekFrom::Start(offset) => Some(offset),
+        SeekFrom::End(offset) => len.checked_add_signed(offset),
+        SeekFrom::Current(offset) => position.checked_add_signed(offset),
+    };
+    target.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Seek before the start of the archive"))
+}
@@ -0,0 +1,186 @@
+use crate::types::*;
+use crate::archive;
+use crate::game_settings;
+use crate::incremental::FileIndex;
+use crate::volumes;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use log::warn;
+
+/// A save folder that crossed one of the configured size thresholds
+#[derive(Debug, Clone)]
+pub struct SizeAlert {
+    pub game_name: String,
+    pub save_path: PathBuf,
+    pub current_size: u64,
+    pub kind: SizeAlertKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum SizeAlertKind {
+    TooLarge { limit: u64 },
+    Grew { previous_size: u64, percent: u32 },
+}
+
+impl SizeAlert {
+    pub fn describe(&self) -> String {
+        match self.kind {
+            SizeAlertKind::TooLarge { limit } => format!(
+                "{} is {} (limit {})",
+                self.game_name, format_size(self.current_size), format_size(limit)
+            ),
+            SizeAlertKind::Grew { previous_size, percent } => format!(
+                "{} grew {}% since last backup ({} → {})",
+                self.game_name, percent, format_size(previous_size), format_size(self.current_size)
+            ),
+        }
+    }
+}
+
+/// A save folder that is empty or lost most of its content since its last backup
+#[derive(Debug, Clone)]
+pub struct SaveLossAlert {
+    pub game_name: String,
+    pub save_path: PathBuf,
+    pub current_size: u64,
+    pub current_files: usize,
+    pub backup: BackupInfo, // Newest backup from before the loss, the one to offer for restore
+    pub backup_files: Option<usize>,
+}
+
+impl SaveLossAlert {
+    pub fn describe(&self) -> String {
+        let backed_up = match self.backup_files {
+            Some(files) => format!("{} files, {}", files, format_size(self.backup.source_size)),
+            None => format_size(self.backup.source_size),
+        };
+        if self.current_files == 0 {
+            format!("{} is empty, the backup from {} had {}",
+                self.game_name, self.backup.created_at.format("%Y-%m-%d %H:%M"), backed_up)
+        } else {
+            format!("{} is down to {} files ({}), the backup from {} had {}",
+                self.game_name, self.current_files, format_size(self.current_size),
+                self.backup.created_at.format("%Y-%m-%d %H:%M"), backed_up)
+        }
+    }
+}
+
+/// Find saves that shrank by more than `max_loss_percent` since their newest backup
+///
+/// A game wiping its own save (or a cloud client "syncing" an empty folder) looks like this.
+pub fn detect_save_loss(saves: &[&GameSave], backups: &[BackupInfo], config: &SizeWatchdogConfig) -> Vec<SaveLossAlert> {
+    let mut alerts = Vec::new();
+
+    if config.max_loss_percent == 0 {
+        return alerts;
+    }
+
+    for save in saves {
+        // Backups are sorted newest first; older metadata has no source size
+        let last_backup = backups.iter()
+            .find(|b| b.original_path == save.save_path && b.source_size > 0 && volumes::archive_exists(b));
+
+        let backup = match last_backup {
+            Some(backup) => backup,
+            None => continue,
+        };
+
+        let lost = backup.source_size.saturating_sub(save.size);
+        if lost * 100 <= backup.source_size * config.max_loss_percent as u64 {
+            continue;
+        }
+
+        let alert = SaveLossAlert {
+            game_name: save.name.clone(),
+            save_path: save.save_path.clone(),
+            current_size: save.size,
+            current_files: count_files(&save.save_path),
+            backup: backup.clone(),
+            backup_files: count_archive_files(backup),
+        };
+        warn!("Possible save loss: {}", alert.describe());
+        alerts.push(alert);
+    }
+
+    alerts
+}
+
+/// Files in a save folder, 1 for a single-file save
+pub fn count_files(path: &Path) -> usize {
+    if path.is_file() {
+        return 1;
+    }
+    WalkDir::new(path).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .count()
+}
+
+/// Save files in a backup's archive, settings files not counted; None if it can't be read
+pub fn count_archive_files(backup: &BackupInfo) -> Option<usize> {
+    // An increment's archive only has the changed files, its index lists them all
+    if backup.base_backup.is_some() {
+        return FileIndex::load(backup).map(|index| index.files.len());
+    }
+    let names = archive::backup_entry_names(backup).ok()?;
+    let archived = names.iter().filter(|name| !name.ends_with('/') && !game_settings::is_settings_entry(name)).count();
+    Some(archived + backup.stored_files.len())
+}
+
+/// Check saves against the global and per-game size thresholds
+pub fn check_save_sizes(saves: &[&GameSave], backups: &[BackupInfo], config: &SizeWatchdogConfig) -> Vec<SizeAlert> {
+    let mut alerts = Vec::new();
+
+    if !config.enabled {
+        return alerts;
+    }
+
+    for save in saves {
+        let (max_size_mb, max_growth_percent) = match config.game_limits.iter().find(|l| l.game_name == save.name) {
+            Some(limit) => (limit.max_size_mb, limit.max_growth_percent),
+            None => (config.max_size_mb, config.max_growth_percent),
+        };
+
+        let limit = max_size_mb * 1024 * 1024;
+        if limit > 0 && save.size > limit {
+            alerts.push(SizeAlert {
+                game_name: save.name.clone(),
+                save_path: save.save_path.clone(),
+                current_size: save.size,
+                kind: SizeAlertKind::TooLarge { limit },
+            });
+            continue;
+        }
+
+        if max_growth_percent == 0 {
+            continue;
+        }
+
+        // Backups are sorted newest first; older metadata has no source size
+        let last_backup = backups.iter()
+            .find(|b| b.original_path == save.save_path && b.source_size > 0);
+
+        if let Some(backup) = last_backup {
+            if save.size > backup.source_size {
+                let growth = (save.size - backup.source_size) * 100 / backup.source_size;
+                if growth > max_growth_percent as u64 {
+                    alerts.push(SizeAlert {
+                        game_name: save.name.clone(),
+                        save_path: save.save_path.clone(),
+                        current_size: save.size,
+                        kind: SizeAlertKind::Grew {
+                            previous_size: backup.source_size,
+                            percent: growth.min(u32::MAX as u64) as u32,
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    for alert in &alerts {
+        warn!("Size watchdog: {}", alert.describe());
+    }
+
+    alerts
+}
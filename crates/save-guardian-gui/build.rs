@@ -5,7 +5,7 @@ extern crate winresource;
 fn main() {
     // Only add the icon resource on Windows
     let mut res = winresource::WindowsResource::new();
-    res.set_icon("assets/icon.ico");
+    res.set_icon("../../assets/icon.ico"); // Shared with the README, at the repository root
     res.set("ProductName", "Save Guardian");
     res.set("FileDescription", "Save Guardian - Game Save Manager");
     res.set("ProductVersion", "1.0.0");
@@ -0,0 +1,176 @@
+//! Subcommands that work on the backup store without opening a window
+//!
+//! The checks themselves live in the core crate; this parses the arguments, prints the
+//! reports and turns them into exit codes for scripts and scheduled tasks.
+
+use save_guardian_core::types::*;
+use save_guardian_core::audit::{self, AuditReport};
+use save_guardian_core::backup::BackupManager;
+use save_guardian_core::dedup_store::DedupStore;
+use save_guardian_core::encryption;
+use save_guardian_core::integrity::BackupSigner;
+use std::path::PathBuf;
+
+/// The settings the app last saved, the same ones the window starts with
+///
+/// Prints why and returns `None` when the file can't be read.
+fn load_config(config_path: &PathBuf) -> Option<Config> {
+    if !config_path.exists() {
+        eprintln!(
+            "No settings at {}; using the defaults. Save the settings in the app once to create it.",
+            config_path.display()
+        );
+    }
+    match Config::load_from_file(config_path) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            eprintln!("Could not read {}: {}", config_path.display(), e);
+            None
+        }
+    }
+}
+
+/// `save-guardian verify`: audit the backup store and print a report
+///
+/// Returns the process exit code: 0 when everything checks out, 1 when problems were
+/// found, 2 for usage errors or a store that can't be read.
+pub fn run_verify_command(args: &[String]) -> i32 {
+    let mut all = false;
+    let mut json = true;
+    let mut backup_dir: Option<PathBuf> = None;
+    let mut config_path = Config::get_config_path();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--text" => json = false,
+            "--json" => json = true,
+            "--backup-dir" => match args.next() {
+                Some(path) => backup_dir = Some(PathBuf::from(path)),
+                None => return verify_usage(),
+            },
+            "--config" => match args.next() {
+                Some(path) => config_path = PathBuf::from(path),
+                None => return verify_usage(),
+            },
+            _ => return verify_usage(),
+        }
+    }
+    if !all {
+        return verify_usage();
+    }
+
+    let config = match load_config(&config_path) {
+        Some(config) => config,
+        None => return 2,
+    };
+    let backup_path = backup_dir.unwrap_or_else(|| config.backup_path.clone());
+    let signer = if config.sign_backups { BackupSigner::load_or_create().ok() } else { None };
+    encryption::unlock_from_keyring(&config.backup_encryption);
+
+    let mut reports = Vec::new();
+    for root in audit::backup_roots(&backup_path) {
+        match audit::audit_backup_root(&root, signer.clone(), config.worker_threads()) {
+            Ok(report) => reports.push(report),
+            Err(e) => {
+                eprintln!("Could not audit {}: {}", root.display(), e);
+                return 2;
+            }
+        }
+    }
+
+    if json {
+        match serde_json::to_string_pretty(&reports) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("Could not write the report: {}", e);
+                return 2;
+            }
+        }
+    } else {
+        for report in &reports {
+            print!("{}", report.describe());
+        }
+    }
+
+    if reports.iter().all(AuditReport::is_clean) { 0 } else { 1 }
+}
+
+fn verify_usage() -> i32 {
+    eprintln!("Usage: save-guardian verify --all [--json | --text] [--backup-dir <path>] [--config <config.toml>]");
+    2
+}
+
+/// `save-guardian store gc|repack`: maintain the dedup store of every backup folder
+///
+/// Returns the process exit code: 0 when done, 1 when some backups or objects failed,
+/// 2 for usage errors or a store that can't be read.
+pub fn run_store_command(args: &[String]) -> i32 {
+    let mut action: Option<&str> = None;
+    let mut backup_dir: Option<PathBuf> = None;
+    let mut config_path = Config::get_config_path();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "gc" | "repack" if action.is_none() => action = Some(arg.as_str()),
+            "--backup-dir" => match args.next() {
+                Some(path) => backup_dir = Some(PathBuf::from(path)),
+                None => return store_usage(),
+            },
+            "--config" => match args.next() {
+                Some(path) => config_path = PathBuf::from(path),
+                None => return store_usage(),
+            },
+            _ => return store_usage(),
+        }
+    }
+    let action = match action {
+        Some(action) => action,
+        None => return store_usage(),
+    };
+
+    let config = match load_config(&config_path) {
+        Some(config) => config,
+        None => return 2,
+    };
+    let min_size = config.dedup_min_size();
+    if action == "repack" && min_size.is_none() {
+        eprintln!("The dedup store is off; turn it on in Settings first");
+        return 2;
+    }
+    let backup_path = backup_dir.unwrap_or_else(|| config.backup_path.clone());
+    let signer = if config.sign_backups { BackupSigner::load_or_create().ok() } else { None };
+
+    let mut failed = false;
+    for root in audit::backup_roots(&backup_path) {
+        let report = if action == "repack" {
+            BackupManager::new(root.clone(), config.backup_retention_days)
+                .map(|manager| manager.with_signer(signer.clone()).with_dedup_store(min_size))
+                .and_then(|manager| manager.repack_into_store())
+        } else {
+            DedupStore::new(&root).collect_garbage(&root)
+        };
+        match report {
+            Ok(report) => {
+                println!("{}: {}", root.display(), report.describe());
+                for (what, error) in &report.failed {
+                    println!("  {}: {}", what, error);
+                }
+                failed |= !report.failed.is_empty();
+            }
+            Err(e) => {
+                eprintln!("Could not {} {}: {}", action, root.display(), e);
+                return 2;
+            }
+        }
+    }
+
+    if failed { 1 } else { 0 }
+}
+
+fn store_usage() -> i32 {
+    eprintln!("Usage: save-guardian store <gc | repack> [--backup-dir <path>] [--config <config.toml>]");
+    2
+}
@@ -0,0 +1,8768 @@
+use save_guardian_core::types::*;
+use save_guardian_core::steam::SteamScanner;
+use save_guardian_core::names::NameResolver;
+use save_guardian_core::non_steam::NonSteamScanner;
+use save_guardian_core::sync::SyncManager;
+use save_guardian_core::sync_rules::SyncRules;
+use save_guardian_core::game_settings;
+use save_guardian_core::backup::{self, BackupEdit, BackupManager, BackupOutcome, BackupStats, CleanupPlan, CleanupReport, GameIdentity, ReassignSuggestion};
+use save_guardian_core::integrity::{BackupSigner, ContentCheck, VerificationStatus};
+use save_guardian_core::lan_server::LanServer;
+use save_guardian_core::watchdog::{self, SaveLossAlert, SizeAlert};
+use save_guardian_core::health::SourceCheck;
+use save_guardian_core::network;
+use save_guardian_core::restore::{self, RestoreQueue, RestoreItemStatus, RestoreSummary};
+use save_guardian_core::maintenance::{self, SweepState};
+use save_guardian_core::stats::{self, StoreHistory};
+use save_guardian_core::cloud::{self, CloudEntry, CloudHistory, CloudManifest, CloudOutcome, CloudProvider, ManifestEntry, ReplicaReport, SyncState, TransferQueue};
+use save_guardian_core::cloud::state::conflict_copy_path;
+use save_guardian_core::cloud::transfer::{TransferDirection, TransferItem, TransferJob, TransferState, TransferStatus};
+use save_guardian_core::activity;
+use save_guardian_core::oplog::{EntryOutcome, OperationHistory, OperationLog};
+use save_guardian_core::progress::ArchiveTask;
+use save_guardian_core::dedup_store::{DedupStore, StoreReport};
+use save_guardian_core::protection;
+use save_guardian_core::anomalies::{self, SuggestedAction, UserdataAnomaly};
+use save_guardian_core::sync_clients::{self, SyncClient, SyncClientFolder};
+use save_guardian_core::steam_cloud::{self, SteamCloudUsage};
+use save_guardian_core::diagnostics::{self, DiagnosticCounts};
+use save_guardian_core::import::{self, DownloadedArchive};
+use save_guardian_core::game_detection::{DetectedGame, GameDetector};
+use save_guardian_core::save_watcher::SaveWatcher;
+use save_guardian_core::app_lookup::{AppIdSuggestion, AppList};
+use save_guardian_core::encryption;
+use save_guardian_core::incremental::{self, BackupChain};
+use save_guardian_core::save_formats::{self, SaveMetadata};
+use chrono::{Datelike, TimeZone, Timelike};
+use eframe::egui;
+use log::{debug, error, info, warn};
+
+pub struct SaveGuardianApp {
+    // Core managers
+    steam_scanner: SteamScanner,
+    name_resolver: NameResolver,
+    names_generation: u64, // Resolver generation the displayed names were last updated for
+    non_steam_scanner: NonSteamScanner,
+    backup_manager: Option<BackupManager>,
+    
+    // Application state
+    config: Config,
+    steam_saves: Vec<GameSave>,
+    non_steam_saves: Vec<GameSave>,
+    steam_accounts: std::collections::HashMap<String, String>, // User ID -> display name
+    sync_client_folders: Vec<SyncClientFolder>, // OneDrive, Dropbox... folders on this machine
+    steam_cloud_usage: std::collections::HashMap<std::path::PathBuf, SteamCloudUsage>, // By save path
+    backups: Vec<BackupInfo>,
+    backup_stats: Option<BackupStats>,
+    size_alerts: Vec<SizeAlert>,
+    save_loss_alerts: Vec<SaveLossAlert>,
+    show_save_loss: bool,
+    dismissed_save_loss: std::collections::HashSet<(std::path::PathBuf, String)>, // Save folder and backup ID the user said were fine
+    uninstalled_saves: Vec<GameSave>, // Saves of games uninstalled since the last scan, waiting for a final backup
+    show_uninstalled: bool,
+    sweep_state: SweepState,
+    store_history: StoreHistory,
+    last_schedule_check: std::time::Instant,
+    startup_syncs_done: bool,
+    startup_pending: bool, // Scan and backup list still to load, see finish_startup
+    held_sync: Option<(usize, SourceCheck)>, // Pair index and why its manual sync was stopped
+    
+    // UI state
+    selected_tab: Tab,
+    selected_game: Option<usize>,
+    selected_backup: Option<usize>,
+    scan_status: ScanStatus,
+    
+    // Dialogs and modals
+    show_settings: bool,
+    show_backup_dialog: bool,
+    show_about: bool,
+    
+    // Settings UI
+    temp_config: Config,
+    
+    // Backup dialog state
+    backup_description: String,
+    
+    // Batch restore: selected backup archives and the queue built from them
+    backup_selection: std::collections::HashSet<std::path::PathBuf>,
+    restore_queue: Option<RestoreQueue>,
+    restore_summary: Option<RestoreSummary>,
+    bulk_edit: Option<BulkEditForm>,
+    bulk_backup: Option<BulkBackup>, // "Backup All" in progress, one save per frame
+    archive_task: Option<ArchiveTask<ArchiveResult>>, // Single backup or restore queue running on a worker thread
+    operations: OperationHistory, // Finished operations with their per-file logs
+    show_operations: bool,
+    operation_view: Option<OperationLog>, // Open in the log detail window
+    operation_filter: Option<EntryOutcome>,
+    reassign_suggestions: Option<Vec<ReassignSuggestion>>, // Backups of games the scan no longer finds
+    userdata_report: Option<Vec<UserdataAnomaly>>,
+    
+    // Game detail window, keyed by save folder
+    details_save_path: Option<std::path::PathBuf>,
+    details_activity: Option<(std::path::PathBuf, activity::ActivityCalendar)>, // Walking the folder is too slow for every frame
+    details_saves: Option<(std::path::PathBuf, Vec<SaveMetadata>)>, // Read from the save files, for the same reason
+    details_time_point: Option<(std::path::PathBuf, usize)>, // Backup picked on the time machine slider, oldest first
+    new_game_tag: String,
+    details_form: Option<(std::path::PathBuf, DetailsForm)>, // Details being edited for a non-Steam game
+    
+    // Restore the state as of a point in time (local date and time, optional single game)
+    show_time_restore: bool,
+    time_restore_date: chrono::NaiveDate,
+    time_restore_hour: u32,
+    time_restore_minute: u32,
+    time_restore_game: Option<String>,
+    
+    // Retention cleanup: what would be deleted, then what was
+    cleanup_preview: Option<CleanupPlan>,
+    cleanup_report: Option<CleanupReport>,
+    
+    // Household profiles
+    new_profile_name: String,
+    
+    // New sync pair being put together in the Sync tab
+    new_pair_left: Option<GameSave>,
+    new_pair_right: Option<GameSave>,
+    
+    // Read-only inspection mode requested on the command line
+    read_only_session: bool,
+    
+    // Search and filters
+    search_query: String,
+    filter_steam: bool,
+    filter_non_steam: bool,
+    tag_filter: Option<String>,
+    genre_filter: Option<String>,
+    sort_by: SortBy,
+    
+    // Cloud sync tracking
+    cloud_transfer: Option<CloudTransfer>,
+    last_sync_time: Option<chrono::DateTime<chrono::Utc>>,
+    cloud_files_synced: usize,
+    cloud_storage_used: u64,
+    auto_sync_next: Option<chrono::DateTime<chrono::Utc>>, // None while automatic sync is off or paused
+    auto_sync_running: bool,
+    auto_sync_last: Option<(chrono::DateTime<chrono::Utc>, ScanStatus)>,
+    cloud_conflicts: Vec<CloudConflict>, // Waiting for a decision when the conflict policy is Prompt
+    cloud_manifest: Option<CloudManifest>, // Last manifest read from or written to the cloud
+    cloud_listing: Option<Vec<CloudEntry>>, // Archives in the cloud, None until listed
+    cloud_search: String,
+    cloud_filter_steam: bool,
+    cloud_filter_non_steam: bool,
+    cloud_sort_by: SortBy,
+    show_cloud_conflicts: bool,
+    cloud_history: Option<CloudHistory>, // Open in the history window
+    connection_report: Option<cloud::ConnectionReport>, // Open in the diagnostics window
+    share_dialog: Option<ShareDialog>,
+    unlock_prompt: Option<String>, // Passphrase being typed to unlock encrypted backups
+    unlock_error: Option<String>,
+    new_backup_passphrase: (String, String), // Typed in Settings, and again to confirm
+    import_dialog: Option<ImportDialog>,
+    cloud_history_filter: String,
+    cloud_history_failed_only: bool,
+    replica_report: ReplicaReport, // Last comparison of the cloud listing with the local backups
+    replica_delete: Option<String>, // Remote archive waiting for the user to confirm its deletion
+    mirror_queue: Vec<String>, // Destinations still to mirror the last upload to, by name
+    paused_transfer: Option<cloud::PausedTransfer>, // Paused in an earlier session, waiting to be resumed
+    lan_server: Option<LanServer>,
+    lan_server_error: Option<String>, // Why the LAN server isn't running although enabled
+    game_detector: Option<GameDetector>, // Watching the save locations while detection is on
+    detected_games: Vec<DetectedGame>, // Waiting for the user to track or ignore them
+    save_watcher: Option<SaveWatcher>, // Backs up saves after they change, while enabled
+    save_watcher_error: Option<String>, // Why the watcher isn't running although enabled
+    app_id_suggestions: Option<Vec<AppIdSuggestion>>, // Steam app IDs found for non-Steam saves, to confirm
+    backup_chains: Vec<BackupChain>, // Incremental backups by the full backup they build on
+    chain_delete: Option<(BackupInfo, Vec<BackupInfo>)>, // Backup being deleted and the increments it would orphan
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tab {
+    GameSaves,
+    Backups,
+    Analytics,
+    Sync,
+    Cloud,
+    Settings,
+}
+
+#[derive(Debug, Clone)]
+enum ScanStatus {
+    Idle,
+    Scanning,
+    Complete(String),
+    Error(String),
+}
+
+/// What a cloud sync decided before transferring anything
+struct SyncPlan {
+    operation: &'static str, // What the user asked for, for the cloud history
+    state: SyncState,
+    remote: Vec<CloudEntry>,
+    kept_local: usize, // Backups skipped because their game is kept out of the cloud or already synced by OneDrive etc.
+    unchanged: usize,  // Files already the same on both sides
+    conflicts: usize,  // Archives changed on both sides, transferred as the conflict policy says
+    restore_to: Option<std::path::PathBuf>, // Extract the downloaded archive here, see restore_from_cloud
+}
+
+/// An archive that changed both locally and in the cloud since the last sync
+#[derive(Debug, Clone)]
+struct CloudConflict {
+    remote: CloudEntry,
+    local_path: std::path::PathBuf,
+    local_size: u64,
+    local_modified: Option<chrono::DateTime<chrono::Utc>>,
+    resolution: ConflictResolution, // Picked in the conflict dialog
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConflictResolution {
+    KeepLocal,
+    KeepCloud,
+    KeepBoth,
+}
+
+impl CloudConflict {
+    fn resolution_for(&self, policy: ConflictPolicy) -> ConflictResolution {
+        match policy {
+            ConflictPolicy::KeepNewest => match (self.local_modified, self.remote.modified) {
+                (Some(local), Some(remote)) if remote > local => ConflictResolution::KeepCloud,
+                (Some(_), Some(_)) => ConflictResolution::KeepLocal,
+                _ => ConflictResolution::KeepBoth,
+            },
+            ConflictPolicy::KeepBoth | ConflictPolicy::Prompt => ConflictResolution::KeepBoth,
+        }
+    }
+}
+
+/// An archive in the cloud listing, with what the manifest or the local backups say about it
+struct CloudRow<'a> {
+    entry: &'a CloudEntry,
+    game: String,
+    save_type: Option<SaveType>, // None when neither the manifest nor a local backup knows the archive
+    created: Option<chrono::DateTime<chrono::Utc>>,
+    machine: Option<&'a str>,
+}
+
+/// The "Import Backup from URL" window: a link to download, then the game to file it under
+struct ImportDialog {
+    url: String,
+    archive: Option<DownloadedArchive>, // Downloaded and read through
+    game: Option<GameIdentity>,         // One of the scanned games, None for `game_name`
+    game_name: String,
+    app_id: String,
+    save_type: SaveType,
+    description: String,
+    error: Option<String>,
+}
+
+/// The "Create share link" window for one cloud archive
+struct ShareDialog {
+    entry: CloudEntry,
+    expires: bool,
+    expiry_date: chrono::NaiveDate, // Last day the link works, if `expires`
+    link: Option<String>,
+    error: Option<String>,
+}
+
+/// Cloud uploads/downloads running in the background, and what to report when done
+struct CloudTransfer {
+    queue: TransferQueue,
+    plan: SyncPlan,
+    reported: bool,
+    mirror: Option<Config>, // Config with the mirrored destination selected, None for the selected one
+    pause_saved: Option<usize>, // Files left when the paused transfer file was last written
+}
+
+/// A "Backup All" run, done one save per frame so it can be paused and cancelled
+struct BulkBackup {
+    pending: std::collections::VecDeque<GameSave>,
+    total: usize,
+    log: OperationLog, // One entry per save done so far
+    paused: bool,
+}
+
+/// What a backup or restore run on a worker thread hands back, see `poll_archive_task`
+enum ArchiveResult {
+    Backup(String, save_guardian_core::types::Result<BackupOutcome>), // Game name, outcome
+    Restore(RestoreQueue, RestoreSummary),
+    Store(save_guardian_core::types::Result<StoreReport>), // Repack or garbage collection of the dedup store
+    Verify(BackupInfo, VerificationStatus, save_guardian_core::types::Result<ContentCheck>), // One backup read back in full
+    FullCheck(save_guardian_core::types::Result<(SweepState, usize)>), // Every backup read back, the problems found and how many were checked
+    Import(save_guardian_core::types::Result<DownloadedArchive>), // Archive fetched from a link, waiting for its game
+    AppIds(save_guardian_core::types::Result<Vec<AppIdSuggestion>>), // Steam apps non-Steam saves probably belong to
+    Consolidate(Vec<BackupInfo>, save_guardian_core::types::Result<BackupInfo>), // Backups to delete once written, the new full backup
+}
+
+/// Changes typed into the bulk edit dialog, applied to every selected backup
+#[derive(Debug, Clone, Default)]
+struct BulkEditForm {
+    tag: String,
+    change_prefix: bool,
+    old_prefix: String,
+    new_prefix: String,
+    reassign_to: Option<GameIdentity>,
+    pinned: Option<bool>,
+}
+
+impl BulkEditForm {
+    fn to_edit(&self) -> BackupEdit {
+        BackupEdit {
+            add_tag: Some(self.tag.trim().to_string()).filter(|tag| !tag.is_empty()),
+            description_prefix: if self.change_prefix {
+                Some((self.old_prefix.clone(), self.new_prefix.clone()))
+            } else {
+                None
+            },
+            reassign_to: self.reassign_to.clone(),
+            pinned: self.pinned,
+        }
+    }
+}
+
+/// Text fields of the details editor, comma-separated where there can be several values
+#[derive(Debug, Clone, Default)]
+struct DetailsForm {
+    genres: String,
+    release_year: String,
+    platforms: String,
+}
+
+impl DetailsForm {
+    fn from_details(details: &GameDetails) -> Self {
+        Self {
+            genres: details.genres.join(", "),
+            release_year: details.release_year.map(|year| year.to_string()).unwrap_or_default(),
+            platforms: details.platforms.join(", "),
+        }
+    }
+
+    fn to_details(&self) -> GameDetails {
+        let split = |text: &str| text.split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+        GameDetails {
+            genres: split(&self.genres),
+            release_year: self.release_year.trim().parse().ok(),
+            platforms: split(&self.platforms),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SortBy {
+    Name,
+    LastModified,
+    Size,
+    Type,
+}
+
+impl Default for SaveGuardianApp {
+    fn default() -> Self {
+        let config = Config::default();
+        let name_resolver = NameResolver::new();
+        let steam_scanner = SteamScanner::new(config.steam_path.clone())
+            .with_name_resolver(name_resolver.clone());
+        let non_steam_scanner = NonSteamScanner::new();
+
+        Self {
+            steam_scanner,
+            name_resolver,
+            names_generation: 0,
+            non_steam_scanner,
+            backup_manager: None, // Made from the loaded config once the window is up
+            config: config.clone(),
+            steam_saves: Vec::new(),
+            non_steam_saves: Vec::new(),
+            steam_accounts: std::collections::HashMap::new(),
+            sync_client_folders: Vec::new(),
+            steam_cloud_usage: std::collections::HashMap::new(),
+            backups: Vec::new(),
+            sweep_state: SweepState::default(),
+            store_history: StoreHistory::default(),
+            last_schedule_check: std::time::Instant::now(),
+            startup_syncs_done: false,
+            startup_pending: true,
+            held_sync: None,
+            backup_stats: None,
+            size_alerts: Vec::new(),
+            save_loss_alerts: Vec::new(),
+            show_save_loss: false,
+            dismissed_save_loss: std::collections::HashSet::new(),
+            uninstalled_saves: Vec::new(),
+            show_uninstalled: false,
+            selected_tab: Tab::GameSaves,
+            selected_game: None,
+            selected_backup: None,
+            scan_status: ScanStatus::Idle,
+            show_settings: false,
+            show_backup_dialog: false,
+            show_about: false,
+            temp_config: config,
+            backup_description: String::new(),
+            backup_selection: std::collections::HashSet::new(),
+            restore_queue: None,
+            restore_summary: None,
+            bulk_edit: None,
+            bulk_backup: None,
+            archive_task: None,
+            operations: OperationHistory::default(),
+            show_operations: false,
+            operation_view: None,
+            operation_filter: None,
+            reassign_suggestions: None,
+            userdata_report: None,
+            details_save_path: None,
+            details_activity: None,
+            details_saves: None,
+            details_time_point: None,
+            new_game_tag: String::new(),
+            details_form: None,
+            show_time_restore: false,
+            time_restore_date: chrono::Local::now().date_naive(),
+            time_restore_hour: 0,
+            time_restore_minute: 0,
+            time_restore_game: None,
+            cleanup_preview: None,
+            cleanup_report: None,
+            new_profile_name: String::new(),
+            new_pair_left: None,
+            new_pair_right: None,
+            read_only_session: false,
+            search_query: String::new(),
+            filter_steam: true,
+            filter_non_steam: true,
+            tag_filter: None,
+            genre_filter: None,
+            sort_by: SortBy::Name,
+            cloud_transfer: None,
+            last_sync_time: None,
+            auto_sync_next: None,
+            auto_sync_running: false,
+            auto_sync_last: None,
+            cloud_conflicts: Vec::new(),
+            cloud_manifest: None,
+            cloud_listing: None,
+            cloud_search: String::new(),
+            cloud_filter_steam: true,
+            cloud_filter_non_steam: true,
+            cloud_sort_by: SortBy::LastModified,
+            show_cloud_conflicts: false,
+            cloud_history: None,
+            connection_report: None,
+            share_dialog: None,
+            unlock_prompt: None,
+            unlock_error: None,
+            new_backup_passphrase: (String::new(), String::new()),
+            import_dialog: None,
+            cloud_history_filter: String::new(),
+            cloud_history_failed_only: false,
+            replica_report: ReplicaReport::default(),
+            replica_delete: None,
+            mirror_queue: Vec::new(),
+            paused_transfer: None,
+            lan_server: None,
+            lan_server_error: None,
+            game_detector: None,
+            detected_games: Vec::new(),
+            save_watcher: None,
+            save_watcher_error: None,
+            app_id_suggestions: None,
+            backup_chains: Vec::new(),
+            chain_delete: None,
+            cloud_files_synced: 0,
+            cloud_storage_used: 0,
+        }
+    }
+}
+
+impl eframe::App for SaveGuardianApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Apply theme
+        self.apply_theme(ctx);
+        
+        // The first frame shows the window, the scan and backup list load right after
+        if self.startup_pending && ctx.frame_nr() > 0 {
+            self.finish_startup();
+        }
+        
+        // Pick up game names resolved in the background since the last frame
+        if self.name_resolver.generation() != self.names_generation {
+            self.normalize_all_game_names();
+        }
+        if self.name_resolver.pending_count() > 0 {
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+        }
+        
+        // Top panel with title and controls
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            self.draw_top_panel(ui);
+        });
+
+        // Bottom status bar
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            self.draw_status_bar(ui);
+        });
+
+        // Main content area
+        egui::CentralPanel::default().show(ctx, |ui| {
+            match self.selected_tab {
+                Tab::GameSaves => self.draw_game_saves_tab(ui),
+                Tab::Backups => self.draw_backups_tab(ui),
+                Tab::Analytics => self.draw_analytics_tab(ui),
+                Tab::Sync => self.draw_sync_tab(ui),
+                Tab::Cloud => self.draw_cloud_tab(ui),
+                Tab::Settings => self.draw_settings_tab(ui),
+            }
+        });
+
+        // Modal dialogs
+        self.poll_cloud_transfer(ctx);
+        self.poll_archive_task(ctx);
+        self.run_bulk_backup(ctx);
+        self.poll_game_detector();
+        self.draw_modals(ctx);
+        
+        if self.startup_pending {
+            ctx.request_repaint();
+            return;
+        }
+        
+        // Wake up once a minute so scheduled syncs and maintenance run while the window is idle
+        self.run_scheduled_tasks();
+        ctx.request_repaint_after(std::time::Duration::from_secs(60));
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, "save_guardian_config", &self.config);
+        // The command-line subcommands read config.toml, not the window's storage
+        if let Err(e) = self.write_config_file() {
+            warn!("Could not write {}: {}", Config::get_config_path().display(), e);
+        }
+    }
+}
+
+impl SaveGuardianApp {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+        // PNG screenshots of Ren'Py saves in the game details
+        egui_extras::install_image_loaders(&cc.egui_ctx);
+        
+        // Load saved configuration; everything slower waits for finish_startup
+        let stored = cc.storage.and_then(|storage| eframe::get_value::<Config>(storage, "save_guardian_config"));
+        let config = match stored {
+            Some(config) => Some(config),
+            None => Config::load_from_file(&Config::get_config_path()).ok(),
+        };
+        if let Some(config) = config {
+            app.config = config.clone();
+            app.temp_config = config;
+        }
+        app.scan_status = ScanStatus::Scanning;
+        
+        app
+    }
+    
+    /// Write the settings to config.toml, where `save-guardian verify` and `store` read them
+    fn write_config_file(&self) -> Result<()> {
+        self.config.save_to_file(&Config::get_config_path())
+    }
+    
+    /// The startup work that used to keep the window from appearing, run once it is on screen
+    ///
+    /// Each step is timed in the log, so large libraries show where the time goes.
+    fn finish_startup(&mut self) {
+        self.startup_pending = false;
+        let started = std::time::Instant::now();
+        
+        diagnostics::timed("Creating managers", || self.rebuild_managers());
+        // Encrypted backups need the passphrase before anything can be backed up or read
+        if self.config.backup_encryption.enabled && !encryption::unlock_from_keyring(&self.config.backup_encryption) {
+            self.unlock_prompt = Some(String::new());
+        }
+        self.sync_client_folders = diagnostics::timed("Detecting sync client folders", sync_clients::detect_folders);
+        // Network drives wait for a full rescan; names are normalized at the end of the scan
+        diagnostics::timed("Quick scan", || self.quick_scan_saves());
+        diagnostics::timed("Loading backups", || self.load_backups());
+        
+        info!("Startup finished in {} ms", started.elapsed().as_millis());
+    }
+
+    fn apply_theme(&self, ctx: &egui::Context) {
+        match self.config.theme {
+            Theme::Dark => ctx.set_visuals(egui::Visuals::dark()),
+            Theme::Light => ctx.set_visuals(egui::Visuals::light()),
+            Theme::System => {
+                // For now, default to dark theme. In a real app, you'd detect system theme
+                ctx.set_visuals(egui::Visuals::dark());
+            }
+        }
+    }
+
+    /// Start the app in read-only inspection mode
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only_session = read_only;
+        if read_only {
+            info!("Running in read-only mode");
+        }
+        self
+    }
+
+    /// Whether destructive operations (restore, delete, sync, cleanup) are disabled
+    fn is_read_only(&self) -> bool {
+        self.read_only_session || self.config.read_only_mode
+    }
+
+    /// Recreate scanners and the backup manager from the current config
+    fn rebuild_managers(&mut self) {
+        self.name_resolver.set_online_config(self.config.online.clone());
+        self.steam_scanner = SteamScanner::new(self.config.steam_path.clone())
+            .with_name_resolver(self.name_resolver.clone());
+        self.non_steam_scanner = Self::create_non_steam_scanner(&self.config);
+        self.backup_manager = Self::create_backup_manager(&self.config);
+        // Count the automatic sync interval from now, it may have changed
+        self.auto_sync_next = None;
+        self.restart_lan_server();
+        self.restart_game_detector();
+        self.restart_save_watcher();
+    }
+    
+    fn create_non_steam_scanner(config: &Config) -> NonSteamScanner {
+        NonSteamScanner::new()
+            .with_custom_locations(config.custom_locations.clone())
+            .with_custom_games(config.custom_games.clone())
+            .with_app_ids(config.steam_app_ids.clone())
+            .with_network_config(config.network.clone())
+    }
+    
+    /// Start, stop or restart the new game detector to match the config and the scanned locations
+    fn restart_game_detector(&mut self) {
+        if !self.config.game_detection.enabled {
+            self.game_detector = None;
+            return;
+        }
+        let locations: Vec<std::path::PathBuf> = self.non_steam_scanner.get_all_locations().iter()
+            .map(|location| location.path.clone())
+            .collect();
+        let running = self.game_detector.as_ref()
+            .map_or(false, |detector| detector.watches(&locations, &self.config.game_detection));
+        if !running {
+            // The old thread has to stop before its folders are watched again
+            self.game_detector = None;
+            self.game_detector = Some(GameDetector::start(locations, &self.config.game_detection));
+        }
+        self.update_detector_known();
+    }
+    
+    /// Start, stop or restart the save watcher to match the config and the scanned saves
+    fn restart_save_watcher(&mut self) {
+        if !self.config.save_watcher.enabled {
+            self.save_watcher = None;
+            self.save_watcher_error = None;
+            return;
+        }
+        let save_paths: Vec<std::path::PathBuf> = self.steam_saves.iter().chain(self.non_steam_saves.iter())
+            .map(|save| save.save_path.clone())
+            .collect();
+        if self.save_watcher.as_ref().map_or(false, |watcher| watcher.watches(&save_paths, &self.config.save_watcher)) {
+            return;
+        }
+        
+        self.save_watcher = None;
+        match SaveWatcher::start(save_paths, &self.config.save_watcher) {
+            Ok(watcher) => {
+                self.save_watcher = Some(watcher);
+                self.save_watcher_error = None;
+            }
+            Err(e) => {
+                warn!("Save watcher not started: {}", e);
+                self.save_watcher_error = Some(e.to_string());
+            }
+        }
+    }
+    
+    /// Back up the saves that changed and have been quiet since; waits while another backup or restore runs
+    fn run_watched_backups(&mut self) {
+        let busy = self.archive_task.is_some() || self.bulk_backup.is_some();
+        let due = match self.save_watcher {
+            Some(ref watcher) if !busy => watcher.due(),
+            _ => return,
+        };
+        if due.is_empty() {
+            return;
+        }
+        
+        let saves: Vec<GameSave> = self.steam_saves.iter().chain(self.non_steam_saves.iter())
+            .filter(|save| due.contains(&save.save_path))
+            .cloned()
+            .collect();
+        if saves.is_empty() {
+            return;
+        }
+        let mut log = OperationLog::new("Automatic Backup");
+        for save in &saves {
+            info!("{} changed {} minutes ago, backing it up", save.name, self.config.save_watcher.quiet_minutes);
+            let outcome = self.backup_one(save);
+            Self::log_backup(&mut log, save, outcome);
+        }
+        self.report_backups(log, 0);
+    }
+    
+    /// Tell the detector which folders are taken: every scanned save, custom games and declined folders
+    fn update_detector_known(&self) {
+        if let Some(ref detector) = self.game_detector {
+            let known = self.steam_saves.iter().chain(self.non_steam_saves.iter())
+                .map(|save| save.save_path.clone())
+                .chain(self.config.custom_games.iter().map(|game| game.save_path.clone()))
+                .chain(self.config.game_detection.ignored_folders.iter().cloned())
+                .collect();
+            detector.set_known(known);
+        }
+    }
+    
+    /// Pick up the games the detector noticed since the last frame, to ask about them
+    fn poll_game_detector(&mut self) {
+        let detected = match self.game_detector {
+            Some(ref detector) => detector.detected(),
+            None => return,
+        };
+        for game in detected {
+            if !self.detected_games.iter().any(|known| known.save_path == game.save_path) {
+                self.detected_games.push(game);
+            }
+        }
+    }
+
+    /// Start, stop or move the LAN server to match the config and the active profile
+    fn restart_lan_server(&mut self) {
+        let root = self.config.effective_backup_path();
+        if !self.config.lan_server.enabled {
+            self.lan_server = None;
+            self.lan_server_error = None;
+            return;
+        }
+        if self.lan_server.as_ref().map_or(false, |server| server.serves(&root, &self.config.lan_server)) {
+            return;
+        }
+
+        // The old server has to let go of the port first
+        self.lan_server = None;
+        match LanServer::start(root, &self.config.lan_server) {
+            Ok(server) => {
+                self.lan_server = Some(server);
+                self.lan_server_error = None;
+            }
+            Err(e) => {
+                warn!("LAN server not started: {}", e);
+                self.lan_server_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Build a backup manager for the active profile, with signing if enabled
+    fn create_backup_manager(config: &Config) -> Option<BackupManager> {
+        let signer = if config.sign_backups {
+            match BackupSigner::load_or_create() {
+                Ok(signer) => Some(signer),
+                Err(e) => {
+                    error!("Failed to load signing key: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        BackupManager::new(config.effective_backup_path(), config.backup_retention_days)
+            .ok()
+            .map(|manager| manager
+                .with_signer(signer)
+                .with_exclusions(config.exclusions.clone())
+                .with_network_config(config.network.clone())
+                .with_latest_links(config.keep_latest_links)
+                .with_retention_exemptions(config.retention_exemptions.clone())
+                .with_duplicate_policy(config.duplicate_backups)
+                .with_protected_saves(config.protected_saves.clone())
+                .with_settings_games(config.settings_games.clone())
+                .with_volume_size(config.volume_size())
+                .with_filename_timestamp(config.filename_timestamp.clone())
+                .with_worker_threads(config.worker_threads())
+                .with_incremental(config.incremental.clone())
+                .with_dedup_store(config.dedup_min_size())
+                .with_archive_format(config.archive_format)
+                .with_encryption(config.backup_encryption.enabled)
+                .with_machine(config.machine()))
+    }
+
+    fn draw_profile_selector(&mut self, ui: &mut egui::Ui) {
+        let current = self.config.active_profile.clone();
+        let mut selected = current.clone();
+        let profile_names: Vec<String> = self.config.profiles.iter().map(|p| p.name.clone()).collect();
+        
+        ui.label("👤");
+        egui::ComboBox::from_id_source("profile_selector")
+            .selected_text(selected.clone().unwrap_or_else(|| "Shared".to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, "Shared");
+                for name in profile_names {
+                    ui.selectable_value(&mut selected, Some(name.clone()), name);
+                }
+            });
+        
+        if selected != current {
+            self.switch_profile(selected);
+        }
+    }
+
+    /// Switch to another household profile and reload its backups
+    fn switch_profile(&mut self, profile: Option<String>) {
+        info!("Switching profile to {:?}", profile);
+        self.config.active_profile = profile.clone();
+        self.temp_config.active_profile = profile;
+        self.backup_manager = Self::create_backup_manager(&self.config);
+        self.restart_lan_server();
+        self.selected_game = None;
+        self.selected_backup = None;
+        self.load_backups();
+        self.scan_status = ScanStatus::Complete(format!(
+            "Switched to profile: {}",
+            self.config.active_profile.as_deref().unwrap_or("Shared")
+        ));
+    }
+
+    fn draw_top_panel(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            // App title with better styling (no problematic emojis)
+            ui.label(egui::RichText::new("Save Guardian").size(20.0).strong().color(egui::Color32::from_rgb(100, 149, 237)));
+            
+            ui.separator();
+            
+            // Tab selection with text-based icons to avoid rendering issues
+            ui.selectable_value(&mut self.selected_tab, Tab::GameSaves, egui::RichText::new("▶ Game Saves").size(14.0));
+            ui.selectable_value(&mut self.selected_tab, Tab::Backups, egui::RichText::new("💾 Backups").size(14.0));
+            ui.selectable_value(&mut self.selected_tab, Tab::Analytics, egui::RichText::new("📊 Analytics").size(14.0));
+            ui.selectable_value(&mut self.selected_tab, Tab::Sync, egui::RichText::new("🔄 Sync").size(14.0));
+            ui.selectable_value(&mut self.selected_tab, Tab::Cloud, egui::RichText::new("☁ Cloud").size(14.0));
+            ui.selectable_value(&mut self.selected_tab, Tab::Settings, egui::RichText::new("⚙ Settings").size(14.0));
+            
+            if !self.config.profiles.is_empty() {
+                ui.separator();
+                self.draw_profile_selector(ui);
+            }
+            
+            if self.is_read_only() {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "🔒 Read-only")
+                    .on_hover_text("Restore, delete, sync and cleanup are disabled");
+            }
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // About button
+                if ui.button(egui::RichText::new("? About").size(12.0)).on_hover_text("About Save Guardian").clicked() {
+                    self.show_about = true;
+                }
+                
+                // Quick backup all button
+                if ui.button(egui::RichText::new("+ Quick Backup").size(12.0)).on_hover_text("Quick backup all recent saves").clicked() {
+                    // TODO: Implement quick backup
+                }
+                
+                // Refresh button with force name update
+                if ui.button(egui::RichText::new("↻ Refresh").size(12.0)).on_hover_text("Refresh all data and fix game names").clicked() {
+                    // Force refresh incorrect names before scanning
+                    self.steam_scanner.refresh_incorrect_names();
+                    self.scan_saves();
+                    self.load_backups();
+                }
+            });
+        });
+    }
+
+    fn draw_status_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            match &self.scan_status {
+                ScanStatus::Idle => {
+                    ui.label("Ready");
+                }
+                ScanStatus::Scanning => {
+                    ui.spinner();
+                    ui.label("Scanning for saves...");
+                }
+                ScanStatus::Complete(msg) => {
+                    ui.label(format!("✅ {}", msg));
+                }
+                ScanStatus::Error(err) => {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", err));
+                }
+            }
+            
+            // The message is about the last operation: its log says what happened to each file
+            let latest = self.operations.latest().filter(|log| match self.scan_status {
+                ScanStatus::Complete(ref msg) | ScanStatus::Error(ref msg) => msg == &log.summary,
+                _ => false,
+            });
+            if let Some(log) = latest {
+                if ui.small_button("Details").on_hover_text(format!("{} entries", log.entries.len())).clicked() {
+                    self.operation_view = Some(log.clone());
+                    self.operation_filter = None;
+                }
+            }
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(format!("Steam: {} | Non-Steam: {} | Backups: {}", 
+                    self.steam_saves.len(), 
+                    self.non_steam_saves.len(),
+                    self.backups.len()
+                ));
+                
+                if self.config.online.offline_mode {
+                    ui.label("✈ Offline").on_hover_text("Offline mode: no online lookups or network cloud sync");
+                }
+                
+                if !self.operations.is_empty() {
+                    let label = egui::RichText::new(format!("📋 {} operations", self.operations.len()));
+                    if ui.add(egui::Label::new(label).sense(egui::Sense::click())).on_hover_text("Backups, syncs, restores and transfers of this session").clicked() {
+                        self.show_operations = true;
+                    }
+                }
+                
+                if !self.size_alerts.is_empty() {
+                    ui.colored_label(egui::Color32::from_rgb(255, 133, 27), format!("⚠ {} size alerts", self.size_alerts.len()));
+                }
+                
+                if !self.uninstalled_saves.is_empty() {
+                    let label = egui::RichText::new(format!("📦 {} uninstalled games to archive", self.uninstalled_saves.len()))
+                        .color(egui::Color32::from_rgb(255, 133, 27));
+                    if ui.add(egui::Label::new(label).sense(egui::Sense::click())).on_hover_text("Take a final backup before Steam cleans up").clicked() {
+                        self.show_uninstalled = true;
+                    }
+                }
+                
+                if !self.save_loss_alerts.is_empty() {
+                    let label = egui::RichText::new(format!("⚠ {} possible save losses", self.save_loss_alerts.len()))
+                        .color(egui::Color32::from_rgb(220, 50, 50));
+                    if ui.add(egui::Label::new(label).sense(egui::Sense::click())).on_hover_text("Open the recovery wizard").clicked() {
+                        self.show_save_loss = true;
+                    }
+                }
+            });
+        });
+    }
+
+    fn draw_game_saves_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            // Search box
+            ui.label("🔍 Search:");
+            ui.text_edit_singleline(&mut self.search_query);
+            
+            ui.separator();
+            
+            // Filters
+            ui.checkbox(&mut self.filter_steam, "Steam");
+            ui.checkbox(&mut self.filter_non_steam, "Non-Steam");
+            
+            let tags = self.config.all_game_tags();
+            if !tags.is_empty() {
+                ui.label("Tag:");
+                egui::ComboBox::from_id_source("tag_filter")
+                    .selected_text(self.tag_filter.clone().unwrap_or_else(|| "Any".to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.tag_filter, None, "Any");
+                        for tag in tags {
+                            let label = tag.clone();
+                            ui.selectable_value(&mut self.tag_filter, Some(tag), label);
+                        }
+                    });
+            }
+            
+            let genres = self.all_genres();
+            if !genres.is_empty() {
+                ui.label("Genre:");
+                egui::ComboBox::from_id_source("genre_filter")
+                    .selected_text(self.genre_filter.clone().unwrap_or_else(|| "Any".to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.genre_filter, None, "Any");
+                        for genre in genres {
+                            let label = genre.clone();
+                            ui.selectable_value(&mut self.genre_filter, Some(genre), label);
+                        }
+                    });
+            }
+            
+            ui.separator();
+            
+            // Sort options
+            ui.label("Sort by:");
+            egui::ComboBox::from_id_source("sort_by")
+                .selected_text(format!("{:?}", self.sort_by))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.sort_by, SortBy::Name, "Name");
+                    ui.selectable_value(&mut self.sort_by, SortBy::LastModified, "Last Modified");
+                    ui.selectable_value(&mut self.sort_by, SortBy::Size, "Size");
+                    ui.selectable_value(&mut self.sort_by, SortBy::Type, "Type");
+                });
+        });
+
+        ui.separator();
+        
+        if !self.size_alerts.is_empty() {
+            egui::CollapsingHeader::new(egui::RichText::new(format!("⚠ {} save size alerts", self.size_alerts.len()))
+                    .color(egui::Color32::from_rgb(255, 133, 27)))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for alert in &self.size_alerts {
+                        ui.label(alert.describe())
+                            .on_hover_text(alert.save_path.display().to_string());
+                    }
+                });
+            ui.separator();
+        }
+        
+        // Toolbar with bulk actions
+        ui.horizontal(|ui| {
+            ui.label("Bulk Actions:");
+            
+            let backup_label = match &self.tag_filter {
+                Some(tag) => format!("💾 Backup All \"{}\"", tag),
+                None => "💾 Backup All Visible".to_string(),
+            };
+            let visible = self.get_filtered_saves();
+            let backup_hint = match stats::DurationEstimator::new(&self.backups) {
+                Some(estimator) => format!("Create backups for all visible saves (~{} for {} games)",
+                    format_duration(estimator.estimate(visible.iter().copied())), visible.len()),
+                None => "Create backups for all visible saves".to_string(),
+            };
+            if ui.add_enabled(self.bulk_backup.is_none(), egui::Button::new(backup_label)).on_hover_text(backup_hint).clicked() {
+                let saves: Vec<GameSave> = self.get_filtered_saves().into_iter().cloned().collect();
+                self.start_bulk_backup(saves);
+            }
+            
+            if ui.button("↗ Export List").on_hover_text("Export save list to file").clicked() {
+                // TODO: Implement export
+            }
+            
+            ui.separator();
+            
+            ui.label(format!("{} saves found", self.get_filtered_saves().len()));
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("↻ Rescan").on_hover_text("Refresh save scan and fix game names").clicked() {
+                    self.steam_scanner.refresh_incorrect_names();
+                    self.scan_saves();
+                }
+                
+                if ui.button("🩺 Userdata Report").on_hover_text("Look for oversized, orphaned and long-unused Steam userdata folders").clicked() {
+                    self.open_userdata_report();
+                }
+                
+                if !self.steam_accounts.is_empty() {
+                    let mut accounts: Vec<(String, String)> = self.steam_accounts.iter()
+                        .map(|(id, name)| (id.clone(), name.clone()))
+                        .collect();
+                    accounts.sort_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()));
+                    let mut profile_to_back_up: Option<String> = None;
+                    ui.menu_button("👤 Back Up Steam Profile", |ui| {
+                        ui.label(egui::RichText::new("Archives the account's whole userdata folder, without caches").size(11.0).color(egui::Color32::GRAY));
+                        for (id, name) in &accounts {
+                            if ui.button(format!("{} ({})", name, id)).clicked() {
+                                profile_to_back_up = Some(id.clone());
+                                ui.close_menu();
+                            }
+                        }
+                    }).response.on_hover_text("One backup of everything Steam keeps for an account, for moving Steam to another drive or PC");
+                    if let Some(id) = profile_to_back_up {
+                        self.backup_steam_profile(&id);
+                    }
+                }
+                
+                if self.config.network.skip_in_quick_scan
+                    && ui.button("⚡ Quick Scan").on_hover_text("Rescan local folders only, skipping network drives").clicked() {
+                    self.quick_scan_saves();
+                }
+            });
+        });
+        
+        ui.separator();
+
+        // Game saves list
+        let mut filtered_saves = self.get_filtered_saves();
+        self.sort_saves(&mut filtered_saves);
+        
+        // Expected backup time of each save, from how long earlier backups took
+        let estimator = stats::DurationEstimator::new(&self.backups);
+        let estimates: Vec<Option<std::time::Duration>> = filtered_saves.iter()
+            .map(|save| estimator.as_ref().map(|e| e.estimate([*save])))
+            .collect();
+        
+        // Clone saves data to avoid borrowing issues
+        let saves_data: Vec<_> = filtered_saves.iter().map(|save| {
+            (
+                save.save_type.clone(),
+                self.save_display_name(save),
+                save.format_size(),
+                save.last_modified.map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                save.save_path.clone(),
+                save.is_network,
+                save.sandbox.clone(),
+                save.origin,
+                self.sync_client_for(&save.save_path),
+                self.steam_cloud_usage.get(&save.save_path).cloned(),
+                self.config.game_tags(&BackupManager::key_for_save(save)).to_vec(),
+                save.app_id,
+                self.game_details(save).unwrap_or_default(),
+            )
+        }).collect();
+
+        let has_profile = self.config.active_profile().is_some();
+        let mut save_to_hide: Option<std::path::PathBuf> = None;
+        let mut protection_change: Option<(std::path::PathBuf, bool)> = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("saves_grid")
+                .num_columns(9)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    // Header
+                    ui.strong("Type");
+                    ui.strong("Origin");
+                    ui.strong("Game");
+                    ui.strong("Genre");
+                    ui.strong("Year");
+                    ui.strong("Size");
+                    ui.strong("Last Modified");
+                    ui.strong("Path");
+                    ui.strong("Actions");
+                    ui.end_row();
+
+                    for (i, (save_type, display_name, size, last_mod, save_path, is_network, sandbox, origin, sync_client, steam_cloud, tags, app_id, details)) in saves_data.iter().enumerate() {
+                        // Type icon with better formatting
+                        let type_icon = match save_type {
+                            SaveType::Steam => "🔵",
+                            SaveType::NonSteam => "🟢",
+                        };
+                        ui.label(egui::RichText::new(type_icon).size(16.0));
+
+                        // Probable launcher, guessed from the folders around the save
+                        match (save_type, origin) {
+                            (SaveType::Steam, _) => { ui.label("Steam"); }
+                            (_, Some(origin)) => { ui.label(origin.label()).on_hover_text(origin.describe()); }
+                            (_, None) => { ui.label("—"); }
+                        }
+
+                        let protected = self.config.is_save_protected(save_path);
+                        let archived = app_id.map_or(false, |id| self.config.archived_apps.contains(&id));
+
+                        // Game name with app ID links and tags
+                        ui.horizontal(|ui| {
+                            ui.label(display_name);
+                            if archived {
+                                ui.label(egui::RichText::new("📦 Archived").size(11.0).color(egui::Color32::GRAY))
+                                    .on_hover_text("Uninstalled from Steam; a pinned final backup was kept");
+                            }
+                            if protected {
+                                ui.label("🔒").on_hover_text("Write-protected: nothing can change this save until protection is lifted");
+                            }
+                            if let Some(sandbox) = sandbox {
+                                ui.label(egui::RichText::new(format!("🧪 {}", sandbox.label())).size(11.0).color(egui::Color32::GRAY))
+                                    .on_hover_text("Found inside this sandbox; backups are kept apart and restores go back into it");
+                            }
+                            if let Some(client) = sync_client {
+                                ui.label(egui::RichText::new(format!("⚠ {}", client.name())).size(11.0).color(egui::Color32::from_rgb(255, 133, 27)))
+                                    .on_hover_text(client.warning());
+                            }
+                            if let Some(app_id) = app_id {
+                                ui.menu_button(egui::RichText::new("🔗").size(12.0), |ui| {
+                                    ui.label(format!("App ID {}", app_id));
+                                    Self::draw_app_links(ui, &self.config.app_links, *app_id);
+                                }).response.on_hover_text("Open the store, SteamDB or ProtonDB page");
+                            }
+                            for tag in tags {
+                                ui.label(egui::RichText::new(format!("🏷 {}", tag)).size(11.0).color(egui::Color32::GRAY));
+                            }
+                        });
+
+                        // Genre (the first two, all of them and the platforms on hover) and release year
+                        let genre_text = match details.genres.len() {
+                            0 => "—".to_string(),
+                            1 | 2 => details.genres.join(", "),
+                            n => format!("{}, {} +{}", details.genres[0], details.genres[1], n - 2),
+                        };
+                        let genre_label = ui.label(genre_text);
+                        if !details.is_empty() {
+                            let mut hover = details.genres.join(", ");
+                            if !details.platforms.is_empty() {
+                                hover.push_str(&format!("\nPlatforms: {}", details.platforms.join(", ")));
+                            }
+                            genre_label.on_hover_text(hover.trim_start().to_string());
+                        }
+                        ui.label(details.release_year.map(|year| year.to_string()).unwrap_or_else(|| "—".to_string()));
+
+                        // Size, and what the game keeps in Steam Cloud
+                        ui.horizontal(|ui| {
+                            ui.label(size);
+                            if let Some(usage) = steam_cloud {
+                                let color = if usage.is_near_limit() { egui::Color32::from_rgb(255, 133, 27) } else { egui::Color32::GRAY };
+                                ui.label(egui::RichText::new(format!("☁ {}", usage.summary())).size(11.0).color(color))
+                                    .on_hover_text(usage.describe());
+                            }
+                        });
+
+                        // Last modified
+                        ui.label(last_mod);
+
+                        // Path (truncated)
+                        let path_str = save_path.to_string_lossy();
+                        let mut truncated_path = if path_str.len() > 50 {
+                            format!("...{}", &path_str[path_str.len() - 47..])
+                        } else {
+                            path_str.to_string()
+                        };
+                        if *is_network {
+                            truncated_path = format!("🌐 {}", truncated_path);
+                        }
+                        ui.label(truncated_path).on_hover_text(if *is_network {
+                            format!("{}\nNetwork location", path_str)
+                        } else {
+                            path_str.to_string()
+                        });
+
+                        // Actions with more options
+                        ui.horizontal(|ui| {
+                            let backup_hint = match estimates[i] {
+                                Some(estimate) => format!("Create a backup of this save (~{})", format_duration(estimate)),
+                                None => "Create a backup of this save".to_string(),
+                            };
+                            if ui.button("💾 Backup").on_hover_text(backup_hint).clicked() {
+                                self.selected_game = Some(i);
+                                self.show_backup_dialog = true;
+                            }
+                            
+                            if ui.button("▶ Open").on_hover_text("Open save folder in the file manager").clicked() {
+                                if save_path.exists() {
+                                    let _ = open_in_file_manager(save_path);
+                                }
+                            }
+                            
+                            if ui.button("⎘ Copy Path").on_hover_text("Copy save path to clipboard").clicked() {
+                                ui.output_mut(|o| o.copied_text = save_path.to_string_lossy().to_string());
+                            }
+                            
+                            if ui.button("i Info").on_hover_text("Show detailed information").clicked() {
+                                self.selected_game = Some(i);
+                                self.details_save_path = Some(save_path.clone());
+                            }
+                            
+                            let (protect_label, protect_hint) = if protected {
+                                ("🔓 Unprotect", "Make this save writable again")
+                            } else {
+                                ("🔒 Protect", "Make this save read-only so no game or cloud client overwrites it; restores and syncs lift the protection while they run")
+                            };
+                            if ui.button(protect_label).on_hover_text(protect_hint).clicked() {
+                                protection_change = Some((save_path.clone(), !protected));
+                            }
+                            
+                            if has_profile && ui.button("🙈 Hide").on_hover_text("Hide this save for the current profile").clicked() {
+                                save_to_hide = Some(save_path.clone());
+                            }
+                        });
+
+                        ui.end_row();
+                    }
+                });
+        });
+        
+        if let Some(path) = save_to_hide {
+            if let Some(profile) = self.config.active_profile_mut() {
+                profile.hidden_saves.push(path);
+            }
+            self.temp_config.profiles = self.config.profiles.clone();
+        }
+        
+        if let Some((path, protect)) = protection_change {
+            self.set_save_protection(path, protect);
+        }
+    }
+    
+    /// Lock or unlock a save folder on disk and remember it, so restores and syncs can unlock it
+    fn set_save_protection(&mut self, path: std::path::PathBuf, protect: bool) {
+        let result = if protect { protection::protect(&path) } else { protection::unprotect(&path) };
+        match result {
+            Ok(_) => {
+                self.config.protected_saves.retain(|p| p != &path);
+                if protect {
+                    self.config.protected_saves.push(path.clone());
+                }
+                self.temp_config.protected_saves = self.config.protected_saves.clone();
+                self.backup_manager = Self::create_backup_manager(&self.config);
+                self.scan_status = ScanStatus::Complete(format!(
+                    "{} {}", if protect { "Write-protected" } else { "Removed write protection from" }, path.display()
+                ));
+            }
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Failed to change write protection: {}", e));
+            }
+        }
+    }
+
+    fn draw_backups_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("💾 Backup Management");
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let read_only = self.is_read_only();
+                let selected_count = self.backup_selection.len();
+                if ui.add_enabled(!read_only && selected_count > 0, egui::Button::new(format!("↺ Restore Selected ({})", selected_count)))
+                    .on_hover_text("Restore all selected backups one after another")
+                    .clicked() {
+                    let selected: Vec<BackupInfo> = self.backups.iter()
+                        .filter(|b| self.backup_selection.contains(&b.backup_path))
+                        .cloned()
+                        .collect();
+                    self.open_restore_queue(selected);
+                }
+                
+                if ui.add_enabled(!read_only && selected_count > 0, egui::Button::new(format!("✏ Edit Selected ({})", selected_count)))
+                    .on_hover_text("Tag, pin, rename descriptions or move the selected backups to another game")
+                    .clicked() {
+                    self.bulk_edit = Some(BulkEditForm::default());
+                }
+                
+                if ui.add_enabled(!read_only, egui::Button::new("🔗 Reassign…"))
+                    .on_hover_text("Move backups of renamed or re-detected games to the game's current name")
+                    .clicked() {
+                    self.open_reassign_dialog();
+                }
+                
+                if !encryption::is_unlocked() && self.backups.iter().any(|b| b.encrypted)
+                    && ui.button("🔒 Unlock…").on_hover_text("Enter the backup passphrase to restore or verify encrypted backups").clicked() {
+                    self.unlock_prompt = Some(String::new());
+                    self.unlock_error = None;
+                }
+                
+                if ui.add_enabled(!read_only && !self.config.online.offline_mode, egui::Button::new("📥 Import from URL…"))
+                    .on_hover_text("Download a backup someone shared as a link and add it to a game's backups")
+                    .clicked() {
+                    self.import_dialog = Some(ImportDialog {
+                        url: String::new(),
+                        archive: None,
+                        game: None,
+                        game_name: String::new(),
+                        app_id: String::new(),
+                        save_type: SaveType::NonSteam,
+                        description: String::new(),
+                        error: None,
+                    });
+                }
+                
+                if ui.add_enabled(!read_only, egui::Button::new("🕘 Restore As Of…"))
+                    .on_hover_text("Restore the newest backups made before a date and time")
+                    .clicked() {
+                    self.open_time_restore();
+                }
+                
+                if selected_count > 0 && ui.button("Clear Selection").clicked() {
+                    self.backup_selection.clear();
+                }
+                
+                if ui.button("Select All").clicked() {
+                    self.backup_selection = self.backups.iter().map(|b| b.backup_path.clone()).collect();
+                }
+                
+                if ui.add_enabled(!read_only, egui::Button::new("✖ Cleanup Old"))
+                    .on_hover_text("Preview the backups past the retention period before deleting them")
+                    .clicked() {
+                    if let Some(ref backup_manager) = self.backup_manager {
+                        match backup_manager.plan_cleanup() {
+                            Ok(plan) => {
+                                self.cleanup_preview = Some(plan);
+                                self.cleanup_report = None;
+                            }
+                            Err(e) => {
+                                self.scan_status = ScanStatus::Error(format!("Cleanup failed: {}", e));
+                            }
+                        }
+                    }
+                }
+            });
+        });
+
+        // Backup stats
+        if let Some(ref stats) = self.backup_stats {
+            ui.horizontal(|ui| {
+                ui.group(|ui| {
+                    ui.label(format!("Total: {}", stats.total_count));
+                });
+                ui.group(|ui| {
+                    ui.label(format!("Steam: {}", stats.steam_count));
+                });
+                ui.group(|ui| {
+                    ui.label(format!("Non-Steam: {}", stats.non_steam_count));
+                });
+                ui.group(|ui| {
+                    ui.label(format!("Size: {}", stats.format_total_size()));
+                });
+                if stats.deduplicated_count > 0 {
+                    ui.group(|ui| {
+                        ui.label(format!("Deduplicated: {} ({} saved)", stats.deduplicated_count, format_size(stats.deduplicated_bytes)))
+                            .on_hover_text("Backups identical to the previous one share its archive through a hard link");
+                    });
+                }
+            });
+        }
+
+        // Results of the scheduled integrity sweep
+        let mut run_sweep_now = false;
+        let mut run_full_check = false;
+        ui.horizontal(|ui| {
+            match self.sweep_state.last_run {
+                Some(last_run) => {
+                    ui.label(format!("🩺 Last integrity sweep: {} ({} archives checked)",
+                        last_run.format("%Y-%m-%d %H:%M"), self.sweep_state.last_checked));
+                }
+                None => {
+                    ui.label("🩺 No integrity sweep has run yet");
+                }
+            }
+            if ui.small_button("Run Now").on_hover_text("Verify the next batch of archives against their checksums").clicked() {
+                run_sweep_now = true;
+            }
+            if ui.add_enabled(self.archive_task.is_none(), egui::Button::new("Verify All").small())
+                .on_hover_text("Read every backup back and compare each file with its recorded checksum")
+                .clicked() {
+                run_full_check = true;
+            }
+        });
+        if run_sweep_now {
+            self.run_verification_sweep();
+        }
+        if run_full_check {
+            self.start_full_check();
+        }
+        
+        if !self.sweep_state.problems.is_empty() {
+            egui::CollapsingHeader::new(egui::RichText::new(format!("⚠ {} damaged or missing backups", self.sweep_state.problems.len()))
+                .color(egui::Color32::from_rgb(220, 50, 50)))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for problem in &self.sweep_state.problems {
+                        ui.label(format!("{} ({}): {}",
+                            problem.game_name, problem.found_at.format("%Y-%m-%d"), problem.description))
+                            .on_hover_text(problem.backup_path.display().to_string());
+                    }
+                });
+        }
+
+        if !self.backup_chains.is_empty() {
+            self.draw_backup_chains(ui);
+        }
+
+        ui.separator();
+
+        // Games whose newest backup was made on another PC
+        let newer_elsewhere = backup::newer_on_other_machines(&self.backups, &self.config.machine_id);
+
+        // Backups list
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("backups_grid")
+                .num_columns(8)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    // Header
+                    ui.label("");
+                    ui.strong("Type");
+                    ui.strong("Game");
+                    ui.strong("Original Location");
+                    ui.strong("Created");
+                    ui.strong("Size");
+                    ui.strong("Description");
+                    ui.strong("Actions");
+                    ui.end_row();
+
+                    // Store backup actions to avoid borrowing issues
+                    let read_only = self.is_read_only();
+                    let mut folder_to_open: Option<BackupInfo> = None;
+                    let mut backup_to_verify: Option<BackupInfo> = None;
+                    let mut backup_to_pin: Option<BackupInfo> = None;
+                    let mut backup_to_delete: Option<BackupInfo> = None;
+                    let mut restore_backup_index: Option<usize> = None;
+                    let mut settings_to_restore: Option<BackupInfo> = None;
+                    let mut selection_toggle: Option<(std::path::PathBuf, bool)> = None;
+                    
+                    for (i, backup) in self.backups.iter().enumerate() {
+                        let mut selected = self.backup_selection.contains(&backup.backup_path);
+                        if ui.checkbox(&mut selected, "").changed() {
+                            selection_toggle = Some((backup.backup_path.clone(), selected));
+                        }
+                        
+                        // Type icon with better formatting
+                        let type_icon = match backup.save_type {
+                            SaveType::Steam => "🔵",
+                            SaveType::NonSteam => "🟢",
+                        };
+                        ui.label(egui::RichText::new(type_icon).size(16.0));
+
+                        // Game name, with the owning account for Family Sharing setups and
+                        // the PC it was made on when that isn't this one
+                        ui.horizontal(|ui| {
+                            match backup.steam_user_id {
+                                Some(ref user_id) if self.steam_accounts.len() > 1 => {
+                                    let account = self.steam_accounts.get(user_id).unwrap_or(user_id);
+                                    ui.label(format!("{} · 👤 {}", backup.game_name, account));
+                                }
+                                _ => {
+                                    ui.label(&backup.game_name);
+                                }
+                            }
+                            if let Some(ref machine) = backup.machine {
+                                let newest = newer_elsewhere.get(&BackupManager::latest_key(backup))
+                                    .map_or(false, |newer| newer.backup_path == backup.backup_path);
+                                if newest {
+                                    ui.label(egui::RichText::new(format!("🖥 Newer, from {}", machine.name)).size(11.0).color(egui::Color32::from_rgb(255, 133, 27)))
+                                        .on_hover_text("Made on another machine after the newest backup from this one; restore it before playing here");
+                                } else if machine.id != self.config.machine_id {
+                                    ui.label(egui::RichText::new(format!("🖥 {}", machine.name)).size(11.0).color(egui::Color32::GRAY));
+                                }
+                            }
+                            if backup.encrypted {
+                                ui.label(egui::RichText::new("🔒").size(11.0)).on_hover_text("Encrypted with the backup passphrase");
+                            }
+                        });
+
+                        // Original location - show the improved path display
+                        let original_path_display = backup.display_original_path();
+                        if backup.is_cloud_download() {
+                            ui.colored_label(egui::Color32::from_rgb(100, 149, 237), original_path_display);
+                        } else {
+                            // Regular backup - show path with tooltip
+                            ui.label(&original_path_display)
+                                .on_hover_text(format!("Full path: {}", backup.original_path.display()));
+                        }
+
+                        // Created date
+                        let created_label = ui.label(backup.created_at.format("%Y-%m-%d %H:%M").to_string());
+                        if let Some(checked_at) = backup.verified_unchanged_at {
+                            created_label.on_hover_text(format!("Verified unchanged at {}, no newer backup was needed",
+                                checked_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")));
+                        }
+
+                        // Size
+                        let size_label = if backup.volumes.is_empty() {
+                            ui.label(backup.format_size())
+                        } else {
+                            ui.label(format!("{} ({} parts)", backup.format_size(), backup.volumes.len()))
+                        };
+                        let mut size_hint = Vec::new();
+                        if let Some(ms) = backup.duration_ms {
+                            size_hint.push(format!("Took {}", format_duration(std::time::Duration::from_millis(ms))));
+                        }
+                        if backup.excluded_files > 0 {
+                            size_hint.push(format!("Skipped {} junk files ({})",
+                                backup.excluded_files, format_size(backup.excluded_bytes)));
+                        }
+                        if let Some(ref base) = backup.base_backup {
+                            size_hint.push(format!("Incremental: only the files changed since {}", base));
+                        }
+                        if !size_hint.is_empty() {
+                            size_label.on_hover_text(size_hint.join("\n"));
+                        }
+
+                        // Description, and what the saved files say about themselves
+                        let desc = backup.description.as_deref().unwrap_or("No description");
+                        if backup.save_metadata.is_empty() {
+                            ui.label(desc);
+                        } else {
+                            ui.vertical(|ui| {
+                                ui.label(desc);
+                                let summaries: Vec<String> = backup.save_metadata.iter().map(SaveMetadata::summary).collect();
+                                let first = if summaries.len() > 1 {
+                                    format!("🎮 {} (+{} more)", summaries[0], summaries.len() - 1)
+                                } else {
+                                    format!("🎮 {}", summaries[0])
+                                };
+                                ui.label(egui::RichText::new(first).size(11.0).color(egui::Color32::GRAY))
+                                    .on_hover_text(summaries.join("\n"));
+                            });
+                        }
+
+                        // Actions
+                        ui.horizontal(|ui| {
+                            let pin_hint = if backup.pinned { "Pinned: kept by retention cleanup. Click to unpin" } else { "Pin to keep this backup past the retention period" };
+                            if ui.selectable_label(backup.pinned, "📌").on_hover_text(pin_hint).clicked() {
+                                backup_to_pin = Some(backup.clone());
+                            }
+                            
+                            // Open backup folder button
+                            if ui.button("📂").on_hover_text("Open backup folder in file explorer").clicked() {
+                                folder_to_open = Some(backup.clone());
+                            }
+                            
+                            let verify_hint = if backup.signature.is_some() { "Verify signature and checksums, reading every file back" } else { "Verify checksums, reading every file back" };
+                            if ui.button("🔏").on_hover_text(verify_hint).clicked() {
+                                backup_to_verify = Some(backup.clone());
+                            }
+                            
+                            if ui.add_enabled(!read_only, egui::Button::new("↺")).on_hover_text("Restore this backup").clicked() {
+                                restore_backup_index = Some(i);
+                            }
+                            
+                            if !backup.settings_files.is_empty() {
+                                let hint = format!("Restore only the {} settings files, not the save", backup.settings_files.len());
+                                if ui.add_enabled(!read_only, egui::Button::new("⚙")).on_hover_text(hint).clicked() {
+                                    settings_to_restore = Some(backup.clone());
+                                }
+                            }
+                            
+                            if ui.add_enabled(!read_only, egui::Button::new("❌")).on_hover_text("Delete this backup").clicked() {
+                                backup_to_delete = Some(backup.clone());
+                            }
+                        });
+
+                        ui.end_row();
+                    }
+                    
+                    // Handle actions outside the loop
+                    if let Some(backup_info) = folder_to_open {
+                        if let Some(ref backup_manager) = self.backup_manager {
+                            match backup_manager.open_backup_folder(&backup_info) {
+                                Ok(_) => {
+                                    self.scan_status = ScanStatus::Complete("Backup folder opened".to_string());
+                                }
+                                Err(e) => {
+                                    self.scan_status = ScanStatus::Error(format!("Failed to open folder: {}", e));
+                                }
+                            }
+                        }
+                    }
+                    
+                    if let Some(backup_info) = backup_to_verify {
+                        self.start_verify_task(backup_info);
+                    }
+                    
+                    if let Some(mut backup_info) = backup_to_pin {
+                        if let Some(ref backup_manager) = self.backup_manager {
+                            backup_info.pinned = !backup_info.pinned;
+                            match backup_manager.update_backup_metadata(&backup_info) {
+                                Ok(()) => {
+                                    let action = if backup_info.pinned { "Pinned" } else { "Unpinned" };
+                                    self.scan_status = ScanStatus::Complete(format!("{} backup of {}", action, backup_info.game_name));
+                                    self.load_backups();
+                                }
+                                Err(e) => {
+                                    self.scan_status = ScanStatus::Error(format!("Failed to update backup: {}", e));
+                                }
+                            }
+                        }
+                    }
+                    
+                    if let Some((backup_path, selected)) = selection_toggle {
+                        if selected {
+                            self.backup_selection.insert(backup_path);
+                        } else {
+                            self.backup_selection.remove(&backup_path);
+                        }
+                    }
+                    
+                    if let Some(backup_info) = settings_to_restore {
+                        if let Some(ref backup_manager) = self.backup_manager {
+                            self.scan_status = match backup_manager.restore_settings(&backup_info) {
+                                Ok(count) => ScanStatus::Complete(format!("Restored {} settings files of {}", count, backup_info.game_name)),
+                                Err(e) => ScanStatus::Error(format!("Settings restore failed: {}", e)),
+                            };
+                        }
+                    }
+                    
+                    if let Some(index) = restore_backup_index {
+                        self.selected_backup = Some(index);
+                        if let Some(backup) = self.backups.get(index).cloned() {
+                            self.open_restore_queue(vec![backup]);
+                        }
+                    }
+                    
+                    if let Some(backup_info) = backup_to_delete {
+                        self.request_delete(backup_info);
+                    }
+                });
+        });
+    }
+
+    /// Which incremental backups build on which, with the chains whose base is gone
+    fn draw_backup_chains(&mut self, ui: &mut egui::Ui) {
+        let orphaned = self.backup_chains.iter().filter(|chain| chain.is_orphaned()).count();
+        let title = if orphaned > 0 {
+            egui::RichText::new(format!("🔗 {} incremental backup chains, {} without their base", self.backup_chains.len(), orphaned))
+                .color(egui::Color32::from_rgb(220, 50, 50))
+        } else {
+            egui::RichText::new(format!("🔗 {} incremental backup chains", self.backup_chains.len()))
+        };
+        
+        let read_only = self.is_read_only();
+        let idle = self.archive_task.is_none();
+        let mut to_consolidate: Option<BackupInfo> = None;
+        let mut to_delete: Option<BackupInfo> = None;
+        egui::CollapsingHeader::new(title)
+            .default_open(orphaned > 0)
+            .show(ui, |ui| {
+                for chain in &self.backup_chains {
+                    let base = chain.base();
+                    ui.horizontal(|ui| {
+                        ui.strong(&base.game_name);
+                        ui.label(format!("{} backups, {}", chain.links.len(), format_size(chain.total_size())));
+                        if let Some(ref missing) = chain.missing_base {
+                            ui.colored_label(egui::Color32::from_rgb(220, 50, 50), "⚠ Base backup missing")
+                                .on_hover_text(format!("These increments only hold the changes since {}, which is gone; they can't be restored", missing));
+                            if ui.add_enabled(!read_only, egui::Button::new("🗑 Delete").small())
+                                .on_hover_text("Delete the increments that can't be restored any more")
+                                .clicked() {
+                                to_delete = Some(base.clone());
+                            }
+                        } else if ui.add_enabled(!read_only && idle, egui::Button::new("⤵ Consolidate").small())
+                            .on_hover_text("Write the newest state into a new full backup; the chain's backups stay until you delete them")
+                            .clicked() {
+                            to_consolidate = Some(chain.tip().clone());
+                        }
+                    });
+                    for link in &chain.links {
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0 * (link.depth + 1) as f32);
+                            let backup = &link.backup;
+                            let created = backup.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                            let label = if link.depth == 0 && !chain.is_orphaned() {
+                                format!("● Full backup {} · {}", created, backup.format_size())
+                            } else if backup.removed_files.is_empty() {
+                                format!("└ {} · {} of changes", created, backup.format_size())
+                            } else {
+                                format!("└ {} · {} of changes, {} removed", created, backup.format_size(), backup.removed_files.len())
+                            };
+                            ui.label(egui::RichText::new(label).size(12.0))
+                                .on_hover_text(backup.description.as_deref().unwrap_or(&backup.id));
+                        });
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+        
+        if let Some(tip) = to_consolidate {
+            self.start_consolidation(tip, Vec::new());
+        }
+        if let Some(base) = to_delete {
+            self.request_delete(base);
+        }
+    }
+    
+    /// Delete a backup, asking first when incremental backups build on it
+    fn request_delete(&mut self, backup_info: BackupInfo) {
+        let dependents: Vec<BackupInfo> = incremental::dependents(&self.backups, &backup_info.id)
+            .into_iter()
+            .cloned()
+            .collect();
+        if dependents.is_empty() {
+            self.delete_backups(vec![backup_info]);
+        } else {
+            self.chain_delete = Some((backup_info, dependents));
+        }
+    }
+    
+    /// Delete backups in the order given, increments before the backups they build on;
+    /// false if one couldn't be deleted
+    fn delete_backups(&mut self, backups: Vec<BackupInfo>) -> bool {
+        let backup_manager = match self.backup_manager {
+            Some(ref backup_manager) => backup_manager,
+            None => return false,
+        };
+        let mut deleted = 0;
+        for backup_info in &backups {
+            if let Err(e) = backup_manager.delete_backup(backup_info) {
+                self.scan_status = ScanStatus::Error(format!("Delete failed: {}", e));
+                self.load_backups();
+                return false;
+            }
+            deleted += 1;
+        }
+        self.scan_status = ScanStatus::Complete(if deleted == 1 {
+            "Backup deleted".to_string()
+        } else {
+            format!("{} backups deleted", deleted)
+        });
+        self.load_backups();
+        true
+    }
+    
+    fn draw_chain_delete(&mut self, ctx: &egui::Context) {
+        let (backup_info, dependents) = match self.chain_delete {
+            Some((ref backup_info, ref dependents)) => (backup_info.clone(), dependents.clone()),
+            None => return,
+        };
+        // Nothing to consolidate when the backup's own base is gone
+        let base_gone = backup_info.base_backup.as_ref().map_or(false, |base| !self.backups.iter().any(|b| &b.id == base));
+        let mut open = true;
+        let mut consolidate = false;
+        let mut delete_all = false;
+        
+        egui::Window::new("🗑 Delete Backup")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Delete the backup of {} from {}?",
+                    backup_info.game_name, backup_info.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")));
+                ui.colored_label(egui::Color32::from_rgb(255, 133, 27),
+                    format!("⚠ {} incremental backups build on it and couldn't be restored without it:", dependents.len()));
+                egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                    for dependent in &dependents {
+                        ui.label(format!("• {} · {}",
+                            dependent.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"), dependent.format_size()));
+                    }
+                });
+                ui.label(egui::RichText::new("Consolidating first keeps the newest state as a new full backup")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let idle = self.archive_task.is_none();
+                    if ui.add_enabled(idle && !base_gone, egui::Button::new("⤵ Consolidate, Then Delete"))
+                        .on_disabled_hover_text("Its own base backup is gone, there is nothing to consolidate")
+                        .clicked() {
+                        consolidate = true;
+                    }
+                    if ui.button(format!("🗑 Delete All {}", dependents.len() + 1)).clicked() {
+                        delete_all = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.chain_delete = None;
+                    }
+                });
+            });
+        
+        if consolidate || delete_all {
+            self.chain_delete = None;
+            let mut backups = dependents;
+            let tip = backups.first().cloned();
+            backups.push(backup_info);
+            match tip {
+                Some(tip) if consolidate => self.start_consolidation(tip, backups),
+                _ => {
+                    self.delete_backups(backups);
+                }
+            }
+        } else if !open {
+            self.chain_delete = None;
+        }
+    }
+    
+    fn draw_analytics_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("📊 Backup Store Analytics");
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("📤 Export CSV").on_hover_text("Export the store size history").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_file_name("save_guardian_store_history.csv")
+                        .add_filter("CSV", &["csv"])
+                        .save_file() {
+                        self.scan_status = match self.store_history.export_csv(&path) {
+                            Ok(()) => ScanStatus::Complete(format!("Exported statistics to {}", path.display())),
+                            Err(e) => ScanStatus::Error(format!("Export failed: {}", e)),
+                        };
+                    }
+                }
+            });
+        });
+        
+        ui.separator();
+        
+        let backup_root = self.config.effective_backup_path();
+        let free_space = stats::available_space(&backup_root);
+        let forecast = stats::forecast(&self.store_history, &self.backups, self.config.backup_retention_days, free_space);
+        let total_size: u64 = self.backups.iter().map(|b| b.size).sum();
+        
+        ui.horizontal(|ui| {
+            ui.group(|ui| {
+                ui.label(format!("Store size: {}", format_size(total_size)));
+            });
+            ui.group(|ui| {
+                ui.label(format!("Free on drive: {}", free_space.map(format_size).unwrap_or_else(|| "Unknown".to_string())));
+            });
+            ui.group(|ui| {
+                let growth = if forecast.daily_growth >= 0.0 {
+                    format!("+{}/day", format_size(forecast.daily_growth as u64))
+                } else {
+                    format!("-{}/day", format_size((-forecast.daily_growth) as u64))
+                };
+                ui.label(format!("Growth: {}", growth));
+            });
+        });
+        
+        ui.add_space(10.0);
+        
+        ui.group(|ui| {
+            ui.strong("Forecast");
+            ui.separator();
+            
+            match forecast.days_until_full {
+                Some(days) => {
+                    let full_on = chrono::Utc::now() + chrono::Duration::days(days as i64);
+                    let color = if days < 30.0 {
+                        egui::Color32::from_rgb(220, 50, 50)
+                    } else if days < 90.0 {
+                        egui::Color32::from_rgb(255, 165, 0)
+                    } else {
+                        egui::Color32::from_rgb(0, 200, 0)
+                    };
+                    ui.colored_label(color, format!(
+                        "At the current rate the backup drive fills up in about {:.0} days ({})",
+                        days, full_on.format("%Y-%m-%d")
+                    ));
+                }
+                None => {
+                    ui.label("The backup store is not growing, no risk of running out of space");
+                }
+            }
+            
+            if let Some(suggested) = forecast.suggested_retention_days {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "💡 Lowering retention from {} to {} days keeps the store within 80% of the drive",
+                        self.config.backup_retention_days, suggested
+                    ));
+                    if ui.button("Apply").clicked() {
+                        self.config.backup_retention_days = suggested;
+                        self.temp_config.backup_retention_days = suggested;
+                        self.rebuild_managers();
+                        self.scan_status = ScanStatus::Complete(format!("Retention set to {} days", suggested));
+                    }
+                });
+            }
+        });
+        
+        ui.add_space(10.0);
+        
+        // How long backups take, slowest games first
+        ui.group(|ui| {
+            ui.strong("Backup Speed");
+            ui.separator();
+            
+            match stats::overall_speed(&self.backups) {
+                Some(overall) => {
+                    ui.label(format!(
+                        "{} timed backups: {} on average, {}/s",
+                        overall.timed_backups,
+                        format_duration(overall.average_duration),
+                        format_size(overall.bytes_per_second as u64)
+                    ));
+                    let mut speeds: Vec<stats::BackupSpeed> = stats::backup_speeds(&self.backups).into_values().collect();
+                    speeds.sort_by(|a, b| b.average_duration.cmp(&a.average_duration));
+                    egui::CollapsingHeader::new(format!("Per game ({})", speeds.len()))
+                        .id_source("backup_speed_per_game")
+                        .show(ui, |ui| {
+                            egui::Grid::new("backup_speed_grid")
+                                .num_columns(4)
+                                .spacing([10.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.strong("Game");
+                                    ui.strong("Backups");
+                                    ui.strong("Average Time");
+                                    ui.strong("Throughput");
+                                    ui.end_row();
+                                    
+                                    for speed in &speeds {
+                                        ui.label(&speed.game_name);
+                                        ui.label(speed.timed_backups.to_string());
+                                        ui.label(format_duration(speed.average_duration));
+                                        ui.label(format!("{}/s", format_size(speed.bytes_per_second as u64)));
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+                None => {
+                    ui.label(egui::RichText::new("No timed backups yet, backups made from now on record how long they took")
+                        .size(11.0).color(egui::Color32::GRAY));
+                }
+            }
+        });
+        
+        ui.add_space(10.0);
+        
+        // Store size over time, newest first
+        ui.strong("History");
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("store_history_grid")
+                .num_columns(4)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Date");
+                    ui.strong("Store Size");
+                    ui.strong("Backups");
+                    ui.strong("Free Space");
+                    ui.end_row();
+                    
+                    for sample in self.store_history.samples.iter().rev().take(100) {
+                        ui.label(sample.at.format("%Y-%m-%d %H:%M").to_string());
+                        ui.label(format_size(sample.total_size));
+                        ui.label(sample.backup_count.to_string());
+                        ui.label(sample.free_space.map(format_size).unwrap_or_else(|| "-".to_string()));
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+
+    fn draw_sync_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("🔄 Save Sync");
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("🔍 Find Pairs").on_hover_text("Detect saves of the same game in two places").clicked() {
+                    let rules = match SyncRules::from_config(&self.config) {
+                        Ok(rules) => rules,
+                        Err(e) => {
+                            self.scan_status = ScanStatus::Error(format!("Could not read the rules file: {}", e));
+                            return;
+                        }
+                    };
+                    let sync_manager = SyncManager::new(self.config.auto_backup).with_rules(rules);
+                    let mut added = 0;
+                    for pair in sync_manager.find_sync_pairs(&self.steam_saves, &self.non_steam_saves) {
+                        // Only complete pairs are worth registering
+                        let exists = match (&pair.left, &pair.right) {
+                            (Some(left), Some(right)) => self.config.sync_pairs.iter().any(|p| {
+                                p.left.as_ref().map(|s| &s.save_path) == Some(&left.save_path)
+                                    && p.right.as_ref().map(|s| &s.save_path) == Some(&right.save_path)
+                            }),
+                            _ => true,
+                        };
+                        if !exists {
+                            self.config.sync_pairs.push(pair);
+                            added += 1;
+                        }
+                    }
+                    self.scan_status = ScanStatus::Complete(format!("Found {} new sync pairs", added));
+                }
+                
+                let without_app_id: Vec<GameSave> = self.non_steam_saves.iter()
+                    .filter(|save| save.app_id.is_none())
+                    .cloned()
+                    .collect();
+                let idle = self.archive_task.is_none() && !without_app_id.is_empty();
+                if ui.add_enabled(idle, egui::Button::new("🔎 Steam App IDs"))
+                    .on_hover_text("Look up which Steam games the non-Steam saves are, from steam_appid.txt files, install folders and names, so they pair by app ID")
+                    .clicked() {
+                    self.start_app_id_lookup(without_app_id);
+                }
+            });
+        });
+        
+        ui.separator();
+        
+        // New pair from any two locations, whatever their save type
+        ui.group(|ui| {
+            ui.strong("New Pair");
+            let all_saves: Vec<GameSave> = self.steam_saves.iter().chain(self.non_steam_saves.iter()).cloned().collect();
+            
+            for (label, side) in [("Left:", &mut self.new_pair_left), ("Right:", &mut self.new_pair_right)] {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    let selected_text = side.as_ref()
+                        .map(|s| format!("{} — {}", s.name, s.save_path.display()))
+                        .unwrap_or_else(|| "Choose a save...".to_string());
+                    egui::ComboBox::from_id_source(label)
+                        .width(450.0)
+                        .selected_text(selected_text)
+                        .show_ui(ui, |ui| {
+                            for save in &all_saves {
+                                let is_selected = side.as_ref().map_or(false, |s| s.save_path == save.save_path);
+                                if ui.selectable_label(is_selected, format!("{} — {}", save.name, save.save_path.display())).clicked() {
+                                    *side = Some(save.clone());
+                                }
+                            }
+                        });
+                    
+                    if ui.button("📁").on_hover_text("Use any folder").clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            let name = folder.file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_else(|| "Custom".to_string());
+                            *side = Some(GameSave::new(name, folder, SaveType::NonSteam, None));
+                        }
+                    }
+                });
+            }
+            
+            let can_add = match (&self.new_pair_left, &self.new_pair_right) {
+                (Some(left), Some(right)) => left.save_path != right.save_path,
+                _ => false,
+            };
+            if ui.add_enabled(can_add, egui::Button::new("+ Add Pair")).clicked() {
+                let sync_manager = SyncManager::new(self.config.auto_backup);
+                match sync_manager.create_manual_sync_pair(self.new_pair_left.take(), self.new_pair_right.take(), None) {
+                    Ok(pair) => self.config.sync_pairs.push(pair),
+                    Err(e) => self.scan_status = ScanStatus::Error(format!("Could not create pair: {}", e)),
+                }
+            }
+        });
+        
+        ui.add_space(10.0);
+        
+        let read_only = self.is_read_only();
+        let mut pair_to_sync: Option<usize> = None;
+        let mut pair_to_remove: Option<usize> = None;
+        
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("sync_pairs_grid")
+                .num_columns(10)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Game");
+                    ui.strong("Left");
+                    ui.strong("Right");
+                    ui.strong("Read-only");
+                    ui.strong("Direction");
+                    ui.strong("Strategy");
+                    ui.strong("Schedule");
+                    ui.strong("Last Result");
+                    ui.strong("Next Run");
+                    ui.strong("Actions");
+                    ui.end_row();
+                    
+                    for (i, pair) in self.config.sync_pairs.iter_mut().enumerate() {
+                        if pair.from_rule {
+                            ui.label(format!("📘 {}", pair.game_name)).on_hover_text("Paired by the community rules file");
+                        } else {
+                            ui.label(&pair.game_name);
+                        }
+                        
+                        for (side, pair_side) in [(&pair.left, PairSide::Left), (&pair.right, PairSide::Right)] {
+                            let locked = pair.locked_side == Some(pair_side);
+                            match side {
+                                Some(save) => {
+                                    let icon = match save.save_type {
+                                        SaveType::Steam => "🔵",
+                                        SaveType::NonSteam => "🟢",
+                                    };
+                                    let path_str = save.save_path.to_string_lossy();
+                                    let truncated = if path_str.len() > 40 {
+                                        format!("...{}", &path_str[path_str.len() - 37..])
+                                    } else {
+                                        path_str.to_string()
+                                    };
+                                    let lock = if locked { "🔒 " } else { "" };
+                                    ui.label(format!("{}{} {}", lock, icon, truncated)).on_hover_text(path_str.as_ref());
+                                }
+                                None => {
+                                    ui.label("—");
+                                }
+                            }
+                        }
+                        
+                        let mut locked_side = pair.locked_side;
+                        egui::ComboBox::from_id_source(format!("sync_lock_{}", i))
+                            .selected_text(match locked_side {
+                                Some(PairSide::Left) => "🔒 Left",
+                                Some(PairSide::Right) => "🔒 Right",
+                                None => "None",
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut locked_side, None, "None");
+                                ui.selectable_value(&mut locked_side, Some(PairSide::Left), "🔒 Left")
+                                    .on_hover_text("Left is the master copy and is never written to");
+                                ui.selectable_value(&mut locked_side, Some(PairSide::Right), "🔒 Right")
+                                    .on_hover_text("Right is the master copy and is never written to");
+                            });
+                        if locked_side != pair.locked_side {
+                            pair.lock_side(locked_side);
+                        }
+                        
+                        let bidirectional_label = if pair.locked_side.is_some() { "⇄ Master wins" } else { "⇄ Newest wins" };
+                        egui::ComboBox::from_id_source(format!("sync_direction_{}", i))
+                            .selected_text(match pair.sync_direction {
+                                SyncDirection::LeftToRight => "Left → Right",
+                                SyncDirection::RightToLeft => "Right → Left",
+                                SyncDirection::Bidirectional => bidirectional_label,
+                            })
+                            .show_ui(ui, |ui| {
+                                // Directions that would write into the locked side are not offered
+                                for (direction, label) in [
+                                    (SyncDirection::LeftToRight, "Left → Right"),
+                                    (SyncDirection::RightToLeft, "Right → Left"),
+                                    (SyncDirection::Bidirectional, bidirectional_label),
+                                ] {
+                                    if pair.allows_direction(&direction) {
+                                        ui.selectable_value(&mut pair.sync_direction, direction, label);
+                                    }
+                                }
+                            });
+                        
+                        egui::ComboBox::from_id_source(format!("sync_strategy_{}", i))
+                            .selected_text(match pair.strategy {
+                                SyncStrategy::Replace => "Replace",
+                                SyncStrategy::Merge => "Merge",
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut pair.strategy, SyncStrategy::Replace, "Replace")
+                                    .on_hover_text("The source folder replaces the destination");
+                                ui.selectable_value(&mut pair.strategy, SyncStrategy::Merge, "Merge")
+                                    .on_hover_text("Keep files from both sides, newer copy wins per file");
+                            });
+                        
+                        ui.horizontal(|ui| {
+                            let mut kind = match pair.schedule {
+                                SyncSchedule::Manual => 0,
+                                SyncSchedule::OnStartup => 1,
+                                SyncSchedule::EveryMinutes(_) => 2,
+                            };
+                            egui::ComboBox::from_id_source(format!("sync_schedule_{}", i))
+                                .selected_text(["Manual", "On app start", "Every"][kind])
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut kind, 0, "Manual");
+                                    ui.selectable_value(&mut kind, 1, "On app start");
+                                    ui.selectable_value(&mut kind, 2, "Every");
+                                });
+                            
+                            let mut minutes = match pair.schedule {
+                                SyncSchedule::EveryMinutes(minutes) => minutes,
+                                _ => 60,
+                            };
+                            if kind == 2 {
+                                ui.add(egui::DragValue::new(&mut minutes).clamp_range(5..=10_080).suffix(" min"));
+                            }
+                            
+                            pair.schedule = match kind {
+                                0 => SyncSchedule::Manual,
+                                1 => SyncSchedule::OnStartup,
+                                _ => SyncSchedule::EveryMinutes(minutes),
+                            };
+                        });
+                        
+                        let last_synced = pair.last_synced
+                            .map(|t| format!("Last synced {}", t.format("%Y-%m-%d %H:%M")))
+                            .unwrap_or_else(|| "Never synced".to_string());
+                        match pair.last_result {
+                            Some(ref result) => {
+                                let color = if result.starts_with('✅') {
+                                    egui::Color32::from_rgb(0, 200, 0)
+                                } else {
+                                    egui::Color32::from_rgb(220, 50, 50)
+                                };
+                                ui.colored_label(color, result).on_hover_text(last_synced);
+                            }
+                            None => {
+                                ui.label("—").on_hover_text(last_synced);
+                            }
+                        }
+                        
+                        ui.label(match pair.schedule {
+                            SyncSchedule::Manual => "—".to_string(),
+                            SyncSchedule::OnStartup => "Next app start".to_string(),
+                            SyncSchedule::EveryMinutes(_) => pair.next_run()
+                                .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_default(),
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            let complete = pair.left.is_some() && pair.right.is_some();
+                            if ui.add_enabled(complete && !read_only, egui::Button::new("🔄 Sync")).clicked() {
+                                pair_to_sync = Some(i);
+                            }
+                            if ui.button("❌").on_hover_text("Remove this pair").clicked() {
+                                pair_to_remove = Some(i);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+        });
+        
+        if let Some(i) = pair_to_sync {
+            self.held_sync = self.sync_pair(i, false).map(|check| (i, check));
+        }
+        
+        if let Some(i) = pair_to_remove {
+            self.config.sync_pairs.remove(i);
+        }
+    }
+    
+    /// Sync a pair, checking its source first unless `allow_unhealthy`
+    ///
+    /// Returns the failed check when the sync was refused because of it.
+    fn sync_pair(&mut self, index: usize, allow_unhealthy: bool) -> Option<SourceCheck> {
+        if self.is_read_only() {
+            return None;
+        }
+        
+        let mut sync_manager = SyncManager::new(self.config.auto_backup)
+            .with_skip_unchanged(self.config.skip_unchanged_backups)
+            .with_protected_saves(self.config.protected_saves.clone());
+        if !allow_unhealthy {
+            sync_manager = sync_manager.with_health_checks(self.backups.clone());
+        }
+        let pair = match self.config.sync_pairs.get_mut(index) {
+            Some(pair) => pair,
+            None => return None,
+        };
+        
+        let direction = pair.sync_direction.clone();
+        pair.last_attempt = Some(chrono::Utc::now());
+        let mut log = OperationLog::new(format!("Sync {}", pair.game_name));
+        let mut held = None;
+        match sync_manager.sync_saves(pair, direction, self.backup_manager.as_ref()) {
+            Ok(result) => {
+                pair.last_result = Some(format!("✅ {} files ({})", result.files_copied, result.format_bytes_copied()));
+                let message = format!(
+                    "Synced {}: {} files ({}) to {}",
+                    pair.game_name, result.files_copied, result.format_bytes_copied(), result.destination_path.display()
+                );
+                log.entries = result.log;
+                log.finish(message.clone());
+                self.scan_status = ScanStatus::Complete(message);
+            }
+            Err(SaveGuardianError::UnhealthySource(check)) => {
+                pair.last_result = Some(format!("⚠ Not synced: {}", check));
+                let message = format!("Did not sync {}: {}", pair.game_name, check);
+                log.skipped(&pair.game_name, check.to_string());
+                log.finish(message.clone());
+                self.scan_status = ScanStatus::Error(message);
+                held = Some(check);
+            }
+            Err(e) => {
+                pair.last_result = Some(format!("❌ {}", e));
+                let message = format!("Sync failed for {}: {}", pair.game_name, e);
+                log.failed(&pair.game_name, e.to_string());
+                log.finish(message.clone());
+                self.scan_status = ScanStatus::Error(message);
+            }
+        }
+        self.record_operation(log);
+        held
+    }
+    
+    /// Sync every pair whose schedule matches, e.g. on startup or when its interval is up
+    fn run_scheduled_syncs(&mut self, on_startup: bool) {
+        if self.is_read_only() {
+            return;
+        }
+        
+        let now = chrono::Utc::now();
+        let due: Vec<usize> = self.config.sync_pairs.iter().enumerate()
+            .filter(|(_, pair)| if on_startup {
+                pair.schedule == SyncSchedule::OnStartup && pair.left.is_some() && pair.right.is_some()
+            } else {
+                pair.is_due(now)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        
+        for index in due {
+            info!("Scheduled sync of {}", self.config.sync_pairs[index].game_name);
+            // Nobody is there to confirm, a refused sync just waits for the next run
+            self.sync_pair(index, false);
+        }
+    }
+
+    fn draw_cloud_tab(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to: Option<String> = None;
+        ui.horizontal(|ui| {
+            ui.heading(format!("☁ {} Cloud Sync", self.config.remote_name()));
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let status_color = if self.config.cloud_enabled() {
+                    egui::Color32::from_rgb(46, 204, 64)
+                } else {
+                    egui::Color32::from_rgb(255, 133, 27)
+                };
+                let status_text = if self.config.cloud_enabled() { "Enabled" } else { "Disabled" };
+                ui.colored_label(status_color, status_text);
+                
+                // Other destinations set up in Settings
+                if !self.config.cloud_remotes.is_empty() {
+                    let current = self.config.remote_name();
+                    ui.add_enabled_ui(!self.is_transferring(), |ui| {
+                        egui::ComboBox::from_id_source("cloud_remote")
+                            .selected_text(&current)
+                            .show_ui(ui, |ui| {
+                                for name in self.config.remote_names() {
+                                    if ui.selectable_label(name == current, &name).clicked() && name != current {
+                                        switch_to = Some(name);
+                                    }
+                                }
+                            });
+                    });
+                    ui.label("Remote:");
+                    if self.config.mirror_to_all_remotes {
+                        ui.label(egui::RichText::new("⧉ Mirroring uploads to all remotes").size(11.0).color(egui::Color32::GRAY));
+                    }
+                }
+            });
+        });
+        if let Some(name) = switch_to {
+            self.switch_remote(&name);
+        }
+        
+        ui.separator();
+        
+        if !self.config.cloud_enabled() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(50.0);
+                ui.label(egui::RichText::new("Cloud sync is disabled").size(16.0));
+                ui.add_space(10.0);
+                ui.label("Configure a Koofr, Nextcloud, ownCloud, WebDAV, SFTP or folder destination in Settings to enable cloud backup.");
+                ui.add_space(20.0);
+                if ui.button(egui::RichText::new("⚙ Go to Settings").size(14.0)).clicked() {
+                    self.selected_tab = Tab::Settings;
+                }
+                
+                ui.add_space(20.0);
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong("Koofr Setup Instructions:");
+                        ui.label("1. Create account at https://app.koofr.net");
+                        ui.label("2. Generate app password in account settings");
+                        ui.label("3. Use WebDAV URL: https://app.koofr.net/dav/Koofr");
+                        ui.label("4. Enter your email and app password in Settings");
+                    });
+                });
+                
+                ui.add_space(10.0);
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong("Nextcloud / ownCloud:");
+                        ui.label("1. Pick the server type in Settings");
+                        ui.label("2. Nextcloud URL: https://<server>/remote.php/dav/files/<user>");
+                        ui.label("   ownCloud URL: https://<server>/remote.php/webdav");
+                        ui.label("3. Use an app password from Settings > Security");
+                    });
+                });
+            });
+            return;
+        }
+        
+        // A transfer paused before the app was closed
+        let mut resume_paused = false;
+        let mut discard_paused = false;
+        if let Some(ref paused) = self.paused_transfer {
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::from_rgb(255, 133, 27), format!(
+                    "⏸ {} paused {}: {} files ({}) left",
+                    paused.operation,
+                    paused.paused_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                    paused.jobs.len(),
+                    format_size(paused.total_bytes()),
+                )).on_hover_text(match paused.mirror {
+                    Some(ref name) => format!("Mirror to {}", name),
+                    None => paused.endpoint.clone(),
+                });
+                if ui.add_enabled(!self.is_transferring(), egui::Button::new("▶ Resume")).clicked() {
+                    resume_paused = true;
+                }
+                if ui.button("✖ Discard").on_hover_text("Forget the files left; the next sync picks up what is still needed").clicked() {
+                    discard_paused = true;
+                }
+            });
+            ui.separator();
+        }
+        if resume_paused {
+            self.resume_paused_transfer();
+        }
+        if discard_paused {
+            self.discard_paused_transfer();
+        }
+        
+        // Cloud sync status and controls
+        ui.horizontal(|ui| {
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.strong("Connection Status");
+                    ui.colored_label(egui::Color32::from_rgb(46, 204, 64), "✓ Connected");
+                    ui.label(format!("Server: {}", self.config.cloud_endpoint()));
+                    if cloud::CloudCipher::from_config(&self.config.cloud_encryption).is_some() {
+                        ui.label("🔐 Uploads are encrypted");
+                    }
+                });
+            });
+            
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.strong("Sync Statistics");
+                    let last_sync_text = match self.last_sync_time {
+                        Some(time) => time.format("%Y-%m-%d %H:%M UTC").to_string(),
+                        None => "Never".to_string(),
+                    };
+                    ui.label(format!("Last sync: {}", last_sync_text));
+                    ui.label(format!("Files synced: {}", self.cloud_files_synced));
+                    let storage_mb = self.cloud_storage_used as f64 / (1024.0 * 1024.0);
+                    ui.label(format!("Cloud storage used: {:.1} MB", storage_mb));
+                });
+            });
+            
+            if self.config.koofr_config.auto_sync {
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong("Automatic Sync");
+                        ui.label(format!("Every {} minutes while the app is open", self.config.koofr_config.sync_interval_minutes));
+                        
+                        if self.auto_sync_running {
+                            ui.label("⟲ Syncing now...");
+                        } else if let Some(next) = self.auto_sync_next {
+                            let secs = (next - chrono::Utc::now()).num_seconds().max(0);
+                            let countdown = if secs >= 3600 {
+                                format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+                            } else {
+                                format!("{}:{:02}", secs / 60, secs % 60)
+                            };
+                            ui.label(format!("Next sync in {}", countdown));
+                            ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+                        } else {
+                            ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "Paused (read-only or offline mode)");
+                        }
+                        
+                        match self.auto_sync_last {
+                            Some((time, ScanStatus::Complete(ref summary))) => {
+                                ui.colored_label(egui::Color32::from_rgb(46, 204, 64), format!("Last run {}", time.with_timezone(&chrono::Local).format("%H:%M")))
+                                    .on_hover_text(summary);
+                            }
+                            Some((time, ScanStatus::Error(ref error))) => {
+                                ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("Last run {} failed", time.with_timezone(&chrono::Local).format("%H:%M")))
+                                    .on_hover_text(error);
+                            }
+                            _ => {
+                                ui.label("Last run: not yet");
+                            }
+                        }
+                    });
+                });
+            }
+        });
+        
+        ui.separator();
+        
+        // Manual sync controls
+        let read_only = self.is_read_only() || self.is_transferring();
+        ui.horizontal(|ui| {
+            ui.label("Manual Sync:");
+            
+            if ui.add_enabled(!read_only, egui::Button::new("↑ Upload All Backups")).on_hover_text("Upload all local backups to cloud").clicked() {
+                self.upload_backups_to_koofr();
+            }
+            
+            if ui.add_enabled(!read_only, egui::Button::new("↓ Download from Cloud")).on_hover_text("Download backups from cloud").clicked() {
+                self.download_backups_from_koofr();
+            }
+            
+            if ui.add_enabled(!read_only, egui::Button::new("⟲ Full Sync")).on_hover_text("Synchronize local and cloud backups").clicked() {
+                self.full_sync_koofr();
+            }
+            
+            if ui.button("📜 History").on_hover_text("Every upload and download, with size, duration and result").clicked() {
+                self.cloud_history = Some(CloudHistory::load(&self.config.effective_backup_path()));
+            }
+            
+            if !self.cloud_conflicts.is_empty() {
+                let label = egui::RichText::new(format!("⚠ {} conflicts", self.cloud_conflicts.len()))
+                    .color(egui::Color32::from_rgb(255, 133, 27));
+                if ui.button(label).on_hover_text("Backups changed both locally and in the cloud").clicked() {
+                    self.show_cloud_conflicts = true;
+                }
+            }
+        });
+        
+        // Games played further on another PC, from the backups here and the cloud manifest
+        let mut check_machines = false;
+        let manifest_backups = self.cloud_manifest.iter().flat_map(|m| m.entries.iter().map(|e| &e.backup));
+        let mut newer_elsewhere: Vec<BackupInfo> = backup::newer_on_other_machines(self.backups.iter().chain(manifest_backups), &self.config.machine_id)
+            .into_values()
+            .collect();
+        newer_elsewhere.sort_by(|a, b| a.game_name.to_lowercase().cmp(&b.game_name.to_lowercase()));
+        ui.horizontal(|ui| {
+            if ui.button("🖥 Check Other Machines").on_hover_text("Read the cloud manifest for backups made on other PCs").clicked() {
+                check_machines = true;
+            }
+            match self.cloud_manifest {
+                Some(_) if newer_elsewhere.is_empty() => {
+                    ui.colored_label(egui::Color32::from_rgb(46, 204, 64), "✓ No newer backups from other machines");
+                }
+                Some(_) => {
+                    ui.colored_label(egui::Color32::from_rgb(255, 133, 27), format!("⚠ {} games have newer backups from other machines", newer_elsewhere.len()));
+                }
+                None => {
+                    ui.label(egui::RichText::new("Not checked yet").size(11.0).color(egui::Color32::GRAY));
+                }
+            }
+        });
+        for newer in &newer_elsewhere {
+            let machine = newer.machine.as_ref().map(|m| m.name.as_str()).unwrap_or("another machine");
+            ui.label(format!("   {} — {} on {}", newer.game_name,
+                newer.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"), machine))
+                .on_hover_text("Download and restore it before playing here, or this machine's older save wins the next backup");
+        }
+        if check_machines {
+            self.cloud_manifest = self.fetch_cloud_manifest();
+            if self.cloud_manifest.is_none() {
+                self.scan_status = ScanStatus::Error("Could not read the cloud manifest, see the log".to_string());
+            }
+        }
+        
+        self.draw_replica_report(ui, read_only);
+        
+        ui.separator();
+        
+        self.draw_cloud_game_selection(ui);
+        
+        ui.separator();
+        
+        // Cloud backup list
+        let mut refresh = false;
+        let mut entry_to_restore: Option<CloudEntry> = None;
+        let mut entry_to_share: Option<CloudEntry> = None;
+        let can_share = self.config.cloud_backend == CloudBackend::WebDav && self.config.koofr_config.server.can_share();
+        ui.horizontal(|ui| {
+            ui.strong("Cloud Backups");
+            if ui.add_enabled(!self.is_transferring(), egui::Button::new("🔄 Refresh")).on_hover_text("List the backups in the cloud").clicked() {
+                refresh = true;
+            }
+            
+            ui.separator();
+            
+            ui.label("🔍 Search:");
+            ui.text_edit_singleline(&mut self.cloud_search);
+            ui.checkbox(&mut self.cloud_filter_steam, "Steam");
+            ui.checkbox(&mut self.cloud_filter_non_steam, "Non-Steam");
+            
+            ui.label("Sort by:");
+            egui::ComboBox::from_id_source("cloud_sort_by")
+                .selected_text(format!("{:?}", self.cloud_sort_by))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.cloud_sort_by, SortBy::Name, "Name");
+                    ui.selectable_value(&mut self.cloud_sort_by, SortBy::LastModified, "Created");
+                    ui.selectable_value(&mut self.cloud_sort_by, SortBy::Size, "Size");
+                    ui.selectable_value(&mut self.cloud_sort_by, SortBy::Type, "Type");
+                });
+        });
+        
+        egui::ScrollArea::vertical().id_source("cloud_backups_scroll").show(ui, |ui| {
+            let listing = match self.cloud_listing {
+                Some(ref listing) if !listing.is_empty() => listing,
+                Some(_) => {
+                    ui.group(|ui| {
+                        ui.label("No cloud backups found.");
+                        ui.label("Upload some backups to see them here.");
+                    });
+                    return;
+                }
+                None => {
+                    ui.label(egui::RichText::new("Refresh to list the backups in the cloud").color(egui::Color32::GRAY));
+                    return;
+                }
+            };
+            
+            let rows = self.filtered_cloud_rows(listing);
+            if rows.len() < listing.len() {
+                ui.label(egui::RichText::new(format!("{} of {} backups match", rows.len(), listing.len())).size(11.0).color(egui::Color32::GRAY));
+            }
+            
+            egui::Grid::new("cloud_backups_grid")
+                .num_columns(6)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Type");
+                    ui.strong("Game");
+                    ui.strong("Created");
+                    ui.strong("From");
+                    ui.strong("Size");
+                    ui.strong("Actions");
+                    ui.end_row();
+                    
+                    for row in &rows {
+                        match row.save_type {
+                            Some(SaveType::Steam) => ui.label(egui::RichText::new("🔵").size(16.0)),
+                            Some(SaveType::NonSteam) => ui.label(egui::RichText::new("🟢").size(16.0)),
+                            None => ui.label("—").on_hover_text("Not in the cloud manifest or the local backups"),
+                        };
+                        ui.label(&row.game).on_hover_text(&row.entry.name);
+                        ui.label(row.created.map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()).unwrap_or_else(|| "—".to_string()));
+                        ui.label(row.machine.unwrap_or("—"));
+                        ui.label(row.entry.size.map(format_size).unwrap_or_else(|| "—".to_string()));
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(!read_only, egui::Button::new("↺ Restore"))
+                                .on_hover_text("Download this backup and restore it to the game's save folder, backing up the current save first")
+                                .clicked() {
+                                entry_to_restore = Some(row.entry.clone());
+                            }
+                            let share_hint = if can_share {
+                                "Create a download link to send this backup to a friend or another device"
+                            } else {
+                                "Sharing links need Koofr, Nextcloud or ownCloud as the server type in Settings"
+                            };
+                            if ui.add_enabled(can_share, egui::Button::new("🔗"))
+                                .on_hover_text(share_hint)
+                                .on_disabled_hover_text(share_hint)
+                                .clicked() {
+                                entry_to_share = Some(row.entry.clone());
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+        });
+        
+        if refresh {
+            self.refresh_cloud_listing();
+        }
+        if let Some(entry) = entry_to_restore {
+            self.restore_from_cloud(entry);
+        }
+        if let Some(entry) = entry_to_share {
+            self.share_dialog = Some(ShareDialog {
+                entry,
+                expires: true,
+                expiry_date: chrono::Local::now().date_naive() + chrono::Duration::days(7),
+                link: None,
+                error: None,
+            });
+        }
+    }
+    
+    /// The cloud listing as shown: searched by game or file name, filtered by save type and sorted
+    ///
+    /// Archives of unknown type only show while both types do.
+    fn filtered_cloud_rows<'a>(&'a self, listing: &'a [CloudEntry]) -> Vec<CloudRow<'a>> {
+        let query = self.cloud_search.trim().to_lowercase();
+        let mut rows: Vec<CloudRow> = listing.iter().map(|entry| {
+            let manifest_entry = self.cloud_manifest.as_ref().and_then(|m| m.entry_for(entry.file_name()));
+            let local_backup = self.backups.iter()
+                .find(|b| b.backup_path.file_name().map_or(false, |name| name.to_string_lossy() == entry.file_name()));
+            let game = match (manifest_entry, local_backup) {
+                (Some(e), _) => e.backup.game_name.clone(),
+                (None, Some(backup)) => backup.game_name.clone(),
+                (None, None) => entry.name.split_once('/').map(|(folder, _)| folder.to_string()).unwrap_or_else(|| entry.file_name().to_string()),
+            };
+            CloudRow {
+                entry,
+                game,
+                save_type: manifest_entry.map(|e| e.backup.save_type.clone()).or_else(|| local_backup.map(|b| b.save_type.clone())),
+                created: manifest_entry.map(|e| e.backup.created_at).or(entry.modified),
+                machine: manifest_entry.map(|e| e.machine.as_str()),
+            }
+        }).collect();
+        
+        rows.retain(|row| match row.save_type {
+            Some(SaveType::Steam) => self.cloud_filter_steam,
+            Some(SaveType::NonSteam) => self.cloud_filter_non_steam,
+            None => self.cloud_filter_steam && self.cloud_filter_non_steam,
+        });
+        if !query.is_empty() {
+            rows.retain(|row| row.game.to_lowercase().contains(&query) || row.entry.name.to_lowercase().contains(&query));
+        }
+        
+        match self.cloud_sort_by {
+            SortBy::Name => rows.sort_by(|a, b| a.game.to_lowercase().cmp(&b.game.to_lowercase()).then(b.created.cmp(&a.created))),
+            SortBy::LastModified => rows.sort_by(|a, b| b.created.cmp(&a.created)),
+            SortBy::Size => rows.sort_by(|a, b| b.entry.size.cmp(&a.entry.size)),
+            SortBy::Type => rows.sort_by(|a, b| a.save_type.cmp(&b.save_type).then(a.game.to_lowercase().cmp(&b.game.to_lowercase()))),
+        }
+        rows
+    }
+    
+    /// Results of the replica check: local archives missing in the cloud and the other way round
+    fn draw_replica_report(&mut self, ui: &mut egui::Ui, read_only: bool) {
+        let mut check_now = false;
+        let mut upload_missing = false;
+        let mut download_unknown = false;
+        let mut delete: Option<String> = None;
+        let report = &self.replica_report;
+        
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!read_only, egui::Button::new("🔎 Check Replicas"))
+                .on_hover_text("Compare the cloud with the local backups for failed or skipped uploads")
+                .clicked() {
+                check_now = true;
+            }
+            match report.checked_at {
+                Some(checked_at) => {
+                    let when = checked_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                    if let Some(ref error) = report.error {
+                        ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("⚠ Last check {} could not list the cloud", when))
+                            .on_hover_text(error);
+                    } else if report.is_clean() {
+                        ui.colored_label(egui::Color32::from_rgb(46, 204, 64),
+                            format!("✓ All {} local archives are in the cloud (checked {})", report.local_files, when));
+                    } else {
+                        ui.colored_label(egui::Color32::from_rgb(255, 133, 27), format!("⚠ Checked {}", when));
+                    }
+                }
+                None => {
+                    ui.label(egui::RichText::new("Not checked yet").size(11.0).color(egui::Color32::GRAY));
+                }
+            }
+        });
+        
+        if !report.missing_remote.is_empty() {
+            egui::CollapsingHeader::new(egui::RichText::new(format!("⚠ {} local archives missing in the cloud", report.missing_remote.len()))
+                .color(egui::Color32::from_rgb(255, 133, 27)))
+                .id_source("replicas_missing_remote")
+                .show(ui, |ui| {
+                    if ui.add_enabled(!read_only, egui::Button::new("↑ Upload These")).clicked() {
+                        upload_missing = true;
+                    }
+                    for missing in &report.missing_remote {
+                        let file_name = missing.local_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        ui.label(format!("{} — {}", missing.game_name, file_name))
+                            .on_hover_text(missing.local_path.display().to_string());
+                    }
+                });
+        }
+        
+        if !report.unknown_local.is_empty() {
+            egui::CollapsingHeader::new(egui::RichText::new(format!("⚠ {} cloud archives unknown here", report.unknown_local.len()))
+                .color(egui::Color32::from_rgb(255, 133, 27)))
+                .id_source("replicas_unknown_local")
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new("Made on another PC, or deleted here since they were uploaded")
+                        .size(11.0).color(egui::Color32::GRAY));
+                    if ui.add_enabled(!read_only, egui::Button::new("↓ Download These")).clicked() {
+                        download_unknown = true;
+                    }
+                    for unknown in &report.unknown_local {
+                        ui.horizontal(|ui| {
+                            ui.label(&unknown.remote_name);
+                            ui.label(unknown.size.map(format_size).unwrap_or_else(|| "—".to_string()));
+                            if self.replica_delete.as_deref() == Some(unknown.remote_name.as_str()) {
+                                ui.colored_label(egui::Color32::from_rgb(220, 50, 50), "Delete from the cloud?");
+                                if ui.small_button("Delete").clicked() {
+                                    delete = Some(unknown.remote_name.clone());
+                                }
+                                if ui.small_button("Keep").clicked() {
+                                    self.replica_delete = None;
+                                }
+                            } else if ui.add_enabled(!read_only, egui::Button::new("🗑").small())
+                                .on_hover_text("Delete this archive from the cloud")
+                                .clicked() {
+                                self.replica_delete = Some(unknown.remote_name.clone());
+                            }
+                        });
+                    }
+                });
+        }
+        
+        if check_now {
+            self.run_replica_check(true);
+        }
+        if upload_missing {
+            self.upload_missing_replicas();
+        }
+        if download_unknown {
+            self.download_unknown_replicas();
+        }
+        if let Some(remote_name) = delete {
+            self.replica_delete = None;
+            self.delete_unknown_replica(&remote_name);
+        }
+    }
+    
+    /// List the archives in the cloud, with the manifest to tell their games and machines
+    fn refresh_cloud_listing(&mut self) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        let provider = cloud::provider_from_config(&self.config);
+        match self.list_remote_archives(provider.as_ref()) {
+            Ok(mut listing) => {
+                listing.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+                self.scan_status = ScanStatus::Complete(format!("{} backups in the cloud", listing.len()));
+                self.cloud_listing = Some(listing);
+            }
+            Err(SaveGuardianError::PathNotFound(_)) => self.cloud_listing = Some(Vec::new()),
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Failed to list cloud files: {}", e));
+                return;
+            }
+        }
+        if let Some(manifest) = self.fetch_cloud_manifest() {
+            self.cloud_manifest = Some(manifest);
+        }
+    }
+    
+    /// Download a cloud backup and extract it straight into the game's save folder
+    ///
+    /// The target comes from the manifest (or a local copy's metadata) with the restore path
+    /// remaps applied; the user picks a folder when it's unknown or gone. The current save is
+    /// backed up before it is overwritten, see restore_downloaded_backup.
+    fn restore_from_cloud(&mut self, entry: CloudEntry) {
+        if self.is_read_only() || !self.check_cloud_ready() {
+            return;
+        }
+        
+        let file_name = entry.file_name().to_string();
+        let original = self.cloud_manifest.as_ref()
+            .and_then(|m| m.entry_for(&file_name))
+            .map(|e| e.backup.original_path.clone())
+            .or_else(|| {
+                self.backups.iter()
+                    .find(|b| save_guardian_core::volumes::archive_files(b).iter().any(|p| p.file_name().map_or(false, |n| n == file_name.as_str())))
+                    .map(|b| b.original_path.clone())
+            });
+        let target = original
+            .map(|path| restore::apply_remaps(&path, &self.config.path_remaps).unwrap_or(path))
+            .filter(|path| path.exists() || path.parent().map_or(false, |p| p.exists()));
+        let target = match target.or_else(|| rfd::FileDialog::new().set_title("Restore the cloud backup to...").pick_folder()) {
+            Some(target) => target,
+            None => return,
+        };
+        
+        let backup_path = self.config.effective_backup_path();
+        if let Err(e) = std::fs::create_dir_all(&backup_path) {
+            self.scan_status = ScanStatus::Error(format!("Failed to create backup directory: {}", e));
+            return;
+        }
+        
+        info!("Restoring {} from the cloud to {}", entry.name, target.display());
+        let remote = self.cloud_listing.clone().unwrap_or_else(|| vec![entry.clone()]);
+        let state = self.load_sync_state(&remote);
+        // A split archive needs all its parts, they sit next to each other in the cloud
+        let manifest_entry = self.cloud_manifest.as_ref().and_then(|m| m.entry_for(&file_name));
+        let parts: Vec<String> = match manifest_entry {
+            Some(e) if !e.backup.volumes.is_empty() => e.backup.volumes.iter()
+                .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .collect(),
+            _ => vec![file_name.clone()],
+        };
+        let folder = entry.name.strip_suffix(file_name.as_str()).unwrap_or("");
+        let jobs: Vec<TransferJob> = parts.iter().map(|part| {
+            let remote_name = format!("{}{}", folder, part);
+            TransferJob {
+                direction: TransferDirection::Download,
+                size: remote.iter().find(|e| e.name == remote_name).and_then(|e| e.size).unwrap_or(0),
+                local_path: backup_path.join(part),
+                sha256: self.cloud_manifest.as_ref().and_then(|m| m.entry_for(part)).and_then(|e| e.expected_sha256()),
+                replace: false,
+                remote_name,
+            }
+        }).collect();
+        let provider = cloud::provider_from_config(&self.config);
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Cloud restore", state, remote, kept_local: 0, unchanged: 0, conflicts: 0, restore_to: Some(target) });
+    }
+    
+    /// Second half of a cloud restore, once the archive is down: back up what's at the
+    /// target, then extract over it
+    fn restore_downloaded_backup(&mut self, local_path: &std::path::Path, target: &std::path::PathBuf) -> std::result::Result<String, String> {
+        let backup = self.backups.iter()
+            .find(|b| save_guardian_core::volumes::holds(b, local_path))
+            .cloned()
+            .ok_or_else(|| format!("{} was downloaded but not registered as a backup", local_path.display()))?;
+        let backup_manager = self.backup_manager.as_ref().ok_or_else(|| "Backup manager not initialized".to_string())?;
+        
+        if target.exists() && calculate_path_size(target) > 0 {
+            let save = self.steam_saves.iter().chain(self.non_steam_saves.iter())
+                .find(|s| &s.save_path == target)
+                .cloned()
+                .unwrap_or_else(|| {
+                    let mut save = GameSave::new(backup.game_name.clone(), target.clone(), backup.save_type.clone(), backup.app_id);
+                    save.steam_user_id = backup.steam_user_id.clone();
+                    save.sandbox = backup.sandbox.clone();
+                    save
+                });
+            backup_manager.create_backup(&save, Some("Before restoring from cloud".to_string()))
+                .map_err(|e| format!("Could not back up the current save, nothing was restored: {}", e))?;
+        }
+        
+        backup_manager.restore_backup(&backup, target, true)
+            .map_err(|e| format!("Restore of {} failed: {}", backup.game_name, e))?;
+        self.load_backups();
+        Ok(format!("✓ Restored {} from the cloud to {}", backup.game_name, target.display()))
+    }
+
+    /// One checkbox per backed up game, to keep big saves out of a small cloud quota
+    fn draw_cloud_game_selection(&mut self, ui: &mut egui::Ui) {
+        // Game key -> (name, backups, bytes), in name order
+        let mut games: Vec<(String, String, usize, u64)> = Vec::new();
+        for backup in &self.backups {
+            let key = BackupManager::latest_key(backup);
+            match games.iter_mut().find(|(k, ..)| *k == key) {
+                Some((_, _, count, bytes)) => {
+                    *count += 1;
+                    *bytes += backup.size;
+                }
+                None => games.push((key, backup.game_name.clone(), 1, backup.size)),
+            }
+        }
+        games.sort_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()));
+        
+        let synced_bytes: u64 = games.iter()
+            .filter(|(key, ..)| !self.config.is_cloud_excluded(key))
+            .map(|(.., bytes)| bytes)
+            .sum();
+        let mut toggled: Option<(String, bool)> = None;
+        
+        egui::CollapsingHeader::new(format!("☁ Games in Cloud Sync ({} of local backups)", format_size(synced_bytes)))
+            .id_source("cloud_game_selection")
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Unticked games are neither uploaded nor downloaded; their backups stay where they are")
+                    .size(11.0).color(egui::Color32::GRAY));
+                egui::ScrollArea::vertical().id_source("cloud_game_selection_scroll").max_height(200.0).show(ui, |ui| {
+                    for (key, name, count, bytes) in &games {
+                        let by_game = self.config.cloud_excluded_games.contains(key);
+                        let by_tag = !by_game && self.config.is_cloud_excluded(key);
+                        let mut synced = !by_game && !by_tag;
+                        ui.horizontal(|ui| {
+                            let label = format!("{} — {} backups, {}", name, count, format_size(*bytes));
+                            let response = ui.add_enabled(!by_tag, egui::Checkbox::new(&mut synced, label));
+                            if by_tag {
+                                response.on_disabled_hover_text("Kept local by one of its tags, see Settings → Cloud Sync");
+                            } else if response.changed() {
+                                toggled = Some((key.clone(), !synced));
+                            }
+                        });
+                    }
+                    if games.is_empty() {
+                        ui.label("No local backups yet.");
+                    }
+                });
+            });
+        
+        if let Some((key, excluded)) = toggled {
+            self.config.set_cloud_excluded(&key, excluded);
+            self.temp_config.cloud_excluded_games = self.config.cloud_excluded_games.clone();
+        }
+    }
+
+    fn draw_settings_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("⚙️ Settings");
+
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.group(|ui| {
+                ui.strong("Paths");
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.label("Steam userdata path:");
+                    ui.text_edit_singleline(&mut self.temp_config.steam_path.to_string_lossy().to_string());
+                    if ui.button("📁 Browse").clicked() {
+                        // TODO: Open file dialog
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Backup directory:");
+                    ui.text_edit_singleline(&mut self.temp_config.backup_path.to_string_lossy().to_string());
+                    if ui.button("📁 Browse").clicked() {
+                        // TODO: Open file dialog
+                    }
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Household Profiles");
+                ui.separator();
+                
+                ui.label(egui::RichText::new("Each profile gets its own backup folder, cloud folder and hidden saves").size(11.0).color(egui::Color32::GRAY));
+                
+                let mut profile_to_remove: Option<usize> = None;
+                for (i, profile) in self.temp_config.profiles.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("👤 {}", profile.name));
+                        ui.label("Cloud folder:");
+                        ui.text_edit_singleline(&mut profile.cloud_folder);
+                        if !profile.hidden_saves.is_empty() && ui.button(format!("Unhide {} saves", profile.hidden_saves.len())).clicked() {
+                            profile.hidden_saves.clear();
+                        }
+                        if ui.button("❌").on_hover_text("Remove profile (backups stay on disk)").clicked() {
+                            profile_to_remove = Some(i);
+                        }
+                    });
+                }
+                
+                if let Some(i) = profile_to_remove {
+                    let removed = self.temp_config.profiles.remove(i);
+                    if self.temp_config.active_profile.as_ref() == Some(&removed.name) {
+                        self.temp_config.active_profile = None;
+                    }
+                }
+                
+                ui.horizontal(|ui| {
+                    ui.label("New profile:");
+                    ui.text_edit_singleline(&mut self.new_profile_name);
+                    let name = self.new_profile_name.trim().to_string();
+                    let problem = self.temp_config.profile_name_problem(&name);
+                    let add = ui.add_enabled(problem.is_none(), egui::Button::new("+ Add"));
+                    let add = match &problem {
+                        Some(problem) if !name.is_empty() => add.on_disabled_hover_text(problem.as_str()),
+                        _ => add,
+                    };
+                    if add.clicked() {
+                        self.temp_config.profiles.push(UserProfile::new(name));
+                        self.new_profile_name.clear();
+                    }
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Backup Settings");
+                ui.separator();
+                
+                ui.checkbox(&mut self.temp_config.auto_backup, "Automatically backup saves before operations");
+                ui.checkbox(&mut self.temp_config.read_only_mode, "Read-only mode (disable restore, delete, sync and cleanup)");
+                ui.checkbox(&mut self.temp_config.sign_backups, "Sign backups to detect tampering")
+                    .on_hover_text(format!("Key stored at {}", BackupSigner::key_path().display()));
+                
+                let save_watcher = &mut self.temp_config.save_watcher;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut save_watcher.enabled, "Back up saves when they change, after")
+                        .on_hover_text("Save folders are watched while the app is open; a game saving often still gets one backup per session");
+                    ui.add_enabled(save_watcher.enabled, egui::DragValue::new(&mut save_watcher.quiet_minutes).clamp_range(1..=120).suffix(" min"));
+                    ui.label("without writes");
+                });
+                match (&self.save_watcher, &self.save_watcher_error) {
+                    (Some(watcher), _) if watcher.pending() > 0 => {
+                        ui.label(egui::RichText::new(format!("{} changed saves waiting for quiet", watcher.pending())).size(11.0).color(egui::Color32::GRAY));
+                    }
+                    (None, Some(error)) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ {}", error));
+                    }
+                    _ => {}
+                }
+                
+                let backup_encryption = &mut self.temp_config.backup_encryption;
+                ui.checkbox(&mut backup_encryption.enabled, "Encrypt new backups with a passphrase (AES-256-GCM)")
+                    .on_hover_text("For saves with account data on a shared PC. Encrypted archives aren't split and keep large files out of the dedup store");
+                if backup_encryption.enabled {
+                    ui.indent("backup_encryption", |ui| {
+                        ui.checkbox(&mut backup_encryption.use_keyring, "Remember the passphrase in the system keyring")
+                            .on_hover_text("Otherwise it is asked for once per session");
+                        let (ref mut passphrase, ref mut confirm) = self.new_backup_passphrase;
+                        ui.horizontal(|ui| {
+                            ui.label(if backup_encryption.verifier.is_some() { "New passphrase:" } else { "Passphrase:" });
+                            ui.add(egui::TextEdit::singleline(passphrase).password(true).desired_width(160.0));
+                            ui.label("Again:");
+                            ui.add(egui::TextEdit::singleline(confirm).password(true).desired_width(160.0));
+                            let ready = !passphrase.is_empty() && passphrase == confirm;
+                            if ui.add_enabled(ready, egui::Button::new("Set")).clicked() {
+                                match encryption::new_verifier(passphrase) {
+                                    Ok(verifier) => {
+                                        backup_encryption.verifier = Some(verifier);
+                                        if let Err(e) = encryption::unlock(backup_encryption, passphrase) {
+                                            warn!("{}", e);
+                                        }
+                                        passphrase.clear();
+                                        confirm.clear();
+                                    }
+                                    Err(e) => warn!("Failed to set the backup passphrase: {}", e),
+                                }
+                            }
+                        });
+                        if backup_encryption.verifier.is_none() {
+                            ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "⚠ Set a passphrase, backups can't be made until you do");
+                        } else if encryption::is_unlocked() {
+                            ui.horizontal(|ui| {
+                                ui.colored_label(egui::Color32::from_rgb(46, 204, 64), "🔓 Unlocked for this session");
+                                if ui.small_button("🔒 Lock").clicked() {
+                                    encryption::lock();
+                                }
+                            });
+                        }
+                        ui.label(egui::RichText::new("Changing the passphrase only applies to new backups; older ones still need the one they were made with. Without it they can't be restored")
+                            .size(11.0).color(egui::Color32::GRAY));
+                    });
+                }
+                ui.checkbox(&mut self.temp_config.keep_latest_links, "Keep a latest/ copy of each game's newest backup")
+                    .on_hover_text("Symlinked where the OS allows it, copied otherwise; handy for scripts");
+                
+                ui.checkbox(&mut self.temp_config.skip_unchanged_backups, "Skip automatic backups of unchanged saves")
+                    .on_hover_text("Pre-sync and bulk backups compare file names, sizes and times with the last backup first");
+                
+                ui.horizontal(|ui| {
+                    ui.label("When nothing changed since the last backup:");
+                    egui::ComboBox::from_id_source("duplicate_backups")
+                        .selected_text(self.temp_config.duplicate_backups.label())
+                        .show_ui(ui, |ui| {
+                            for policy in [DuplicatePolicy::HardLink, DuplicatePolicy::Skip, DuplicatePolicy::Keep] {
+                                ui.selectable_value(&mut self.temp_config.duplicate_backups, policy, policy.label());
+                            }
+                        });
+                }).response.on_hover_text("Skipping also drops the description you typed; hard links keep it at no extra space");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Archive format:");
+                    egui::ComboBox::from_id_source("archive_format")
+                        .selected_text(self.temp_config.archive_format.label())
+                        .show_ui(ui, |ui| {
+                            for format in ArchiveFormat::ALL {
+                                ui.selectable_value(&mut self.temp_config.archive_format, format, format.label());
+                            }
+                        });
+                });
+                ui.label(egui::RichText::new("For new backups; existing ones keep their format and restore as before. tar.zst is much quicker and smaller for large saves, 7z the smallest but slow; only ZIP archives are recompressed for the cloud or repacked into the store")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                ui.horizontal(|ui| {
+                    ui.label("Keep backups for");
+                    ui.add(egui::DragValue::new(&mut self.temp_config.backup_retention_days).clamp_range(1..=365).suffix(" days"));
+                });
+                
+                ui.horizontal(|ui| {
+                    let mut split = self.temp_config.volume_size_mb > 0;
+                    if ui.checkbox(&mut split, "Split archives into parts of").changed() {
+                        self.temp_config.volume_size_mb = if split { save_guardian_core::volumes::FAT32_VOLUME_MB } else { 0 };
+                    }
+                    if split {
+                        ui.add(egui::DragValue::new(&mut self.temp_config.volume_size_mb).clamp_range(1..=u32::MAX as u64).suffix(" MB"));
+                        if ui.button("FAT32").clicked() {
+                            self.temp_config.volume_size_mb = save_guardian_core::volumes::FAT32_VOLUME_MB;
+                        }
+                    }
+                });
+                ui.label(egui::RichText::new("For FAT32 drives (4 GB per file) and cloud storage with an upload size limit; parts are joined again on restore")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                let incremental = &mut self.temp_config.incremental;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut incremental.enabled, "Incremental backups, a full one after every");
+                    ui.add_enabled(incremental.enabled, egui::DragValue::new(&mut incremental.full_every).clamp_range(1..=100));
+                    ui.label("incremental");
+                });
+                ui.label(egui::RichText::new("Only files changed since the game's last backup are archived; restoring reads the full backup and each increment after it")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                let dedup = &mut self.temp_config.dedup_store;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut dedup.enabled, "Keep files of at least");
+                    ui.add_enabled(dedup.enabled, egui::DragValue::new(&mut dedup.min_file_mb).clamp_range(1..=100_000).suffix(" MB"));
+                    ui.label("once, in a store shared by all backups");
+                });
+                ui.label(egui::RichText::new("Backups of a game that rewrites the same large files take their space only once. Stored files stay in the backup folder's store/ and aren't uploaded with the archives")
+                    .size(11.0).color(egui::Color32::GRAY));
+                let mut store_action: Option<bool> = None;
+                ui.horizontal(|ui| {
+                    let idle = !self.is_read_only() && self.archive_task.is_none();
+                    if ui.add_enabled(idle && self.config.dedup_store.enabled, egui::Button::new("Repack existing backups"))
+                        .on_hover_text("Move the large files of older archives into the store; saved settings with the store on are used")
+                        .clicked() {
+                        store_action = Some(true);
+                    }
+                    if ui.add_enabled(idle, egui::Button::new("Collect garbage"))
+                        .on_hover_text("Delete stored files no backup uses any more")
+                        .clicked() {
+                        store_action = Some(false);
+                    }
+                });
+                if let Some(repack) = store_action {
+                    self.start_store_task(repack);
+                }
+                
+                let timestamp = &mut self.temp_config.filename_timestamp;
+                ui.horizontal(|ui| {
+                    ui.label("File name time:");
+                    ui.add(egui::TextEdit::singleline(&mut timestamp.format).desired_width(140.0))
+                        .on_hover_text("strftime syntax, e.g. %Y%m%d_%H%M%S or %Y-%m-%d_%H-%M-%S");
+                    ui.radio_value(&mut timestamp.local_time, false, "UTC");
+                    ui.radio_value(&mut timestamp.local_time, true, "Local time");
+                    if timestamp.format != FilenameTimestampConfig::DEFAULT_FORMAT && ui.small_button("Default").clicked() {
+                        timestamp.format = FilenameTimestampConfig::DEFAULT_FORMAT.to_string();
+                    }
+                });
+                match backup::timestamp_format_error(&timestamp.format) {
+                    Some(error) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("⚠ {}, the default is used", error));
+                    }
+                    None => {
+                        let example = backup::filename_timestamp(chrono::Utc::now(), timestamp);
+                        ui.label(egui::RichText::new(format!("New backups are named like Game_{}.zip; older ones keep their names, and backup times are stored in UTC either way", example))
+                            .size(11.0).color(egui::Color32::GRAY));
+                    }
+                }
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Junk Exclusions");
+                ui.separator();
+                
+                let exclusions = &mut self.temp_config.exclusions;
+                ui.checkbox(&mut exclusions.enabled, "Skip logs, crash dumps and caches when backing up");
+                ui.label(egui::RichText::new(format!("Defaults: {}", save_guardian_core::exclusions::default_patterns(save_guardian_core::exclusions::SaveEngine::Generic).join(", ")))
+                    .size(11.0).color(egui::Color32::GRAY))
+                    .on_hover_text("Unreal, Unity and Chromium-based saves get extra engine-specific patterns");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Extra patterns:");
+                    ui.text_edit_singleline(&mut exclusions.extra_patterns)
+                        .on_hover_text("Comma-separated, e.g. *.bak, Screenshots/");
+                });
+                
+                let mut override_to_remove: Option<usize> = None;
+                for (i, game_override) in exclusions.game_overrides.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label("Game:");
+                        ui.text_edit_singleline(&mut game_override.game_name);
+                        ui.checkbox(&mut game_override.use_defaults, "Use defaults");
+                        ui.label("Patterns:");
+                        ui.text_edit_singleline(&mut game_override.patterns);
+                        if ui.button("❌").clicked() {
+                            override_to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = override_to_remove {
+                    exclusions.game_overrides.remove(i);
+                }
+                
+                if ui.button("+ Add per-game override").clicked() {
+                    exclusions.game_overrides.push(GameExclusionOverride {
+                        game_name: String::new(),
+                        use_defaults: true,
+                        patterns: String::new(),
+                    });
+                }
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Size Watchdog");
+                ui.separator();
+                
+                let watchdog = &mut self.temp_config.size_watchdog;
+                ui.checkbox(&mut watchdog.enabled, "Warn about runaway save folder growth");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Warn if a save exceeds");
+                    ui.add(egui::DragValue::new(&mut watchdog.max_size_mb).clamp_range(0..=1_000_000).suffix(" MB"));
+                    ui.label("or grew by more than");
+                    ui.add(egui::DragValue::new(&mut watchdog.max_growth_percent).clamp_range(0..=10_000).suffix(" %"));
+                    ui.label("since its last backup (0 = off)");
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Offer recovery if a save shrinks by more than");
+                    ui.add(egui::DragValue::new(&mut watchdog.max_loss_percent).clamp_range(0..=100).suffix(" %"));
+                    ui.label("since its last backup (0 = off)");
+                });
+                
+                let mut limit_to_remove: Option<usize> = None;
+                for (i, limit) in watchdog.game_limits.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label("Game:");
+                        ui.text_edit_singleline(&mut limit.game_name);
+                        ui.add(egui::DragValue::new(&mut limit.max_size_mb).clamp_range(0..=1_000_000).suffix(" MB"));
+                        ui.add(egui::DragValue::new(&mut limit.max_growth_percent).clamp_range(0..=10_000).suffix(" %"));
+                        if ui.button("❌").clicked() {
+                            limit_to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = limit_to_remove {
+                    watchdog.game_limits.remove(i);
+                }
+                
+                if ui.button("+ Add per-game limit").clicked() {
+                    watchdog.game_limits.push(GameSizeLimit {
+                        game_name: String::new(),
+                        max_size_mb: watchdog.max_size_mb,
+                        max_growth_percent: watchdog.max_growth_percent,
+                    });
+                }
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Integrity Sweeps");
+                ui.separator();
+                
+                let verification = &mut self.temp_config.verification;
+                ui.checkbox(&mut verification.enabled, "Periodically re-check archives for bit-rot and missing files");
+                ui.horizontal(|ui| {
+                    ui.label("Every");
+                    ui.add(egui::DragValue::new(&mut verification.interval_hours).clamp_range(1..=720).suffix(" h"));
+                    ui.label("check");
+                    ui.add(egui::DragValue::new(&mut verification.batch_size).clamp_range(1..=1000));
+                    ui.label("archives, continuing where the last run stopped");
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Worker threads:");
+                    let threads = &mut self.temp_config.worker_threads;
+                    ui.add(egui::DragValue::new(threads).clamp_range(0..=64)
+                        .custom_formatter(|n, _| if n == 0.0 { "Automatic".to_string() } else { format!("{}", n) }));
+                    if *threads == 0 {
+                        ui.label(format!("({})", save_guardian_core::workers::thread_count(0)));
+                    }
+                });
+                ui.label(egui::RichText::new("Archives hashed at once during sweeps and checks, and threads compressing a backup; fewer keeps a laptop quiet, more finishes sooner on a desktop")
+                    .size(11.0).color(egui::Color32::GRAY));
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Restore Path Remapping");
+                ui.separator();
+                ui.label(egui::RichText::new("Rewrites backup locations when restoring on a machine with a different user name or drive letters")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                let mut remap_to_remove: Option<usize> = None;
+                for (i, remap) in self.temp_config.path_remaps.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut remap.from);
+                        ui.label("→");
+                        ui.text_edit_singleline(&mut remap.to);
+                        if ui.button("❌").clicked() {
+                            remap_to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remap_to_remove {
+                    self.temp_config.path_remaps.remove(i);
+                }
+                
+                ui.horizontal(|ui| {
+                    if ui.button("+ Add rule").clicked() {
+                        self.temp_config.path_remaps.push(PathRemap { from: String::new(), to: String::new() });
+                    }
+                    
+                    if ui.button("💡 Suggest").on_hover_text("Suggest rules for backups whose folders don't exist on this PC").clicked() {
+                        let mut added = 0;
+                        for suggestion in restore::suggest_remaps(&self.backups, &self.temp_config.steam_path) {
+                            if !self.temp_config.path_remaps.iter().any(|r| r.from.eq_ignore_ascii_case(&suggestion.from)) {
+                                self.temp_config.path_remaps.push(suggestion);
+                                added += 1;
+                            }
+                        }
+                        self.scan_status = ScanStatus::Complete(format!("Suggested {} remapping rules", added));
+                    }
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Retention Exemptions");
+                ui.separator();
+                ui.label(egui::RichText::new("Cleanup never deletes pinned backups or backups matching one of these rules")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                let mut exemption_to_remove: Option<usize> = None;
+                for (i, rule) in self.temp_config.retention_exemptions.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_source(format!("retention_exemption_kind_{}", i))
+                            .selected_text(rule.kind.label())
+                            .show_ui(ui, |ui| {
+                                for kind in [ExemptionKind::TagEquals, ExemptionKind::DescriptionContains] {
+                                    ui.selectable_value(&mut rule.kind, kind, kind.label());
+                                }
+                            });
+                        ui.text_edit_singleline(&mut rule.pattern);
+                        if ui.button("❌").clicked() {
+                            exemption_to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = exemption_to_remove {
+                    self.temp_config.retention_exemptions.remove(i);
+                }
+                
+                if ui.button("+ Add rule").clicked() {
+                    self.temp_config.retention_exemptions.push(RetentionExemption {
+                        kind: ExemptionKind::DescriptionContains,
+                        pattern: String::new(),
+                    });
+                }
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("App ID Links");
+                ui.separator();
+                ui.label(egui::RichText::new("Web pages offered next to a Steam game's app ID; {app_id} in the address is replaced with the game's ID")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                let mut link_to_remove: Option<usize> = None;
+                for (i, link) in self.temp_config.app_links.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut link.enabled, "");
+                        ui.add(egui::TextEdit::singleline(&mut link.label).desired_width(120.0));
+                        ui.add(egui::TextEdit::singleline(&mut link.url).desired_width(360.0));
+                        if ui.button("❌").clicked() {
+                            link_to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = link_to_remove {
+                    self.temp_config.app_links.remove(i);
+                }
+                
+                ui.horizontal(|ui| {
+                    if ui.button("+ Add link").clicked() {
+                        self.temp_config.app_links.push(AppLink {
+                            label: String::new(),
+                            url: "https://".to_string(),
+                            enabled: true,
+                        });
+                    }
+                    if ui.button("Reset to defaults").clicked() {
+                        self.temp_config.app_links = AppLink::defaults();
+                    }
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Network Drives");
+                ui.separator();
+                
+                let network = &mut self.temp_config.network;
+                ui.checkbox(&mut network.skip_in_quick_scan, "Skip network locations (SMB/NFS shares, mapped drives) during quick scans");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Treat a share as offline after");
+                    ui.add(egui::DragValue::new(&mut network.offline_timeout_secs).clamp_range(1..=120).suffix(" s"));
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Retry transient errors");
+                    ui.add(egui::DragValue::new(&mut network.retry_attempts).clamp_range(1..=10).suffix(" times"));
+                    ui.label("waiting");
+                    ui.add(egui::DragValue::new(&mut network.retry_delay_ms).clamp_range(0..=10_000).suffix(" ms"));
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Online Access");
+                ui.separator();
+                
+                let online = &mut self.temp_config.online;
+                ui.checkbox(&mut online.offline_mode, "Offline mode: never make network requests");
+                ui.label(egui::RichText::new("Game names then come from the cache and Steam's own files; WebDAV and SFTP sync are off, folder targets still work")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                ui.add_enabled_ui(!online.offline_mode, |ui| {
+                    ui.label("Look up game names with:");
+                    ui.checkbox(&mut online.steam_store_api, "Steam Store API (store.steampowered.com)");
+                    ui.checkbox(&mut online.steamspy, "SteamSpy (steamspy.com)");
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Reuse saved API responses for");
+                    ui.add(egui::DragValue::new(&mut online.api_cache_days).clamp_range(0..=365).suffix(" days"));
+                    ui.label("(0 = don't save)");
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Cloud Sync");
+                ui.separator();
+                
+                // Several destinations, one selected for editing (and syncing, once saved)
+                let mut select: Option<String> = None;
+                let mut add = false;
+                let mut remove = false;
+                ui.horizontal(|ui| {
+                    ui.label("Remote:");
+                    let current = self.temp_config.remote_name();
+                    egui::ComboBox::from_id_source("settings_cloud_remote")
+                        .selected_text(&current)
+                        .show_ui(ui, |ui| {
+                            for name in self.temp_config.remote_names() {
+                                if ui.selectable_label(name == current, &name).clicked() && name != current {
+                                    select = Some(name);
+                                }
+                            }
+                        });
+                    ui.label("Name:");
+                    let default_name = self.temp_config.cloud_name();
+                    ui.add(egui::TextEdit::singleline(&mut self.temp_config.cloud_remote_name)
+                        .hint_text(default_name)
+                        .desired_width(120.0));
+                    if ui.button("➕ Add Remote").on_hover_text("Set up another destination, e.g. a home NAS next to Koofr").clicked() {
+                        add = true;
+                    }
+                    if ui.add_enabled(!self.temp_config.cloud_remotes.is_empty(), egui::Button::new("🗑 Remove"))
+                        .on_hover_text("Forget this destination's settings; its files stay where they are")
+                        .clicked() {
+                        remove = true;
+                    }
+                });
+                let current = self.temp_config.remote_name();
+                if self.temp_config.cloud_remotes.iter().any(|r| r.name == current) {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), "⚠ Another remote has this name");
+                }
+                if let Some(name) = select {
+                    self.temp_config.select_remote(&name);
+                }
+                if add {
+                    self.temp_config.add_remote();
+                }
+                if remove {
+                    self.temp_config.remove_selected_remote();
+                }
+                if !self.temp_config.cloud_remotes.is_empty() {
+                    ui.checkbox(&mut self.temp_config.mirror_to_all_remotes, "Mirror uploads to all enabled remotes")
+                        .on_hover_text("After uploading to the selected remote, the same backups go to the others one after another");
+                }
+                ui.add_space(5.0);
+                
+                ui.horizontal(|ui| {
+                    ui.label("Destination:");
+                    ui.selectable_value(&mut self.temp_config.cloud_backend, CloudBackend::WebDav, "WebDAV (Koofr, Nextcloud, ...)");
+                    ui.selectable_value(&mut self.temp_config.cloud_backend, CloudBackend::Sftp, "SFTP");
+                    ui.selectable_value(&mut self.temp_config.cloud_backend, CloudBackend::Folder, "Folder / NAS / USB");
+                });
+                ui.add_space(5.0);
+                
+                match self.temp_config.cloud_backend {
+                    CloudBackend::WebDav => self.draw_webdav_settings(ui),
+                    CloudBackend::Sftp => self.draw_sftp_settings(ui),
+                    CloudBackend::Folder => self.draw_folder_target_settings(ui),
+                }
+                
+                ui.checkbox(&mut self.temp_config.koofr_config.auto_sync, "Automatic sync");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Sync interval:");
+                    ui.add(egui::Slider::new(&mut self.temp_config.koofr_config.sync_interval_minutes, 5..=1440).text("minutes"));
+                });
+                
+                let replica_check = &mut self.temp_config.replica_check;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut replica_check.enabled, "Check that every backup has a cloud copy every");
+                    ui.add_enabled(replica_check.enabled, egui::DragValue::new(&mut replica_check.interval_hours).clamp_range(1..=720).suffix(" h"));
+                }).response.on_hover_text("Lists the cloud and flags archives missing there or unknown here, see the Cloud tab");
+                
+                if self.temp_config.cloud_backend != CloudBackend::Folder {
+                    let retry = &mut self.temp_config.cloud_retry;
+                    ui.horizontal(|ui| {
+                        ui.label("Retry failed requests:");
+                        ui.add(egui::DragValue::new(&mut retry.attempts).clamp_range(1..=10).suffix(" attempts"));
+                        ui.label("waiting");
+                        ui.add(egui::DragValue::new(&mut retry.initial_delay_ms).clamp_range(100..=60_000).speed(100).suffix(" ms"));
+                        ui.label("doubling up to");
+                        ui.add(egui::DragValue::new(&mut retry.max_delay_ms).clamp_range(100..=600_000).speed(1000).suffix(" ms"));
+                        ui.checkbox(&mut retry.jitter, "Jitter");
+                    }).response.on_hover_text("Timeouts, dropped connections and server errors (5xx) are retried; wrong credentials are not");
+                }
+                
+                ui.horizontal(|ui| {
+                    ui.label("Backup changed both locally and in the cloud:");
+                    let policy = &mut self.temp_config.cloud_conflict_policy;
+                    ui.selectable_value(policy, ConflictPolicy::KeepNewest, "Keep newest");
+                    ui.selectable_value(policy, ConflictPolicy::KeepBoth, "Keep both");
+                    ui.selectable_value(policy, ConflictPolicy::Prompt, "Ask me");
+                });
+                
+                let bandwidth = &mut self.temp_config.bandwidth;
+                ui.horizontal(|ui| {
+                    ui.label("Limit uploads to");
+                    ui.add(egui::DragValue::new(&mut bandwidth.upload_kbps).clamp_range(0..=1_000_000).speed(10).suffix(" KB/s"));
+                    ui.label("and downloads to");
+                    ui.add(egui::DragValue::new(&mut bandwidth.download_kbps).clamp_range(0..=1_000_000).speed(10).suffix(" KB/s"));
+                    ui.label("(0 = unlimited)");
+                });
+                
+                let encryption = &mut self.temp_config.cloud_encryption;
+                ui.checkbox(&mut encryption.enabled, "Encrypt backups before uploading (AES-256-GCM)");
+                if encryption.enabled {
+                    ui.horizontal(|ui| {
+                        ui.label("Passphrase:");
+                        ui.add(egui::TextEdit::singleline(&mut encryption.passphrase).password(true));
+                    });
+                    if encryption.passphrase.is_empty() {
+                        ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "⚠ Enter a passphrase, uploads stay unencrypted without one");
+                    }
+                }
+                ui.label(egui::RichText::new("Downloads are decrypted automatically. Keep the passphrase somewhere safe: without it, encrypted backups can't be restored")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                ui.horizontal(|ui| {
+                    ui.label("Before uploading:");
+                    egui::ComboBox::from_id_source("cloud_recompression")
+                        .selected_text(self.temp_config.cloud_recompression.label())
+                        .show_ui(ui, |ui| {
+                            for recompression in [CloudRecompression::Off, CloudRecompression::Zstd, CloudRecompression::DeflateMax] {
+                                ui.selectable_value(&mut self.temp_config.cloud_recompression, recompression, recompression.label());
+                            }
+                        });
+                }).response.on_hover_text("Uses less cloud storage at the cost of CPU time during uploads");
+                ui.label(egui::RichText::new("Local archives stay as they are. Zstandard saves the most and is turned back into a normal zip on download; maximum Deflate saves less but opens in any zip tool")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                ui.checkbox(&mut self.temp_config.exclude_sync_client_saves, "Don't upload saves inside OneDrive, Dropbox, Google Drive or iCloud folders")
+                    .on_hover_text("Those clients already sync the save; a second copy in the cloud can race with them on restore");
+                
+                let tags = self.temp_config.all_game_tags();
+                if !tags.is_empty() {
+                    ui.add_space(5.0);
+                    ui.label("Keep games with these tags out of the cloud:");
+                    ui.horizontal_wrapped(|ui| {
+                        for tag in tags {
+                            let mut excluded = self.temp_config.cloud_excluded_tags.iter().any(|t| t.eq_ignore_ascii_case(&tag));
+                            if ui.checkbox(&mut excluded, tag.as_str()).changed() {
+                                if excluded {
+                                    self.temp_config.cloud_excluded_tags.push(tag);
+                                } else {
+                                    self.temp_config.cloud_excluded_tags.retain(|t| !t.eq_ignore_ascii_case(&tag));
+                                }
+                            }
+                        }
+                    });
+                }
+                
+                if ui.button("✓ Test Connection").on_hover_text("Check the server address, login, sync folder and write access step by step").clicked() {
+                    self.test_cloud_connection();
+                }
+            });
+            
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("LAN Sharing");
+                ui.separator();
+                
+                let lan = &mut self.temp_config.lan_server;
+                ui.checkbox(&mut lan.enabled, "Share the backup folder on the local network (read-only)")
+                    .on_hover_text("Other PCs, a Steam Deck or a phone can browse and download backups in a browser or mount them as a WebDAV drive");
+                ui.horizontal(|ui| {
+                    ui.label("Port:");
+                    ui.add(egui::DragValue::new(&mut lan.port).clamp_range(1024..=65535));
+                    ui.label("User:");
+                    ui.add(egui::TextEdit::singleline(&mut lan.username).desired_width(100.0));
+                    ui.label("Password:");
+                    ui.add(egui::TextEdit::singleline(&mut lan.password).password(true).desired_width(120.0));
+                });
+                if lan.password.is_empty() {
+                    ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "⚠ Without a password anyone on the network can download your backups");
+                }
+                
+                match (&self.lan_server, &self.lan_server_error) {
+                    (Some(server), _) => {
+                        for url in server.urls() {
+                            ui.horizontal(|ui| {
+                                ui.colored_label(egui::Color32::from_rgb(46, 204, 64), format!("✓ Serving at {}", url));
+                                if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                                    ui.output_mut(|o| o.copied_text = url.clone());
+                                }
+                            });
+                        }
+                    }
+                    (None, Some(error)) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ {}", error));
+                    }
+                    (None, None) => {
+                        ui.label(egui::RichText::new("Not running; changes apply when settings are saved").size(11.0).color(egui::Color32::GRAY));
+                    }
+                }
+            });
+            
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Scan Settings");
+                ui.separator();
+                
+                ui.checkbox(&mut self.temp_config.auto_backup, "Enable automatic scanning on startup");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Scan depth:");
+                    ui.add(egui::Slider::new(&mut self.temp_config.backup_retention_days, 1..=7).text("levels").clamp_to_range(true));
+                });
+                
+                ui.checkbox(&mut self.temp_config.auto_backup, "Include system locations in scan");
+                ui.checkbox(&mut self.temp_config.auto_backup, "Detect saves by content analysis");
+                
+                ui.horizontal(|ui| {
+                    ui.label("Community rules file:");
+                    let text = self.temp_config.sync_rules_file.as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| "None".to_string());
+                    ui.label(text);
+                    if ui.button("📁").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Rules", &["toml"]).pick_file() {
+                            self.temp_config.sync_rules_file = Some(path);
+                        }
+                    }
+                    if self.temp_config.sync_rules_file.is_some() && ui.button("❌").clicked() {
+                        self.temp_config.sync_rules_file = None;
+                    }
+                });
+                ui.label(egui::RichText::new("Known save locations per game; Find Pairs uses them before matching names")
+                    .size(11.0).color(egui::Color32::GRAY));
+                
+                ui.add_space(5.0);
+                ui.checkbox(&mut self.temp_config.game_detection.enabled, "Detect new games from running programs")
+                    .on_hover_text("When a running program writes to a folder in the save locations that no found game uses, offer to track it");
+                let mut remove_game = None;
+                for (i, game) in self.temp_config.custom_games.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("❌").on_hover_text("Stop tracking; its backups are kept").clicked() {
+                            remove_game = Some(i);
+                        }
+                        ui.label(&game.name);
+                        ui.label(egui::RichText::new(game.save_path.display().to_string()).size(11.0).color(egui::Color32::GRAY));
+                    });
+                }
+                if let Some(i) = remove_game {
+                    self.temp_config.custom_games.remove(i);
+                }
+                let ignored = self.temp_config.game_detection.ignored_folders.len();
+                if ignored > 0 {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} ignored folders", ignored));
+                        if ui.small_button("Forget").on_hover_text("Offer them again when their programs run").clicked() {
+                            self.temp_config.game_detection.ignored_folders.clear();
+                        }
+                    });
+                }
+            });
+            
+            ui.add_space(10.0);
+
+            ui.group(|ui| {
+                ui.strong("Appearance");
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.label("Theme:");
+                    egui::ComboBox::from_id_source("theme_combo")
+                        .selected_text(format!("{:?}", self.temp_config.theme))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.temp_config.theme, Theme::Dark, "🌑 Dark");
+                            ui.selectable_value(&mut self.temp_config.theme, Theme::Light, "☀️ Light");
+                            ui.selectable_value(&mut self.temp_config.theme, Theme::System, "⚙️ System");
+                        });
+                });
+                
+                ui.checkbox(&mut self.temp_config.auto_backup, "Show detailed file information");
+                ui.checkbox(&mut self.temp_config.auto_backup, "Enable advanced tooltips");
+                ui.checkbox(&mut self.temp_config.auto_backup, "Show confirmation dialogs");
+            });
+            
+            ui.add_space(10.0);
+            
+            ui.group(|ui| {
+                ui.strong("Advanced Options");
+                ui.separator();
+                
+                ui.checkbox(&mut self.temp_config.auto_backup, "Enable logging");
+                ui.checkbox(&mut self.temp_config.auto_backup, "Monitor saves for changes");
+                ui.checkbox(&mut self.temp_config.auto_backup, "Enable cloud sync preparation");
+                
+                if ui.button("✖ Clear All Cache").on_hover_text("Clear application cache and temporary files").clicked() {
+                    // TODO: Implement cache clearing
+                }
+                
+                if ui.button("↺ Reset to Defaults").on_hover_text("Reset all settings to default values").clicked() {
+                    self.temp_config = Config::default();
+                }
+            });
+
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("✓ Save Settings").clicked() {
+                    let previous_endpoint = self.config.sync_endpoint();
+                    if self.config.backup_encryption.use_keyring && !self.temp_config.backup_encryption.use_keyring {
+                        encryption::forget_keyring();
+                    }
+                    self.config = self.temp_config.clone();
+                    if self.config.sync_endpoint() != previous_endpoint {
+                        self.forget_remote_state();
+                    }
+                    self.rebuild_managers();
+                    self.load_backups();
+                    self.scan_status = match self.write_config_file() {
+                        Ok(()) => ScanStatus::Complete("Settings saved successfully!".to_string()),
+                        Err(e) => ScanStatus::Error(format!(
+                            "Settings saved, but {} could not be written for the command line: {}",
+                            Config::get_config_path().display(), e
+                        )),
+                    };
+                }
+                
+                if ui.button("↺ Reset to Default").clicked() {
+                    self.temp_config = Config::default();
+                }
+            });
+        });
+    }
+
+    fn draw_modals(&mut self, ctx: &egui::Context) {
+        // About dialog
+        if self.show_about {
+            egui::Window::new("About Save Guardian")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.heading("🛡️ Save Guardian");
+                        ui.label("Version 1.0.0");
+                        ui.add_space(10.0);
+                        ui.label("A sleek save manager for Steam and non-Steam games");
+                        ui.add_space(10.0);
+                        ui.label("Features:");
+                        ui.label("• Automatic save detection");
+                        ui.label("• Backup and restore");
+                        ui.label("• Cloud sync support");
+                        ui.label("• Modern, intuitive interface");
+                        ui.add_space(10.0);
+                        ui.label(egui::RichText::new("Reporting a problem? Attach the diagnostics: versions, counts, your settings without passwords or account names, and the recent log")
+                            .size(11.0).color(egui::Color32::GRAY));
+                        ui.horizontal(|ui| {
+                            if ui.button("📋 Copy Diagnostics").clicked() {
+                                let report = self.diagnostics_report();
+                                ui.output_mut(|o| o.copied_text = report);
+                                self.scan_status = ScanStatus::Complete("Diagnostics copied to the clipboard".to_string());
+                            }
+                            if ui.button("💾 Save Diagnostics…").clicked() {
+                                self.save_diagnostics();
+                            }
+                            ui.hyperlink_to("🐞 Report an Issue", diagnostics::ISSUES_URL);
+                        });
+                        ui.add_space(10.0);
+                        if ui.button("Close").clicked() {
+                            self.show_about = false;
+                        }
+                    });
+                });
+        }
+
+        // Backup dialog
+        if self.show_backup_dialog {
+            if let Some(game_idx) = self.selected_game {
+                let saves = self.get_filtered_saves();
+                if let Some(save) = saves.get(game_idx) {
+                    // Clone the save data to avoid borrowing issues
+                    let save_name = save.name.clone();
+                    let save_path = save.save_path.clone();
+                    let save_size = save.format_size();
+                    let save_clone = (*save).clone();
+                    
+                    egui::Window::new(format!("Backup {}", save_name))
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ctx, |ui| {
+                            ui.label(format!("Create backup of: {}", save_name));
+                            ui.label(format!("Path: {}", save_path.display()));
+                            ui.label(format!("Size: {}", save_size));
+                            
+                            ui.add_space(10.0);
+                            
+                            // Use persistent description field
+                            ui.horizontal(|ui| {
+                                ui.label("Description:");
+                                ui.text_edit_singleline(&mut self.backup_description);
+                            });
+                            
+                            ui.add_space(10.0);
+                            
+                            ui.horizontal(|ui| {
+                                let idle = self.archive_task.is_none();
+                                if ui.add_enabled(idle, egui::Button::new("💾 Create Backup")).clicked() {
+                                    let description = if self.backup_description.is_empty() { 
+                                        None 
+                                    } else { 
+                                        Some(self.backup_description.clone()) 
+                                    };
+                                    self.start_single_backup(save_clone.clone(), description);
+                                    self.backup_description.clear();
+                                    self.show_backup_dialog = false;
+                                }
+                                
+                                if ui.button("Cancel").clicked() {
+                                    self.backup_description.clear();
+                                    self.show_backup_dialog = false;
+                                }
+                            });
+                        });
+                }
+            }
+        }
+        
+        // Restore queue
+        if self.restore_queue.is_some() {
+            self.draw_restore_queue(ctx);
+        }
+        
+        // Single backup or restore queue running on a worker thread
+        if self.archive_task.is_some() {
+            self.draw_archive_task(ctx);
+        }
+        
+        // Game details with the activity heatmap
+        if self.details_save_path.is_some() {
+            self.draw_game_details(ctx);
+        }
+        
+        // Point-in-time restore picker
+        if self.show_time_restore {
+            self.draw_time_restore(ctx);
+        }
+        
+        // Cleanup preview and log
+        if self.cleanup_preview.is_some() || self.cleanup_report.is_some() {
+            self.draw_cleanup_dialog(ctx);
+        }
+        
+        // Bulk metadata edit of the selected backups
+        if self.bulk_edit.is_some() {
+            self.draw_bulk_edit(ctx);
+        }
+        
+        // Rebinding backups of renamed games
+        if self.reassign_suggestions.is_some() {
+            self.draw_reassign_dialog(ctx);
+        }
+        
+        // Oddities in Steam's userdata folders
+        if self.userdata_report.is_some() {
+            self.draw_userdata_report(ctx);
+        }
+        
+        // Cloud upload/download progress
+        if self.cloud_transfer.is_some() {
+            self.draw_cloud_transfer(ctx);
+        }
+        
+        // "Backup All" progress
+        if self.bulk_backup.is_some() {
+            self.draw_bulk_backup(ctx);
+        }
+        
+        // Operations of this session, and the per-file log of one
+        if self.show_operations {
+            self.draw_operations(ctx);
+        }
+        if self.operation_view.is_some() {
+            self.draw_operation_log(ctx);
+        }
+        
+        // Final backups of uninstalled games
+        if self.show_uninstalled && !self.uninstalled_saves.is_empty() {
+            self.draw_uninstalled_prompt(ctx);
+        }
+        
+        // Backups changed on both sides, when the conflict policy is Prompt
+        if self.show_cloud_conflicts && !self.cloud_conflicts.is_empty() {
+            self.draw_cloud_conflicts(ctx);
+        }
+        
+        // Past uploads and downloads
+        if self.cloud_history.is_some() {
+            self.draw_cloud_history(ctx);
+        }
+        
+        // Step by step result of "Test Connection"
+        if self.connection_report.is_some() {
+            self.draw_connection_report(ctx);
+        }
+        
+        // Passphrase of the encrypted backups
+        if self.unlock_prompt.is_some() {
+            self.draw_unlock_prompt(ctx);
+        }
+        
+        // Backup shared as a link, being downloaded or assigned to a game
+        if self.import_dialog.is_some() {
+            self.draw_import_dialog(ctx);
+        }
+        
+        // Steam app IDs found for non-Steam saves
+        if self.app_id_suggestions.is_some() {
+            self.draw_app_id_suggestions(ctx);
+        }
+        
+        // Programs writing to save folders no scanned game uses
+        if !self.detected_games.is_empty() {
+            self.draw_detected_games(ctx);
+        }
+        
+        // Download link for one cloud archive
+        if self.share_dialog.is_some() {
+            self.draw_share_dialog(ctx);
+        }
+        
+        // Manual sync stopped by the source check
+        if self.held_sync.is_some() {
+            self.draw_held_sync(ctx);
+        }
+        
+        // Deleting a backup incremental ones build on
+        if self.chain_delete.is_some() {
+            self.draw_chain_delete(ctx);
+        }
+        
+        // Recovery wizard for saves that were wiped
+        if self.show_save_loss && !self.save_loss_alerts.is_empty() {
+            self.draw_save_loss_wizard(ctx);
+        }
+        
+        // Additional dialogs would go here...
+    }
+    
+    fn draw_bulk_edit(&mut self, ctx: &egui::Context) {
+        let selected: Vec<BackupInfo> = self.backups.iter()
+            .filter(|b| self.backup_selection.contains(&b.backup_path))
+            .cloned()
+            .collect();
+        let games = self.known_game_identities();
+        let mut form = match self.bulk_edit.take() {
+            Some(form) => form,
+            None => return,
+        };
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+        
+        egui::Window::new(format!("✏ Edit {} Backups", selected.len()))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Only the changes you fill in are applied, everything else is kept")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.add_space(5.0);
+                
+                ui.horizontal(|ui| {
+                    ui.label("Add tag:");
+                    ui.text_edit_singleline(&mut form.tag);
+                });
+                
+                ui.checkbox(&mut form.change_prefix, "Change description prefix");
+                if form.change_prefix {
+                    ui.horizontal(|ui| {
+                        ui.label("Replace:");
+                        ui.text_edit_singleline(&mut form.old_prefix);
+                        ui.label("with:");
+                        ui.text_edit_singleline(&mut form.new_prefix);
+                    });
+                    ui.label(egui::RichText::new("Leave \"Replace\" empty to put the new prefix in front of every description")
+                        .size(11.0).color(egui::Color32::GRAY));
+                }
+                
+                ui.horizontal(|ui| {
+                    ui.label("Game:");
+                    let selected_text = form.reassign_to.as_ref().map_or("Keep".to_string(), |game| game.describe());
+                    egui::ComboBox::from_id_source("bulk_edit_game")
+                        .selected_text(selected_text)
+                        .width(280.0)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut form.reassign_to, None, "Keep");
+                            for game in &games {
+                                ui.selectable_value(&mut form.reassign_to, Some(game.clone()), game.describe());
+                            }
+                        });
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Pin:");
+                    ui.selectable_value(&mut form.pinned, None, "Keep");
+                    ui.selectable_value(&mut form.pinned, Some(true), "📌 Pin");
+                    ui.selectable_value(&mut form.pinned, Some(false), "Unpin");
+                });
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!form.to_edit().is_empty(), egui::Button::new("✓ Apply")).clicked() {
+                        apply = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        
+        if apply {
+            if let Some(ref backup_manager) = self.backup_manager {
+                let report = backup_manager.edit_backups(&selected, &form.to_edit());
+                self.scan_status = if report.failed.is_empty() {
+                    ScanStatus::Complete(report.describe())
+                } else {
+                    ScanStatus::Error(report.describe())
+                };
+            }
+            self.load_backups();
+        } else if open && !cancel {
+            self.bulk_edit = Some(form);
+        }
+    }
+    
+    fn open_reassign_dialog(&mut self) {
+        self.load_backups();
+        let saves: Vec<GameSave> = self.steam_saves.iter().chain(self.non_steam_saves.iter()).cloned().collect();
+        self.reassign_suggestions = Some(backup::suggest_reassignments(&self.backups, &saves));
+    }
+    
+    fn draw_reassign_dialog(&mut self, ctx: &egui::Context) {
+        let mut suggestions = match self.reassign_suggestions.take() {
+            Some(suggestions) => suggestions,
+            None => return,
+        };
+        let mut games: Vec<GameIdentity> = Vec::new();
+        for identity in self.steam_saves.iter().chain(self.non_steam_saves.iter()).map(GameIdentity::of_save) {
+            if !games.contains(&identity) {
+                games.push(identity);
+            }
+        }
+        games.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        let mut to_apply: Option<usize> = None;
+        let mut open = true;
+        
+        egui::Window::new("🔗 Reassign Backups")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                if suggestions.is_empty() {
+                    ui.label("Every backup belongs to a game found by the last scan. Nothing to reassign.");
+                    return;
+                }
+                
+                ui.label("These backups are filed under a game name the scan no longer finds, e.g. after a rename or better name detection. Move them to the game's current identity to keep its history together.");
+                ui.label(egui::RichText::new("Games that are simply uninstalled show up here too; leave those as they are")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("reassign_grid")
+                        .num_columns(4)
+                        .spacing([10.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Backed up as");
+                            ui.strong("Backups");
+                            ui.strong("Move to");
+                            ui.strong("");
+                            ui.end_row();
+                            
+                            for (i, suggestion) in suggestions.iter_mut().enumerate() {
+                                ui.label(suggestion.from.describe());
+                                ui.label(suggestion.backups.len().to_string());
+                                
+                                ui.horizontal(|ui| {
+                                    let selected_text = suggestion.to.as_ref().map_or("Choose a game".to_string(), |game| game.describe());
+                                    egui::ComboBox::from_id_source(format!("reassign_{}", i))
+                                        .selected_text(selected_text)
+                                        .width(280.0)
+                                        .show_ui(ui, |ui| {
+                                            for game in &games {
+                                                ui.selectable_value(&mut suggestion.to, Some(game.clone()), game.describe());
+                                            }
+                                        });
+                                    if let Some(ref reason) = suggestion.reason {
+                                        ui.label(egui::RichText::new(format!("({})", reason)).size(11.0).color(egui::Color32::GRAY));
+                                    }
+                                });
+                                
+                                if ui.add_enabled(suggestion.to.is_some(), egui::Button::new("Reassign")).clicked() {
+                                    to_apply = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        
+        if let Some(index) = to_apply {
+            let suggestion = &suggestions[index];
+            if let Some(ref backup_manager) = self.backup_manager {
+                let edit = BackupEdit {
+                    reassign_to: suggestion.to.clone(),
+                    ..BackupEdit::default()
+                };
+                let report = backup_manager.edit_backups(&suggestion.backups, &edit);
+                self.scan_status = if report.failed.is_empty() {
+                    ScanStatus::Complete(format!("Moved {} backups of {} to {}",
+                        report.updated, suggestion.from.name, suggestion.to.as_ref().map_or(String::new(), |game| game.describe())))
+                } else {
+                    ScanStatus::Error(report.describe())
+                };
+            }
+            self.open_reassign_dialog();
+        } else if open {
+            self.reassign_suggestions = Some(suggestions);
+        }
+    }
+    
+    /// Every game seen in the scan or the backup catalog, for picking a backup's new game
+    fn known_game_identities(&self) -> Vec<GameIdentity> {
+        let mut games: Vec<GameIdentity> = Vec::new();
+        let identities = self.steam_saves.iter().chain(self.non_steam_saves.iter()).map(GameIdentity::of_save)
+            .chain(self.backups.iter().map(GameIdentity::of_backup));
+        for identity in identities {
+            if !games.contains(&identity) {
+                games.push(identity);
+            }
+        }
+        games.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        games
+    }
+    
+    fn draw_save_loss_wizard(&mut self, ctx: &egui::Context) {
+        let read_only = self.is_read_only();
+        let mut open = true;
+        let mut to_restore: Option<SaveLossAlert> = None;
+        let mut to_dismiss: Option<usize> = None;
+        let mut folder_to_open: Option<std::path::PathBuf> = None;
+        
+        egui::Window::new(egui::RichText::new("⚠ Possible Save Loss Detected").color(egui::Color32::from_rgb(220, 50, 50)))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label("These save folders are empty or much smaller than in their last backup. A game or cloud client may have wiped them.");
+                ui.label(egui::RichText::new("Check the folder before restoring; if you cleared it on purpose, dismiss the warning")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (i, alert) in self.save_loss_alerts.iter().enumerate() {
+                        ui.group(|ui| {
+                            ui.strong(&alert.game_name);
+                            ui.label(alert.describe());
+                            ui.label(egui::RichText::new(alert.save_path.display().to_string()).size(11.0).color(egui::Color32::GRAY));
+                            ui.horizontal(|ui| {
+                                let restore_label = format!("↺ Restore Backup from {}", alert.backup.created_at.format("%Y-%m-%d %H:%M"));
+                                if ui.add_enabled(!read_only, egui::Button::new(restore_label))
+                                    .on_hover_text("Review and run the restore in the restore queue")
+                                    .clicked() {
+                                    to_restore = Some(alert.clone());
+                                }
+                                if ui.button("📂 Open Folder").clicked() {
+                                    folder_to_open = Some(alert.save_path.clone());
+                                }
+                                if ui.button("Dismiss").on_hover_text("The change was intentional, don't warn again until the next backup").clicked() {
+                                    to_dismiss = Some(i);
+                                }
+                            });
+                        });
+                    }
+                });
+            });
+        
+        if let Some(path) = folder_to_open {
+            let _ = open_in_file_manager(&path);
+        }
+        if let Some(alert) = to_restore {
+            self.open_restore_queue(vec![alert.backup.clone()]);
+            self.show_save_loss = false;
+        }
+        if let Some(i) = to_dismiss {
+            let alert = self.save_loss_alerts.remove(i);
+            self.dismissed_save_loss.insert((alert.save_path, alert.backup.id));
+        }
+        if !open {
+            self.show_save_loss = false;
+        }
+    }
+    
+    fn draw_held_sync(&mut self, ctx: &egui::Context) {
+        let (index, check) = match &self.held_sync {
+            Some(held) => held.clone(),
+            None => return,
+        };
+        let game_name = match self.config.sync_pairs.get(index) {
+            Some(pair) => pair.game_name.clone(),
+            None => {
+                self.held_sync = None;
+                return;
+            }
+        };
+        let mut open = true;
+        let mut sync_anyway = false;
+        let mut close = false;
+        let mut folder_to_open: Option<std::path::PathBuf> = None;
+        
+        egui::Window::new(egui::RichText::new(format!("⚠ Sync {} Stopped", game_name)).color(egui::Color32::from_rgb(255, 133, 27)))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(check.describe());
+                ui.label(egui::RichText::new(check.hint()).size(11.0).color(egui::Color32::GRAY));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("📂 Open Source").clicked() {
+                        folder_to_open = Some(std::path::PathBuf::from(&check.source));
+                    }
+                    if ui.button("📂 Open Destination").clicked() {
+                        folder_to_open = Some(check.destination.clone());
+                    }
+                });
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    let sync_button = egui::Button::new(egui::RichText::new("Sync Anyway").color(egui::Color32::from_rgb(220, 50, 50)));
+                    if ui.add(sync_button).on_hover_text("Replace the destination with the source as it is").clicked() {
+                        sync_anyway = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        
+        if let Some(path) = folder_to_open {
+            let _ = open_in_file_manager(&path);
+        }
+        if sync_anyway {
+            self.held_sync = None;
+            self.sync_pair(index, true);
+        } else if close || !open {
+            self.held_sync = None;
+        }
+    }
+    
+    fn draw_cleanup_dialog(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut run_cleanup = false;
+        let mut close = false;
+        
+        egui::Window::new("✖ Cleanup Old Backups")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                if let Some(ref report) = self.cleanup_report {
+                    ui.strong(report.describe());
+                    ui.separator();
+                    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                        for line in report.log_lines() {
+                            ui.label(line);
+                        }
+                    });
+                } else if let Some(ref plan) = self.cleanup_preview {
+                    let candidates = &plan.candidates;
+                    if candidates.is_empty() {
+                        ui.label(format!("No backups are older than {} days. Nothing to clean up.", self.config.backup_retention_days));
+                    } else {
+                        let total: u64 = candidates.iter().map(|c| c.backup.size).sum();
+                        ui.label(format!("These {} backups are older than {} days and will be deleted ({} in total):",
+                            candidates.len(), self.config.backup_retention_days, format_size(total)));
+                        ui.separator();
+                        
+                        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                            egui::Grid::new("cleanup_preview_grid")
+                                .num_columns(4)
+                                .spacing([10.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.strong("Game");
+                                    ui.strong("Created");
+                                    ui.strong("Age");
+                                    ui.strong("Size");
+                                    ui.end_row();
+                                    
+                                    for candidate in candidates {
+                                        ui.label(&candidate.backup.game_name);
+                                        ui.label(candidate.backup.created_at.format("%Y-%m-%d %H:%M").to_string());
+                                        ui.label(format!("{} days", candidate.age_days));
+                                        ui.label(candidate.backup.format_size());
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    }
+                    
+                    if !plan.exempt.is_empty() {
+                        ui.separator();
+                        egui::CollapsingHeader::new(format!("📌 {} old backups kept by pins or exemption rules", plan.exempt.len()))
+                            .show(ui, |ui| {
+                                for (candidate, reason) in &plan.exempt {
+                                    ui.label(format!("{} from {} ({})",
+                                        candidate.backup.game_name,
+                                        candidate.backup.created_at.format("%Y-%m-%d %H:%M"),
+                                        reason));
+                                }
+                            });
+                    }
+                }
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if let Some(ref plan) = self.cleanup_preview {
+                        if self.cleanup_report.is_none() && !plan.candidates.is_empty()
+                            && ui.button(format!("🗑 Delete {} backups", plan.candidates.len())).clicked() {
+                            run_cleanup = true;
+                        }
+                    }
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        
+        if run_cleanup && !self.is_read_only() {
+            if let (Some(plan), Some(backup_manager)) = (self.cleanup_preview.as_ref(), self.backup_manager.as_ref()) {
+                let report = backup_manager.run_cleanup(&plan.candidates);
+                self.scan_status = if report.failed.is_empty() {
+                    ScanStatus::Complete(report.describe())
+                } else {
+                    ScanStatus::Error(report.describe())
+                };
+                self.cleanup_report = Some(report);
+                self.load_backups();
+            }
+        }
+        
+        if !open || close {
+            self.cleanup_preview = None;
+            self.cleanup_report = None;
+        }
+    }
+    
+    fn draw_game_details(&mut self, ctx: &egui::Context) {
+        let save = match self.details_save_path.as_ref().and_then(|path| {
+            self.steam_saves.iter().chain(self.non_steam_saves.iter()).find(|s| &s.save_path == path)
+        }) {
+            Some(save) => save.clone(),
+            None => {
+                self.details_save_path = None;
+                return;
+            }
+        };
+        
+        let key = BackupManager::key_for_save(&save);
+        // Every backup of the game, incremental ones included, oldest first for the time machine
+        let mut history: Vec<BackupInfo> = BackupManager::backups_for_save(&self.backups, &save)
+            .into_iter()
+            .cloned()
+            .collect();
+        history.sort_by_key(|b| b.created_at);
+        let backup_count = history.len();
+        let newest_point = backup_count.saturating_sub(1);
+        let mut time_point = match self.details_time_point {
+            Some((ref path, point)) if path == &save.save_path => point.min(newest_point),
+            _ => newest_point,
+        };
+        let read_only = self.is_read_only();
+        let mut restore_to: Option<BackupInfo> = None;
+        let calendar = match self.details_activity {
+            Some((ref path, ref calendar)) if path == &save.save_path => calendar.clone(),
+            _ => {
+                let calendar = activity::save_activity(&save, &self.backups);
+                self.details_activity = Some((save.save_path.clone(), calendar.clone()));
+                calendar
+            }
+        };
+        let save_metadata = match self.details_saves {
+            Some((ref path, ref found)) if path == &save.save_path => found.clone(),
+            _ => {
+                let found = save_formats::scan_folder(&save.save_path);
+                self.details_saves = Some((save.save_path.clone(), found.clone()));
+                found
+            }
+        };
+        let tags = self.config.game_tags(&key).to_vec();
+        let known_tags = self.config.all_game_tags();
+        let app_links = self.config.app_links.clone();
+        let details = self.game_details(&save).unwrap_or_default();
+        let editable_details = save.save_type == SaveType::NonSteam;
+        if editable_details && self.details_form.as_ref().map_or(true, |(path, _)| path != &save.save_path) {
+            self.details_form = Some((save.save_path.clone(), DetailsForm::from_details(&details)));
+        }
+        let mut include_settings = self.config.includes_settings(&key);
+        let settings_files = if include_settings { game_settings::find_settings_files(&save.save_path) } else { Vec::new() };
+        let mut details_to_save: Option<GameDetails> = None;
+        let lookup_idle = self.archive_task.is_none();
+        let mut find_app_id = false;
+        let mut clear_app_id = false;
+        let mut tag_to_add: Option<String> = None;
+        let mut tag_to_remove: Option<String> = None;
+        let mut open = true;
+        
+        egui::Window::new(format!("i {}", self.save_display_name(&save)))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("game_details_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("Path:");
+                        ui.label(save.save_path.display().to_string());
+                        ui.end_row();
+                        ui.label("Size:");
+                        ui.label(save.format_size());
+                        ui.end_row();
+                        ui.label("Last modified:");
+                        ui.label(save.last_modified.map(|dt| dt.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_else(|| "Unknown".to_string()));
+                        ui.end_row();
+                        if let Some(app_id) = save.app_id {
+                            ui.label("App ID:");
+                            ui.horizontal(|ui| {
+                                ui.label(app_id.to_string());
+                                Self::draw_app_links(ui, &app_links, app_id);
+                                if editable_details && ui.small_button("❌").on_hover_text("Not this Steam game; go back to the folder name").clicked() {
+                                    clear_app_id = true;
+                                }
+                            });
+                            ui.end_row();
+                        } else if editable_details {
+                            ui.label("App ID:");
+                            if ui.add_enabled(lookup_idle, egui::Button::new("🔎 Find on Steam"))
+                                .on_hover_text("Match this save's install folder, executable and name against the Steam app list")
+                                .clicked() {
+                                find_app_id = true;
+                            }
+                            ui.end_row();
+                        }
+                        ui.label("Backups:");
+                        ui.label(backup_count.to_string());
+                        ui.end_row();
+                        if !editable_details {
+                            ui.label("Genre:");
+                            ui.label(if details.genres.is_empty() { "—".to_string() } else { details.genres.join(", ") });
+                            ui.end_row();
+                            ui.label("Released:");
+                            ui.label(details.release_year.map(|year| year.to_string()).unwrap_or_else(|| "—".to_string()));
+                            ui.end_row();
+                            ui.label("Platforms:");
+                            ui.label(if details.platforms.is_empty() { "—".to_string() } else { details.platforms.join(", ") });
+                            ui.end_row();
+                        }
+                    });
+                
+                if let Some((_, ref mut form)) = self.details_form {
+                    ui.separator();
+                    ui.strong("Details");
+                    egui::Grid::new("game_details_form")
+                        .num_columns(2)
+                        .spacing([10.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("Genres:");
+                            ui.add(egui::TextEdit::singleline(&mut form.genres).hint_text("e.g. RPG, Strategy"));
+                            ui.end_row();
+                            ui.label("Released:");
+                            ui.add(egui::TextEdit::singleline(&mut form.release_year).hint_text("Year"));
+                            ui.end_row();
+                            ui.label("Platforms:");
+                            ui.add(egui::TextEdit::singleline(&mut form.platforms).hint_text("e.g. Windows, Linux"));
+                            ui.end_row();
+                        });
+                    if ui.button("💾 Save Details").clicked() {
+                        details_to_save = Some(form.to_details());
+                    }
+                }
+                
+                ui.separator();
+                ui.strong("Tags");
+                ui.horizontal_wrapped(|ui| {
+                    for tag in &tags {
+                        if ui.small_button(format!("🏷 {} ❌", tag)).on_hover_text("Remove tag").clicked() {
+                            tag_to_remove = Some(tag.clone());
+                        }
+                    }
+                    if tags.is_empty() {
+                        ui.label(egui::RichText::new("No tags").color(egui::Color32::GRAY));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let response = ui.text_edit_singleline(&mut self.new_game_tag);
+                    let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("➕ Add Tag").clicked() || entered) && !self.new_game_tag.trim().is_empty() {
+                        tag_to_add = Some(self.new_game_tag.trim().to_string());
+                    }
+                });
+                let suggestions: Vec<&String> = known_tags.iter()
+                    .filter(|known| !tags.iter().any(|t| t.eq_ignore_ascii_case(known)))
+                    .collect();
+                if !suggestions.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(egui::RichText::new("Used elsewhere:").size(11.0).color(egui::Color32::GRAY));
+                        for known in suggestions {
+                            if ui.small_button(known.as_str()).clicked() {
+                                tag_to_add = Some(known.clone());
+                            }
+                        }
+                    });
+                }
+                
+                ui.separator();
+                ui.strong("Settings");
+                ui.checkbox(&mut include_settings, "Include settings (graphics, keybinds) in backups")
+                    .on_hover_text("Config files next to the save go into a separate part of the archive, restorable on their own");
+                if include_settings {
+                    if settings_files.is_empty() {
+                        ui.label(egui::RichText::new("No settings files found next to this save").size(11.0).color(egui::Color32::GRAY));
+                    }
+                    for file in &settings_files {
+                        ui.label(egui::RichText::new(format!("⚙ {}", file.display())).size(11.0).color(egui::Color32::GRAY));
+                    }
+                }
+                
+                if !save_metadata.is_empty() {
+                    ui.separator();
+                    ui.strong("Saves");
+                    for metadata in &save_metadata {
+                        Self::draw_save_metadata(ui, metadata, &save.save_path);
+                    }
+                }
+                
+                if let Some(backup) = history.get(time_point) {
+                    ui.separator();
+                    ui.strong("Time machine");
+                    if backup_count > 1 {
+                        let local = |b: &BackupInfo| b.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string();
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(local(&history[0])).size(11.0).color(egui::Color32::GRAY));
+                            ui.spacing_mut().slider_width = (ui.available_width() - 90.0).max(120.0);
+                            ui.add(egui::Slider::new(&mut time_point, 0..=newest_point).show_value(false));
+                            ui.label(egui::RichText::new(local(&history[newest_point])).size(11.0).color(egui::Color32::GRAY));
+                        });
+                    }
+                    let backup = &history[time_point.min(newest_point)];
+                    egui::Grid::new("time_machine_grid")
+                        .num_columns(2)
+                        .spacing([10.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("Backed up:");
+                            ui.label(format!("{} ({} of {})",
+                                backup.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"), time_point + 1, backup_count));
+                            ui.end_row();
+                            ui.label("Size:");
+                            ui.label(if backup.source_size > 0 {
+                                format!("{}, {} archived", format_size(backup.source_size), backup.format_size())
+                            } else {
+                                backup.format_size()
+                            });
+                            ui.end_row();
+                            ui.label("Kind:");
+                            ui.label(match backup.base_backup {
+                                Some(ref base) => format!("Incremental, on top of {}", base),
+                                None => "Full backup".to_string(),
+                            });
+                            ui.end_row();
+                            if let Some(ref machine) = backup.machine {
+                                ui.label("Made on:");
+                                ui.label(&machine.name);
+                                ui.end_row();
+                            }
+                            ui.label("Description:");
+                            ui.label(backup.description.as_deref().unwrap_or("—"));
+                            ui.end_row();
+                            if !backup.tags.is_empty() {
+                                ui.label("Tags:");
+                                ui.label(backup.tags.join(", "));
+                                ui.end_row();
+                            }
+                        });
+                    for metadata in &backup.save_metadata {
+                        Self::draw_save_metadata(ui, metadata, &backup.backup_path);
+                    }
+                    let hint = if backup.base_backup.is_some() {
+                        "Queue this backup for restore; the backups it builds on are restored with it"
+                    } else {
+                        "Queue this backup for restore"
+                    };
+                    if ui.add_enabled(!read_only, egui::Button::new("↺ Restore to here")).on_hover_text(hint).clicked() {
+                        restore_to = Some(backup.clone());
+                    }
+                }
+                
+                ui.separator();
+                ui.strong("Save activity");
+                ui.label(egui::RichText::new("Days the save changed, from file times and backup history")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.add_space(5.0);
+                Self::draw_activity_heatmap(ui, &calendar);
+            });
+        
+        self.details_time_point = Some((save.save_path.clone(), time_point));
+        if let Some(backup) = restore_to {
+            self.open_restore_queue(vec![backup]);
+        }
+        
+        if let Some(tag) = tag_to_add {
+            self.config.add_game_tag(&key, &tag);
+            self.new_game_tag.clear();
+        }
+        if let Some(tag) = tag_to_remove {
+            self.config.remove_game_tag(&key, &tag);
+            if self.tag_filter.as_ref().map_or(false, |t| t.eq_ignore_ascii_case(&tag)) && !self.config.all_game_tags().iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                self.tag_filter = None;
+            }
+        }
+        self.temp_config.game_tags = self.config.game_tags.clone();
+        
+        if include_settings != self.config.includes_settings(&key) {
+            self.config.set_include_settings(&key, include_settings);
+            self.temp_config.settings_games = self.config.settings_games.clone();
+            self.backup_manager = Self::create_backup_manager(&self.config);
+        }
+        
+        if let Some(details) = details_to_save {
+            self.config.set_game_details(&key, details);
+            self.temp_config.game_details = self.config.game_details.clone();
+            self.scan_status = ScanStatus::Complete(format!("Saved details of {}", save.display_name()));
+        }
+        
+        if find_app_id {
+            self.start_app_id_lookup(vec![save.clone()]);
+        }
+        if clear_app_id {
+            info!("Removing the Steam app ID of {:?}", save.save_path);
+            self.config.steam_app_ids.retain(|assignment| assignment.save_path != save.save_path);
+            self.temp_config.steam_app_ids = self.config.steam_app_ids.clone();
+            self.non_steam_scanner = Self::create_non_steam_scanner(&self.config);
+            self.scan_saves();
+        }
+        
+        if !open {
+            self.details_save_path = None;
+            self.details_activity = None;
+            self.details_saves = None;
+            self.details_time_point = None;
+            self.details_form = None;
+        }
+    }
+    
+    fn diagnostics_report(&self) -> String {
+        let counts = DiagnosticCounts {
+            steam_saves: self.steam_saves.len(),
+            non_steam_saves: self.non_steam_saves.len(),
+            backups: self.backups.len(),
+            backup_bytes: self.backups.iter().map(|b| b.size).sum(),
+            steam_accounts: self.steam_accounts.len(),
+        };
+        diagnostics::build_report(&self.config, &counts, env!("CARGO_PKG_VERSION"))
+    }
+    
+    fn save_diagnostics(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("save_guardian_diagnostics.md")
+            .add_filter("Markdown", &["md"])
+            .save_file() {
+            self.scan_status = match std::fs::write(&path, self.diagnostics_report()) {
+                Ok(()) => ScanStatus::Complete(format!("Diagnostics saved to {}", path.display())),
+                Err(e) => ScanStatus::Error(format!("Failed to save diagnostics: {}", e)),
+            };
+        }
+    }
+    
+    /// Hyperlinks to the enabled web pages for a Steam app ID
+    fn draw_app_links(ui: &mut egui::Ui, links: &[AppLink], app_id: u32) {
+        for link in links.iter().filter(|link| link.enabled) {
+            ui.hyperlink_to(&link.label, link.url_for(app_id));
+        }
+    }
+    
+    /// One save slot: the screenshot stored in the save if it has one, then its summary;
+    /// format and file name on hover
+    fn draw_save_metadata(ui: &mut egui::Ui, metadata: &SaveMetadata, folder: &std::path::Path) {
+        ui.horizontal(|ui| {
+            if let Some(ref png) = metadata.screenshot {
+                let uri = format!("bytes://{}/{}", folder.display(), metadata.file);
+                ui.add(egui::Image::from_bytes(uri, png.clone()).max_height(48.0));
+            }
+            ui.label(format!("🎮 {}", metadata.summary()))
+                .on_hover_text(format!("{}\n{}", metadata.format.label(), metadata.file));
+        });
+    }
+    
+    /// GitHub-style calendar of the last year, one square per day, weeks as columns
+    fn draw_activity_heatmap(ui: &mut egui::Ui, calendar: &activity::ActivityCalendar) {
+        const CELL: f32 = 11.0;
+        const GAP: f32 = 2.0;
+        const WEEKS: i64 = 53;
+        
+        let today = chrono::Local::now().date_naive();
+        // Start on the Monday of the week 52 weeks ago, so the last column is this week
+        let start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64 + (WEEKS - 1) * 7);
+        let max_count = calendar.max_count().max(1);
+        
+        let size = egui::vec2(WEEKS as f32 * (CELL + GAP), 7.0 * (CELL + GAP));
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let mut hovered: Option<(chrono::NaiveDate, u32)> = None;
+        
+        for week in 0..WEEKS {
+            for weekday in 0..7 {
+                let day = start + chrono::Duration::days(week * 7 + weekday);
+                if day > today {
+                    continue;
+                }
+                let count = calendar.count(day);
+                let cell = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(week as f32 * (CELL + GAP), weekday as f32 * (CELL + GAP)),
+                    egui::vec2(CELL, CELL),
+                );
+                let color = if count == 0 {
+                    egui::Color32::from_gray(60)
+                } else {
+                    // Four shades of green by share of the busiest day
+                    let level = ((count as f32 / max_count as f32) * 3.0).ceil() as u8;
+                    egui::Color32::from_rgb(20, 80 + level * 45, 40)
+                };
+                painter.rect_filled(cell, 2.0, color);
+                
+                if response.hover_pos().map_or(false, |pos| cell.contains(pos)) {
+                    hovered = Some((day, count));
+                }
+            }
+        }
+        
+        if let Some((day, count)) = hovered {
+            response.on_hover_text(format!("{}: {} changes", day.format("%Y-%m-%d"), count));
+        }
+        
+        ui.label(format!("Active on {} of the last 365 days", calendar.active_days(today - chrono::Duration::days(364), today)));
+    }
+    
+    /// Back up several saves in one go, skipping unchanged ones if the user opted into that
+    fn backup_saves(&mut self, saves: &[GameSave]) {
+        if self.backup_manager.is_none() {
+            return;
+        }
+        
+        let mut log = OperationLog::new("Backup");
+        for save in saves {
+            let outcome = self.backup_one(save);
+            Self::log_backup(&mut log, save, outcome);
+        }
+        
+        self.report_backups(log, 0);
+    }
+    
+    /// Archive a whole `userdata/<id>` folder as one backup, restored like any other
+    fn backup_steam_profile(&mut self, user_id: &str) {
+        let backup_manager = match self.backup_manager {
+            Some(ref backup_manager) => backup_manager,
+            None => return,
+        };
+        let account = self.steam_accounts.get(user_id).cloned().unwrap_or_else(|| user_id.to_string());
+        let profile = GameSave::steam_profile(user_id, self.config.steam_path.join(user_id));
+        info!("Backing up the Steam profile of {} ({:?})", account, profile.save_path);
+        
+        let outcome = backup_manager.create_backup_checked(&profile, Some(format!("Entire Steam profile of {}", account)));
+        let message = match outcome {
+            Ok(BackupOutcome::Created(ref backup)) => {
+                let mut message = format!("Backed up the Steam profile of {}: {} of {}", account, format_size(backup.size), format_size(backup.source_size));
+                if backup.excluded_files > 0 {
+                    message.push_str(&format!(", {} cache files left out", backup.excluded_files));
+                }
+                message
+            }
+            Ok(BackupOutcome::Unchanged(ref backup)) => format!(
+                "The Steam profile of {} is unchanged since its backup of {}",
+                account, backup.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            ),
+            Err(ref e) => format!("Backing up the Steam profile of {} failed: {}", account, e),
+        };
+        self.scan_status = if outcome.is_ok() {
+            ScanStatus::Complete(message.clone())
+        } else {
+            ScanStatus::Error(message.clone())
+        };
+        
+        let mut log = OperationLog::new(format!("Back Up Steam Profile {}", account));
+        Self::log_backup(&mut log, &profile, outcome);
+        log.finish(message);
+        self.record_operation(log);
+        self.load_backups();
+    }
+    
+    fn log_backup(log: &mut OperationLog, save: &GameSave, outcome: save_guardian_core::types::Result<BackupOutcome>) {
+        match outcome {
+            Ok(BackupOutcome::Created(backup)) => {
+                let archive = backup.backup_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                log.copied(&save.name, Some(format!("Backed up to {}", archive)));
+            }
+            Ok(BackupOutcome::Unchanged(_)) => log.skipped(&save.name, "Unchanged since its last backup"),
+            Err(e) => {
+                warn!("Backup of {} failed: {}", save.name, e);
+                log.failed(&save.name, e.to_string());
+            }
+        }
+    }
+    
+    fn backup_one(&self, save: &GameSave) -> save_guardian_core::types::Result<BackupOutcome> {
+        let backup_manager = self.backup_manager.as_ref()
+            .ok_or_else(|| SaveGuardianError::BackupOperationFailed("No backup folder configured".to_string()))?;
+        if self.config.skip_unchanged_backups {
+            backup_manager.create_backup_if_changed(save, None)
+        } else {
+            backup_manager.create_backup_checked(save, None)
+        }
+    }
+    
+    /// `not_reached` saves were left out by cancelling, they are at the end of the log
+    fn report_backups(&mut self, mut log: OperationLog, not_reached: usize) {
+        let unchanged = log.count(EntryOutcome::Skipped) - not_reached;
+        let failed = log.count(EntryOutcome::Failed);
+        let mut message = format!("Backed up {} saves, {} unchanged since their last backup", log.count(EntryOutcome::Copied), unchanged);
+        if not_reached > 0 {
+            message.push_str(&format!(", {} skipped", not_reached));
+        }
+        if failed > 0 {
+            message.push_str(&format!(", {} failed", failed));
+        }
+        self.scan_status = if failed > 0 {
+            ScanStatus::Error(message.clone())
+        } else {
+            ScanStatus::Complete(message.clone())
+        };
+        log.finish(message);
+        self.record_operation(log);
+        self.load_backups();
+    }
+    
+    /// Back up one save on a worker thread, showing each file as it is archived
+    fn start_single_backup(&mut self, save: GameSave, description: Option<String>) {
+        if self.archive_task.is_some() || self.backup_manager.is_none() {
+            return;
+        }
+        let backup_manager = match Self::create_backup_manager(&self.config) {
+            Some(backup_manager) => backup_manager,
+            None => return,
+        };
+        info!("Backing up {} with progress", save.name);
+        self.scan_status = ScanStatus::Scanning;
+        self.archive_task = Some(ArchiveTask::spawn(format!("Backing up {}", save.name), move |progress| {
+            let outcome = backup_manager.with_progress(progress).create_backup_checked(&save, description);
+            ArchiveResult::Backup(save.name, outcome)
+        }));
+    }
+    
+    /// Pick up the result of a finished single backup or restore queue
+    fn poll_archive_task(&mut self, ctx: &egui::Context) {
+        let finished = match self.archive_task {
+            Some(ref task) => task.try_finish(),
+            None => return,
+        };
+        let result = match finished {
+            Some(result) => result,
+            None => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                return;
+            }
+        };
+        self.archive_task = None;
+        
+        match result {
+            Ok(ArchiveResult::Backup(game_name, outcome)) => self.report_single_backup(&game_name, outcome),
+            Ok(ArchiveResult::Restore(queue, summary)) => self.report_restore_queue(queue, summary),
+            Ok(ArchiveResult::Store(report)) => self.report_store_task(report),
+            Ok(ArchiveResult::Verify(backup_info, status, contents)) => self.report_verify_task(&backup_info, status, contents),
+            Ok(ArchiveResult::FullCheck(outcome)) => self.report_full_check(outcome),
+            Ok(ArchiveResult::Import(downloaded)) => self.report_import_download(downloaded),
+            Ok(ArchiveResult::AppIds(found)) => self.report_app_id_lookup(found),
+            Ok(ArchiveResult::Consolidate(replaced, outcome)) => self.report_consolidation(replaced, outcome),
+            Err(e) => self.scan_status = ScanStatus::Error(e.to_string()),
+        }
+    }
+    
+    fn report_single_backup(&mut self, game_name: &str, outcome: save_guardian_core::types::Result<BackupOutcome>) {
+        match outcome {
+            Ok(BackupOutcome::Created(backup)) => {
+                let mut message = if backup.excluded_files > 0 {
+                    format!("Backup created successfully (skipped {} junk files, {})",
+                        backup.excluded_files, format_size(backup.excluded_bytes))
+                } else {
+                    "Backup created successfully".to_string()
+                };
+                if backup.deduplicated {
+                    message.push_str(&format!(" - no changes since last backup, shares its archive ({} saved)", backup.format_size()));
+                }
+                if backup.base_backup.is_some() {
+                    message.push_str(&format!(" - incremental, {} of changed files", backup.format_size()));
+                }
+                self.scan_status = ScanStatus::Complete(message);
+                self.load_backups();
+            }
+            Ok(BackupOutcome::Unchanged(previous)) => {
+                self.scan_status = ScanStatus::Complete(format!(
+                    "No changes since last backup ({}), nothing to do",
+                    previous.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ));
+            }
+            Err(SaveGuardianError::Cancelled) => {
+                self.scan_status = ScanStatus::Complete(format!("Backup of {} cancelled, nothing was kept", game_name));
+            }
+            Err(e) => {
+                if matches!(e, SaveGuardianError::Locked) {
+                    self.unlock_prompt = Some(String::new());
+                }
+                self.scan_status = ScanStatus::Error(format!("Backup failed: {}", e));
+            }
+        }
+    }
+    
+    /// Repack the backups into the dedup store, or collect its garbage, on a worker thread
+    fn start_store_task(&mut self, repack: bool) {
+        if self.archive_task.is_some() {
+            return;
+        }
+        let backup_manager = match Self::create_backup_manager(&self.config) {
+            Some(backup_manager) => backup_manager,
+            None => return,
+        };
+        let title = if repack { "Repacking backups into the store" } else { "Collecting unused stored files" };
+        info!("{}", title);
+        self.scan_status = ScanStatus::Scanning;
+        self.archive_task = Some(ArchiveTask::spawn(title, move |progress| {
+            let report = if repack {
+                backup_manager.with_progress(progress).repack_into_store()
+            } else {
+                let root = backup_manager.backup_root().clone();
+                DedupStore::new(&root).collect_garbage(&root)
+            };
+            ArchiveResult::Store(report)
+        }));
+    }
+    
+    fn report_store_task(&mut self, report: save_guardian_core::types::Result<StoreReport>) {
+        match report {
+            Ok(report) => {
+                for (what, error) in &report.failed {
+                    warn!("Store maintenance failed for {}: {}", what, error);
+                }
+                self.scan_status = if report.failed.is_empty() {
+                    ScanStatus::Complete(report.describe())
+                } else {
+                    ScanStatus::Error(format!("{} (see the log)", report.describe()))
+                };
+                self.load_backups();
+            }
+            Err(SaveGuardianError::Cancelled) => {
+                self.scan_status = ScanStatus::Complete("Repack cancelled; backups repacked so far keep their new archives".to_string());
+                self.load_backups();
+            }
+            Err(e) => self.scan_status = ScanStatus::Error(format!("Store maintenance failed: {}", e)),
+        }
+    }
+    
+    /// Write the state an incremental backup ends its chain in as a new full backup on a
+    /// worker thread, then delete `replaced`, newest first
+    fn start_consolidation(&mut self, tip: BackupInfo, replaced: Vec<BackupInfo>) {
+        if self.archive_task.is_some() {
+            return;
+        }
+        let backup_manager = match Self::create_backup_manager(&self.config) {
+            Some(backup_manager) => backup_manager,
+            None => return,
+        };
+        self.scan_status = ScanStatus::Scanning;
+        self.archive_task = Some(ArchiveTask::spawn(format!("Consolidating backups of {}", tip.game_name), move |progress| {
+            let backup_manager = backup_manager.with_progress(progress);
+            ArchiveResult::Consolidate(replaced, backup_manager.consolidate_chain(&tip))
+        }));
+    }
+    
+    fn report_consolidation(&mut self, replaced: Vec<BackupInfo>, outcome: save_guardian_core::types::Result<BackupInfo>) {
+        match outcome {
+            Ok(backup) if replaced.is_empty() => {
+                self.scan_status = ScanStatus::Complete(format!(
+                    "Consolidated into a new full backup of {} ({}); the chain's backups can be deleted now", backup.game_name, backup.format_size()
+                ));
+                self.load_backups();
+            }
+            Ok(backup) => {
+                let count = replaced.len();
+                if self.delete_backups(replaced) {
+                    self.scan_status = ScanStatus::Complete(format!(
+                        "Consolidated into a new full backup of {} and deleted the {} backups it replaces", backup.game_name, count
+                    ));
+                }
+            }
+            Err(SaveGuardianError::Cancelled) => {
+                self.scan_status = ScanStatus::Complete("Consolidation cancelled, nothing was deleted".to_string());
+            }
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Consolidation failed, nothing was deleted: {}", e));
+            }
+        }
+    }
+    
+    /// Check one backup's archive against its checksums and read every file back, on a worker thread
+    fn start_verify_task(&mut self, backup_info: BackupInfo) {
+        if self.archive_task.is_some() {
+            return;
+        }
+        let backup_manager = match Self::create_backup_manager(&self.config) {
+            Some(backup_manager) => backup_manager,
+            None => return,
+        };
+        self.scan_status = ScanStatus::Scanning;
+        self.archive_task = Some(ArchiveTask::spawn(format!("Verifying {}", backup_info.game_name), move |progress| {
+            let backup_manager = backup_manager.with_progress(progress);
+            let status = backup_manager.verify_backup(&backup_info);
+            let contents = if status == VerificationStatus::MissingArchive {
+                Ok(ContentCheck::default())
+            } else {
+                backup_manager.verify_contents(&backup_info)
+            };
+            ArchiveResult::Verify(backup_info, status, contents)
+        }));
+    }
+    
+    fn report_verify_task(&mut self, backup_info: &BackupInfo, status: VerificationStatus, contents: save_guardian_core::types::Result<ContentCheck>) {
+        let contents = match contents {
+            Ok(contents) => contents,
+            Err(SaveGuardianError::Cancelled) => {
+                self.scan_status = ScanStatus::Complete(format!("Verification of {} cancelled", backup_info.game_name));
+                return;
+            }
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Verification failed: {}", e));
+                return;
+            }
+        };
+        match maintenance::describe_problems(&status, Some(&contents)) {
+            Some(problems) => {
+                warn!("Backup {} failed verification: {}", backup_info.id, problems);
+                self.scan_status = ScanStatus::Error(format!("{}: {}", backup_info.game_name, problems));
+            }
+            None => {
+                self.scan_status = ScanStatus::Complete(format!("{}: {}; {}", backup_info.game_name, status.describe(), contents.describe()));
+            }
+        }
+    }
+    
+    /// Read every backup back in full on a worker thread, see `maintenance::run_full_check`
+    fn start_full_check(&mut self) {
+        if self.archive_task.is_some() {
+            return;
+        }
+        let backup_manager = match Self::create_backup_manager(&self.config) {
+            Some(backup_manager) => backup_manager,
+            None => return,
+        };
+        let mut state = self.sweep_state.clone();
+        info!("Verifying every backup");
+        self.scan_status = ScanStatus::Scanning;
+        self.archive_task = Some(ArchiveTask::spawn("Verifying all backups", move |progress| {
+            let outcome = maintenance::run_full_check(&backup_manager.with_progress(progress), &mut state)
+                .map(|checked| (state, checked));
+            ArchiveResult::FullCheck(outcome)
+        }));
+    }
+    
+    fn report_full_check(&mut self, outcome: save_guardian_core::types::Result<(SweepState, usize)>) {
+        match outcome {
+            Ok((state, checked)) => {
+                self.sweep_state = state;
+                if let Some(ref backup_manager) = self.backup_manager {
+                    if let Err(e) = self.sweep_state.save(backup_manager.backup_root()) {
+                        warn!("{}", e);
+                    }
+                }
+                self.scan_status = if self.sweep_state.problems.is_empty() {
+                    ScanStatus::Complete(format!("All {} backups verified, every file matches its checksum", checked))
+                } else {
+                    ScanStatus::Error(format!("Checked {} backups: {} damaged or missing", checked, self.sweep_state.problems.len()))
+                };
+            }
+            Err(SaveGuardianError::Cancelled) => self.scan_status = ScanStatus::Complete("Verification cancelled".to_string()),
+            Err(e) => self.scan_status = ScanStatus::Error(format!("Verification failed: {}", e)),
+        }
+    }
+    
+    fn report_restore_queue(&mut self, queue: RestoreQueue, summary: RestoreSummary) {
+        info!("Restore queue finished: {}", summary.describe());
+        let mut log = OperationLog::new("Restore");
+        for item in &queue.items {
+            match item.status {
+                RestoreItemStatus::Restored => log.copied(&item.backup.game_name, Some(format!("Restored to {}", item.target.display()))),
+                RestoreItemStatus::Failed(ref error) => log.failed(&item.backup.game_name, error.clone()),
+                RestoreItemStatus::NeedsTarget => log.skipped(&item.backup.game_name, "No folder picked to restore to"),
+                RestoreItemStatus::Pending if summary.cancelled => log.skipped(&item.backup.game_name, "Cancelled, left as it was"),
+                RestoreItemStatus::Pending => {}
+            }
+        }
+        log.finish(summary.describe());
+        self.record_operation(log);
+        self.scan_status = if summary.failed.is_empty() {
+            ScanStatus::Complete(summary.describe())
+        } else {
+            ScanStatus::Error(summary.describe())
+        };
+        // The window may have been closed while the queue ran
+        if self.restore_queue.is_some() {
+            self.restore_queue = Some(queue);
+            self.restore_summary = Some(summary);
+        }
+        self.backup_selection.clear();
+        self.scan_saves();
+    }
+    
+    fn draw_archive_task(&mut self, ctx: &egui::Context) {
+        let task = match self.archive_task {
+            Some(ref task) => task,
+            None => return,
+        };
+        let progress = task.progress().snapshot();
+        let cancelling = task.progress().is_cancelled();
+        let mut cancel = false;
+        
+        egui::Window::new(format!("⏳ {}", task.title))
+            .collapsible(true)
+            .resizable(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                // No file count yet while a download is still coming in
+                if progress.total > 0 {
+                    ui.label(format!("{} of {} files", progress.done, progress.total));
+                    ui.add(egui::ProgressBar::new(progress.done as f32 / progress.total as f32).show_percentage());
+                } else {
+                    ui.spinner();
+                }
+                if !progress.current.is_empty() {
+                    ui.label(egui::RichText::new(&progress.current).size(11.0).color(egui::Color32::GRAY));
+                }
+                
+                ui.separator();
+                if cancelling {
+                    ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "Cancelling…");
+                } else if ui.button("✖ Cancel")
+                    .on_hover_text("Stop now: a new backup is thrown away, a restore leaves the save folder as it was")
+                    .clicked() {
+                    cancel = true;
+                }
+            });
+        
+        if cancel {
+            task.progress().cancel();
+        }
+    }
+    
+    /// Back up many saves in the background of the UI, one per frame, see `run_bulk_backup`
+    fn start_bulk_backup(&mut self, saves: Vec<GameSave>) {
+        if self.backup_manager.is_none() || saves.is_empty() {
+            return;
+        }
+        info!("Backing up {} saves", saves.len());
+        self.scan_status = ScanStatus::Scanning;
+        self.bulk_backup = Some(BulkBackup {
+            total: saves.len(),
+            pending: saves.into(),
+            log: OperationLog::new("Backup All"),
+            paused: false,
+        });
+    }
+    
+    /// Back up the next save of a running "Backup All", unless it is paused
+    fn run_bulk_backup(&mut self, ctx: &egui::Context) {
+        let next = match self.bulk_backup {
+            Some(ref mut bulk) if !bulk.paused => bulk.pending.pop_front(),
+            _ => return,
+        };
+        let save = match next {
+            Some(save) => save,
+            None => {
+                self.finish_bulk_backup();
+                return;
+            }
+        };
+        
+        let outcome = self.backup_one(&save);
+        if let Some(ref mut bulk) = self.bulk_backup {
+            Self::log_backup(&mut bulk.log, &save, outcome);
+        }
+        ctx.request_repaint();
+    }
+    
+    /// Report a finished or cancelled "Backup All"; saves not reached yet count as skipped
+    fn finish_bulk_backup(&mut self) {
+        if let Some(mut bulk) = self.bulk_backup.take() {
+            info!("Bulk backup finished, {} of {} saves not reached", bulk.pending.len(), bulk.total);
+            for save in &bulk.pending {
+                bulk.log.skipped(&save.name, "Cancelled before its turn");
+            }
+            self.report_backups(bulk.log, bulk.pending.len());
+        }
+    }
+    
+    fn record_operation(&mut self, log: OperationLog) {
+        info!(
+            "{}: {} copied, {} skipped, {} removed, {} failed",
+            log.name, log.count(EntryOutcome::Copied), log.count(EntryOutcome::Skipped),
+            log.count(EntryOutcome::Removed), log.count(EntryOutcome::Failed)
+        );
+        self.operations.push(log);
+    }
+    
+    fn draw_operations(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut view: Option<OperationLog> = None;
+        
+        egui::Window::new("📋 Operations")
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Finished since the app started, newest first. Open one to see each file.")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(380.0).show(ui, |ui| {
+                    egui::Grid::new("operations_grid")
+                        .num_columns(4)
+                        .spacing([10.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for log in self.operations.newest_first() {
+                                let finished = log.finished_at.unwrap_or(log.started_at);
+                                ui.label(finished.with_timezone(&chrono::Local).format("%H:%M:%S").to_string());
+                                if log.succeeded() {
+                                    ui.label(&log.name);
+                                } else {
+                                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), &log.name);
+                                }
+                                ui.label(&log.summary);
+                                if ui.small_button("Details").clicked() {
+                                    view = Some(log.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        
+        if !open {
+            self.show_operations = false;
+        }
+        if view.is_some() {
+            self.operation_view = view;
+            self.operation_filter = None;
+        }
+    }
+    
+    fn draw_operation_log(&mut self, ctx: &egui::Context) {
+        let log = match self.operation_view {
+            Some(ref log) => log,
+            None => return,
+        };
+        let mut open = true;
+        let mut filter = self.operation_filter;
+        let shown: Vec<_> = log.entries.iter()
+            .filter(|entry| filter.map_or(true, |outcome| entry.outcome == outcome))
+            .collect();
+        
+        egui::Window::new(format!("📋 {}", log.name))
+            .id(egui::Id::new("operation_log"))
+            .open(&mut open)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                let started = log.started_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S");
+                ui.label(egui::RichText::new(format!("Started {}", started)).size(11.0).color(egui::Color32::GRAY));
+                ui.label(&log.summary);
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut filter, None, format!("All ({})", log.entries.len()));
+                    for outcome in [EntryOutcome::Copied, EntryOutcome::Skipped, EntryOutcome::Removed, EntryOutcome::Failed] {
+                        let count = log.count(outcome);
+                        if count > 0 {
+                            ui.selectable_value(&mut filter, Some(outcome), format!("{} ({})", outcome.label(), count));
+                        }
+                    }
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("📋 Copy").on_hover_text("Copy the shown entries as text").clicked() {
+                            let text: Vec<String> = shown.iter()
+                                .map(|entry| format!("{}\t{}\t{}", entry.outcome.label(), entry.item, entry.reason.as_deref().unwrap_or("")))
+                                .collect();
+                            ui.output_mut(|o| o.copied_text = text.join("\n"));
+                        }
+                    });
+                });
+                
+                if shown.is_empty() {
+                    ui.label(egui::RichText::new("Nothing to list").color(egui::Color32::GRAY));
+                    return;
+                }
+                
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::Grid::new("operation_log_grid")
+                        .num_columns(3)
+                        .spacing([10.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for entry in &shown {
+                                let color = match entry.outcome {
+                                    EntryOutcome::Copied => egui::Color32::from_rgb(46, 204, 64),
+                                    EntryOutcome::Skipped => egui::Color32::GRAY,
+                                    EntryOutcome::Removed => egui::Color32::from_rgb(255, 133, 27),
+                                    EntryOutcome::Failed => egui::Color32::from_rgb(220, 50, 50),
+                                };
+                                ui.colored_label(color, entry.outcome.label());
+                                ui.label(&entry.item);
+                                ui.label(entry.reason.as_deref().unwrap_or(""));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        
+        self.operation_filter = filter;
+        if !open {
+            self.operation_view = None;
+        }
+    }
+    
+    fn draw_bulk_backup(&mut self, ctx: &egui::Context) {
+        let bulk = match self.bulk_backup {
+            Some(ref bulk) => bulk,
+            None => return,
+        };
+        let done = bulk.total - bulk.pending.len();
+        let remaining = stats::DurationEstimator::new(&self.backups).map(|e| e.estimate(bulk.pending.iter()));
+        let mut toggle_pause = false;
+        let mut cancel = false;
+        
+        egui::Window::new("💾 Backing Up")
+            .collapsible(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(format!("{} of {} saves", done, bulk.total));
+                ui.add(egui::ProgressBar::new(done as f32 / bulk.total.max(1) as f32).show_percentage());
+                if let Some(next) = bulk.pending.front() {
+                    let label = if bulk.paused { "Next" } else { "Backing up" };
+                    ui.label(format!("{}: {}", label, next.name));
+                }
+                let mut counts = format!("{} created, {} unchanged", bulk.log.count(EntryOutcome::Copied), bulk.log.count(EntryOutcome::Skipped));
+                let failed = bulk.log.count(EntryOutcome::Failed);
+                if failed > 0 {
+                    counts.push_str(&format!(", {} failed", failed));
+                }
+                ui.label(egui::RichText::new(counts).size(11.0).color(egui::Color32::GRAY));
+                if let Some(remaining) = remaining {
+                    ui.label(egui::RichText::new(format!("About {} left", format_duration(remaining))).size(11.0).color(egui::Color32::GRAY));
+                }
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if bulk.paused {
+                        if ui.button("▶ Resume").clicked() {
+                            toggle_pause = true;
+                        }
+                        ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "⏸ Paused");
+                    } else if ui.button("⏸ Pause").on_hover_text("Stop after the current save, e.g. to start a game; resume later").clicked() {
+                        toggle_pause = true;
+                    }
+                    if ui.button("✖ Cancel").on_hover_text("Skip the saves not backed up yet").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        
+        if cancel {
+            self.finish_bulk_backup();
+        } else if toggle_pause {
+            if let Some(ref mut bulk) = self.bulk_backup {
+                bulk.paused = !bulk.paused;
+                info!("Bulk backup {}", if bulk.paused { "paused" } else { "resumed" });
+            }
+        }
+    }
+    
+    fn open_time_restore(&mut self) {
+        let now = chrono::Local::now();
+        self.time_restore_date = now.date_naive();
+        self.time_restore_hour = now.hour();
+        self.time_restore_minute = now.minute();
+        self.show_time_restore = true;
+    }
+    
+    /// The picked local date and time as UTC
+    fn time_restore_moment(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let time = chrono::NaiveTime::from_hms_opt(self.time_restore_hour, self.time_restore_minute, 59)?;
+        chrono::Local.from_local_datetime(&self.time_restore_date.and_time(time))
+            .earliest()
+            .map(|local| local.with_timezone(&chrono::Utc))
+    }
+    
+    fn draw_time_restore(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut to_restore: Option<Vec<BackupInfo>> = None;
+        
+        let mut game_names: Vec<String> = self.backups.iter().map(|b| b.game_name.clone()).collect();
+        game_names.sort();
+        game_names.dedup();
+        
+        egui::Window::new("🕘 Restore As Of")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(550.0)
+            .show(ctx, |ui| {
+                ui.label("Pick a moment to restore each game as it was then, using its newest backup made at or before that time.");
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.label("Date:");
+                    ui.add(egui_extras::DatePickerButton::new(&mut self.time_restore_date));
+                    ui.label("Time:");
+                    ui.add(egui::DragValue::new(&mut self.time_restore_hour).clamp_range(0..=23).custom_formatter(|v, _| format!("{:02}", v as u32)));
+                    ui.label(":");
+                    ui.add(egui::DragValue::new(&mut self.time_restore_minute).clamp_range(0..=59).custom_formatter(|v, _| format!("{:02}", v as u32)));
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Games:");
+                    egui::ComboBox::from_id_source("time_restore_game")
+                        .selected_text(self.time_restore_game.clone().unwrap_or_else(|| "All games".to_string()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.time_restore_game, None, "All games");
+                            for name in &game_names {
+                                ui.selectable_value(&mut self.time_restore_game, Some(name.clone()), name);
+                            }
+                        });
+                });
+                
+                ui.separator();
+                
+                let resolved = match self.time_restore_moment() {
+                    Some(at) => restore::backups_as_of(&self.backups, at, self.time_restore_game.as_deref()),
+                    None => Vec::new(),
+                };
+                
+                if resolved.is_empty() {
+                    ui.label("No backups were made before this moment.");
+                } else {
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("time_restore_grid")
+                            .num_columns(3)
+                            .spacing([10.0, 4.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for backup in &resolved {
+                                    ui.label(&backup.game_name);
+                                    ui.label(backup.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                                    ui.label(backup.description.as_deref().unwrap_or(""));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                }
+                
+                ui.separator();
+                if ui.add_enabled(!resolved.is_empty(), egui::Button::new(format!("↺ Restore {} backups…", resolved.len())))
+                    .on_hover_text("Review the targets in the restore queue before anything is overwritten")
+                    .clicked() {
+                    to_restore = Some(resolved);
+                }
+            });
+        
+        if let Some(backups) = to_restore {
+            self.show_time_restore = false;
+            self.open_restore_queue(backups);
+        } else if !open {
+            self.show_time_restore = false;
+        }
+    }
+    
+    fn open_restore_queue(&mut self, backups: Vec<BackupInfo>) {
+        let mut queue = RestoreQueue::new(backups, &self.config.path_remaps);
+        queue.check_health(&self.backups);
+        self.restore_queue = Some(queue);
+        self.restore_summary = None;
+    }
+    
+    fn draw_restore_queue(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut run_queue = false;
+        let mut close = false;
+        
+        egui::Window::new("↺ Restore Queue")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                let queue = match self.restore_queue.as_mut() {
+                    Some(queue) => queue,
+                    None => return,
+                };
+                
+                ui.label("Backups are restored top to bottom. Existing saves at the target are overwritten.");
+                if queue.unresolved_count() > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0),
+                        format!("⚠ {} backups point to folders that no longer exist, choose a target for each", queue.unresolved_count()));
+                }
+                let mut clients: Vec<SyncClient> = Vec::new();
+                for item in &queue.items {
+                    if let Some(client) = sync_clients::client_for(&item.target, &self.sync_client_folders) {
+                        if !clients.contains(&client) {
+                            clients.push(client);
+                        }
+                    }
+                }
+                if !clients.is_empty() {
+                    let names: Vec<&str> = clients.iter().map(|c| c.name()).collect();
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!(
+                        "⚠ Some targets are inside a {} folder. Pause it until the restore is done, or it may upload half-restored saves or put the old ones back",
+                        names.join(" / ")
+                    ));
+                }
+                if queue.warning_count() > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!(
+                        "⚠ {} backups are empty or much smaller than the saves they would replace, and are held back",
+                        queue.warning_count()
+                    ));
+                    ui.checkbox(&mut queue.allow_unhealthy, "Restore them anyway");
+                }
+                ui.separator();
+                
+                let mut retargeted = false;
+                let mut move_up: Option<usize> = None;
+                let mut move_down: Option<usize> = None;
+                let mut remove: Option<usize> = None;
+                let item_count = queue.items.len();
+                
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("restore_queue_grid")
+                        .num_columns(4)
+                        .spacing([10.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (i, item) in queue.items.iter_mut().enumerate() {
+                                let status_icon = match item.status {
+                                    RestoreItemStatus::Pending => "⏳",
+                                    RestoreItemStatus::NeedsTarget => "⚠",
+                                    RestoreItemStatus::Restored => "✅",
+                                    RestoreItemStatus::Failed(_) => "❌",
+                                };
+                                let status_label = match item.warning {
+                                    Some(ref check) if item.status == RestoreItemStatus::Pending => ui
+                                        .colored_label(egui::Color32::from_rgb(220, 50, 50), format!("⚠ {}", item.backup.game_name))
+                                        .on_hover_text(format!("{}\n{}", check, check.hint())),
+                                    _ => ui.label(format!("{} {}", status_icon, item.backup.game_name)),
+                                };
+                                if let RestoreItemStatus::Failed(ref error) = item.status {
+                                    status_label.on_hover_text(error);
+                                }
+                                
+                                let mut target = item.target.to_string_lossy().to_string();
+                                let mut target_edit = ui.add(egui::TextEdit::singleline(&mut target).desired_width(350.0));
+                                if item.target != item.backup.original_path {
+                                    target_edit = target_edit.on_hover_text(format!("Remapped from {}", item.backup.original_path.display()));
+                                }
+                                if target_edit.changed() {
+                                    item.set_target(std::path::PathBuf::from(target));
+                                    retargeted = true;
+                                }
+                                
+                                if ui.button("📁").on_hover_text("Choose target folder").clicked() {
+                                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                                        item.set_target(folder);
+                                        retargeted = true;
+                                    }
+                                }
+                                
+                                ui.horizontal(|ui| {
+                                    if ui.add_enabled(i > 0, egui::Button::new("⏶")).clicked() {
+                                        move_up = Some(i);
+                                    }
+                                    if ui.add_enabled(i + 1 < item_count, egui::Button::new("⏷")).clicked() {
+                                        move_down = Some(i);
+                                    }
+                                    if ui.button("❌").on_hover_text("Remove from queue").clicked() {
+                                        remove = Some(i);
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+                
+                if let Some(i) = move_up {
+                    queue.move_up(i);
+                }
+                if let Some(i) = move_down {
+                    queue.move_down(i);
+                }
+                if let Some(i) = remove {
+                    queue.items.remove(i);
+                }
+                if retargeted {
+                    queue.check_health(&self.backups);
+                }
+                
+                if let Some(ref summary) = self.restore_summary {
+                    ui.separator();
+                    ui.strong(summary.describe());
+                    for (game_name, error) in &summary.failed {
+                        ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("{}: {}", game_name, error));
+                    }
+                }
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let pending = queue.items.iter().filter(|i| i.status == RestoreItemStatus::Pending).count();
+                    let can_run = pending > 0 && queue.unresolved_count() == 0 && self.archive_task.is_none();
+                    if ui.add_enabled(can_run, egui::Button::new(format!("▶ Restore {} backups", pending))).clicked() {
+                        run_queue = true;
+                    }
+                    
+                    if self.restore_summary.as_ref().map_or(false, |s| !s.failed.is_empty())
+                        && ui.button("↻ Retry Failed").clicked() {
+                        queue.retry_failed();
+                        queue.check_health(&self.backups);
+                    }
+                    
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        
+        if run_queue && !self.is_read_only() && self.archive_task.is_none() {
+            if let (Some(queue), Some(backup_manager)) = (self.restore_queue.clone(), Self::create_backup_manager(&self.config)) {
+                let pending = queue.items.iter().filter(|i| i.status == RestoreItemStatus::Pending).count();
+                self.scan_status = ScanStatus::Scanning;
+                self.archive_task = Some(ArchiveTask::spawn(format!("Restoring {} backups", pending), move |progress| {
+                    let mut queue = queue;
+                    let summary = queue.run(&backup_manager.with_progress(progress));
+                    ArchiveResult::Restore(queue, summary)
+                }));
+            }
+        }
+        
+        if !open || close {
+            self.restore_queue = None;
+            self.restore_summary = None;
+        }
+    }
+
+    // Helper methods
+    fn scan_saves(&mut self) {
+        self.run_scan(false);
+    }
+    
+    /// Scan without touching network drives, if the user opted into that
+    fn quick_scan_saves(&mut self) {
+        self.run_scan(self.config.network.skip_in_quick_scan);
+    }
+    
+    fn run_scan(&mut self, skip_network: bool) {
+        self.scan_status = ScanStatus::Scanning;
+        
+        // Don't pre-load hardcoded database - let the API fetching work dynamically
+        // self.steam_scanner.load_game_database();
+        
+        // Refresh any incorrect cached names before scanning
+        self.steam_scanner.refresh_incorrect_names();
+        
+        // A Steam library on a NAS that is offline would otherwise stall the whole scan
+        let steam_on_network = network::is_network_path(&self.config.steam_path);
+        let steam_result = if steam_on_network && skip_network {
+            info!("Skipping Steam userdata on network drive during quick scan");
+            Ok(Vec::new())
+        } else if steam_on_network && !network::is_reachable(
+            &self.config.steam_path,
+            std::time::Duration::from_secs(self.config.network.offline_timeout_secs),
+        ) {
+            Err(SaveGuardianError::PathNotFound(self.config.steam_path.clone()))
+        } else {
+            self.steam_scanner.scan_steam_saves()
+        };
+        
+        // Scan Steam saves
+        match steam_result {
+            Ok(users) => {
+                self.steam_saves.clear();
+                self.steam_accounts.clear();
+                let mut seen_games: std::collections::HashMap<(Option<String>, u32), GameSave> = std::collections::HashMap::new();
+                
+                for user in users {
+                    self.steam_accounts.insert(user.id.clone(), user.name.clone().unwrap_or_else(|| user.id.clone()));
+                    
+                    for game in user.games {
+                        // Deduplicate per account and app ID; Family Sharing puts the same
+                        // game under several accounts and each keeps its own progress
+                        if let Some(app_id) = game.app_id {
+                            let key = (game.steam_user_id.clone(), app_id);
+                            // Keep the most recent version of the game (by last_modified)
+                            let should_add = match seen_games.get(&key) {
+                                Some(existing_game) => {
+                                    match (game.last_modified, existing_game.last_modified) {
+                                        (Some(new_time), Some(existing_time)) => new_time > existing_time,
+                                        (Some(_), None) => true,
+                                        _ => false,
+                                    }
+                                }
+                                None => true,
+                            };
+                            
+                            if should_add {
+                                seen_games.insert(key, game.clone());
+                            }
+                        } else {
+                            // For games without app_id, add them all (shouldn't happen for Steam games)
+                            self.steam_saves.push(game);
+                        }
+                    }
+                }
+                
+                // Add all the deduplicated games
+                for (_, mut game) in seen_games {
+                    game.is_network = steam_on_network;
+                    self.steam_saves.push(game);
+                }
+
+                info!("After deduplication: {} unique Steam games", self.steam_saves.len());
+                self.refresh_steam_cloud_usage();
+            }
+            Err(e) => {
+                error!("Failed to scan Steam saves: {}", e);
+            }
+        }
+        
+        // Scan non-Steam saves
+        let non_steam_result = if skip_network {
+            self.non_steam_scanner.quick_scan_non_steam_saves()
+        } else {
+            self.non_steam_scanner.scan_non_steam_saves()
+        };
+        match non_steam_result {
+            Ok(saves) => {
+                self.non_steam_saves = saves;
+            }
+            Err(e) => {
+                error!("Failed to scan non-Steam saves: {}", e);
+            }
+        }
+        
+        self.scan_status = ScanStatus::Complete(format!(
+            "Found {} Steam saves and {} non-Steam saves",
+            self.steam_saves.len(),
+            self.non_steam_saves.len()
+        ));
+        
+        info!("Scan complete: {} Steam, {} non-Steam", self.steam_saves.len(), self.non_steam_saves.len());
+        
+        // Game updates and installers sometimes reset permissions; don't let the 🔒 lie quietly
+        let reopened: Vec<&std::path::PathBuf> = self.config.protected_saves.iter()
+            .filter(|path| path.exists() && !protection::is_protected(path))
+            .collect();
+        if !reopened.is_empty() {
+            for path in &reopened {
+                warn!("Write-protected save {:?} is writable again", path);
+            }
+            self.scan_status = ScanStatus::Error(format!(
+                "{} write-protected saves became writable outside Save Guardian; unprotect and protect them again to relock",
+                reopened.len()
+            ));
+        }
+        
+        // Always normalize names after any scan to ensure UI consistency
+        self.normalize_all_game_names();
+        self.refresh_size_alerts();
+        self.check_uninstalled_games();
+        self.update_detector_known();
+        self.restart_save_watcher();
+    }
+    
+    /// Read each Steam save's Steam Cloud usage and quota, kept next to its remote folder
+    fn refresh_steam_cloud_usage(&mut self) {
+        let quotas = self.steam_scanner.cloud_quotas();
+        self.steam_cloud_usage = self.steam_saves.iter()
+            .filter_map(|save| {
+                let app_folder = save.save_path.parent()?;
+                let quota = save.app_id.and_then(|app_id| quotas.get(&app_id).copied());
+                Some((save.save_path.clone(), steam_cloud::read_usage(app_folder, quota)?))
+            })
+            .collect();
+        let near_limit = self.steam_cloud_usage.values().filter(|usage| usage.is_near_limit()).count();
+        info!("Steam Cloud usage known for {} games, {} near their quota", self.steam_cloud_usage.len(), near_limit);
+    }
+    
+    /// Compare installed Steam apps with the last scan and queue saves of removed games for archiving
+    fn check_uninstalled_games(&mut self) {
+        let installed = match self.steam_scanner.installed_app_ids() {
+            Some(installed) => installed,
+            None => return,
+        };
+        
+        let removed: Vec<u32> = self.config.installed_steam_apps.iter()
+            .filter(|app_id| !installed.contains(app_id))
+            .copied()
+            .collect();
+        for save in &self.steam_saves {
+            let app_id = match save.app_id {
+                Some(app_id) => app_id,
+                None => continue,
+            };
+            if removed.contains(&app_id)
+                && !self.config.archived_apps.contains(&app_id)
+                && !self.uninstalled_saves.iter().any(|s| s.save_path == save.save_path) {
+                info!("{} ({}) was uninstalled, offering a final backup", save.name, app_id);
+                self.uninstalled_saves.push(save.clone());
+                self.show_uninstalled = true;
+            }
+        }
+        
+        // A reinstalled game is no longer archived
+        self.config.archived_apps.retain(|app_id| !installed.contains(app_id));
+        let mut installed: Vec<u32> = installed.into_iter().collect();
+        installed.sort_unstable();
+        self.config.installed_steam_apps = installed;
+        self.temp_config.installed_steam_apps = self.config.installed_steam_apps.clone();
+        self.temp_config.archived_apps = self.config.archived_apps.clone();
+    }
+    
+    fn open_userdata_report(&mut self) {
+        let folders = self.steam_scanner.userdata_app_folders();
+        let installed = self.steam_scanner.installed_app_ids();
+        let report = anomalies::find_anomalies(
+            &folders,
+            installed.as_ref(),
+            |app_id| self.name_resolver.name(app_id),
+            &self.config.ignored_anomalies,
+        );
+        info!("Userdata report: {} anomalies in {} app folders", report.len(), folders.len());
+        self.userdata_report = Some(report);
+    }
+    
+    fn draw_userdata_report(&mut self, ctx: &egui::Context) {
+        let report = match self.userdata_report {
+            Some(ref report) => report.clone(),
+            None => return,
+        };
+        let read_only = self.is_read_only();
+        let mut open = true;
+        let mut to_archive: Option<(usize, GameSave)> = None;
+        let mut to_back_up: Option<(usize, GameSave)> = None;
+        let mut to_ignore: Option<usize> = None;
+        
+        egui::Window::new("🩺 Steam Userdata Report")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(format!(
+                    "Remote folders over {}, app folders without an installed game and saves unchanged for over {} days",
+                    format_size(anomalies::HUGE_REMOTE_BYTES), anomalies::STALE_DAYS
+                )).size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                if report.is_empty() {
+                    ui.label("Nothing unusual in userdata");
+                    return;
+                }
+                
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for (i, anomaly) in report.iter().enumerate() {
+                        // Archive and back up need the save the scan found in this folder
+                        let save = self.steam_saves.iter().find(|s| s.save_path == anomaly.remote_path());
+                        let suggested = anomaly.suggested_action();
+                        let label = |text: &str, action: SuggestedAction| {
+                            if action == suggested { egui::RichText::new(text).strong() } else { egui::RichText::new(text) }
+                        };
+                        
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                ui.strong(anomaly.describe());
+                                let account = self.steam_accounts.get(&anomaly.user_id).unwrap_or(&anomaly.user_id);
+                                ui.label(egui::RichText::new(format!("👤 {} - {}", account, anomaly.path.display()))
+                                    .size(11.0).color(egui::Color32::GRAY));
+                            });
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.button("Ignore").on_hover_text("Don't report this again").clicked() {
+                                    to_ignore = Some(i);
+                                }
+                                if ui.button("📂").on_hover_text("Open the folder").clicked() {
+                                    let _ = open_in_file_manager(&anomaly.path);
+                                }
+                                if let Some(save) = save {
+                                    if ui.add_enabled(!read_only, egui::Button::new(label("💾 Back Up", SuggestedAction::BackUp))).clicked() {
+                                        to_back_up = Some((i, save.clone()));
+                                    }
+                                    if ui.add_enabled(!read_only, egui::Button::new(label("📦 Archive", SuggestedAction::Archive)))
+                                        .on_hover_text("Take a final backup and pin it so cleanup never removes it")
+                                        .clicked() {
+                                        to_archive = Some((i, save.clone()));
+                                    }
+                                }
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+            });
+        
+        let mut handled: Option<usize> = None;
+        if let Some((i, save)) = to_archive {
+            match self.archive_uninstalled(&save) {
+                Ok(()) => {
+                    self.scan_status = ScanStatus::Complete(format!("Archived {}", save.name));
+                    self.load_backups();
+                    handled = Some(i);
+                }
+                Err(e) => self.scan_status = ScanStatus::Error(format!("Failed to archive {}: {}", save.name, e)),
+            }
+        }
+        if let Some((i, save)) = to_back_up {
+            self.backup_saves(std::slice::from_ref(&save));
+            handled = Some(i);
+        }
+        if let Some(i) = to_ignore {
+            let anomaly = &report[i];
+            self.config.ignored_anomalies.push(IgnoredAnomaly { kind: anomaly.kind, path: anomaly.path.clone() });
+            self.temp_config.ignored_anomalies = self.config.ignored_anomalies.clone();
+            handled = Some(i);
+        }
+        
+        if !open {
+            self.userdata_report = None;
+        } else if let (Some(i), Some(report)) = (handled, self.userdata_report.as_mut()) {
+            report.remove(i);
+        }
+    }
+    
+    /// Take a pinned final backup of an uninstalled game and mark it archived
+    fn archive_uninstalled(&mut self, save: &GameSave) -> Result<()> {
+        let backup_manager = match self.backup_manager {
+            Some(ref manager) => manager,
+            None => return Err(SaveGuardianError::BackupOperationFailed("Backup manager not available".to_string())),
+        };
+        
+        // Even an unchanged save gets its last backup pinned, so cleanup never takes it
+        let mut backup = backup_manager.create_backup_if_changed(save, Some("Final backup after uninstall".to_string()))?
+            .into_backup();
+        if !backup.pinned {
+            backup.pinned = true;
+            backup_manager.update_backup_metadata(&backup)?;
+        }
+        
+        if let Some(app_id) = save.app_id {
+            if !self.config.archived_apps.contains(&app_id) {
+                self.config.archived_apps.push(app_id);
+                self.temp_config.archived_apps = self.config.archived_apps.clone();
+            }
+        }
+        Ok(())
+    }
+    
+    fn draw_uninstalled_prompt(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut to_archive: Vec<GameSave> = Vec::new();
+        let mut to_skip: Option<usize> = None;
+        let mut not_now = false;
+        
+        egui::Window::new("📦 Games Uninstalled")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label("These games were removed from Steam. Their saves are still here, but Steam may delete them later.");
+                ui.label(egui::RichText::new("Archiving takes a final backup, pins it so cleanup never removes it, and marks the game as archived")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (i, save) in self.uninstalled_saves.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                ui.strong(self.save_display_name(save));
+                                ui.label(egui::RichText::new(format!("{} - {}", save.format_size(), save.save_path.display()))
+                                    .size(11.0).color(egui::Color32::GRAY));
+                            });
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.button("Skip").on_hover_text("Don't archive this game").clicked() {
+                                    to_skip = Some(i);
+                                }
+                                if ui.button("📦 Archive").clicked() {
+                                    to_archive.push(save.clone());
+                                }
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    if ui.button(format!("📦 Archive All ({})", self.uninstalled_saves.len())).clicked() {
+                        to_archive = self.uninstalled_saves.clone();
+                    }
+                    if ui.button("Not Now").clicked() {
+                        not_now = true;
+                    }
+                });
+            });
+        
+        if let Some(i) = to_skip {
+            self.uninstalled_saves.remove(i);
+        }
+        
+        if !to_archive.is_empty() {
+            let mut archived = 0;
+            let mut errors = Vec::new();
+            for save in &to_archive {
+                match self.archive_uninstalled(save) {
+                    Ok(()) => {
+                        archived += 1;
+                        self.uninstalled_saves.retain(|s| s.save_path != save.save_path);
+                    }
+                    Err(e) => errors.push(format!("{}: {}", save.name, e)),
+                }
+            }
+            self.scan_status = if errors.is_empty() {
+                ScanStatus::Complete(format!("Archived {} uninstalled games", archived))
+            } else {
+                ScanStatus::Error(format!("Archived {} games, {} failed: {}", archived, errors.len(), errors.join("; ")))
+            };
+            self.load_backups();
+        }
+        
+        if !open || not_now {
+            self.show_uninstalled = false;
+        }
+    }
+    
+    /// Re-check every save against the size watchdog thresholds
+    fn refresh_size_alerts(&mut self) {
+        let saves: Vec<&GameSave> = self.steam_saves.iter().chain(self.non_steam_saves.iter()).collect();
+        self.size_alerts = watchdog::check_save_sizes(&saves, &self.backups, &self.config.size_watchdog);
+        
+        let alerts: Vec<SaveLossAlert> = watchdog::detect_save_loss(&saves, &self.backups, &self.config.size_watchdog)
+            .into_iter()
+            .filter(|alert| !self.dismissed_save_loss.contains(&(alert.save_path.clone(), alert.backup.id.clone())))
+            .collect();
+        // Only pop the wizard up for losses we haven't shown yet
+        if alerts.iter().any(|alert| !self.save_loss_alerts.iter().any(|known| known.save_path == alert.save_path)) {
+            self.show_save_loss = true;
+        }
+        self.save_loss_alerts = alerts;
+    }
+    
+    /// Apply the cached names to the scanned saves; cache-only, so it is cheap enough for every frame
+    fn normalize_all_game_names(&mut self) {
+        self.names_generation = self.name_resolver.generation();
+        for save in &mut self.steam_saves {
+            if let Some(app_id) = save.app_id {
+                let correct_name = match self.name_resolver.lookup(app_id) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if save.name != correct_name {
+                    info!("Normalizing game name: '{}' -> '{}' for app {}", save.name, correct_name, app_id);
+                    save.name = correct_name;
+                }
+            }
+        }
+        // Non-Steam saves with a confirmed app ID go by the game's Steam name too
+        for save in &mut self.non_steam_saves {
+            if let Some(name) = save.app_id.and_then(|app_id| self.name_resolver.lookup(app_id)) {
+                save.name = name;
+            }
+        }
+    }
+    
+    fn load_backups(&mut self) {
+        if let Some(ref backup_manager) = self.backup_manager {
+            self.sweep_state = SweepState::load(backup_manager.backup_root());
+            self.replica_report = ReplicaReport::load(backup_manager.backup_root(), &self.config.sync_endpoint());
+            self.replica_report.forget_deleted();
+            if self.cloud_transfer.as_ref().map_or(true, |t| t.reported) {
+                self.paused_transfer = cloud::PausedTransfer::load(backup_manager.backup_root());
+            }
+            
+            match backup_manager.list_backups(None, None) {
+                Ok(backups) => {
+                    self.backups = backups;
+                    
+                    self.store_history = StoreHistory::load(backup_manager.backup_root());
+                    if self.store_history.record(backup_manager.backup_root(), &self.backups) {
+                        if let Err(e) = self.store_history.save(backup_manager.backup_root()) {
+                            warn!("{}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to load backups: {}", e);
+                }
+            }
+            
+            self.backup_stats = Some(BackupManager::stats_for(&self.backups));
+            self.backup_chains = incremental::chains(&self.backups);
+        }
+        
+        self.refresh_size_alerts();
+    }
+    
+    /// Scheduler tick: automatic cloud sync, scheduled sync pairs and the verification sweep
+    fn run_scheduled_tasks(&mut self) {
+        // Runs on the first frame rather than in new(), so --read-only is already applied
+        if !self.startup_syncs_done {
+            self.startup_syncs_done = true;
+            self.run_scheduled_syncs(true);
+        }
+        
+        self.run_auto_sync();
+        self.run_mirror_queue();
+        self.run_watched_backups();
+        
+        if self.last_schedule_check.elapsed() < std::time::Duration::from_secs(60) {
+            return;
+        }
+        self.last_schedule_check = std::time::Instant::now();
+        
+        self.run_scheduled_syncs(false);
+        
+        if self.sweep_state.is_due(&self.config.verification) {
+            self.run_verification_sweep();
+        }
+        
+        if self.replica_report.is_due(&self.config.replica_check) {
+            self.run_replica_check(false);
+        }
+    }
+    
+    /// Full Sync at the interval from the cloud settings, while the app is open
+    fn run_auto_sync(&mut self) {
+        let paused = self.is_read_only()
+            || (self.config.online.offline_mode && self.config.cloud_uses_network())
+            || self.paused_transfer.is_some();
+        if !self.config.koofr_config.auto_sync || !self.config.cloud_enabled() || paused {
+            self.auto_sync_next = None;
+            return;
+        }
+        
+        let now = chrono::Utc::now();
+        let interval = chrono::Duration::minutes(self.config.koofr_config.sync_interval_minutes.max(1) as i64);
+        let next = *self.auto_sync_next.get_or_insert(now + interval);
+        if now < next || self.auto_sync_running {
+            return;
+        }
+        
+        if self.is_transferring() {
+            // Don't fail because of a manual transfer, just look again shortly
+            self.auto_sync_next = Some(now + chrono::Duration::minutes(1));
+            return;
+        }
+        
+        info!("Automatic cloud sync, every {} minutes", self.config.koofr_config.sync_interval_minutes);
+        self.auto_sync_next = Some(now + interval);
+        self.auto_sync_running = true;
+        self.full_sync_koofr();
+        
+        // Nothing to transfer or failed before starting; otherwise reported when the transfer ends
+        let started = self.cloud_transfer.as_ref().map_or(false, |t| !t.reported);
+        if !started {
+            self.finish_auto_sync();
+        }
+    }
+    
+    fn finish_auto_sync(&mut self) {
+        if self.auto_sync_running {
+            self.auto_sync_running = false;
+            self.auto_sync_last = Some((chrono::Utc::now(), self.scan_status.clone()));
+        }
+    }
+    
+    fn run_verification_sweep(&mut self) {
+        let backup_manager = match self.backup_manager {
+            Some(ref manager) => manager,
+            None => return,
+        };
+        
+        let previous_problems = self.sweep_state.problems.len();
+        match maintenance::run_sweep(backup_manager, &mut self.sweep_state, self.config.verification.batch_size) {
+            Ok(()) => {
+                if let Err(e) = self.sweep_state.save(backup_manager.backup_root()) {
+                    warn!("{}", e);
+                }
+                if self.sweep_state.problems.len() > previous_problems {
+                    self.scan_status = ScanStatus::Error(format!(
+                        "Integrity sweep found {} damaged or missing backups",
+                        self.sweep_state.problems.len()
+                    ));
+                }
+            }
+            Err(e) => {
+                error!("Verification sweep failed: {}", e);
+            }
+        }
+    }
+    
+    /// Compare the cloud listing with the local backups, see cloud/replicas.rs
+    ///
+    /// Scheduled checks stay quiet and just wait when the cloud can't be reached right now;
+    /// a manual check reports why it didn't run.
+    fn run_replica_check(&mut self, manual: bool) {
+        if manual {
+            if !self.check_cloud_ready() {
+                return;
+            }
+        } else if self.is_read_only()
+            || !self.config.cloud_enabled()
+            || (self.config.online.offline_mode && self.config.cloud_uses_network())
+            || self.is_transferring()
+        {
+            return;
+        }
+        
+        let backup_root = self.config.effective_backup_path();
+        let provider = cloud::provider_from_config(&self.config);
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(SaveGuardianError::PathNotFound(_)) => Vec::new(),
+            Err(e) => {
+                warn!("Replica check could not list the cloud: {}", e);
+                self.replica_report.endpoint = self.config.sync_endpoint();
+                self.replica_report.record_error(e.to_string());
+                if let Err(e) = self.replica_report.save(&backup_root) {
+                    warn!("{}", e);
+                }
+                if manual {
+                    self.scan_status = ScanStatus::Error(format!("Failed to list cloud files: {}", e));
+                }
+                return;
+            }
+        };
+        
+        let manifest = self.fetch_cloud_manifest();
+        let previous = self.replica_report.missing_remote.len() + self.replica_report.unknown_local.len();
+        self.replica_report = cloud::replicas::compare(
+            &self.config.sync_endpoint(),
+            &self.backups,
+            &remote,
+            |backup| {
+                self.config.is_cloud_excluded(&BackupManager::latest_key(backup))
+                    || (self.config.exclude_sync_client_saves && self.sync_client_for(&backup.original_path).is_some())
+            },
+            |entry| self.is_cloud_entry_excluded(entry, manifest.as_ref()),
+        );
+        if let Err(e) = self.replica_report.save(&backup_root) {
+            warn!("{}", e);
+        }
+        
+        let mut listing = remote;
+        listing.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        self.cloud_listing = Some(listing);
+        if manifest.is_some() {
+            self.cloud_manifest = manifest;
+        }
+        
+        let found = self.replica_report.missing_remote.len() + self.replica_report.unknown_local.len();
+        if manual || found > previous {
+            self.scan_status = if self.replica_report.is_clean() {
+                ScanStatus::Complete(format!("✓ All {} local archives have a copy in the cloud", self.replica_report.local_files))
+            } else {
+                ScanStatus::Error(format!(
+                    "Replica check: {} archives missing in the cloud, {} in the cloud unknown here",
+                    self.replica_report.missing_remote.len(),
+                    self.replica_report.unknown_local.len()
+                ))
+            };
+        }
+    }
+    
+    /// Upload the local archives the replica check found missing in the cloud
+    fn upload_missing_replicas(&mut self) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        let remote = self.cloud_listing.clone().unwrap_or_default();
+        let state = self.load_sync_state(&remote);
+        let jobs: Vec<TransferJob> = self.replica_report.missing_remote.iter()
+            .filter(|m| m.local_path.exists())
+            .map(|m| {
+                let file_name = m.local_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                TransferJob {
+                    direction: TransferDirection::Upload,
+                    remote_name: cloud::game_archive_path(&m.game_name, &file_name),
+                    size: std::fs::metadata(&m.local_path).map(|m| m.len()).unwrap_or(0),
+                    local_path: m.local_path.clone(),
+                    sha256: None,
+                    replace: false,
+                }
+            })
+            .collect();
+        if jobs.is_empty() {
+            self.scan_status = ScanStatus::Complete("Nothing left to upload".to_string());
+            return;
+        }
+        let provider = cloud::provider_from_config(&self.config);
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Replica repair", state, remote, kept_local: 0, unchanged: 0, conflicts: 0, restore_to: None });
+    }
+    
+    /// Download the cloud archives the replica check found no local backup for
+    fn download_unknown_replicas(&mut self) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        let remote = self.cloud_listing.clone().unwrap_or_default();
+        let unknown: Vec<CloudEntry> = remote.iter()
+            .filter(|e| self.replica_report.unknown_local.iter().any(|u| u.remote_name == e.name))
+            .cloned()
+            .collect();
+        let mut state = self.load_sync_state(&remote);
+        let manifest = self.fetch_cloud_manifest();
+        let (jobs, unchanged, kept_local) = match self.download_jobs(&unknown, &mut state, manifest.as_ref()) {
+            Ok(jobs) => jobs,
+            Err(message) => {
+                self.scan_status = ScanStatus::Error(message);
+                return;
+            }
+        };
+        if jobs.is_empty() {
+            self.scan_status = ScanStatus::Complete("Nothing left to download, check again to refresh".to_string());
+            return;
+        }
+        let provider = cloud::provider_from_config(&self.config);
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Replica repair", state, remote, kept_local, unchanged, conflicts: 0, restore_to: None });
+    }
+    
+    /// Delete a cloud archive no local backup knows, after the user confirmed it
+    fn delete_unknown_replica(&mut self, remote_name: &str) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        let provider = cloud::provider_from_config(&self.config);
+        match provider.delete(remote_name) {
+            Ok(()) => {
+                info!("Deleted {} from {}", remote_name, provider.name());
+                self.replica_report.unknown_local.retain(|u| u.remote_name != remote_name);
+                if let Some(ref mut listing) = self.cloud_listing {
+                    listing.retain(|e| e.name != remote_name);
+                }
+                if let Err(e) = self.replica_report.save(&self.config.effective_backup_path()) {
+                    warn!("{}", e);
+                }
+                self.scan_status = ScanStatus::Complete(format!("Deleted {} from the cloud", cloud::remote_file_name(remote_name)));
+            }
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Failed to delete {}: {}", remote_name, e));
+            }
+        }
+    }
+    
+    /// Label a save with its owning Steam account when several accounts are present
+    fn save_display_name(&self, save: &GameSave) -> String {
+        match save.steam_user_id {
+            Some(ref user_id) if self.steam_accounts.len() > 1 => {
+                let account = self.steam_accounts.get(user_id).unwrap_or(user_id);
+                format!("{} · 👤 {}", save.display_name(), account)
+            }
+            _ => save.display_name(),
+        }
+    }
+
+    /// The sync client (OneDrive, Dropbox...) whose folder holds this path, if any
+    fn sync_client_for(&self, path: &std::path::Path) -> Option<SyncClient> {
+        sync_clients::client_for(path, &self.sync_client_folders)
+    }
+    
+    /// Details entered in the app first, then what the Steam Store or SteamSpy said
+    fn game_details(&self, save: &GameSave) -> Option<GameDetails> {
+        if let Some(details) = self.config.game_details(&BackupManager::key_for_save(save)) {
+            return Some(details.clone());
+        }
+        save.app_id.and_then(|app_id| self.name_resolver.details(app_id))
+    }
+    
+    /// Every genre of the scanned games, sorted, for the filter
+    fn all_genres(&self) -> Vec<String> {
+        let mut genres: Vec<String> = Vec::new();
+        for save in self.steam_saves.iter().chain(self.non_steam_saves.iter()) {
+            for genre in self.game_details(save).map(|d| d.genres).unwrap_or_default() {
+                if !genres.iter().any(|g| g.eq_ignore_ascii_case(&genre)) {
+                    genres.push(genre);
+                }
+            }
+        }
+        genres.sort_by_key(|g| g.to_lowercase());
+        genres
+    }
+    
+    fn get_filtered_saves(&self) -> Vec<&GameSave> {
+        let mut saves = Vec::new();
+        
+        if self.filter_steam {
+            saves.extend(self.steam_saves.iter());
+        }
+        
+        if self.filter_non_steam {
+            saves.extend(self.non_steam_saves.iter());
+        }
+        
+        saves.retain(|save| !self.config.is_save_hidden(&save.save_path));
+        
+        if let Some(tag) = &self.tag_filter {
+            saves.retain(|save| self.config.game_has_tag(&BackupManager::key_for_save(save), tag));
+        }
+        
+        if let Some(genre) = &self.genre_filter {
+            saves.retain(|save| self.game_details(save)
+                .map_or(false, |details| details.genres.iter().any(|g| g.eq_ignore_ascii_case(genre))));
+        }
+        
+        if !self.search_query.is_empty() {
+            let query = self.search_query.to_lowercase();
+            saves.retain(|save| {
+                // Use the same display string as in the UI so results are consistent
+                let display = self.save_display_name(save).to_lowercase();
+                display.contains(&query) ||
+                save.save_path.to_string_lossy().to_lowercase().contains(&query)
+            });
+        }
+        
+        saves
+    }
+    
+    fn sort_saves(&self, saves: &mut Vec<&GameSave>) {
+        match self.sort_by {
+            SortBy::Name => saves.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortBy::LastModified => saves.sort_by(|a, b| b.last_modified.cmp(&a.last_modified)),
+            SortBy::Size => saves.sort_by(|a, b| b.size.cmp(&a.size)),
+            SortBy::Type => saves.sort_by(|a, b| a.save_type.cmp(&b.save_type)),
+        }
+    }
+    
+    fn initialize_cloud_folder(&self, provider: &dyn CloudProvider) -> save_guardian_core::types::Result<()> {
+        match provider.mkdir("") {
+            Ok(()) => {
+                info!("Cloud folder is ready");
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Could not initialize cloud folder: {}", e);
+                Err(e)
+            }
+        }
+    }
+    
+    fn draw_webdav_settings(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.temp_config.koofr_config.enabled, "Enable cloud sync");
+        
+        let server = self.temp_config.koofr_config.server;
+        ui.horizontal(|ui| {
+            ui.label("Server type:");
+            egui::ComboBox::from_id_source("webdav_server")
+                .selected_text(server.label())
+                .show_ui(ui, |ui| {
+                    for kind in [WebDavServer::Koofr, WebDavServer::Nextcloud, WebDavServer::OwnCloud, WebDavServer::Generic] {
+                        ui.selectable_value(&mut self.temp_config.koofr_config.server, kind, kind.label());
+                    }
+                });
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("Server URL:");
+            ui.text_edit_singleline(&mut self.temp_config.koofr_config.server_url);
+        });
+        ui.label(egui::RichText::new(server.url_hint()).size(11.0).color(egui::Color32::GRAY));
+        
+        ui.horizontal(|ui| {
+            ui.label("Username:");
+            ui.text_edit_singleline(&mut self.temp_config.koofr_config.username);
+        });
+        ui.label(egui::RichText::new(server.username_hint()).size(11.0).color(egui::Color32::GRAY));
+        
+        ui.horizontal(|ui| {
+            ui.label("Password:");
+            ui.add(egui::TextEdit::singleline(&mut self.temp_config.koofr_config.password).password(true));
+        });
+        ui.label(egui::RichText::new(server.password_hint()).size(11.0).color(egui::Color32::GRAY));
+        
+        ui.horizontal(|ui| {
+            ui.label("Sync Folder:");
+            ui.text_edit_singleline(&mut self.temp_config.koofr_config.sync_folder);
+        });
+        ui.label(egui::RichText::new("Folder below the server URL that holds the backups, nested folders like Backups/SaveGuardian are created as needed")
+            .size(11.0).color(egui::Color32::GRAY));
+    }
+    
+    fn draw_sftp_settings(&mut self, ui: &mut egui::Ui) {
+        let sftp = &mut self.temp_config.sftp_config;
+        ui.checkbox(&mut sftp.enabled, "Enable cloud sync");
+        
+        ui.horizontal(|ui| {
+            ui.label("Host:");
+            ui.text_edit_singleline(&mut sftp.host);
+            ui.label("Port:");
+            ui.add(egui::DragValue::new(&mut sftp.port).clamp_range(1..=65535));
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("Username:");
+            ui.text_edit_singleline(&mut sftp.username);
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("Sign in with:");
+            ui.selectable_value(&mut sftp.auth, SftpAuth::KeyFile, "SSH key");
+            ui.selectable_value(&mut sftp.auth, SftpAuth::Password, "Password");
+        });
+        
+        match sftp.auth {
+            SftpAuth::KeyFile => {
+                ui.horizontal(|ui| {
+                    ui.label("Private key:");
+                    let mut key_path = sftp.key_path.to_string_lossy().to_string();
+                    if ui.text_edit_singleline(&mut key_path).changed() {
+                        sftp.key_path = std::path::PathBuf::from(key_path);
+                    }
+                    if ui.button("📁").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_file() {
+                            sftp.key_path = path;
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Key passphrase:");
+                    ui.add(egui::TextEdit::singleline(&mut sftp.key_passphrase).password(true));
+                });
+                ui.label(egui::RichText::new("Leave empty for keys without a passphrase").size(11.0).color(egui::Color32::GRAY));
+            }
+            SftpAuth::Password => {
+                ui.horizontal(|ui| {
+                    ui.label("Password:");
+                    ui.add(egui::TextEdit::singleline(&mut sftp.password).password(true));
+                });
+            }
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("Remote folder:");
+            ui.text_edit_singleline(&mut sftp.remote_path);
+        });
+        ui.label(egui::RichText::new("Relative to your login folder unless it starts with /. The server key is checked against ~/.ssh/known_hosts")
+            .size(11.0).color(egui::Color32::GRAY));
+    }
+    
+    fn draw_folder_target_settings(&mut self, ui: &mut egui::Ui) {
+        let target = &mut self.temp_config.folder_target;
+        ui.checkbox(&mut target.enabled, "Enable cloud sync");
+        
+        ui.horizontal(|ui| {
+            ui.label("Folder:");
+            let mut path = target.path.to_string_lossy().to_string();
+            if ui.text_edit_singleline(&mut path).changed() {
+                target.path = std::path::PathBuf::from(path);
+            }
+            if ui.button("📁").clicked() {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    target.path = folder;
+                }
+            }
+        });
+        ui.label(egui::RichText::new("A NAS share, USB drive or Syncthing folder; backups are copied there like to a cloud account")
+            .size(11.0).color(egui::Color32::GRAY));
+    }
+    
+    fn test_cloud_connection(&mut self) {
+        self.connection_report = None;
+        let complete = match self.temp_config.cloud_backend {
+            CloudBackend::WebDav => {
+                let koofr_config = &self.temp_config.koofr_config;
+                !koofr_config.server_url.is_empty() && !koofr_config.username.is_empty() && !koofr_config.password.is_empty()
+            }
+            CloudBackend::Sftp => {
+                let sftp_config = &self.temp_config.sftp_config;
+                let credentials = match sftp_config.auth {
+                    SftpAuth::Password => !sftp_config.password.is_empty(),
+                    SftpAuth::KeyFile => sftp_config.key_path.exists(),
+                };
+                !sftp_config.host.is_empty() && !sftp_config.username.is_empty() && credentials
+            }
+            CloudBackend::Folder => !self.temp_config.folder_target.path.as_os_str().is_empty(),
+        };
+        
+        if !complete {
+            self.scan_status = ScanStatus::Error("Please fill in all cloud connection details".to_string());
+            return;
+        }
+        
+        if self.temp_config.online.offline_mode && self.temp_config.cloud_uses_network() {
+            self.scan_status = ScanStatus::Error("Turn off offline mode to test a network connection".to_string());
+            return;
+        }
+        
+        self.scan_status = ScanStatus::Scanning;
+        
+        let report = cloud::diagnose::diagnose(&self.temp_config);
+        self.scan_status = match report.first_failure() {
+            None => ScanStatus::Complete(format!("✓ {} connection successful!", self.temp_config.cloud_name())),
+            Some(step) => ScanStatus::Error(format!("Connection check failed at \"{}\": {}", step.name, step.detail)),
+        };
+        self.connection_report = Some(report);
+    }
+    
+    fn upload_backups_to_koofr(&mut self) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        self.queue_mirrors();
+        
+        // Refresh backups list before uploading
+        self.load_backups();
+        
+        if self.backups.is_empty() {
+            self.scan_status = ScanStatus::Error("No backups found. Create some backups first!".to_string());
+            return;
+        }
+        
+        let provider = cloud::provider_from_config(&self.config);
+        
+        // Continue even if this fails - the folder might already exist or be accessible
+        let _ = self.initialize_cloud_folder(provider.as_ref());
+        
+        // Without a listing every backup counts as missing remotely
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(e) => {
+                warn!("Could not list cloud files, uploading everything: {}", e);
+                Vec::new()
+            }
+        };
+        let mut state = self.load_sync_state(&remote);
+        let manifest = self.fetch_cloud_manifest();
+        let (jobs, kept_local, unchanged) = self.upload_jobs(&remote, &mut state, manifest.as_ref());
+        if jobs.is_empty() {
+            self.save_sync_state(&state);
+            self.scan_status = ScanStatus::Complete(format!("All backups are already in the cloud ({} unchanged{})", unchanged,
+                if kept_local > 0 { format!(", {} kept local", kept_local) } else { String::new() }));
+            return;
+        }
+        
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Upload", state, remote, kept_local, unchanged, conflicts: 0, restore_to: None });
+    }
+    
+    /// Upload jobs for new or changed local backups, except games tagged to stay local
+    /// and, if so configured, saves a sync client like OneDrive already uploads
+    fn upload_jobs(&self, remote: &[CloudEntry], state: &mut SyncState, manifest: Option<&CloudManifest>) -> (Vec<TransferJob>, usize, usize) {
+        let mut jobs = Vec::new();
+        let mut kept_local = 0;
+        let mut unchanged = 0;
+        
+        for backup in &self.backups {
+            if !save_guardian_core::volumes::archive_exists(backup) {
+                warn!("Backup file does not exist: {}", backup.backup_path.display());
+                continue;
+            }
+            
+            if self.config.is_cloud_excluded(&BackupManager::latest_key(backup)) {
+                info!("Skipping {}, its game is kept out of the cloud", backup.game_name);
+                kept_local += 1;
+                continue;
+            }
+            
+            if self.config.exclude_sync_client_saves {
+                if let Some(client) = self.sync_client_for(&backup.original_path) {
+                    info!("Skipping {}, its save is already synced by {}", backup.game_name, client.name());
+                    kept_local += 1;
+                    continue;
+                }
+            }
+            
+            // Split archives go up part by part, each within the size limit they were split for
+            for local_path in save_guardian_core::volumes::archive_files(backup) {
+                let filename = local_path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("backup.zip");
+                
+                // Archives already in the cloud stay where they are, flat or in a game folder
+                // Servers on macOS may list the name decomposed
+                let remote_entry = remote.iter().find(|e| save_guardian_core::normalize::same_text(e.file_name(), filename));
+                let remote_name = match remote_entry {
+                    Some(entry) => entry.name.clone(),
+                    None => cloud::game_archive_path(&backup.game_name, filename),
+                };
+                if !state.upload_needed(&remote_name, &local_path, remote_entry) {
+                    if !state.files.contains_key(&remote_name) {
+                        state.record(&remote_name, &local_path, remote_entry);
+                    }
+                    unchanged += 1;
+                    continue;
+                }
+                // Recompressed or encrypted copies differ in size, the manifest's hash still matches
+                if remote_entry.map_or(false, |entry| self.has_same_archive(entry, &local_path, manifest)) {
+                    state.record(&remote_name, &local_path, remote_entry);
+                    unchanged += 1;
+                    continue;
+                }
+                
+                jobs.push(TransferJob {
+                    direction: TransferDirection::Upload,
+                    remote_name,
+                    size: std::fs::metadata(&local_path).map(|m| m.len()).unwrap_or(backup.size),
+                    local_path,
+                    sha256: None,
+                    replace: true,
+                });
+            }
+        }
+        
+        (jobs, kept_local, unchanged)
+    }
+    
+    fn download_backups_from_koofr(&mut self) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        
+        let provider = cloud::provider_from_config(&self.config);
+        
+        // Continue even if this fails - the folder might already exist
+        let _ = self.initialize_cloud_folder(provider.as_ref());
+        
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(SaveGuardianError::PathNotFound(_)) => {
+                self.scan_status = ScanStatus::Error("Cloud sync folder not found. Try uploading some backups first.".to_string());
+                return;
+            }
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Failed to list cloud files: {}", e));
+                return;
+            }
+        };
+        
+        if remote.is_empty() {
+            self.scan_status = ScanStatus::Complete("No files found in cloud folder to download".to_string());
+            return;
+        }
+        
+        let mut state = self.load_sync_state(&remote);
+        let manifest = self.fetch_cloud_manifest();
+        let (jobs, unchanged, kept_local) = match self.download_jobs(&remote, &mut state, manifest.as_ref()) {
+            Ok(jobs) => jobs,
+            Err(message) => {
+                self.scan_status = ScanStatus::Error(message);
+                return;
+            }
+        };
+        
+        if jobs.is_empty() {
+            self.save_sync_state(&state);
+            let skipped = if kept_local > 0 { format!(", {} of games kept out of the cloud skipped", kept_local) } else { String::new() };
+            self.scan_status = ScanStatus::Complete(format!("All {} cloud backups are already here, nothing downloaded{}", unchanged, skipped));
+            return;
+        }
+        
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Download", state, remote, kept_local, unchanged, conflicts: 0, restore_to: None });
+    }
+    
+    /// Download jobs for new or changed archives in the cloud folder
+    ///
+    /// Returns the jobs, the number of unchanged archives and of archives of games kept out of the cloud.
+    ///
+    /// The manifest, if it could be read, has the hash every download is checked against,
+    /// and tells which game an archive belongs to for games kept out of the cloud.
+    fn download_jobs(&self, remote: &[CloudEntry], state: &mut SyncState, manifest: Option<&CloudManifest>) -> std::result::Result<(Vec<TransferJob>, usize, usize), String> {
+        let backup_path = self.config.effective_backup_path();
+        info!("Download destination: {}", backup_path.display());
+        
+        // Ensure backup directory exists
+        std::fs::create_dir_all(&backup_path)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        
+        let mut jobs = Vec::new();
+        let mut unchanged = 0;
+        let mut kept_local = 0;
+        for entry in remote {
+            if self.is_cloud_entry_excluded(entry, manifest) {
+                debug!("Not downloading {}, its game is kept out of the cloud", entry.name);
+                kept_local += 1;
+                continue;
+            }
+            
+            let local_path = backup_path.join(entry.file_name());
+            if !state.download_needed(entry, &local_path) {
+                if !state.files.contains_key(&entry.name) {
+                    state.record(&entry.name, &local_path, Some(entry));
+                }
+                unchanged += 1;
+                continue;
+            }
+            // Without a sync record (another PC, a lost state file) the sizes of encrypted or
+            // recompressed archives never match, the hashes in the metadata still do
+            if self.has_same_archive(entry, &local_path, manifest) {
+                debug!("{} is already here, same hash as in the manifest", entry.name);
+                state.record(&entry.name, &local_path, Some(entry));
+                unchanged += 1;
+                continue;
+            }
+            
+            jobs.push(TransferJob {
+                direction: TransferDirection::Download,
+                remote_name: entry.name.clone(),
+                local_path,
+                size: entry.size.unwrap_or(0),
+                sha256: manifest.and_then(|m| m.entry_for(entry.file_name())).and_then(|e| e.expected_sha256()),
+                replace: false,
+            });
+        }
+        info!("Found {} files to download, {} unchanged, {} kept out", jobs.len(), unchanged, kept_local);
+        Ok((jobs, unchanged, kept_local))
+    }
+    
+    /// Whether `local_path` is the archive the manifest describes for a cloud file, by the
+    /// archive hash recorded in both metadata files
+    fn has_same_archive(&self, entry: &CloudEntry, local_path: &std::path::Path, manifest: Option<&CloudManifest>) -> bool {
+        let remote_hash = match manifest.and_then(|m| m.entry_for(entry.file_name())).and_then(|e| e.backup.archive_sha256.as_deref()) {
+            Some(hash) => hash,
+            None => return false,
+        };
+        local_path.exists() && self.backups.iter()
+            .filter(|b| save_guardian_core::volumes::holds(b, local_path))
+            .any(|b| b.archive_sha256.as_deref() == Some(remote_hash))
+    }
+    
+    /// Whether a cloud archive belongs to a game kept out of cloud sync
+    ///
+    /// The game comes from the manifest, or from a local backup with the same file name.
+    fn is_cloud_entry_excluded(&self, entry: &CloudEntry, manifest: Option<&CloudManifest>) -> bool {
+        let file_name = entry.file_name();
+        let key = manifest
+            .and_then(|m| m.entry_for(file_name))
+            .map(|e| BackupManager::latest_key(&e.backup))
+            .or_else(|| {
+                self.backups.iter()
+                    .find(|b| save_guardian_core::volumes::archive_files(b).iter().any(|p| p.file_name().map_or(false, |n| n == file_name)))
+                    .map(BackupManager::latest_key)
+            });
+        key.map_or(false, |key| self.config.is_cloud_excluded(&key))
+    }
+    
+    /// The cloud manifest, or None (logged) if it can't be read
+    fn fetch_cloud_manifest(&self) -> Option<CloudManifest> {
+        Self::fetch_manifest_from(&self.config)
+    }
+    
+    fn fetch_manifest_from(config: &Config) -> Option<CloudManifest> {
+        let provider = cloud::provider_from_config(config);
+        match CloudManifest::fetch(provider.as_ref()) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                warn!("Could not read the cloud manifest, guessing metadata from file names: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// Add just uploaded archives to the cloud manifest, with their full metadata
+    fn update_cloud_manifest(&self, uploads: &[(String, std::path::PathBuf, Option<String>)]) -> Option<CloudManifest> {
+        let (remote, target) = match self.cloud_transfer {
+            Some(ref transfer) => (transfer.plan.remote.clone(), transfer.mirror.as_ref().unwrap_or(&self.config)),
+            None => return None,
+        };
+        // Never replace a manifest we couldn't read, other machines' entries would be lost
+        let mut manifest = Self::fetch_manifest_from(target)?;
+        
+        let machine = self.config.machine();
+        for (remote_name, local_path, sha256) in uploads {
+            if let Some(backup) = self.backups.iter().find(|b| save_guardian_core::volumes::holds(b, local_path)) {
+                manifest.record(remote_name, backup, &machine, sha256.clone());
+            }
+        }
+        // An empty listing usually means listing failed before the upload, keep the entries then
+        if !remote.is_empty() {
+            let uploaded: Vec<String> = uploads.iter().map(|(name, ..)| name.clone()).collect();
+            manifest.prune(&remote, &uploaded);
+        }
+        
+        let provider = cloud::provider_from_config(target);
+        if let Err(e) = manifest.upload(provider.as_ref()) {
+            warn!("Failed to upload the cloud manifest: {}", e);
+        }
+        Some(manifest)
+    }
+    
+    /// Backup archives in the cloud folder and its game folders
+    fn list_remote_archives(&self, provider: &dyn CloudProvider) -> save_guardian_core::types::Result<Vec<CloudEntry>> {
+        cloud::list_archives(provider)
+    }
+    
+    /// What earlier syncs with the current destination transferred, updated with a fresh listing
+    fn load_sync_state(&self, remote: &[CloudEntry]) -> SyncState {
+        let backup_root = self.config.effective_backup_path();
+        let mut state = SyncState::load(&backup_root, &self.config.sync_endpoint());
+        state.learn_etags(remote);
+        state.prune(remote, &backup_root);
+        state
+    }
+    
+    fn save_sync_state(&self, state: &SyncState) {
+        if let Err(e) = state.save(&self.config.effective_backup_path()) {
+            warn!("{}", e);
+        }
+    }
+    
+    /// Common checks before any cloud transfer, reporting the reason in the status bar
+    fn check_cloud_ready(&mut self) -> bool {
+        if self.is_read_only() {
+            self.scan_status = ScanStatus::Error("Cloud sync is disabled in read-only mode".to_string());
+            return false;
+        }
+        
+        if !self.config.cloud_enabled() {
+            self.scan_status = ScanStatus::Error("Cloud sync is not enabled".to_string());
+            return false;
+        }
+        
+        if self.config.online.offline_mode && self.config.cloud_uses_network() {
+            self.scan_status = ScanStatus::Error(format!("{} sync is off in offline mode", self.config.cloud_name()));
+            return false;
+        }
+        
+        if self.is_transferring() {
+            self.scan_status = ScanStatus::Error("A cloud transfer is already running".to_string());
+            return false;
+        }
+        
+        true
+    }
+    
+    fn is_transferring(&self) -> bool {
+        self.cloud_transfer.as_ref().map_or(false, |t| !t.queue.status().is_finished())
+    }
+    
+    fn start_cloud_transfer(&mut self, provider: Box<dyn CloudProvider>, jobs: Vec<TransferJob>, plan: SyncPlan) {
+        info!("Starting cloud transfer of {} files with {}, {} unchanged", jobs.len(), provider.name(), plan.unchanged);
+        self.scan_status = ScanStatus::Scanning;
+        self.cloud_transfer = Some(CloudTransfer {
+            queue: TransferQueue::start(provider, jobs, self.config.bandwidth, self.config.cloud_recompression),
+            plan,
+            reported: false,
+            mirror: None,
+            pause_saved: None,
+        });
+    }
+    
+    /// Queue the other enabled destinations for mirroring, if so configured
+    ///
+    /// They run one after the other once the selected destination's transfer is done.
+    fn queue_mirrors(&mut self) {
+        if !self.config.mirror_to_all_remotes {
+            return;
+        }
+        for remote in &self.config.cloud_remotes {
+            if self.config.with_remote(remote).cloud_enabled() && !self.mirror_queue.contains(&remote.name) {
+                self.mirror_queue.push(remote.name.clone());
+            }
+        }
+    }
+    
+    /// Start the next queued mirror upload once nothing else is transferring
+    fn run_mirror_queue(&mut self) {
+        let idle = self.cloud_transfer.as_ref().map_or(true, |t| t.reported);
+        if self.mirror_queue.is_empty() || !idle || self.is_read_only() || self.paused_transfer.is_some() {
+            return;
+        }
+        let name = self.mirror_queue.remove(0);
+        let config = match self.config.cloud_remotes.iter().find(|r| r.name == name) {
+            Some(remote) => self.config.with_remote(remote),
+            None => return, // Removed or selected meanwhile
+        };
+        if config.online.offline_mode && config.cloud_uses_network() {
+            info!("Not mirroring to {}, it is off in offline mode", name);
+            return;
+        }
+        
+        let provider = cloud::provider_from_config(&config);
+        let _ = self.initialize_cloud_folder(provider.as_ref());
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(SaveGuardianError::PathNotFound(_)) => Vec::new(),
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Mirror to {}: failed to list files: {}", name, e));
+                return;
+            }
+        };
+        let backup_root = config.effective_backup_path();
+        let mut state = SyncState::load(&backup_root, &config.sync_endpoint());
+        state.learn_etags(&remote);
+        state.prune(&remote, &backup_root);
+        let manifest = Self::fetch_manifest_from(&config);
+        let (jobs, kept_local, unchanged) = self.upload_jobs(&remote, &mut state, manifest.as_ref());
+        if jobs.is_empty() {
+            self.save_sync_state(&state);
+            info!("Mirror {} is up to date, {} backups unchanged", name, unchanged);
+            return;
+        }
+        
+        info!("Mirroring {} backups to {}", jobs.len(), name);
+        self.scan_status = ScanStatus::Scanning;
+        self.cloud_transfer = Some(CloudTransfer {
+            queue: TransferQueue::start(provider, jobs, self.config.bandwidth, self.config.cloud_recompression),
+            plan: SyncPlan { operation: "Mirror", state, remote, kept_local, unchanged, conflicts: 0, restore_to: None },
+            reported: false,
+            mirror: Some(config),
+            pause_saved: None,
+        });
+    }
+    
+    /// Select another cloud destination, forgetting what was shown about the previous one
+    fn switch_remote(&mut self, name: &str) {
+        if self.is_transferring() {
+            self.scan_status = ScanStatus::Error("Wait for the cloud transfer to finish before switching remotes".to_string());
+            return;
+        }
+        if !self.config.select_remote(name) {
+            return;
+        }
+        // Unsaved settings edits stay, only the selection follows
+        self.temp_config.select_remote(name);
+        self.forget_remote_state();
+        self.scan_status = ScanStatus::Complete(format!("Switched to {}", self.config.remote_name()));
+    }
+    
+    /// Drop listings and findings that belong to the previously selected destination
+    fn forget_remote_state(&mut self) {
+        self.cloud_listing = None;
+        self.cloud_manifest = None;
+        self.cloud_conflicts.clear();
+        self.replica_delete = None;
+        self.auto_sync_next = None;
+        self.replica_report = ReplicaReport::load(&self.config.effective_backup_path(), &self.config.sync_endpoint());
+        self.replica_report.forget_deleted();
+    }
+    
+    /// Pick up a finished transfer: register downloads and report the totals
+    fn poll_cloud_transfer(&mut self, ctx: &egui::Context) {
+        let (status, kept_local, unchanged, conflicts, provider_name, operation, mirrored) = match self.cloud_transfer {
+            Some(ref transfer) if !transfer.reported => (
+                transfer.queue.status(),
+                transfer.plan.kept_local,
+                transfer.plan.unchanged,
+                transfer.plan.conflicts,
+                transfer.queue.provider_name().to_string(),
+                transfer.plan.operation,
+                transfer.mirror.as_ref().map(|config| config.remote_name()),
+            ),
+            _ => return,
+        };
+        
+        if !status.is_finished() {
+            self.save_paused_transfer(&status);
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+            return;
+        }
+        
+        // Failed and cancelled files too, that's what the history is for
+        let run = if self.auto_sync_running { "Automatic sync" } else { operation };
+        let backup_root = self.config.effective_backup_path();
+        if self.cloud_transfer.as_ref().map_or(false, |t| t.pause_saved.is_some()) {
+            cloud::PausedTransfer::clear(&backup_root);
+        }
+        let mut history = CloudHistory::load(&backup_root);
+        history.record_transfer(&status, run, mirrored.as_deref().unwrap_or(&provider_name));
+        if let Err(e) = history.save(&backup_root) {
+            warn!("{}", e);
+        }
+        if self.cloud_history.is_some() {
+            self.cloud_history = Some(history);
+        }
+        if mirrored.is_none() {
+            self.replica_report.forget_transferred(&status);
+            if let Err(e) = self.replica_report.save(&backup_root) {
+                warn!("{}", e);
+            }
+        }
+        
+        let done: Vec<&TransferItem> = status.items.iter().filter(|item| item.state == TransferState::Done).collect();
+        let downloaded: Vec<&TransferItem> = done.iter().copied().filter(|item| item.job.direction == TransferDirection::Download).collect();
+        let uploaded = done.len() - downloaded.len();
+        let total_size: u64 = done.iter().map(|item| item.bytes_done).sum();
+        // Found on the remote right before uploading, counted with the unchanged ones
+        let already_there = status.count(&TransferState::AlreadyThere);
+        let unchanged = unchanged + already_there;
+        
+        // What the uploading machines recorded, so downloads keep their real game, paths and hashes
+        let manifest = if downloaded.is_empty() { None } else { self.fetch_cloud_manifest() };
+        for item in &downloaded {
+            info!("Downloaded {} ({} bytes) to {}", item.job.remote_name, item.bytes_done, item.job.local_path.display());
+            // Create metadata for the downloaded backup so it appears in the Backups tab
+            let file_name = item.job.local_path.file_name().and_then(|n| n.to_str()).unwrap_or(&item.job.remote_name);
+            let entry = manifest.as_ref().and_then(|m| m.entry_for(cloud::remote_file_name(&item.job.remote_name)));
+            // A recompressed upload is unpacked again, the file on disk is what counts
+            let size = std::fs::metadata(&item.job.local_path).map(|m| m.len()).unwrap_or(item.bytes_done);
+            self.create_metadata_for_downloaded_backup(file_name, &item.job.local_path, size, entry);
+        }
+        if manifest.is_some() {
+            self.cloud_manifest = manifest;
+        }
+        
+        if !done.is_empty() {
+            // Update sync statistics
+            self.last_sync_time = Some(chrono::Utc::now());
+            self.cloud_files_synced = done.len();
+            self.cloud_storage_used = total_size;
+        }
+        if !downloaded.is_empty() {
+            // Refresh backups list to show the downloaded files
+            self.load_backups();
+        }
+        
+        // Remember what was transferred so the next sync skips it
+        if let Some(ref mut transfer) = self.cloud_transfer {
+            let plan = &mut transfer.plan;
+            for item in &done {
+                // Uploads learn their new version tag on the next listing
+                let remote = plan.remote.iter().find(|e| e.name == item.job.remote_name);
+                // A cloud copy kept under another name is new locally, it gets uploaded under that name
+                if item.job.local_path.file_name().and_then(|n| n.to_str()) != Some(cloud::remote_file_name(&item.job.remote_name)) {
+                    continue;
+                }
+                match item.job.direction {
+                    TransferDirection::Upload => plan.state.record_upload(&item.job.remote_name, &item.job.local_path, item.bytes_done),
+                    TransferDirection::Download => plan.state.record(&item.job.remote_name, &item.job.local_path, remote),
+                }
+            }
+            for item in status.items.iter().filter(|item| item.state == TransferState::AlreadyThere) {
+                let remote = plan.remote.iter().find(|e| e.name == item.job.remote_name);
+                plan.state.record(&item.job.remote_name, &item.job.local_path, remote);
+            }
+        }
+        if let Some(ref transfer) = self.cloud_transfer {
+            self.save_sync_state(&transfer.plan.state);
+        }
+        
+        let uploads: Vec<(String, std::path::PathBuf, Option<String>)> = done.iter()
+            .filter(|item| item.job.direction == TransferDirection::Upload)
+            .map(|item| (item.job.remote_name.clone(), item.job.local_path.clone(), item.sha256.clone()))
+            .collect();
+        if !uploads.is_empty() {
+            if let Some(manifest) = self.update_cloud_manifest(&uploads) {
+                // A mirror's manifest isn't the one the Cloud tab shows
+                if mirrored.is_none() {
+                    self.cloud_manifest = Some(manifest);
+                }
+            }
+        }
+        
+        let provider_name = match mirrored {
+            Some(ref remote) => format!("{} (mirror)", remote),
+            None => provider_name,
+        };
+        let mut summary = match (uploaded, downloaded.len()) {
+            (0, 0) => "No files were transferred".to_string(),
+            (up, 0) => format!("✓ Uploaded {} backups ({}) to {}", up, format_size(total_size), provider_name),
+            (0, down) => format!("✓ Downloaded {} backup files ({}) from {}", down, format_size(total_size), provider_name),
+            (up, down) => format!("✓ Downloaded {} and uploaded {} backups ({}) with {}", down, up, format_size(total_size), provider_name),
+        };
+        if unchanged > 0 {
+            summary.push_str(&format!(", {} skipped as already on both sides", unchanged));
+        }
+        if kept_local > 0 {
+            summary.push_str(&format!(", {} kept local", kept_local));
+        }
+        if conflicts > 0 {
+            summary.push_str(&format!(", {} conflicts resolved", conflicts));
+        }
+        if !self.cloud_conflicts.is_empty() {
+            summary.push_str(&format!(", {} conflicts to resolve", self.cloud_conflicts.len()));
+        }
+        let retried = status.retried();
+        if retried > 0 {
+            summary.push_str(&format!(", {} retried", retried));
+        }
+        let failed = status.failed();
+        if failed > 0 {
+            summary.push_str(&format!(", {} failed", failed));
+        }
+        let cancelled = status.count(&TransferState::Cancelled);
+        if cancelled > 0 {
+            summary.push_str(&format!(", {} cancelled", cancelled));
+        }
+        
+        let mut log = OperationLog::new(format!("{} with {}", run, provider_name));
+        for item in &status.items {
+            let verb = match item.job.direction {
+                TransferDirection::Upload => "Uploaded",
+                TransferDirection::Download => "Downloaded",
+            };
+            match item.state {
+                TransferState::Done if item.retries > 0 => log.copied(&item.job.remote_name, Some(format!("{} {} after {} retries", verb, format_size(item.bytes_done), item.retries))),
+                TransferState::Done => log.copied(&item.job.remote_name, Some(format!("{} {}", verb, format_size(item.bytes_done)))),
+                TransferState::AlreadyThere => log.skipped(&item.job.remote_name, "Already in the cloud with the same size"),
+                TransferState::Failed(ref error) => log.failed(&item.job.remote_name, error.clone()),
+                TransferState::Cancelled => log.skipped(&item.job.remote_name, "Cancelled"),
+                TransferState::Queued | TransferState::Running => log.skipped(&item.job.remote_name, "Not started"),
+            }
+        }
+        log.finish(summary.clone());
+        self.record_operation(log);
+        
+        self.scan_status = if (done.is_empty() && already_there == 0) || failed > 0 {
+            ScanStatus::Error(summary)
+        } else {
+            ScanStatus::Complete(summary)
+        };
+        
+        // A one-click cloud restore extracts its download right away
+        let restore_to = self.cloud_transfer.as_ref().and_then(|t| t.plan.restore_to.clone());
+        if let (Some(target), Some(item)) = (restore_to, downloaded.first()) {
+            self.scan_status = match self.restore_downloaded_backup(&item.job.local_path, &target) {
+                Ok(message) => ScanStatus::Complete(message),
+                Err(message) => {
+                    error!("{}", message);
+                    ScanStatus::Error(message)
+                }
+            };
+        }
+        if let Some(ref mut transfer) = self.cloud_transfer {
+            transfer.reported = true;
+        }
+        self.finish_auto_sync();
+    }
+    
+    /// Keep the files a paused transfer has left on disk, so it can be resumed after a restart
+    fn save_paused_transfer(&mut self, status: &TransferStatus) {
+        let backup_root = self.config.effective_backup_path();
+        let transfer = match self.cloud_transfer {
+            Some(ref mut transfer) if transfer.queue.is_paused() => transfer,
+            _ => return,
+        };
+        let jobs = status.remaining_jobs();
+        if transfer.pause_saved == Some(jobs.len()) {
+            return;
+        }
+        
+        let config = transfer.mirror.as_ref().unwrap_or(&self.config);
+        let paused = cloud::PausedTransfer {
+            endpoint: config.sync_endpoint(),
+            mirror: transfer.mirror.as_ref().map(|config| config.remote_name()),
+            operation: transfer.plan.operation.to_string(),
+            paused_at: chrono::Utc::now(),
+            restore_to: transfer.plan.restore_to.clone(),
+            jobs,
+        };
+        match paused.save(&backup_root) {
+            Ok(()) => {
+                transfer.pause_saved = Some(paused.jobs.len());
+                // There's one paused transfer file; this one replaces any older one
+                if let Some(older) = self.paused_transfer.take() {
+                    warn!("Dropping the paused {} from {}, another transfer was paused", older.operation, older.paused_at);
+                }
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+    
+    /// Continue a transfer paused in an earlier session with the files it had left
+    fn resume_paused_transfer(&mut self) {
+        let paused = match self.paused_transfer.take() {
+            Some(paused) => paused,
+            None => return,
+        };
+        if !self.check_cloud_ready() {
+            self.paused_transfer = Some(paused);
+            return;
+        }
+        
+        let mirror = match paused.mirror {
+            Some(ref name) => match self.config.cloud_remotes.iter().find(|r| &r.name == name) {
+                Some(remote) => Some(self.config.with_remote(remote)),
+                None => {
+                    self.scan_status = ScanStatus::Error(format!("The remote \"{}\" no longer exists, discard the paused transfer", name));
+                    self.paused_transfer = Some(paused);
+                    return;
+                }
+            },
+            None => None,
+        };
+        let config = mirror.clone().unwrap_or_else(|| self.config.clone());
+        if config.sync_endpoint() != paused.endpoint {
+            self.scan_status = ScanStatus::Error(format!("The paused transfer is for {}; select that remote to resume it", paused.endpoint));
+            self.paused_transfer = Some(paused);
+            return;
+        }
+        
+        let provider = cloud::provider_from_config(&config);
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(SaveGuardianError::PathNotFound(_)) => Vec::new(),
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Failed to list cloud files: {}", e));
+                self.paused_transfer = Some(paused);
+                return;
+            }
+        };
+        let state = SyncState::load(&config.effective_backup_path(), &paused.endpoint);
+        
+        info!("Resuming the paused {} with {} files", paused.operation, paused.jobs.len());
+        self.scan_status = ScanStatus::Scanning;
+        self.cloud_transfer = Some(CloudTransfer {
+            queue: TransferQueue::start(provider, paused.jobs.clone(), self.config.bandwidth, self.config.cloud_recompression),
+            plan: SyncPlan { operation: "Resumed transfer", state, remote, kept_local: 0, unchanged: 0, conflicts: 0, restore_to: paused.restore_to.clone() },
+            reported: false,
+            mirror,
+            // The file stays until the transfer ends, in case the app closes before that
+            pause_saved: Some(paused.jobs.len()),
+        });
+    }
+    
+    fn discard_paused_transfer(&mut self) {
+        if let Some(paused) = self.paused_transfer.take() {
+            cloud::PausedTransfer::clear(&self.config.effective_backup_path());
+            self.scan_status = ScanStatus::Complete(format!("Discarded the paused {} ({} files)", paused.operation, paused.jobs.len()));
+        }
+    }
+    
+    fn draw_cloud_transfer(&mut self, ctx: &egui::Context) {
+        let (status, cancelling, paused) = match self.cloud_transfer {
+            Some(ref transfer) => (transfer.queue.status(), transfer.queue.is_cancelled(), transfer.queue.is_paused()),
+            None => return,
+        };
+        let mut cancel = false;
+        let mut close = false;
+        let mut toggle_pause = false;
+        
+        egui::Window::new("⇅ Cloud Transfers")
+            .collapsible(true)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let total = status.total_bytes().max(1);
+                let finished_files = status.items.len() - status.count(&TransferState::Queued) - status.count(&TransferState::Running);
+                ui.label(format!("{} of {} files, {} of {}",
+                    finished_files, status.items.len(), format_size(status.done_bytes()), format_size(status.total_bytes())));
+                ui.add(egui::ProgressBar::new(status.done_bytes() as f32 / total as f32).show_percentage());
+                
+                if let Some(item) = status.current() {
+                    let arrow = match item.job.direction {
+                        TransferDirection::Upload => "↑",
+                        TransferDirection::Download => "↓",
+                    };
+                    ui.add_space(5.0);
+                    ui.label(format!("{} {}", arrow, item.job.remote_name));
+                    let fraction = if item.job.size > 0 { item.bytes_done as f32 / item.job.size as f32 } else { 0.0 };
+                    ui.add(egui::ProgressBar::new(fraction.min(1.0))
+                        .text(format!("{} / {}", format_size(item.bytes_done), format_size(item.job.size))));
+                }
+                
+                ui.horizontal(|ui| {
+                    ui.label(format!("Speed: {}/s", format_size(status.speed() as u64)));
+                    if paused && !status.is_finished() {
+                        let text = if status.current().is_some() { "⏸ Pausing after this file" } else { "⏸ Paused" };
+                        ui.colored_label(egui::Color32::from_rgb(255, 133, 27), text);
+                    } else if let Some(eta) = status.eta() {
+                        let secs = eta.as_secs();
+                        ui.label(format!("ETA: {}:{:02}", secs / 60, secs % 60));
+                    }
+                });
+                
+                egui::CollapsingHeader::new("Files")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                            for item in &status.items {
+                                let state = match item.state {
+                                    TransferState::Queued => "⏳ Queued".to_string(),
+                                    TransferState::Running => "⇅ Running".to_string(),
+                                    TransferState::Done => "✓ Done".to_string(),
+                                    TransferState::AlreadyThere => "= Already there".to_string(),
+                                    TransferState::Cancelled => "✖ Cancelled".to_string(),
+                                    TransferState::Failed(ref error) => format!("⚠ {}", error),
+                                };
+                                if item.retries > 0 {
+                                    ui.label(format!("{}  {} (retried {}×)", item.job.remote_name, state, item.retries));
+                                } else {
+                                    ui.label(format!("{}  {}", item.job.remote_name, state));
+                                }
+                            }
+                        });
+                    });
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if status.is_finished() {
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    } else {
+                        if paused {
+                            if ui.add_enabled(!cancelling, egui::Button::new("▶ Resume")).clicked() {
+                                toggle_pause = true;
+                            }
+                        } else if ui.add_enabled(!cancelling, egui::Button::new("⏸ Pause"))
+                            .on_hover_text("Stop after the file in flight; the rest can be resumed, even after restarting the app")
+                            .clicked() {
+                            toggle_pause = true;
+                        }
+                        if ui.add_enabled(!cancelling, egui::Button::new("✖ Cancel")).on_hover_text("Abort the file in flight and skip the rest").clicked() {
+                            cancel = true;
+                        }
+                    }
+                    if cancelling && !status.is_finished() {
+                        ui.label("Cancelling…");
+                    }
+                });
+            });
+        
+        if cancel {
+            if let Some(ref transfer) = self.cloud_transfer {
+                transfer.queue.cancel();
+            }
+        }
+        if toggle_pause {
+            if let Some(ref transfer) = self.cloud_transfer {
+                if paused {
+                    info!("Resuming cloud transfer");
+                    transfer.queue.resume();
+                } else {
+                    info!("Pausing cloud transfer");
+                    transfer.queue.pause();
+                }
+            }
+        }
+        if close {
+            self.cloud_transfer = None;
+        }
+    }
+    
+    /// Archive hash and signature for a downloaded copy of `original`
+    ///
+    /// They carry over when the bytes are the same. A recompressed upload comes back as an
+    /// archive with the same files but other bytes: it gets its own hash, and no signature.
+    fn downloaded_archive_hash(backup_path: &std::path::Path, same_archive: bool, original: &BackupInfo) -> (Option<String>, Option<String>) {
+        if !same_archive {
+            return (None, None);
+        }
+        let local = save_guardian_core::integrity::sha256_file(backup_path).ok();
+        if local.is_some() && local == original.archive_sha256 {
+            (original.archive_sha256.clone(), original.signature.clone())
+        } else {
+            (local, None)
+        }
+    }
+    
+    fn create_metadata_for_downloaded_backup(&self, filename: &str, backup_path: &std::path::PathBuf, size: u64, manifest_entry: Option<&ManifestEntry>) {
+        use save_guardian_core::types::*;
+        use std::path::PathBuf;
+        
+        // Extract information from filename
+        // Format: GameName_AppID_SaveType_Timestamp.zip (or .tar.zst, .7z)
+        let backup_id = ArchiveFormat::strip_extension(filename);
+        
+        // The cloud manifest has the metadata as the uploading machine saved it, nothing to guess
+        if let Some(entry) = manifest_entry {
+            info!("Using cloud manifest metadata for {} from {}", filename, entry.machine);
+            // Each part of a split archive points at the same backup; the parts land side by side
+            if !entry.backup.volumes.is_empty() {
+                let folder = backup_path.parent().map(PathBuf::from).unwrap_or_default();
+                let local = |path: &PathBuf| folder.join(path.file_name().unwrap_or_default());
+                let backup_info = BackupInfo {
+                    backup_path: local(&entry.backup.backup_path),
+                    volumes: entry.backup.volumes.iter().map(local).collect(),
+                    deduplicated: false,
+                    verified_unchanged_at: None,
+                    duration_ms: None, // Another machine's speed says nothing about this one
+                    ..entry.backup.clone()
+                };
+                self.save_backup_metadata_directly(&backup_info);
+                return;
+            }
+            let same_archive = save_guardian_core::normalize::same_text(cloud::remote_file_name(&entry.remote_name), filename);
+            let (archive_sha256, signature) = Self::downloaded_archive_hash(backup_path, same_archive, &entry.backup);
+            let backup_info = BackupInfo {
+                id: backup_id.to_string(),
+                backup_path: backup_path.clone(),
+                size,
+                archive_sha256,
+                signature,
+                deduplicated: false,
+                verified_unchanged_at: None,
+                duration_ms: None,
+                ..entry.backup.clone()
+            };
+            self.save_backup_metadata_directly(&backup_info);
+            return;
+        }
+        
+        // Without the manifest there's no telling which backup a part belongs to or where it ends
+        if save_guardian_core::volumes::is_volume_name(filename) {
+            warn!("Downloaded {} without manifest metadata, it can't be registered as a backup", filename);
+            return;
+        }
+        
+        // First, try to find if we have a local copy of this backup's metadata already
+        // This happens when we previously uploaded this backup and still have the local copy
+        if let Some(ref backup_manager) = self.backup_manager {
+            // Look for a backup of the same game: archive names start with the game's key,
+            // whatever time format follows. The longest key wins, "Game_2" over "Game".
+            match backup_manager.list_backups(None, None) {
+                Ok(existing_backups) => {
+                    let matching = existing_backups.into_iter()
+                        .map(|backup| (BackupManager::latest_key(&backup), backup))
+                        .filter(|(key, _)| backup_id.starts_with(&format!("{}_", key)))
+                        .max_by_key(|(key, _)| key.len());
+                    if let Some((key, existing_backup)) = matching {
+                        info!("Found matching local backup metadata for {}", key);
+                        
+                        // Only the exact same archive keeps its hash and signature
+                        let same_archive = existing_backup.backup_path.file_name()
+                            .and_then(|n| n.to_str()) == Some(filename);
+                        let (archive_sha256, signature) = Self::downloaded_archive_hash(backup_path, same_archive, &existing_backup);
+                        
+                        // Use the original backup's information but mark it as downloaded
+                        let backup_info = BackupInfo {
+                            id: backup_id.to_string(),
+                            game_name: existing_backup.game_name.clone(),
+                            app_id: existing_backup.app_id,
+                            save_type: existing_backup.save_type.clone(),
+                            original_path: existing_backup.original_path.clone(), // Use the REAL original path!
+                            backup_path: backup_path.clone(),
+                            created_at: chrono::Utc::now(),
+                            size,
+                            description: Some(format!("📥 Downloaded from cloud - Original: {}", existing_backup.original_path.display())),
+                            archive_sha256: archive_sha256.clone(),
+                            signature: signature.clone(),
+                            steam_user_id: existing_backup.steam_user_id.clone(),
+                            sandbox: existing_backup.sandbox.clone(),
+                            source_size: existing_backup.source_size,
+                            excluded_files: existing_backup.excluded_files,
+                            excluded_bytes: existing_backup.excluded_bytes,
+                            pinned: false,
+                            tags: existing_backup.tags.clone(),
+                            content_sha256: existing_backup.content_sha256.clone(),
+                            deduplicated: false,
+                            source_fingerprint: None,
+                            verified_unchanged_at: None,
+                            machine: existing_backup.machine.clone(),
+                            settings_files: existing_backup.settings_files.clone(),
+                            volumes: Vec::new(),
+                            duration_ms: None,
+                            base_backup: None,
+                            removed_files: Vec::new(),
+                            stored_files: Vec::new(),
+                            save_metadata: existing_backup.save_metadata.clone(),
+                            file_checksums: existing_backup.file_checksums.clone(),
+                            encrypted: encryption::is_encrypted_file(backup_path),
+                        };
+                        
+                        self.save_backup_metadata_directly(&backup_info);
+                        return;
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+        
+        // If we didn't find existing metadata, fall back to parsing the filename
+        info!("No existing metadata found, parsing filename: {}", filename);
+        
+        // Parse filename to extract game info
+        // Format: GameName_AppID_SaveType_Timestamp, the timestamp in whatever format was configured
+        let parts: Vec<&str> = backup_id.split('_').collect();
+        let save_type_index = parts.iter().rposition(|part| *part == "steam" || *part == "nonsteam");
+        let (game_name, app_id, save_type, original_path) = if let Some(save_type_index) = save_type_index.filter(|&i| i > 0) {
+            let save_type = if parts[save_type_index] == "steam" { SaveType::Steam } else { SaveType::NonSteam };
+            
+            // Try to extract app_id if it's a number
+            let mut app_id = None;
+            let mut name_parts = Vec::new();
+            
+            for part in &parts[..save_type_index] {
+                // Check if this part looks like an app ID (numeric)
+                if let Ok(id) = part.parse::<u32>() {
+                    app_id = Some(id);
+                } else {
+                    name_parts.push(*part);
+                }
+            }
+            
+            let game_name = if name_parts.is_empty() {
+                "Downloaded Game".to_string()
+            } else {
+                name_parts.join(" ").replace('_', " ")
+            };
+            
+            // Try to find the actual save path from current scanned saves
+            let actual_original_path = self.find_actual_save_path(&game_name, app_id, &save_type)
+                .unwrap_or_else(|| self.reconstruct_likely_original_path(&game_name, app_id, &save_type));
+            
+            (game_name, app_id, save_type, actual_original_path)
+        } else {
+            let fallback_path = PathBuf::from("📥 Downloaded from Cloud Storage");
+            ("Downloaded Game".to_string(), None, SaveType::NonSteam, fallback_path)
+        };
+        
+        // Create backup info
+        let backup_info = BackupInfo {
+            id: backup_id.to_string(),
+            game_name: game_name.clone(),
+            app_id,
+            save_type: save_type.clone(),
+            original_path,
+            backup_path: backup_path.clone(),
+            created_at: chrono::Utc::now(),
+            size,
+            description: Some(format!("📥 Downloaded from cloud storage - {}", game_name)),
+            archive_sha256: None,
+            signature: None,
+            steam_user_id: None,
+            sandbox: None,
+            source_size: 0,
+            excluded_files: 0,
+            excluded_bytes: 0,
+            pinned: false,
+            tags: Vec::new(),
+            content_sha256: None,
+            deduplicated: false,
+            source_fingerprint: None,
+            verified_unchanged_at: None,
+            machine: None,
+            settings_files: Vec::new(),
+            volumes: Vec::new(),
+            duration_ms: None,
+            base_backup: None,
+            removed_files: Vec::new(),
+            stored_files: Vec::new(),
+            save_metadata: Vec::new(),
+            file_checksums: Vec::new(),
+            encrypted: encryption::is_encrypted_file(backup_path),
+        };
+        
+        self.save_backup_metadata_directly(&backup_info);
+    }
+    
+    /// Find actual save path from currently scanned saves
+    fn find_actual_save_path(&self, game_name: &str, app_id: Option<u32>, save_type: &SaveType) -> Option<std::path::PathBuf> {
+        match save_type {
+            SaveType::Steam => {
+                // Look through Steam saves for matching game
+                for save in &self.steam_saves {
+                    if let Some(id) = app_id {
+                        if save.app_id == Some(id) {
+                            info!("Found actual Steam save path for app ID {}: {}", id, save.save_path.display());
+                            return Some(save.save_path.clone());
+                        }
+                    }
+                    
+                    // Also try name matching as fallback
+                    if save.name.to_lowercase().contains(&game_name.to_lowercase()) {
+                        info!("Found Steam save path by name match '{}': {}", game_name, save.save_path.display());
+                        return Some(save.save_path.clone());
+                    }
+                }
+            },
+            SaveType::NonSteam => {
+                // Look through non-Steam saves for matching game
+                for save in &self.non_steam_saves {
+                    if save.name.to_lowercase().contains(&game_name.to_lowercase()) ||
+                       game_name.to_lowercase().contains(&save.name.to_lowercase()) {
+                        info!("Found actual non-Steam save path for '{}': {}", game_name, save.save_path.display());
+                        return Some(save.save_path.clone());
+                    }
+                }
+            }
+        }
+        
+        None
+    }
+    
+    /// Save backup metadata directly to file
+    fn save_backup_metadata_directly(&self, backup_info: &BackupInfo) {
+        let metadata_path = self.config.effective_backup_path().join(format!("{}.backup.json", backup_info.id));
+        
+        if let Ok(metadata_json) = serde_json::to_string_pretty(backup_info) {
+            if let Err(e) = std::fs::write(&metadata_path, metadata_json) {
+                warn!("Failed to create metadata for downloaded backup {}: {}", backup_info.id, e);
+            } else {
+                info!("Created metadata for downloaded backup: {} -> {}", metadata_path.display(), backup_info.original_path.display());
+            }
+        }
+    }
+    
+    /// Reconstruct likely original path for a downloaded backup
+    fn reconstruct_likely_original_path(&self, game_name: &str, app_id: Option<u32>, save_type: &SaveType) -> std::path::PathBuf {
+        use std::path::PathBuf;
+        
+        match save_type {
+            SaveType::Steam => {
+                // For Steam games, reconstruct the likely Steam userdata path
+                if let Some(id) = app_id {
+                    // Steam saves are typically in: Steam/userdata/{user_id}/{app_id}/remote/
+                    // We'll use a generic user_id since we don't know which user
+                    let steam_path = PathBuf::from(&self.config.steam_path)
+                        .join("[Steam User]")
+                        .join(id.to_string())
+                        .join("remote");
+                    return steam_path;
+                } else {
+                    // Fallback for Steam games without app ID
+                    return PathBuf::from(format!("Steam Save Location - {}", game_name));
+                }
+            },
+            SaveType::NonSteam => {
+                // For non-Steam games, try common locations
+                let clean_name = game_name.replace(' ', "").replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "");
+                
+                // Try Documents first (most common)
+                if let Some(docs_dir) = dirs::document_dir() {
+                    return docs_dir.join("My Games").join(&clean_name);
+                }
+                
+                // Fallback to AppData
+                if let Some(appdata_dir) = dirs::data_local_dir() {
+                    return appdata_dir.join(&clean_name);
+                }
+                
+                // Final fallback
+                return PathBuf::from(format!("Documents/My Games/{}", clean_name));
+            }
+        }
+    }
+    
+    fn full_sync_koofr(&mut self) {
+        info!("Starting full cloud sync");
+        
+        if !self.check_cloud_ready() {
+            return;
+        }
+        // Full Sync downloads from the selected destination only, the others just get the uploads
+        self.queue_mirrors();
+        
+        // Initialize cloud folder first
+        let provider = cloud::provider_from_config(&self.config);
+        if let Err(e) = self.initialize_cloud_folder(provider.as_ref()) {
+            // Continue anyway - might already exist
+            warn!("Failed to initialize cloud folder: {}", e);
+        }
+        
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(e) => {
+                warn!("Could not list cloud files, only uploading: {}", e);
+                Vec::new()
+            }
+        };
+        let mut state = self.load_sync_state(&remote);
+        let manifest = self.fetch_cloud_manifest();
+        
+        // Archives changed on both sides go by the conflict policy instead of being overwritten
+        let mut conflicts = self.find_cloud_conflicts(&remote, &state, manifest.as_ref());
+        let is_conflict = |name: &str| conflicts.iter().any(|c| c.remote.name == name);
+        let clear_remote: Vec<CloudEntry> = remote.iter().filter(|e| !is_conflict(&e.name)).cloned().collect();
+        
+        // Bring down what's new in the cloud first, then upload new local backups
+        let (mut jobs, downloads_unchanged, kept_in_cloud) = match self.download_jobs(&clear_remote, &mut state, manifest.as_ref()) {
+            Ok(jobs) => jobs,
+            Err(message) => {
+                warn!("{}", message);
+                (Vec::new(), 0, 0)
+            }
+        };
+        self.load_backups();
+        let (upload_jobs, kept_local, uploads_unchanged) = self.upload_jobs(&remote, &mut state, manifest.as_ref());
+        let kept_local = kept_local + kept_in_cloud;
+        // A file coming down now must not go straight back up
+        let upload_jobs: Vec<TransferJob> = upload_jobs.into_iter()
+            .filter(|upload| !jobs.iter().any(|download| download.remote_name == upload.remote_name))
+            .filter(|upload| !is_conflict(&upload.remote_name))
+            .collect();
+        jobs.extend(upload_jobs);
+        
+        let policy = self.config.cloud_conflict_policy;
+        let mut resolved = 0;
+        if policy != ConflictPolicy::Prompt {
+            for conflict in conflicts.drain(..) {
+                info!("Conflict on {}, resolving by {:?}", conflict.remote.name, policy);
+                jobs.extend(Self::conflict_jobs(&conflict, conflict.resolution_for(policy)));
+                resolved += 1;
+            }
+        } else if !conflicts.is_empty() {
+            info!("{} cloud conflicts left for the user to resolve", conflicts.len());
+            self.cloud_conflicts = conflicts;
+            self.show_cloud_conflicts = true;
+        }
+        
+        // Files present on both sides are counted once
+        let unchanged = downloads_unchanged.max(uploads_unchanged);
+        if jobs.is_empty() {
+            self.save_sync_state(&state);
+            self.scan_status = if self.cloud_conflicts.is_empty() {
+                ScanStatus::Complete(format!("Cloud and local backups are already in sync ({} skipped, 0 downloaded)", unchanged))
+            } else {
+                ScanStatus::Error(format!("{} backups changed both locally and in the cloud, choose which to keep", self.cloud_conflicts.len()))
+            };
+            return;
+        }
+        
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Full Sync", state, remote, kept_local, unchanged, conflicts: resolved, restore_to: None });
+    }
+    
+    /// Archives with the same name in the cloud and locally whose contents both changed
+    fn find_cloud_conflicts(&self, remote: &[CloudEntry], state: &SyncState, manifest: Option<&CloudManifest>) -> Vec<CloudConflict> {
+        let backup_path = self.config.effective_backup_path();
+        remote.iter()
+            .filter_map(|entry| {
+                let local_path = backup_path.join(entry.file_name());
+                if !state.is_conflict(&entry.name, &local_path, entry) || self.has_same_archive(entry, &local_path, manifest) {
+                    return None;
+                }
+                // Games kept out of the cloud are never uploaded, so there is nothing to decide
+                let backup = self.backups.iter().find(|b| save_guardian_core::volumes::holds(b, &local_path));
+                if backup.map_or(false, |b| self.config.is_cloud_excluded(&BackupManager::latest_key(b))) {
+                    return None;
+                }
+                let metadata = std::fs::metadata(&local_path).ok()?;
+                Some(CloudConflict {
+                    remote: entry.clone(),
+                    local_path,
+                    local_size: metadata.len(),
+                    local_modified: metadata.modified().ok().map(chrono::DateTime::<chrono::Utc>::from),
+                    resolution: ConflictResolution::KeepBoth,
+                })
+            })
+            .collect()
+    }
+    
+    fn conflict_jobs(conflict: &CloudConflict, resolution: ConflictResolution) -> Vec<TransferJob> {
+        let upload = TransferJob {
+            direction: TransferDirection::Upload,
+            remote_name: conflict.remote.name.clone(),
+            local_path: conflict.local_path.clone(),
+            size: conflict.local_size,
+            sha256: None,
+            replace: true,
+        };
+        // Both sides changed, so the manifest may describe either; the cloud copy isn't checked
+        let download_to = |local_path| TransferJob {
+            direction: TransferDirection::Download,
+            remote_name: conflict.remote.name.clone(),
+            local_path,
+            size: conflict.remote.size.unwrap_or(0),
+            sha256: None,
+            replace: false,
+        };
+        
+        match resolution {
+            ConflictResolution::KeepLocal => vec![upload],
+            ConflictResolution::KeepCloud => vec![download_to(conflict.local_path.clone())],
+            // The cloud copy comes down first, before the upload replaces it
+            ConflictResolution::KeepBoth => vec![download_to(conflict_copy_path(&conflict.local_path)), upload],
+        }
+    }
+    
+    /// Transfer the conflicts as chosen in the dialog
+    fn resolve_cloud_conflicts(&mut self) {
+        if !self.check_cloud_ready() {
+            return;
+        }
+        
+        let provider = cloud::provider_from_config(&self.config);
+        let remote = match self.list_remote_archives(provider.as_ref()) {
+            Ok(remote) => remote,
+            Err(e) => {
+                self.scan_status = ScanStatus::Error(format!("Failed to list cloud files: {}", e));
+                return;
+            }
+        };
+        
+        let conflicts = std::mem::take(&mut self.cloud_conflicts);
+        self.show_cloud_conflicts = false;
+        let jobs: Vec<TransferJob> = conflicts.iter()
+            .flat_map(|conflict| Self::conflict_jobs(conflict, conflict.resolution))
+            .collect();
+        let state = self.load_sync_state(&remote);
+        self.start_cloud_transfer(provider, jobs, SyncPlan { operation: "Conflict resolution", state, remote, kept_local: 0, unchanged: 0, conflicts: conflicts.len(), restore_to: None });
+    }
+    
+    fn draw_cloud_conflicts(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut apply = false;
+        let mut later = false;
+        let transferring = self.is_transferring();
+        
+        egui::Window::new("⚠ Cloud Conflicts")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label("These backups changed both here and in the cloud since the last sync.");
+                ui.label(egui::RichText::new("\"Keep both\" downloads the cloud copy as \"<name> (cloud).zip\" and uploads the local one")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                let describe = |size: Option<u64>, modified: Option<chrono::DateTime<chrono::Utc>>| {
+                    let size = size.map(format_size).unwrap_or_else(|| "?".to_string());
+                    match modified {
+                        Some(time) => format!("{}, {}", size, time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")),
+                        None => size,
+                    }
+                };
+                
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("cloud_conflicts_grid")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Backup");
+                            ui.strong("Local");
+                            ui.strong("Cloud");
+                            ui.strong("Keep");
+                            ui.end_row();
+                            
+                            for conflict in &mut self.cloud_conflicts {
+                                ui.label(&conflict.remote.name);
+                                ui.label(describe(Some(conflict.local_size), conflict.local_modified));
+                                ui.label(describe(conflict.remote.size, conflict.remote.modified));
+                                ui.horizontal(|ui| {
+                                    ui.selectable_value(&mut conflict.resolution, ConflictResolution::KeepLocal, "Local");
+                                    ui.selectable_value(&mut conflict.resolution, ConflictResolution::KeepCloud, "Cloud");
+                                    ui.selectable_value(&mut conflict.resolution, ConflictResolution::KeepBoth, "Both");
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let button = ui.add_enabled(!transferring, egui::Button::new(format!("✓ Apply ({})", self.cloud_conflicts.len())));
+                    if button.on_disabled_hover_text("Waiting for the current transfer to finish").clicked() {
+                        apply = true;
+                    }
+                    if ui.button("Later").on_hover_text("Decide on the next Full Sync").clicked() {
+                        later = true;
+                    }
+                });
+            });
+        
+        if apply {
+            self.resolve_cloud_conflicts();
+        } else if !open || later {
+            self.show_cloud_conflicts = false;
+        }
+    }
+    
+    /// The cloud history, newest first, to look up what a past sync actually moved
+    fn draw_connection_report(&mut self, ctx: &egui::Context) {
+        let report = match self.connection_report {
+            Some(ref report) => report,
+            None => return,
+        };
+        let mut open = true;
+        let mut retest = false;
+        
+        egui::Window::new("🩺 Connection Diagnostics")
+            .open(&mut open)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&report.destination).size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                for step in &report.steps {
+                    ui.horizontal(|ui| {
+                        match step.outcome {
+                            cloud::StepOutcome::Passed => ui.colored_label(egui::Color32::from_rgb(46, 204, 64), "✓"),
+                            cloud::StepOutcome::Failed => ui.colored_label(egui::Color32::from_rgb(220, 50, 50), "✗"),
+                            cloud::StepOutcome::Skipped => ui.colored_label(egui::Color32::GRAY, "–"),
+                        };
+                        ui.strong(step.name);
+                        if step.outcome == cloud::StepOutcome::Skipped {
+                            ui.label(egui::RichText::new("skipped").color(egui::Color32::GRAY));
+                        } else {
+                            ui.label(&step.detail);
+                            ui.label(egui::RichText::new(format!("{} ms", step.duration_ms)).size(11.0).color(egui::Color32::GRAY));
+                        }
+                    });
+                    if let Some(ref hint) = step.hint {
+                        ui.indent(step.name, |ui| {
+                            ui.label(egui::RichText::new(format!("💡 {}", hint)).color(egui::Color32::from_rgb(255, 133, 27)));
+                        });
+                    }
+                }
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if report.passed() {
+                        ui.colored_label(egui::Color32::from_rgb(46, 204, 64), "Everything works, backups can be synced");
+                    } else {
+                        ui.colored_label(egui::Color32::from_rgb(220, 50, 50), "Fix the first failed step and test again");
+                    }
+                    if ui.button("🔄 Test Again").clicked() {
+                        retest = true;
+                    }
+                });
+            });
+        
+        if !open {
+            self.connection_report = None;
+        } else if retest {
+            self.test_cloud_connection();
+        }
+    }
+    
+    /// Look up the Steam apps of non-Steam saves on a worker thread; the app list may have to be downloaded first
+    fn start_app_id_lookup(&mut self, saves: Vec<GameSave>) {
+        if self.archive_task.is_some() || saves.is_empty() {
+            return;
+        }
+        let online = self.config.online.clone();
+        let known = self.name_resolver.known_names();
+        // Programs seen writing the saves of detected games
+        let exe_names: std::collections::HashMap<std::path::PathBuf, String> = self.config.custom_games.iter()
+            .filter_map(|game| Some((game.save_path.clone(), game.exe_name.clone()?)))
+            .collect();
+        info!("Looking up Steam app IDs of {} non-Steam saves", saves.len());
+        self.archive_task = Some(ArchiveTask::spawn("Looking up Steam app IDs", move |progress| {
+            let found = AppList::load(&online, known, &progress).map(|apps| {
+                saves.iter()
+                    .filter_map(|save| apps.suggest(save, exe_names.get(&save.save_path).map(String::as_str)))
+                    .collect()
+            });
+            ArchiveResult::AppIds(found)
+        }));
+    }
+    
+    fn report_app_id_lookup(&mut self, found: save_guardian_core::types::Result<Vec<AppIdSuggestion>>) {
+        match found {
+            Ok(found) if found.is_empty() => {
+                self.scan_status = ScanStatus::Complete("No Steam game matches these saves".to_string());
+            }
+            Ok(found) => {
+                self.scan_status = ScanStatus::Complete(format!("Found the Steam app of {} saves", found.len()));
+                self.app_id_suggestions = Some(found);
+            }
+            Err(SaveGuardianError::Cancelled) => {}
+            Err(e) => self.scan_status = ScanStatus::Error(format!("Steam app ID lookup failed: {}", e)),
+        }
+    }
+    
+    fn draw_app_id_suggestions(&mut self, ctx: &egui::Context) {
+        let suggestions = match self.app_id_suggestions {
+            Some(ref suggestions) => suggestions.clone(),
+            None => return,
+        };
+        let mut open = true;
+        let mut to_use: Vec<AppIdSuggestion> = Vec::new();
+        let mut to_skip: Option<usize> = None;
+        let mut close = false;
+        
+        egui::Window::new("🔎 Steam App IDs")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("A confirmed app ID gives the save the game's Steam name and pairs it with the Steam save. Backups made under the old name can be rebound from the Backups tab")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("app_id_suggestions_grid")
+                        .num_columns(4)
+                        .spacing([10.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (i, suggestion) in suggestions.iter().enumerate() {
+                                ui.label(&suggestion.save_name).on_hover_text(suggestion.save_path.display().to_string());
+                                ui.label(format!("{} ({})", suggestion.app_name.as_deref().unwrap_or("Unknown Game"), suggestion.app_id));
+                                ui.label(egui::RichText::new(suggestion.source.describe()).size(11.0).color(egui::Color32::GRAY));
+                                ui.horizontal(|ui| {
+                                    if ui.button("✔ Use").clicked() {
+                                        to_use.push(suggestion.clone());
+                                    }
+                                    if ui.button("Skip").clicked() {
+                                        to_skip = Some(i);
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+                
+                ui.horizontal(|ui| {
+                    if ui.button(format!("✔ Use All ({})", suggestions.len())).clicked() {
+                        to_use = suggestions.clone();
+                    }
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        
+        if let (Some(i), Some(suggestions)) = (to_skip, self.app_id_suggestions.as_mut()) {
+            suggestions.remove(i);
+        }
+        if !to_use.is_empty() {
+            if let Some(ref mut suggestions) = self.app_id_suggestions {
+                suggestions.retain(|s| !to_use.iter().any(|used| used.save_path == s.save_path));
+            }
+            self.assign_app_ids(&to_use);
+        }
+        if !open || close || self.app_id_suggestions.as_ref().map_or(false, |s| s.is_empty()) {
+            self.app_id_suggestions = None;
+        }
+    }
+    
+    /// Remember confirmed Steam app IDs and rescan, so the saves show up under their Steam names
+    fn assign_app_ids(&mut self, suggestions: &[AppIdSuggestion]) {
+        for suggestion in suggestions {
+            info!("{:?} is Steam app {}", suggestion.save_path, suggestion.app_id);
+            self.config.steam_app_ids.retain(|assignment| assignment.save_path != suggestion.save_path);
+            self.config.steam_app_ids.push(AppIdAssignment { save_path: suggestion.save_path.clone(), app_id: suggestion.app_id });
+            if let Some(ref name) = suggestion.app_name {
+                self.name_resolver.fill_missing(vec![(suggestion.app_id, name.clone())]);
+            }
+        }
+        self.temp_config.steam_app_ids = self.config.steam_app_ids.clone();
+        self.non_steam_scanner = Self::create_non_steam_scanner(&self.config);
+        self.scan_saves();
+        self.scan_status = ScanStatus::Complete(format!("Assigned Steam app IDs to {} saves", suggestions.len()));
+    }
+    
+    fn draw_detected_games(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut track: Option<usize> = None;
+        let mut ignore: Option<usize> = None;
+        let mut not_now = false;
+        
+        egui::Window::new("🎮 New Game Detected")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Tracking adds the game with this folder as its saves, so it is backed up like any other")
+                    .size(11.0).color(egui::Color32::GRAY));
+                ui.separator();
+                
+                for (i, game) in self.detected_games.iter_mut().enumerate() {
+                    ui.strong(format!("New game detected: {} — track its saves?", game.exe_name));
+                    ui.label(egui::RichText::new(game.save_path.display().to_string()).size(11.0).color(egui::Color32::GRAY));
+                    ui.horizontal(|ui| {
+                        ui.label("Name:");
+                        ui.add(egui::TextEdit::singleline(&mut game.name).desired_width(200.0));
+                        if ui.add_enabled(!game.name.trim().is_empty(), egui::Button::new("✔ Track Saves")).clicked() {
+                            track = Some(i);
+                        }
+                        if ui.button("Ignore").on_hover_text("Never offer this folder again").clicked() {
+                            ignore = Some(i);
+                        }
+                    });
+                    ui.separator();
+                }
+                
+                if ui.button("Not Now").on_hover_text("Ask again after a restart").clicked() {
+                    not_now = true;
+                }
+            });
+        
+        if let Some(i) = track {
+            let game = self.detected_games.remove(i);
+            self.track_detected_game(&game);
+        }
+        if let Some(i) = ignore {
+            let game = self.detected_games.remove(i);
+            info!("Ignoring {} writing to {:?}", game.exe_name, game.save_path);
+            self.config.game_detection.ignored_folders.push(game.save_path);
+            self.temp_config.game_detection.ignored_folders = self.config.game_detection.ignored_folders.clone();
+            self.update_detector_known();
+        }
+        if !open || not_now {
+            self.detected_games.clear();
+        }
+    }
+    
+    /// Add a detected game as a custom game and scan, so its save shows up with the others
+    fn track_detected_game(&mut self, game: &DetectedGame) {
+        let mut custom_game = game.to_custom_game();
+        custom_game.name = custom_game.name.trim().to_string();
+        info!("Tracking {} ({}) at {:?}", custom_game.name, game.exe_name, custom_game.save_path);
+        self.config.custom_games.push(custom_game.clone());
+        self.temp_config.custom_games = self.config.custom_games.clone();
+        self.non_steam_scanner = Self::create_non_steam_scanner(&self.config);
+        self.scan_saves();
+        self.scan_status = ScanStatus::Complete(format!(
+            "Now tracking {} saves in {}", custom_game.name, custom_game.save_path.display()
+        ));
+    }
+    
+    fn draw_unlock_prompt(&mut self, ctx: &egui::Context) {
+        let passphrase = match self.unlock_prompt {
+            Some(ref mut passphrase) => passphrase,
+            None => return,
+        };
+        let mut open = true;
+        let mut unlock = false;
+        
+        egui::Window::new("🔒 Unlock Encrypted Backups")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Enter the backup passphrase to create, restore or verify encrypted backups this session.");
+                let response = ui.add(egui::TextEdit::singleline(passphrase).password(true).desired_width(260.0));
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    unlock = true;
+                }
+                if let Some(ref error) = self.unlock_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ {}", error));
+                }
+                if self.config.backup_encryption.use_keyring {
+                    ui.label(egui::RichText::new("It is saved to the system keyring, so you won't be asked again")
+                        .size(11.0).color(egui::Color32::GRAY));
+                }
+                if ui.add_enabled(!passphrase.is_empty(), egui::Button::new("🔓 Unlock")).clicked() {
+                    unlock = true;
+                }
+            });
+        
+        if unlock {
+            match encryption::unlock(&self.config.backup_encryption, passphrase) {
+                Ok(()) => {
+                    self.unlock_prompt = None;
+                    self.unlock_error = None;
+                    self.scan_status = ScanStatus::Complete("Encrypted backups unlocked".to_string());
+                }
+                Err(e) => self.unlock_error = Some(e.to_string()),
+            }
+        } else if !open {
+            self.unlock_prompt = None;
+            self.unlock_error = None;
+        }
+    }
+    
+    fn draw_import_dialog(&mut self, ctx: &egui::Context) {
+        let mut dialog = match self.import_dialog.take() {
+            Some(dialog) => dialog,
+            None => return,
+        };
+        let mut games: Vec<GameIdentity> = Vec::new();
+        for identity in self.steam_saves.iter().chain(self.non_steam_saves.iter()).map(GameIdentity::of_save) {
+            if !games.contains(&identity) {
+                games.push(identity);
+            }
+        }
+        games.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        let downloading = self.archive_task.is_some();
+        let mut open = true;
+        let mut start_download = false;
+        let mut import_now = false;
+        
+        egui::Window::new("📥 Import Backup from URL")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let archive = match dialog.archive {
+                    Some(ref archive) => archive,
+                    None => {
+                        ui.label("Paste a download or share link to a backup archive (.zip, .tar.zst or .7z), e.g. one a friend sent you.");
+                        ui.label(egui::RichText::new("Dropbox, Google Drive, Nextcloud and ownCloud share links are turned into their direct download")
+                            .size(11.0).color(egui::Color32::GRAY));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut dialog.url).hint_text("https://…").desired_width(400.0));
+                            let ready = !dialog.url.trim().is_empty() && !downloading;
+                            if ui.add_enabled(ready, egui::Button::new("Download")).clicked() {
+                                start_download = true;
+                            }
+                        });
+                        if let Some(ref error) = dialog.error {
+                            ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ {}", error));
+                        }
+                        return;
+                    }
+                };
+                
+                ui.colored_label(egui::Color32::from_rgb(46, 204, 64), format!(
+                    "✓ {} - {}, {} files, every one read back fine", archive.file_name, format_size(archive.size), archive.files
+                ));
+                for metadata in &archive.save_metadata {
+                    ui.label(format!("🎮 {}", metadata.summary()));
+                }
+                ui.separator();
+                
+                egui::Grid::new("import_grid").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+                    ui.label("Game:");
+                    let selected_text = dialog.game.as_ref().map_or("Another game…".to_string(), |game| game.describe());
+                    egui::ComboBox::from_id_source("import_game")
+                        .selected_text(selected_text)
+                        .width(360.0)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut dialog.game, None, "Another game…");
+                            for game in &games {
+                                ui.selectable_value(&mut dialog.game, Some(game.clone()), game.describe());
+                            }
+                        });
+                    ui.end_row();
+                    
+                    if dialog.game.is_none() {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut dialog.game_name);
+                        ui.end_row();
+                        ui.label("Type:");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut dialog.save_type, SaveType::Steam, "Steam");
+                            ui.radio_value(&mut dialog.save_type, SaveType::NonSteam, "Non-Steam");
+                            if dialog.save_type == SaveType::Steam {
+                                ui.label("App ID:");
+                                ui.add(egui::TextEdit::singleline(&mut dialog.app_id).desired_width(90.0));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                    
+                    ui.label("Description:");
+                    ui.add(egui::TextEdit::singleline(&mut dialog.description).hint_text(format!("📥 Imported from {}", archive.url)).desired_width(360.0));
+                    ui.end_row();
+                });
+                
+                if dialog.game.is_none() {
+                    ui.label(egui::RichText::new("Games the scan doesn't find restore to a folder you pick")
+                        .size(11.0).color(egui::Color32::GRAY));
+                }
+                if let Some(ref error) = dialog.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ {}", error));
+                }
+                ui.separator();
+                let named = dialog.game.is_some() || !dialog.game_name.trim().is_empty();
+                if ui.add_enabled(named, egui::Button::new("📥 Import")).clicked() {
+                    import_now = true;
+                }
+            });
+        
+        if start_download {
+            dialog.error = None;
+            self.start_import_download(dialog.url.trim().to_string());
+        }
+        if import_now {
+            if let Some(archive) = dialog.archive.take() {
+                match self.import_downloaded_archive(&archive, &dialog) {
+                    Ok(backup_info) => {
+                        self.scan_status = ScanStatus::Complete(format!("Imported {} as a backup of {}", archive.file_name, backup_info.game_name));
+                        self.load_backups();
+                        return;
+                    }
+                    Err(e) => {
+                        dialog.error = Some(e.to_string());
+                        dialog.archive = Some(archive);
+                    }
+                }
+            }
+        }
+        if !open {
+            if let Some(ref archive) = dialog.archive {
+                archive.discard();
+            }
+            return;
+        }
+        self.import_dialog = Some(dialog);
+    }
+    
+    fn start_import_download(&mut self, url: String) {
+        if self.archive_task.is_some() {
+            return;
+        }
+        let folder = self.config.effective_backup_path();
+        self.archive_task = Some(ArchiveTask::spawn("Downloading backup", move |progress| {
+            ArchiveResult::Import(import::download(&url, &folder, &progress))
+        }));
+    }
+    
+    /// Fill in the game the downloaded archive's name points to, a scanned one if it matches
+    fn report_import_download(&mut self, downloaded: save_guardian_core::types::Result<DownloadedArchive>) {
+        let dialog = match self.import_dialog {
+            Some(ref mut dialog) => dialog,
+            None => {
+                // Window closed while downloading
+                if let Ok(archive) = downloaded {
+                    archive.discard();
+                }
+                return;
+            }
+        };
+        let archive = match downloaded {
+            Ok(archive) => archive,
+            Err(SaveGuardianError::Cancelled) => return,
+            Err(e) => {
+                dialog.error = Some(e.to_string());
+                return;
+            }
+        };
+        
+        let (name, app_id, save_type) = archive.guess_game();
+        let key = save_guardian_core::normalize::match_key(&name);
+        dialog.game = self.steam_saves.iter().chain(self.non_steam_saves.iter())
+            .find(|save| (app_id.is_some() && save.app_id == app_id) || save_guardian_core::normalize::match_key(&save.name) == key)
+            .map(GameIdentity::of_save);
+        dialog.game_name = name;
+        dialog.app_id = app_id.map(|id| id.to_string()).unwrap_or_default();
+        dialog.save_type = save_type;
+        dialog.error = None;
+        dialog.archive = Some(archive);
+    }
+    
+    fn import_downloaded_archive(&self, archive: &DownloadedArchive, dialog: &ImportDialog) -> save_guardian_core::types::Result<BackupInfo> {
+        let backup_manager = self.backup_manager.as_ref()
+            .ok_or_else(|| SaveGuardianError::BackupOperationFailed("The backup folder isn't available".to_string()))?;
+        let (game, original_path) = match dialog.game {
+            Some(ref game) => {
+                let save = self.steam_saves.iter().chain(self.non_steam_saves.iter())
+                    .find(|save| GameIdentity::of_save(save) == *game);
+                let path = save.map(|save| save.save_path.clone())
+                    .unwrap_or_else(|| self.reconstruct_likely_original_path(&game.name, game.app_id, &game.save_type));
+                (game.clone(), path)
+            }
+            None => {
+                let app_id = if dialog.save_type == SaveType::Steam { dialog.app_id.trim().parse().ok() } else { None };
+                let game = GameIdentity {
+                    name: dialog.game_name.trim().to_string(),
+                    app_id,
+                    save_type: dialog.save_type.clone(),
+                    steam_user_id: None,
+                };
+                let path = self.find_actual_save_path(&game.name, game.app_id, &game.save_type)
+                    .unwrap_or_else(|| self.reconstruct_likely_original_path(&game.name, game.app_id, &game.save_type));
+                (game, path)
+            }
+        };
+        let description = Some(dialog.description.trim().to_string()).filter(|d| !d.is_empty());
+        backup_manager.import_archive(archive, &game, original_path, description)
+    }
+    
+    fn draw_share_dialog(&mut self, ctx: &egui::Context) {
+        let dialog = match self.share_dialog {
+            Some(ref mut dialog) => dialog,
+            None => return,
+        };
+        let mut open = true;
+        let mut create = false;
+        let koofr = self.config.koofr_config.server == WebDavServer::Koofr;
+        
+        egui::Window::new("🔗 Share Backup")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&dialog.entry.name).strong());
+                if let Some(size) = dialog.entry.size {
+                    ui.label(egui::RichText::new(format_size(size)).size(11.0).color(egui::Color32::GRAY));
+                }
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.add_enabled(!koofr, egui::Checkbox::new(&mut dialog.expires, "Expires after"))
+                        .on_disabled_hover_text("Koofr links stay valid until deleted on koofr.net");
+                    ui.add_enabled_ui(dialog.expires && !koofr, |ui| {
+                        ui.add(egui_extras::DatePickerButton::new(&mut dialog.expiry_date).id_source("share_expiry"));
+                    });
+                });
+                if self.config.cloud_encryption.enabled {
+                    ui.colored_label(egui::Color32::from_rgb(255, 133, 27),
+                        "⚠ Cloud uploads are encrypted; whoever downloads this needs your passphrase to open it");
+                }
+                
+                if ui.button("Create Link").clicked() {
+                    create = true;
+                }
+                
+                if let Some(ref link) = dialog.link {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut link.as_str()).desired_width(360.0));
+                        if ui.button("📋 Copy").clicked() {
+                            ui.output_mut(|o| o.copied_text = link.clone());
+                        }
+                    });
+                }
+                if let Some(ref error) = dialog.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ {}", error));
+                }
+            });
+        
+        if create {
+            let expires = if dialog.expires && !koofr { Some(dialog.expiry_date) } else { None };
+            match cloud::provider_from_config(&self.config).share_link(&dialog.entry.name, expires) {
+                Ok(link) => {
+                    info!("Created share link for {}", dialog.entry.name);
+                    dialog.link = Some(link);
+                    dialog.error = None;
+                }
+                Err(e) => {
+                    dialog.link = None;
+                    dialog.error = Some(e.to_string());
+                }
+            }
+        }
+        if !open {
+            self.share_dialog = None;
+        }
+    }
+    
+    fn draw_cloud_history(&mut self, ctx: &egui::Context) {
+        let history = match self.cloud_history {
+            Some(ref history) => history,
+            None => return,
+        };
+        let mut open = true;
+        let filter = self.cloud_history_filter.to_lowercase();
+        let failed_only = self.cloud_history_failed_only;
+        let shown: Vec<&cloud::CloudOperation> = history.operations.iter().rev()
+            .filter(|op| !failed_only || !op.succeeded())
+            .filter(|op| filter.is_empty() || op.remote_name.to_lowercase().contains(&filter) || op.run.to_lowercase().contains(&filter))
+            .collect();
+        let mut filter_text = self.cloud_history_filter.clone();
+        let mut failed_toggle = failed_only;
+        
+        egui::Window::new("📜 Cloud History")
+            .open(&mut open)
+            .default_width(820.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut filter_text).on_hover_text("File name or run, e.g. \"Full Sync\"");
+                    ui.checkbox(&mut failed_toggle, "Problems only");
+                    ui.label(egui::RichText::new(format!("{} of {} operations", shown.len(), history.operations.len()))
+                        .size(11.0).color(egui::Color32::GRAY));
+                });
+                ui.separator();
+                
+                if shown.is_empty() {
+                    ui.label(egui::RichText::new("No cloud operations recorded yet").color(egui::Color32::GRAY));
+                    return;
+                }
+                
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::Grid::new("cloud_history_grid")
+                        .num_columns(7)
+                        .spacing([10.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("When");
+                            ui.strong("Run");
+                            ui.strong("");
+                            ui.strong("File");
+                            ui.strong("Size");
+                            ui.strong("Took");
+                            ui.strong("Result");
+                            ui.end_row();
+                            
+                            for op in &shown {
+                                ui.label(op.finished_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string());
+                                ui.label(format!("{} ({})", op.run, op.provider));
+                                match op.direction {
+                                    TransferDirection::Upload => ui.label("⬆").on_hover_text("Upload"),
+                                    TransferDirection::Download => ui.label("⬇").on_hover_text("Download"),
+                                };
+                                ui.label(cloud::remote_file_name(&op.remote_name)).on_hover_text(format!("{}\n{}", op.remote_name, op.local_path.display()));
+                                ui.label(format_size(op.size));
+                                let secs = op.duration_ms / 1000;
+                                ui.label(if secs >= 60 { format!("{}m {:02}s", secs / 60, secs % 60) } else { format!("{:.1}s", op.duration_ms as f64 / 1000.0) });
+                                let retries = if op.retries > 0 { format!(", {} retries", op.retries) } else { String::new() };
+                                match op.outcome {
+                                    CloudOutcome::Done => ui.colored_label(egui::Color32::from_rgb(46, 204, 64), format!("✓ Done{}", retries)),
+                                    CloudOutcome::Failed(ref error) => ui.colored_label(egui::Color32::from_rgb(220, 50, 50), format!("✗ Failed{}", retries))
+                                        .on_hover_text(error),
+                                    CloudOutcome::Cancelled => ui.colored_label(egui::Color32::from_rgb(255, 133, 27), "Cancelled"),
+                                };
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        
+        self.cloud_history_filter = filter_text;
+        self.cloud_history_failed_only = failed_toggle;
+        if !open {
+            self.cloud_history = None;
+        }
+    }
+}
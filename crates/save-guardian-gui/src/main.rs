@@ -0,0 +1,63 @@
+// Hide console window on Windows
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+mod cli;
+mod gui;
+
+use save_guardian_core::diagnostics;
+use eframe::egui;
+use gui::SaveGuardianApp;
+
+fn main() -> Result<(), eframe::Error> {
+    // Initialize logging, keeping recent lines for diagnostics reports
+    diagnostics::init_logging();
+    
+    // `save-guardian verify --all` audits the backup store without opening a window
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("verify") {
+        attach_parent_console();
+        std::process::exit(cli::run_verify_command(&args[2..]));
+    }
+    // `save-guardian store gc|repack` maintains the dedup store
+    if args.get(1).map(String::as_str) == Some("store") {
+        attach_parent_console();
+        std::process::exit(cli::run_store_command(&args[2..]));
+    }
+    
+    // --read-only disables every destructive operation for this session
+    let read_only = std::env::args().any(|arg| arg == "--read-only");
+    
+    // Set up eframe options
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([1200.0, 800.0])
+            .with_min_inner_size([800.0, 600.0]),
+        ..Default::default()
+    };
+    
+    // Run the application
+    eframe::run_native(
+        "Save Guardian",
+        options,
+        Box::new(move |cc| Box::new(SaveGuardianApp::new(cc).with_read_only(read_only))),
+    )
+}
+
+/// Give the subcommands the console they were started from
+///
+/// Release builds on Windows are GUI programs with no console of their own, so without this
+/// the report and error messages would go nowhere.
+#[cfg(windows)]
+fn attach_parent_console() {
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // Fails when there is no parent console (e.g. a scheduled task), which is fine
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
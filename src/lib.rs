@@ -1,9 +0,0 @@
-pub mod types;
-pub mod steam;
-pub mod non_steam;
-pub mod backup;
-pub mod sync;
-pub mod config;
-
-// Re-export commonly used types
-pub use types::*;
\ No newline at end of file